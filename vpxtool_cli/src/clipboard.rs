@@ -0,0 +1,38 @@
+//! System clipboard access for the text based frontend.
+use arboard::{Clipboard, ImageData};
+use image::DynamicImage;
+use std::borrow::Cow;
+use std::io;
+use std::sync::{LazyLock, Mutex};
+
+/// Kept for the whole session as on X11 the copied text is served by the process that owns it,
+/// dropping the clipboard would lose the text unless a clipboard manager is running.
+static CLIPBOARD: LazyLock<Mutex<Option<Clipboard>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Copies text to the clipboard, ANSI styling is removed
+pub(crate) fn copy(text: &str) -> io::Result<()> {
+    let plain = console::strip_ansi_codes(text).to_string();
+    with_clipboard(|clipboard| clipboard.set_text(plain))
+}
+
+/// Copies the image to the clipboard as image data, fails on platforms or sessions where the
+/// clipboard doesn't take images
+pub(crate) fn copy_image(image: &DynamicImage) -> io::Result<()> {
+    let rgba = image.to_rgba8();
+    let image_data = ImageData {
+        width: rgba.width() as usize,
+        height: rgba.height() as usize,
+        bytes: Cow::Owned(rgba.into_raw()),
+    };
+    with_clipboard(|clipboard| clipboard.set_image(image_data))
+}
+
+fn with_clipboard(f: impl FnOnce(&mut Clipboard) -> Result<(), arboard::Error>) -> io::Result<()> {
+    let mut clipboard = CLIPBOARD
+        .lock()
+        .map_err(|_| io::Error::other("Clipboard lock poisoned"))?;
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new().map_err(io::Error::other)?);
+    }
+    f(clipboard.as_mut().expect("clipboard initialized above")).map_err(io::Error::other)
+}
@@ -0,0 +1,265 @@
+//! Checks the configuration for common setup mistakes.
+//!
+//! Every check results in a pass, a warning or a failure with a hint on how to fix it. Warnings
+//! are for setups that can work, like a missing rom folder when no table needs PinMAME.
+use colored::Colorize;
+use is_executable::IsExecutable;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use vpxtool_shared::config::ResolvedConfig;
+use vpxtool_shared::indexer;
+use vpxtool_shared::reference::ReferenceHashes;
+use vpxtool_shared::rom_audit::RomManifest;
+
+/// How long the launcher gets to print its version
+const LAUNCHER_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(PartialEq, Debug)]
+pub(crate) enum Outcome {
+    Pass,
+    Warn { hint: String },
+    Fail { hint: String },
+}
+
+#[derive(PartialEq, Debug)]
+pub(crate) struct Check {
+    pub name: &'static str,
+    pub detail: String,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: String) -> Check {
+        Check {
+            name,
+            detail,
+            outcome: Outcome::Pass,
+        }
+    }
+
+    fn warn<H: Into<String>>(name: &'static str, detail: String, hint: H) -> Check {
+        Check {
+            name,
+            detail,
+            outcome: Outcome::Warn { hint: hint.into() },
+        }
+    }
+
+    fn fail<H: Into<String>>(name: &'static str, detail: String, hint: H) -> Check {
+        Check {
+            name,
+            detail,
+            outcome: Outcome::Fail { hint: hint.into() },
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self.outcome, Outcome::Fail { .. })
+    }
+
+    /// `[PASS] name: detail` with the hint on the next line
+    pub fn report(&self) -> String {
+        let (status, hint) = match &self.outcome {
+            Outcome::Pass => ("[PASS]".green(), None),
+            Outcome::Warn { hint } => ("[WARN]".yellow(), Some(hint)),
+            Outcome::Fail { hint } => ("[FAIL]".red(), Some(hint)),
+        };
+        let mut report = format!("{} {}: {}", status, self.name, self.detail);
+        if let Some(hint) = hint {
+            report.push_str(&format!("\n       {}", hint.dimmed()));
+        }
+        report
+    }
+}
+
+pub(crate) fn run_checks(config: &ResolvedConfig) -> Vec<Check> {
+    let mut checks = vec![check_executable(&config.vpx_executable)];
+    if !checks[0].is_failure() {
+        checks.push(check_launcher_version(&config.vpx_executable));
+    }
+    let tables_folder = check_dir(
+        "Tables folder",
+        &config.tables_folder,
+        "Set tables_folder in the config file with `vpxtool config edit`",
+    );
+    let tables_folder_ok = !tables_folder.is_failure();
+    checks.push(tables_folder);
+    if tables_folder_ok {
+        checks.push(check_tables_found(&config.tables_folder));
+    }
+    if let Some(index_folder) = config.tables_index_path.parent() {
+        checks.push(check_dir(
+            "Index folder",
+            index_folder,
+            "The tables index is written here, make sure the folder exists",
+        ));
+    }
+    let rom_folder = config.global_pinmame_rom_folder();
+    checks.push(match check_dir("ROM folder", &rom_folder, "") {
+        Check {
+            outcome: Outcome::Fail { .. },
+            name,
+            detail,
+        } => Check::warn(
+            name,
+            detail,
+            "Tables that need PinMAME won't find their rom, set rom_folder in the config file",
+        ),
+        check => check,
+    });
+    if let Some(reference_hashes) = &config.reference_hashes {
+        checks.push(check_reference_hashes(reference_hashes));
+    }
+    if let Some(rom_manifest) = &config.rom_manifest {
+        checks.push(check_rom_manifest(rom_manifest));
+    }
+    checks
+}
+
+fn check_executable(path: &Path) -> Check {
+    let name = "Visual Pinball executable";
+    let detail = path.display().to_string();
+    let hint = "Set vpx_executable in the config file with `vpxtool config edit`";
+    if !path.exists() {
+        Check::fail(name, format!("{} does not exist", detail), hint)
+    } else if !path.is_file() {
+        Check::fail(name, format!("{} is not a file", detail), hint)
+    } else if !path.is_executable() {
+        Check::fail(
+            name,
+            format!("{} is not executable", detail),
+            "Make the file executable, for example with `chmod +x`",
+        )
+    } else {
+        Check::pass(name, detail)
+    }
+}
+
+/// Runs the launcher with `--version`, killing it if it does not exit in time
+fn check_launcher_version(path: &Path) -> Check {
+    let name = "Visual Pinball launch";
+    let hint = "Make sure the executable starts when run from a terminal";
+    let mut child = match Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => return Check::fail(name, format!("Unable to start: {}", err), hint),
+    };
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < LAUNCHER_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(100))
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Check::warn(
+                    name,
+                    format!("No answer after {}s", LAUNCHER_TIMEOUT.as_secs()),
+                    "This version might not support --version, try launching a table",
+                );
+            }
+            Err(err) => return Check::fail(name, err.to_string(), hint),
+        }
+    }
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let version = stdout.lines().find(|line| !line.trim().is_empty());
+            Check::pass(name, version.unwrap_or("ok").trim().to_string())
+        }
+        Ok(output) => Check::fail(name, format!("Exited with {}", output.status), hint),
+        Err(err) => Check::fail(name, err.to_string(), hint),
+    }
+}
+
+fn check_dir(name: &'static str, path: &Path, hint: &str) -> Check {
+    let detail = path.display().to_string();
+    if !path.exists() {
+        Check::fail(name, format!("{} does not exist", detail), hint)
+    } else if !path.is_dir() {
+        Check::fail(name, format!("{} is not a folder", detail), hint)
+    } else {
+        Check::pass(name, detail)
+    }
+}
+
+fn check_tables_found(tables_folder: &Path) -> Check {
+    let name = "Tables";
+    match indexer::find_vpx_files(true, tables_folder) {
+        Ok(files) if files.is_empty() => Check::fail(
+            name,
+            format!("No .vpx files in {}", tables_folder.display()),
+            "Point tables_folder to the folder that holds your tables",
+        ),
+        Ok(files) => Check::pass(name, format!("{} .vpx files found", files.len())),
+        Err(err) => Check::fail(
+            name,
+            format!("Unable to list {}: {}", tables_folder.display(), err),
+            "Check the permissions of the tables folder",
+        ),
+    }
+}
+
+fn check_reference_hashes(path: &Path) -> Check {
+    let name = "Reference hashes";
+    let hint = "Fix or remove reference_hashes in the config file";
+    match ReferenceHashes::read(path) {
+        Ok(_) => Check::pass(name, path.display().to_string()),
+        Err(err) => Check::fail(name, err.to_string(), hint),
+    }
+}
+
+fn check_rom_manifest(path: &Path) -> Check {
+    let name = "ROM manifest";
+    let hint = "Fix or remove rom_manifest in the config file";
+    match RomManifest::read(path) {
+        Ok(_) => Check::pass(name, path.display().to_string()),
+        Err(err) => Check::fail(name, err.to_string(), hint),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io;
+    use testdir::testdir;
+
+    #[test]
+    fn test_path_checks() -> io::Result<()> {
+        let dir = testdir!();
+        let file = dir.join("file.txt");
+        std::fs::write(&file, "")?;
+        let missing = dir.join("missing");
+
+        let dir_check = check_dir("Folder", &dir, "hint");
+        let file_as_dir = check_dir("Folder", &file, "hint");
+        let missing_dir = check_dir("Folder", &missing, "hint");
+        let no_tables = check_tables_found(&dir);
+        let missing_executable = check_executable(&missing);
+        let dir_as_executable = check_executable(&dir);
+
+        assert_eq!(dir_check.outcome, Outcome::Pass);
+        assert_eq!(
+            file_as_dir.detail,
+            format!("{} is not a folder", file.display())
+        );
+        assert!(file_as_dir.is_failure());
+        assert!(missing_dir.is_failure());
+        assert!(no_tables.is_failure());
+        assert!(missing_executable.is_failure());
+        assert_eq!(
+            dir_as_executable.detail,
+            format!("{} is not a file", dir.display())
+        );
+        Ok(())
+    }
+}
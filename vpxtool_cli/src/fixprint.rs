@@ -2,6 +2,31 @@ use colored::Colorize;
 use std::io;
 use std::io::Write;
 use std::process::ExitCode;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the commands print besides errors and the data that was asked for
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum Verbosity {
+    /// Only errors and requested data, no progress bars, summaries or info lines
+    Quiet = 0,
+    Normal = 1,
+    /// Also print extra details like script encodings
+    Verbose = 2,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
 
 // These macros are needed because the normal ones panic when there's a broken pipe.
 // This is especially problematic for CLI tools that are frequently piped into `head` or `grep -q`
@@ -50,6 +75,70 @@ macro_rules! eprint {
   })
 }
 
+/// Like `println!` for info lines and summaries, prints nothing in quiet mode
+#[macro_export]
+macro_rules! status {
+  ($($arg:tt)*) => ({
+    if $crate::fixprint::verbosity() >= $crate::fixprint::Verbosity::Normal {
+      $crate::println!($($arg)*)
+    } else {
+      Ok(())
+    }
+  })
+}
+
+/// Like `println!` for extra details, only prints in verbose mode
+#[macro_export]
+macro_rules! detail {
+  ($($arg:tt)*) => ({
+    if $crate::fixprint::verbosity() >= $crate::fixprint::Verbosity::Verbose {
+      $crate::println!($($arg)*)
+    } else {
+      Ok(())
+    }
+  })
+}
+
+/// Writes the log messages of vpxtool_shared, like an invalid config setting, to stderr so
+/// they don't end up in the output on stdout
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            match record.level() {
+                log::Level::Error => eprintln!("{}", format!("Error: {}", record.args()).red()),
+                log::Level::Warn => {
+                    eprintln!("{}", format!("Warning: {}", record.args()).yellow())
+                }
+                _ => eprintln!("{}", record.args()),
+            }
+            .ok();
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Shows warnings, and in verbose mode info messages, as long as the verbosity is not quiet
+pub fn init_logger() {
+    let level = match verbosity() {
+        Verbosity::Quiet => log::LevelFilter::Error,
+        Verbosity::Normal => log::LevelFilter::Warn,
+        Verbosity::Verbose => log::LevelFilter::Info,
+    };
+    // only fails when a logger is set already
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
 pub fn safe_main(main: fn() -> io::Result<ExitCode>) -> ExitCode {
     // from https://github.com/rust-lang/rust/issues/46016#issuecomment-1242039016
     match main() {
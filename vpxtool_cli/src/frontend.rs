@@ -2,49 +2,266 @@ use crate::backglass::find_hole;
 use crate::patcher::LineEndingsResult::{NoChanges, Unified};
 use crate::patcher::{patch_vbs_file, unify_line_endings_vbs_file};
 use crate::{
-    DiffColor, ProgressBarProgress, confirm, info_diff, info_edit, info_gather, open_editor,
-    run_diff, script_diff, strip_cr_lf,
-    vpx::{ExtractResult, extractvbs, ini_path_for, vbs_path_for},
+    DiffColor, JsonSettings, ProgressBarProgress, clipboard, confirm, info_diff, info_edit,
+    info_gather, open_editor, run_diff, script_diff,
+    script_encoding::{extracted_script, extractvbs},
+    script_view, strip_cr_lf, table_json, theme, thumbnails,
+    vpx::{ExtractResult, ini_path_for, vbs_path_for},
 };
 use base64::Engine;
-use colored::Colorize;
-use console::Emoji;
-use dialoguer::theme::ColorfulTheme;
-use dialoguer::{FuzzySelect, Input, MultiSelect, Select};
+use console::{Emoji, Term};
+use dialoguer::{Confirm, FuzzySelect, History, Input, MultiSelect, Select};
 use indicatif::{ProgressBar, ProgressStyle};
 use is_executable::IsExecutable;
 use pinmame_nvram::dips::{get_all_dip_switches, set_dip_switches};
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
 use std::fs::OpenOptions;
 use std::io::BufReader;
+use std::ops::{Deref, DerefMut};
 use std::{
+    ffi::OsStr,
     fs::File,
     io,
     io::Write,
     path::{Path, PathBuf},
-    process::{ExitStatus, exit},
+    process::{Child, ExitStatus, Stdio, exit},
+    sync::Mutex,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
-use vpxtool_shared::config::ResolvedConfig;
+use vpxtool_shared::changelog;
+use vpxtool_shared::completeness::{Completeness, completeness};
+use vpxtool_shared::config;
+use vpxtool_shared::config::{FilterPreset, ResolvedConfig};
+use vpxtool_shared::display::{
+    InfoField, Token, format_play_time, format_rating, format_size, manufacturer_color,
+    name_disambiguations, row_state, search_stem, truncate_left, truncate_words,
+};
+use vpxtool_shared::index::{Index, LAUNCHABLE_TERM, MISSING_WHEEL_TERM, TableFilter, collection};
+use vpxtool_shared::index_diff;
 use vpxtool_shared::indexer;
-use vpxtool_shared::indexer::{IndexError, IndexedTable, Progress};
+use vpxtool_shared::indexer::{
+    ChdRequirements, IndexError, IndexOptions, IndexProgress, IndexedTable, RomIntegrity,
+    TablesIndex, WarningKind, extract_archived_vpx, extracted_vpx_path, verify_rom_zip,
+};
+use vpxtool_shared::notes::{
+    MAX_RATING, TableNotes, add_play_time, notes_path_for, parse_tags, read_notes, write_notes,
+};
+use vpxtool_shared::reference::ReferenceHashes;
+use vpxtool_shared::rom_audit::RomManifest;
+use vpxtool_shared::staging;
+use vpxtool_shared::table_ini;
 use vpxtool_shared::vpinball_config::{VPinballConfig, WindowInfo, WindowType};
+use vpxtool_shared::vps::{VpsDatabase, VpsGame};
+use vpxtool_shared::watch::FolderWatcher;
 
 const LAUNCH: Emoji = Emoji("🚀", "[launch]");
 const CRASH: Emoji = Emoji("💥", "[crash]");
+const EDIT: Emoji = Emoji("✏️", "[edit]");
+
+/// Number of main menu entries shown before the tables
+const MENU_ENTRIES: usize = MenuEntry::MAIN.len();
+
+/// Longer changelogs are cut off in the table info
+const MAX_CHANGELOG_LINES: usize = 12;
+/// Fits sizes up to `1023.9 MB`
+const SIZE_COLUMN_WIDTH: usize = 9;
+/// Columns taken by the selection marker in front of the list rows
+const ROW_PREFIX_WIDTH: usize = 2;
+/// How long the index file has to stay unchanged before it is read again, also how often it is
+/// checked where there are no change notifications
+const INDEX_WATCH_SETTLE: Duration = Duration::from_millis(500);
+/// Tables launched in the background that have not been seen exiting yet
+static RUNNING_TABLES: Mutex<Vec<(PathBuf, Child)>> = Mutex::new(Vec::new());
+
+/// Smaller terminals garble the menus, (columns, rows)
+const MIN_TERMINAL_SIZE: (u16, u16) = (80, 24);
+/// Filters kept for <↑>/<↓> in the filter input, only for the running session
+const FILTER_HISTORY_SIZE: usize = 20;
+
+/// Modes switched on and off from the main menu for the current session
+#[derive(Clone, Copy, Default)]
+struct MenuToggles {
+    dry_run: bool,
+    warnings_first: bool,
+    /// Tables with the most warnings first, tables without warnings last
+    most_warnings_first: bool,
+    rating_first: bool,
+    /// Lowest completeness score first, after the tables with warnings if both are on
+    completeness_first: bool,
+    /// List every table on two lines, the second one with a compact status
+    two_line: bool,
+    /// List tables by their relative path instead of their name
+    show_paths: bool,
+    /// Append the number of warnings to the table rows
+    warning_counts: bool,
+}
+
+/// Entries of the main menu and of its `Settings…` and `Tools…` submenus
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MenuEntry {
+    Search,
+    Recent,
+    RecentlyAdded,
+    MostPlayed,
+    Reindex,
+    Filter,
+    Settings,
+    Tools,
+    DryRun,
+    WarningsFirst,
+    MostWarningsFirst,
+    RatingFirst,
+    CompletenessFirst,
+    ShowPaths,
+    WarningCounts,
+    TwoLine,
+    Collection,
+    MissingWheel,
+    Launchable,
+    NextPreset,
+    SavePreset,
+    RescanRoms,
+    Random,
+    Jump,
+    NextWarning,
+    PreviousWarning,
+    WarningsReport,
+    Batch,
+    UndoMove,
+}
+
+impl MenuEntry {
+    /// Shown above the tables, few enough to leave room for the tables on a small terminal
+    const MAIN: [MenuEntry; 8] = [
+        MenuEntry::Search,
+        MenuEntry::Recent,
+        MenuEntry::RecentlyAdded,
+        MenuEntry::MostPlayed,
+        MenuEntry::Reindex,
+        MenuEntry::Filter,
+        MenuEntry::Settings,
+        MenuEntry::Tools,
+    ];
+
+    /// How the tables are listed, each entry shows its current state
+    const SETTINGS: [MenuEntry; 13] = [
+        MenuEntry::DryRun,
+        MenuEntry::WarningsFirst,
+        MenuEntry::MostWarningsFirst,
+        MenuEntry::RatingFirst,
+        MenuEntry::CompletenessFirst,
+        MenuEntry::ShowPaths,
+        MenuEntry::WarningCounts,
+        MenuEntry::TwoLine,
+        MenuEntry::Collection,
+        MenuEntry::MissingWheel,
+        MenuEntry::Launchable,
+        MenuEntry::NextPreset,
+        MenuEntry::SavePreset,
+    ];
+
+    const TOOLS: [MenuEntry; 8] = [
+        MenuEntry::RescanRoms,
+        MenuEntry::Random,
+        MenuEntry::Jump,
+        MenuEntry::NextWarning,
+        MenuEntry::PreviousWarning,
+        MenuEntry::WarningsReport,
+        MenuEntry::Batch,
+        MenuEntry::UndoMove,
+    ];
+}
+
+/// What the menu entries show next to their name
+struct MenuState<'a> {
+    toggles: MenuToggles,
+    filter: &'a TableFilter,
+    filter_input: &'a str,
+    collection: Option<&'a PathBuf>,
+    preset: Option<&'a str>,
+}
 
-const SEARCH: &str = "> Search";
-const RECENT: &str = "> Recent";
-const SEARCH_INDEX: usize = 0;
-const RECENT_INDEX: usize = 1;
+impl MenuState<'_> {
+    fn label(&self, entry: MenuEntry) -> String {
+        let toggles = self.toggles;
+        match entry {
+            MenuEntry::Search => "Search".to_string(),
+            MenuEntry::Recent => "Recent".to_string(),
+            MenuEntry::RecentlyAdded => "Recently added".to_string(),
+            MenuEntry::MostPlayed => "Most played".to_string(),
+            MenuEntry::Reindex => "Reindex".to_string(),
+            MenuEntry::Filter => format!(
+                "Filter: {}",
+                if self.filter.is_empty() {
+                    "none"
+                } else {
+                    self.filter_input.trim()
+                }
+            ),
+            MenuEntry::Settings => "Settings…".to_string(),
+            MenuEntry::Tools => "Tools…".to_string(),
+            MenuEntry::DryRun => format!("Dry run: {}", on_off(toggles.dry_run)),
+            MenuEntry::WarningsFirst => {
+                format!("Warnings first: {}", on_off(toggles.warnings_first))
+            }
+            MenuEntry::MostWarningsFirst => format!(
+                "Most warnings first: {}",
+                on_off(toggles.most_warnings_first)
+            ),
+            MenuEntry::RatingFirst => format!("Rating first: {}", on_off(toggles.rating_first)),
+            MenuEntry::CompletenessFirst => format!(
+                "Least complete first: {}",
+                on_off(toggles.completeness_first)
+            ),
+            MenuEntry::ShowPaths => format!("Show paths: {}", on_off(toggles.show_paths)),
+            MenuEntry::WarningCounts => {
+                format!("Warning counts: {}", on_off(toggles.warning_counts))
+            }
+            MenuEntry::TwoLine => format!("Two-line view: {}", on_off(toggles.two_line)),
+            MenuEntry::Collection => format!(
+                "Collection: {}",
+                self.collection
+                    .map_or("all".to_string(), |folder| folder.display().to_string())
+            ),
+            MenuEntry::MissingWheel => format!(
+                "Missing wheel only: {}",
+                on_off(self.filter.missing_wheel())
+            ),
+            MenuEntry::Launchable => {
+                format!("Launchable only: {}", on_off(self.filter.launchable()))
+            }
+            MenuEntry::NextPreset => format!("Next preset ({})", self.preset.unwrap_or("none")),
+            MenuEntry::SavePreset => "Save preset".to_string(),
+            MenuEntry::RescanRoms => "Rescan ROMs".to_string(),
+            MenuEntry::Random => "Random table".to_string(),
+            MenuEntry::Jump => "Jump to #".to_string(),
+            MenuEntry::NextWarning => "Next table with warnings".to_string(),
+            MenuEntry::PreviousWarning => "Previous table with warnings".to_string(),
+            MenuEntry::WarningsReport => "All warnings".to_string(),
+            MenuEntry::Batch => "Batch".to_string(),
+            MenuEntry::UndoMove => "Undo last move".to_string(),
+        }
+    }
+}
 
 #[derive(PartialEq, Eq)]
 enum TableOption {
     Launch,
     LaunchFullscreen,
     LaunchWindowed,
+    OpenInEditor,
     ForceReload,
     InfoShow,
     InfoEdit,
     InfoDiff,
+    OpenResource,
+    CopyPreview,
+    CopyJson,
+    ShowVBS,
     ExtractVBS,
     EditVBS,
     PatchVBS,
@@ -52,20 +269,33 @@ enum TableOption {
     ShowVBSDiff,
     CreateVBSPatch,
     DIPSwitches,
+    VerifyROM,
     NVRAMClear,
     B2SAutoPositionDMD,
+    B2SOpenImage,
     EditINI,
+    ExtractArchive,
+    SuppressWarnings,
+    EditTags,
+    Rate,
+    Promote,
+    MoveToFolder,
 }
 
 impl TableOption {
-    const ALL: [TableOption; 17] = [
+    const ALL: [TableOption; 30] = [
         TableOption::Launch,
         TableOption::LaunchFullscreen,
         TableOption::LaunchWindowed,
+        TableOption::OpenInEditor,
         TableOption::ForceReload,
         TableOption::InfoShow,
         TableOption::InfoEdit,
         TableOption::InfoDiff,
+        TableOption::OpenResource,
+        TableOption::CopyPreview,
+        TableOption::CopyJson,
+        TableOption::ShowVBS,
         TableOption::ExtractVBS,
         TableOption::EditVBS,
         TableOption::PatchVBS,
@@ -73,9 +303,17 @@ impl TableOption {
         TableOption::ShowVBSDiff,
         TableOption::CreateVBSPatch,
         TableOption::DIPSwitches,
+        TableOption::VerifyROM,
         TableOption::NVRAMClear,
         TableOption::B2SAutoPositionDMD,
+        TableOption::B2SOpenImage,
         TableOption::EditINI,
+        TableOption::ExtractArchive,
+        TableOption::SuppressWarnings,
+        TableOption::EditTags,
+        TableOption::Rate,
+        TableOption::Promote,
+        TableOption::MoveToFolder,
     ];
 
     fn from_index(index: usize) -> Option<TableOption> {
@@ -83,20 +321,33 @@ impl TableOption {
             0 => Some(TableOption::Launch),
             1 => Some(TableOption::LaunchFullscreen),
             2 => Some(TableOption::LaunchWindowed),
-            3 => Some(TableOption::ForceReload),
-            4 => Some(TableOption::InfoShow),
-            5 => Some(TableOption::InfoEdit),
-            6 => Some(TableOption::InfoDiff),
-            7 => Some(TableOption::ExtractVBS),
-            8 => Some(TableOption::EditVBS),
-            9 => Some(TableOption::PatchVBS),
-            10 => Some(TableOption::UnifyLineEndings),
-            11 => Some(TableOption::ShowVBSDiff),
-            12 => Some(TableOption::CreateVBSPatch),
-            13 => Some(TableOption::DIPSwitches),
-            14 => Some(TableOption::NVRAMClear),
-            15 => Some(TableOption::B2SAutoPositionDMD),
-            16 => Some(TableOption::EditINI),
+            3 => Some(TableOption::OpenInEditor),
+            4 => Some(TableOption::ForceReload),
+            5 => Some(TableOption::InfoShow),
+            6 => Some(TableOption::InfoEdit),
+            7 => Some(TableOption::InfoDiff),
+            8 => Some(TableOption::OpenResource),
+            9 => Some(TableOption::CopyPreview),
+            10 => Some(TableOption::CopyJson),
+            11 => Some(TableOption::ShowVBS),
+            12 => Some(TableOption::ExtractVBS),
+            13 => Some(TableOption::EditVBS),
+            14 => Some(TableOption::PatchVBS),
+            15 => Some(TableOption::UnifyLineEndings),
+            16 => Some(TableOption::ShowVBSDiff),
+            17 => Some(TableOption::CreateVBSPatch),
+            18 => Some(TableOption::DIPSwitches),
+            19 => Some(TableOption::VerifyROM),
+            20 => Some(TableOption::NVRAMClear),
+            21 => Some(TableOption::B2SAutoPositionDMD),
+            22 => Some(TableOption::B2SOpenImage),
+            23 => Some(TableOption::EditINI),
+            24 => Some(TableOption::ExtractArchive),
+            25 => Some(TableOption::SuppressWarnings),
+            26 => Some(TableOption::EditTags),
+            27 => Some(TableOption::Rate),
+            28 => Some(TableOption::Promote),
+            29 => Some(TableOption::MoveToFolder),
             _ => None,
         }
     }
@@ -106,10 +357,15 @@ impl TableOption {
             TableOption::Launch => "Launch".to_string(),
             TableOption::LaunchFullscreen => "Launch fullscreen".to_string(),
             TableOption::LaunchWindowed => "Launch windowed".to_string(),
+            TableOption::OpenInEditor => "Open in VPinball editor".to_string(),
             TableOption::ForceReload => "Force reload".to_string(),
             TableOption::InfoShow => "Info > Show".to_string(),
             TableOption::InfoEdit => "Info > Edit".to_string(),
             TableOption::InfoDiff => "Info > Diff".to_string(),
+            TableOption::OpenResource => "Info > Open resource page".to_string(),
+            TableOption::CopyPreview => "Info > Copy preview image".to_string(),
+            TableOption::CopyJson => "Info > Copy as JSON".to_string(),
+            TableOption::ShowVBS => "VBScript > Show".to_string(),
             TableOption::ExtractVBS => "VBScript > Extract".to_string(),
             TableOption::EditVBS => "VBScript > Edit".to_string(),
             TableOption::PatchVBS => "VBScript > Patch typical standalone issues".to_string(),
@@ -117,18 +373,93 @@ impl TableOption {
             TableOption::ShowVBSDiff => "VBScript > Diff".to_string(),
             TableOption::CreateVBSPatch => "VBScript > Create patch file".to_string(),
             TableOption::DIPSwitches => "DIP Switches".to_string(),
+            TableOption::VerifyROM => "ROM > Verify".to_string(),
             TableOption::NVRAMClear => "NVRAM > Clear".to_string(),
             TableOption::B2SAutoPositionDMD => "Backglass > Auto-position DMD".to_string(),
+            TableOption::B2SOpenImage => "Backglass > Open image".to_string(),
             TableOption::EditINI => "INI > Edit".to_string(),
+            TableOption::ExtractArchive => "Archive > Extract".to_string(),
+            TableOption::SuppressWarnings => "Warnings > Suppress".to_string(),
+            TableOption::EditTags => "Tags > Edit".to_string(),
+            TableOption::Rate => "Rating > Set".to_string(),
+            TableOption::Promote => "Staging > Promote to tables folder".to_string(),
+            TableOption::MoveToFolder => "Move to folder".to_string(),
+        }
+    }
+
+    /// Options that write to the table or its sidecar files, hidden in read-only mode
+    fn is_mutating(&self) -> bool {
+        !matches!(
+            self,
+            TableOption::Launch
+                | TableOption::LaunchFullscreen
+                | TableOption::LaunchWindowed
+                | TableOption::ForceReload
+                | TableOption::InfoShow
+                | TableOption::InfoDiff
+                | TableOption::OpenResource
+                | TableOption::CopyPreview
+                | TableOption::CopyJson
+                | TableOption::ShowVBS
+                | TableOption::ShowVBSDiff
+                | TableOption::VerifyROM
+                | TableOption::B2SOpenImage
+        )
+    }
+
+    /// Options that hand a file to an editor, what changes is up to the user so these are hidden
+    /// in dry-run mode
+    fn opens_editor(&self) -> bool {
+        matches!(
+            self,
+            TableOption::OpenInEditor
+                | TableOption::InfoEdit
+                | TableOption::EditVBS
+                | TableOption::EditINI
+        )
+    }
+}
+
+/// Actions that run on all tables picked with the Batch entry
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum BatchAction {
+    ExtractVBS,
+    AddTags,
+    RemoveTags,
+    MoveToFolder,
+    ForceReload,
+}
+
+impl BatchAction {
+    const ALL: [BatchAction; 5] = [
+        BatchAction::ExtractVBS,
+        BatchAction::AddTags,
+        BatchAction::RemoveTags,
+        BatchAction::MoveToFolder,
+        BatchAction::ForceReload,
+    ];
+
+    fn display(&self) -> &'static str {
+        match self {
+            BatchAction::ExtractVBS => "VBScript > Extract",
+            BatchAction::AddTags => "Tags > Add",
+            BatchAction::RemoveTags => "Tags > Remove",
+            BatchAction::MoveToFolder => "Move to folder",
+            BatchAction::ForceReload => "Force reload",
         }
     }
+
+    /// Same as [TableOption::is_mutating]
+    fn is_mutating(&self) -> bool {
+        *self != BatchAction::ForceReload
+    }
 }
 
 pub fn frontend_index(
     resolved_config: &ResolvedConfig,
     recursive: bool,
     force_reindex: Vec<PathBuf>,
-) -> Result<Vec<IndexedTable>, IndexError> {
+) -> Result<Index, IndexError> {
     let pb = ProgressBar::hidden();
     pb.set_style(
         ProgressStyle::with_template(
@@ -137,83 +468,455 @@ pub fn frontend_index(
         .unwrap(),
     );
     let progress = ProgressBarProgress::new(pb);
-    let index = indexer::index_folder(
+    let index = index_tables_folder(
+        resolved_config,
         recursive,
-        &resolved_config.tables_folder,
-        &resolved_config.tables_index_path,
-        Some(&resolved_config.global_pinmame_rom_folder()),
-        &progress,
         force_reindex,
+        Some(&mut |p| progress.update(p)),
     );
     progress.finish_and_clear();
     let index = index?;
 
     let mut tables: Vec<IndexedTable> = index.tables();
-    tables.sort_by_key(|indexed| display_table_line(indexed).to_lowercase());
-    Ok(tables)
+    // by name, the frontend sorts them again with its toggles
+    tables.sort_by_cached_key(|indexed| {
+        resolved_config
+            .display_template
+            .render(indexed)
+            .to_lowercase()
+    });
+    Ok(Index::new(tables))
+}
+
+/// Updates the index file with everything the frontend shows, like the wheels, the staged tables
+/// and the NVRAM files
+pub fn index_tables_folder(
+    resolved_config: &ResolvedConfig,
+    recursive: bool,
+    force_reindex: Vec<PathBuf>,
+    progress: Option<&mut dyn FnMut(IndexProgress)>,
+) -> Result<TablesIndex, IndexError> {
+    let options = IndexOptions {
+        recursive,
+        ..resolved_config.index_options()
+    };
+    indexer::index_folder(
+        &resolved_config.tables_folder,
+        &resolved_config.tables_index_path,
+        &options,
+        progress,
+        force_reindex,
+    )
+}
+
+/// Looks up the roms of all tables again without parsing the tables, the index file is updated
+/// as well so the result survives a restart.
+fn rescan_roms(config: &ResolvedConfig, tables: &mut [IndexedTable]) -> io::Result<String> {
+    if !config.has_rom_folder() {
+        return Ok(
+            "No rom folder found, set rom_folder in the config file to manage roms".to_string(),
+        );
+    }
+    let rom_folder = config.global_pinmame_rom_folder();
+    let roms = indexer::find_roms(&rom_folder)?;
+    let changed = tables
+        .iter_mut()
+        .map(|table| table.relink_rom(&roms))
+        .filter(|changed| *changed)
+        .count();
+    if let Some(mut index) = indexer::read_index(&config.tables_index_path)? {
+        index.relink_roms(&roms);
+        indexer::write_index(&index, &config.tables_index_path)?;
+    }
+    Ok(format!(
+        "Found {} ROMs in {}, {} tables updated",
+        roms.len(),
+        rom_folder.display(),
+        changed
+    ))
+}
+
+/// The config settings the table warnings depend on, worked out once in [frontend] as they read
+/// the VPinball ini and the VPS database
+struct WarningSettings {
+    /// Warning kinds left out in the frontend
+    ignored: Vec<WarningKind>,
+    chd_requirements: ChdRequirements,
+    /// For the newer version warning in the table info
+    vps: VpsSession,
+}
+
+impl WarningSettings {
+    fn new(config: &ResolvedConfig) -> WarningSettings {
+        WarningSettings {
+            ignored: config.ignored_warnings(),
+            chd_requirements: config.chd_requirements(),
+            vps: VpsSession::new(config),
+        }
+    }
+}
+
+/// The VPS database of the config, read the first time a table info shows it and then kept for
+/// the rest of the session as it is several megabytes of json
+struct VpsSession {
+    path: Option<PathBuf>,
+    database: OnceCell<io::Result<VpsDatabase>>,
+}
+
+impl VpsSession {
+    fn new(config: &ResolvedConfig) -> VpsSession {
+        VpsSession {
+            path: config.vps_database.clone(),
+            database: OnceCell::new(),
+        }
+    }
+
+    /// `None` when no VPS database is configured
+    fn database(&self) -> Option<&io::Result<VpsDatabase>> {
+        let path = self.path.as_ref()?;
+        Some(self.database.get_or_init(|| VpsDatabase::read(path)))
+    }
+}
+
+fn table_warnings(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    table: &IndexedTable,
+) -> Vec<String> {
+    table.warnings_ignoring(
+        &warning_settings.ignored,
+        &config.installed_components,
+        &warning_settings.chd_requirements,
+    )
+}
+
+/// Sorts tables by their display name, optionally moving tables with warnings, the least complete
+/// tables and highly rated tables to the top.
+///
+/// Warnings are only computed once per table as they check the file system.
+fn sort_tables(
+    resolved_config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    tables: &mut [IndexedTable],
+    toggles: MenuToggles,
+) {
+    tables.sort_by_cached_key(|indexed| {
+        let warnings = if toggles.warnings_first
+            || toggles.most_warnings_first
+            || toggles.completeness_first
+        {
+            table_warnings(resolved_config, warning_settings, indexed)
+        } else {
+            Vec::new()
+        };
+        let clean = toggles.warnings_first && warnings.is_empty();
+        let warning_count = if toggles.most_warnings_first {
+            Reverse(warnings.len())
+        } else {
+            Reverse(0)
+        };
+        let score = if toggles.completeness_first {
+            table_completeness(resolved_config, indexed, &warnings).score
+        } else {
+            0
+        };
+        let rating = if toggles.rating_first {
+            Reverse(indexed.rating.unwrap_or(0))
+        } else {
+            Reverse(0)
+        };
+        let name = resolved_config
+            .display_template
+            .render(indexed)
+            .to_lowercase();
+        (clean, warning_count, score, rating, name)
+    });
+}
+
+fn table_completeness(
+    config: &ResolvedConfig,
+    table: &IndexedTable,
+    warnings: &[String],
+) -> Completeness {
+    completeness(table, &config.completeness_weights, warnings)
 }
 
 pub fn frontend(
     config: &ResolvedConfig,
-    mut vpx_files_with_tableinfo: Vec<IndexedTable>,
+    vpx_files_with_tableinfo: Vec<IndexedTable>,
     vpinball_executable: &Path,
+    dry_run: bool,
 ) {
+    theme::set_high_contrast(config.high_contrast);
+    show_changes_since_last_session(config, &vpx_files_with_tableinfo);
+    let warning_settings = &WarningSettings::new(config);
+    let mut vpx_files_with_tableinfo = ListedTables::from(vpx_files_with_tableinfo);
+    let mut toggles = MenuToggles {
+        dry_run,
+        show_paths: config.show_paths,
+        warning_counts: config.warning_counts,
+        ..Default::default()
+    };
     let mut main_selection_opt = None;
+    // the entry last picked in a submenu, selected again when a submenu opens
+    let mut submenu_entry: Option<MenuEntry> = None;
+    // the table last opened from the main list, to keep it selected when the list changes
+    let mut selected_table: Option<PathBuf> = None;
+    // unlike selected_table this survives using the menu entries, like changing the filter
+    let mut last_opened_table: Option<PathBuf> = None;
+    let mut filter_input = String::new();
+    let mut filter_history = FilterHistory::default();
+    // None on startup so the first list counts as a filter change
+    let mut listed_filter: Option<String> = None;
+    let mut presets = config.filter_presets.clone();
+    // subfolder of the tables folder to list, all tables when `None`
+    let mut active_collection: Option<PathBuf> = None;
+    // the table last opened in each collection, restored when switching back
+    let mut collection_selections: HashMap<Option<PathBuf>, PathBuf> = HashMap::new();
+    // shown once next to the prompt, like the table picked at random
+    let mut status: Option<String> = None;
+    let mut table_lines = TableLines::default();
+    // `vpxtool index --watch` can update the index while the frontend is open
+    let index_updates = watch_index_file(config);
     loop {
-        let tables: Vec<String> = vpx_files_with_tableinfo
+        wait_for_terminal_size();
+        // only the latest index matters when several came in while a menu was open
+        if let Some(update) = index_updates.try_iter().last() {
+            match update {
+                Ok(index) => {
+                    let mut tables = index.tables();
+                    sort_tables(config, warning_settings, &mut tables, toggles);
+                    if !same_tables(&tables, &vpx_files_with_tableinfo) {
+                        status = Some(format!("[index updated: {} tables]", tables.len()));
+                        *vpx_files_with_tableinfo = tables;
+                    }
+                }
+                Err(err) => {
+                    let msg = format!("Unable to reload tables: {:?}", err);
+                    prompt_error(&msg);
+                }
+            }
+        }
+        let filter = TableFilter::parse(&filter_input);
+        let active_preset = presets.iter().position(|preset| {
+            preset.filter.trim() == filter_input.trim()
+                && preset.warnings_first == toggles.warnings_first
+                && preset.most_warnings_first == toggles.most_warnings_first
+                && preset.rating_first == toggles.rating_first
+                && preset.completeness_first == toggles.completeness_first
+        });
+        // positions in vpx_files_with_tableinfo of the tables that are listed
+        let visible: Vec<usize> = vpx_files_with_tableinfo
             .iter()
-            .map(display_table_line_full)
+            .enumerate()
+            .filter(|(_, indexed)| indexed.matches(&filter))
+            .filter(|(_, indexed)| {
+                active_collection.is_none()
+                    || collection(&indexed.path, &config.tables_folder) == active_collection
+            })
+            .map(|(i, _)| i)
             .collect();
+        if listed_filter.as_ref() != Some(&filter_input) {
+            listed_filter = Some(filter_input.clone());
+            if config.auto_select_first && !visible.is_empty() {
+                main_selection_opt = Some(MENU_ENTRIES);
+            } else {
+                // the last opened table if it is still listed, otherwise the menu entry stays
+                // selected
+                selected_table = last_opened_table.clone();
+            }
+        }
+        if let Some(position) = selected_table.as_ref().and_then(|path| {
+            visible
+                .iter()
+                .position(|i| &vpx_files_with_tableinfo[*i].path == path)
+        }) {
+            main_selection_opt = Some(position + MENU_ENTRIES);
+        }
+        // the list might have shrunk after reindexing or filtering
+        main_selection_opt = main_selection_opt.filter(|s| *s < MENU_ENTRIES + visible.len());
+        let all_lines =
+            table_lines.update(config, warning_settings, &vpx_files_with_tableinfo, toggles);
+        let tables: Vec<String> = visible.iter().map(|i| all_lines[*i].clone()).collect();
 
-        let mut selections = vec![SEARCH.bold().to_string(), RECENT.bold().to_string()];
-        selections.extend(tables.clone());
+        let menu_state = MenuState {
+            toggles,
+            filter: &filter,
+            filter_input: &filter_input,
+            collection: active_collection.as_ref(),
+            preset: active_preset.map(|i| presets[i].name.as_str()),
+        };
+        let mut selections = MenuEntry::MAIN
+            .iter()
+            .map(|entry| theme::header(&format!("> {}", menu_state.label(*entry))).to_string())
+            .collect::<Vec<String>>();
+        // part of the last menu entry so moving the selection skips it
+        if let Some(header) = table_list_header(config, toggles) {
+            let last = selections.last_mut().unwrap();
+            last.push_str(&format!("\n{}{}", " ".repeat(ROW_PREFIX_WIDTH), header));
+        }
+        // blank lines below every table, part of the item so moving the selection skips them
+        let spacing = "\n".repeat(usize::from(config.row_spacing));
+        selections.extend(tables.iter().map(|line| format!("{}{}", line, spacing)));
 
-        main_selection_opt = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select a table")
+        let mut main_prompt = "Select a table".to_string();
+        if config.read_only {
+            main_prompt.push_str(&format!(" {}", theme::secondary("[read-only]")));
+        }
+        if toggles.dry_run {
+            main_prompt.push_str(&format!(" {}", theme::secondary("[dry-run]")));
+        }
+        if let Some(i) = active_preset {
+            let preset = format!("[preset: {}]", presets[i].name);
+            main_prompt.push_str(&format!(" {}", theme::secondary(&preset)));
+        }
+        if let Some(status) = status.take() {
+            main_prompt.push_str(&format!(" {}", theme::secondary(&status)));
+        }
+        for running in background_status() {
+            main_prompt.push_str(&format!(" {}", theme::secondary(&running)));
+        }
+        if !filter.is_empty() || active_collection.is_some() {
+            let shown = format!("[{}/{}]", visible.len(), vpx_files_with_tableinfo.len());
+            main_prompt.push_str(&format!(" {}", theme::secondary(&shown)));
+        }
+        let menu_theme = theme::menu_theme();
+        let mut main_select = Select::with_theme(&menu_theme)
+            .with_prompt(main_prompt)
             .default(main_selection_opt.unwrap_or(0))
-            .items(&selections[..])
-            .interact_opt()
-            .unwrap();
+            .items(&selections[..]);
+        let lines_per_table =
+            if toggles.two_line { 2 } else { 1 } + usize::from(config.row_spacing);
+        if lines_per_table > 1 {
+            // paging counts items, not lines, a page of multi-line tables has to fit the terminal
+            let (rows, _) = Term::stderr().size();
+            main_select = main_select.max_length((usize::from(rows) / lines_per_table).max(1));
+        }
+        main_selection_opt = main_select.interact_opt().unwrap();
 
         match main_selection_opt {
             Some(selection) => {
-                // search
+                selected_table = None;
+                let entry = match MenuEntry::MAIN.get(selection) {
+                    Some(MenuEntry::Settings) => choose_menu_entry(
+                        "Settings",
+                        &MenuEntry::SETTINGS,
+                        &menu_state,
+                        &mut submenu_entry,
+                    ),
+                    Some(MenuEntry::Tools) => choose_menu_entry(
+                        "Tools",
+                        &MenuEntry::TOOLS,
+                        &menu_state,
+                        &mut submenu_entry,
+                    ),
+                    Some(entry) => Some(*entry),
+                    None => {
+                        let index = visible[selection - MENU_ENTRIES];
 
-                match selection {
-                    SEARCH_INDEX => {
+                        let info = vpx_files_with_tableinfo[index].clone();
+                        selected_table = Some(info.path.clone());
+                        last_opened_table = selected_table.clone();
+                        let info_str =
+                            display_table_line_full(config, warning_settings, &info, None, toggles);
+                        if let Some(moved) = table_menu(
+                            config,
+                            warning_settings,
+                            &mut vpx_files_with_tableinfo,
+                            vpinball_executable,
+                            &info,
+                            &info_str,
+                            toggles,
+                        ) {
+                            selected_table = Some(moved);
+                            last_opened_table = selected_table.clone();
+                        }
+                        continue;
+                    }
+                };
+                match entry {
+                    Some(MenuEntry::Search) => {
+                        if visible.is_empty() {
+                            prompt("No tables to search");
+                            continue;
+                        }
+                        // the file name is searched as well when the line only shows the name
+                        let items = visible
+                            .iter()
+                            .zip(&tables)
+                            .map(|(i, line)| {
+                                match search_stem(line, &vpx_files_with_tableinfo[*i]) {
+                                    Some(stem) => format!("{} {}", line, theme::hint(&stem)),
+                                    None => line.clone(),
+                                }
+                            })
+                            .collect::<Vec<String>>();
                         // show a fuzzy search
-                        let selected = FuzzySelect::with_theme(&ColorfulTheme::default())
+                        let selected = FuzzySelect::with_theme(&theme::menu_theme())
                             .with_prompt("Search a table:")
-                            .items(&tables)
+                            .items(&items)
                             .interact_opt()
                             .unwrap();
 
                         if let Some(selected_index) = selected {
-                            let info = vpx_files_with_tableinfo
-                                .get(selected_index)
-                                .unwrap()
-                                .clone();
-                            let info_str = display_table_line_full(&info);
-                            table_menu(
+                            let info = vpx_files_with_tableinfo[visible[selected_index]].clone();
+                            let info_str = display_table_line_full(
+                                config,
+                                warning_settings,
+                                &info,
+                                None,
+                                toggles,
+                            );
+                            if let Some(moved) = table_menu(
                                 config,
+                                warning_settings,
                                 &mut vpx_files_with_tableinfo,
                                 vpinball_executable,
                                 &info,
                                 &info_str,
-                            );
+                                toggles,
+                            ) {
+                                last_opened_table = Some(moved);
+                            }
                         }
                     }
-                    RECENT_INDEX => {
-                        // take the last 10 most recent tables
-                        let mut recent: Vec<IndexedTable> = vpx_files_with_tableinfo.clone();
-                        recent.sort_by_key(|indexed| indexed.last_modified);
+                    Some(
+                        entry @ (MenuEntry::Recent
+                        | MenuEntry::RecentlyAdded
+                        | MenuEntry::MostPlayed),
+                    ) => {
+                        // take the last 50 most recent tables
+                        let mut recent: Vec<IndexedTable> = visible
+                            .iter()
+                            .map(|i| vpx_files_with_tableinfo[*i].clone())
+                            .collect();
+                        match entry {
+                            MenuEntry::Recent => {
+                                recent.sort_by_key(|indexed| indexed.last_modified)
+                            }
+                            MenuEntry::RecentlyAdded => {
+                                recent.sort_by_key(|indexed| indexed.added())
+                            }
+                            _ => {
+                                recent.retain(|indexed| indexed.play_time_secs.is_some());
+                                recent.sort_by_key(|indexed| indexed.play_time_secs);
+                            }
+                        }
                         let last_modified = recent.iter().rev().take(50).collect::<Vec<_>>();
                         let last_modified_str: Vec<String> = last_modified
                             .iter()
-                            .map(|indexed| display_table_line_full(indexed))
+                            .map(|indexed| {
+                                display_table_line_full(
+                                    config,
+                                    warning_settings,
+                                    indexed,
+                                    None,
+                                    toggles,
+                                )
+                            })
                             .collect();
 
-                        let selected = Select::with_theme(&ColorfulTheme::default())
+                        let selected = Select::with_theme(&theme::menu_theme())
                             .with_prompt("Select a table")
                             .items(&last_modified_str)
                             .default(0)
@@ -222,29 +925,338 @@ pub fn frontend(
 
                         if let Some(selected_index) = selected {
                             let info = last_modified.get(selected_index).unwrap();
-                            let info_str = display_table_line_full(info);
-                            table_menu(
+                            let info_str = display_table_line_full(
                                 config,
+                                warning_settings,
+                                info,
+                                None,
+                                toggles,
+                            );
+                            if let Some(moved) = table_menu(
+                                config,
+                                warning_settings,
                                 &mut vpx_files_with_tableinfo,
                                 vpinball_executable,
                                 info,
                                 &info_str,
-                            );
+                                toggles,
+                            ) {
+                                last_opened_table = Some(moved);
+                            }
                         }
                     }
-                    _ => {
-                        let index = selection - 2;
-
-                        let info = vpx_files_with_tableinfo.get(index).unwrap().clone();
-                        let info_str = display_table_line_full(&info);
-                        table_menu(
+                    Some(MenuEntry::Reindex) => match frontend_index(config, true, vec![]) {
+                        Ok(index) => {
+                            let mut tables = index.into_tables();
+                            sort_tables(config, warning_settings, &mut tables, toggles);
+                            *vpx_files_with_tableinfo = tables;
+                        }
+                        Err(err) => {
+                            let msg = format!("Unable to reload tables: {:?}", err);
+                            prompt_error(&msg);
+                        }
+                    },
+                    Some(MenuEntry::RescanRoms) => {
+                        match rescan_roms(config, &mut vpx_files_with_tableinfo) {
+                            Ok(msg) => {
+                                sort_tables(
+                                    config,
+                                    warning_settings,
+                                    &mut vpx_files_with_tableinfo,
+                                    toggles,
+                                );
+                                prompt(&msg);
+                            }
+                            Err(err) => {
+                                let msg = format!("Unable to rescan ROMs: {}", err);
+                                prompt_error(&msg);
+                            }
+                        }
+                    }
+                    Some(MenuEntry::DryRun) => {
+                        toggles.dry_run = !toggles.dry_run;
+                    }
+                    Some(MenuEntry::WarningsFirst) => {
+                        toggles.warnings_first = !toggles.warnings_first;
+                        sort_tables(
                             config,
+                            warning_settings,
                             &mut vpx_files_with_tableinfo,
-                            vpinball_executable,
-                            &info,
-                            &info_str,
+                            toggles,
+                        );
+                    }
+                    Some(MenuEntry::MostWarningsFirst) => {
+                        toggles.most_warnings_first = !toggles.most_warnings_first;
+                        sort_tables(
+                            config,
+                            warning_settings,
+                            &mut vpx_files_with_tableinfo,
+                            toggles,
+                        );
+                    }
+                    Some(MenuEntry::RatingFirst) => {
+                        toggles.rating_first = !toggles.rating_first;
+                        sort_tables(
+                            config,
+                            warning_settings,
+                            &mut vpx_files_with_tableinfo,
+                            toggles,
+                        );
+                    }
+                    Some(MenuEntry::CompletenessFirst) => {
+                        toggles.completeness_first = !toggles.completeness_first;
+                        sort_tables(
+                            config,
+                            warning_settings,
+                            &mut vpx_files_with_tableinfo,
+                            toggles,
+                        );
+                    }
+                    Some(MenuEntry::ShowPaths) => {
+                        toggles.show_paths = !toggles.show_paths;
+                    }
+                    Some(MenuEntry::WarningCounts) => {
+                        toggles.warning_counts = !toggles.warning_counts;
+                    }
+                    Some(MenuEntry::TwoLine) => {
+                        toggles.two_line = !toggles.two_line;
+                    }
+                    Some(MenuEntry::Collection) => {
+                        let collections = Index::new(vpx_files_with_tableinfo.clone())
+                            .collections(&config.tables_folder);
+                        if collections.is_empty() {
+                            prompt("No subfolders with tables in the tables folder");
+                            continue;
+                        }
+                        if let Some(picked) =
+                            choose_collection(&collections, active_collection.as_ref())
+                        {
+                            if let Some(opened) = last_opened_table.take() {
+                                collection_selections.insert(active_collection.clone(), opened);
+                            }
+                            active_collection = picked;
+                            // the list selection moves to the table opened last in the collection
+                            selected_table = collection_selections.get(&active_collection).cloned();
+                            last_opened_table = selected_table.clone();
+                        }
+                    }
+                    Some(MenuEntry::Filter) => {
+                        let help = theme::hint(
+                            "(+tag includes, -tag excludes, rom:name, author:name, a OR b, \"exact phrase\", empty clears, <↑/↓> earlier filters)",
+                        );
+                        filter_input = Input::<String>::with_theme(&theme::menu_theme())
+                            .with_prompt(format!("Filter {}", help))
+                            .with_initial_text(filter_input.trim())
+                            .history_with(&mut filter_history)
+                            .allow_empty(true)
+                            .interact_text()
+                            .unwrap();
+                    }
+                    Some(MenuEntry::MissingWheel) => {
+                        filter_input = toggle_filter_term(&filter_input, MISSING_WHEEL_TERM);
+                    }
+                    Some(MenuEntry::Launchable) => {
+                        filter_input = toggle_filter_term(&filter_input, LAUNCHABLE_TERM);
+                    }
+                    Some(MenuEntry::NextPreset) => {
+                        if presets.is_empty() {
+                            prompt("No presets yet, set up a filter and use Save preset");
+                            continue;
+                        }
+                        // after the last preset we go back to no filter
+                        let next = active_preset.map_or(0, |i| i + 1);
+                        let (next_filter, sorted) = match presets.get(next) {
+                            Some(preset) => (
+                                preset.filter.clone(),
+                                MenuToggles {
+                                    warnings_first: preset.warnings_first,
+                                    most_warnings_first: preset.most_warnings_first,
+                                    rating_first: preset.rating_first,
+                                    completeness_first: preset.completeness_first,
+                                    ..toggles
+                                },
+                            ),
+                            None => (
+                                String::new(),
+                                MenuToggles {
+                                    warnings_first: false,
+                                    most_warnings_first: false,
+                                    rating_first: false,
+                                    completeness_first: false,
+                                    ..toggles
+                                },
+                            ),
+                        };
+                        filter_input = next_filter;
+                        if toggles.warnings_first != sorted.warnings_first
+                            || toggles.most_warnings_first != sorted.most_warnings_first
+                            || toggles.rating_first != sorted.rating_first
+                            || toggles.completeness_first != sorted.completeness_first
+                        {
+                            toggles = sorted;
+                            sort_tables(
+                                config,
+                                warning_settings,
+                                &mut vpx_files_with_tableinfo,
+                                toggles,
+                            );
+                        }
+                    }
+                    Some(MenuEntry::Random) => {
+                        let candidates = Index::new(
+                            visible
+                                .iter()
+                                .map(|i| vpx_files_with_tableinfo[*i].clone())
+                                .collect(),
+                        );
+                        match candidates.random(&mut rand::rng()) {
+                            Some(picked) => {
+                                // the list selection moves to the picked table
+                                selected_table = Some(picked.path.clone());
+                                last_opened_table = selected_table.clone();
+                                status = Some(format!(
+                                    "[picked: {}]",
+                                    config.display_template.render(picked)
+                                ));
+                            }
+                            None => prompt("No tables to pick from"),
+                        }
+                    }
+                    Some(MenuEntry::Jump) => {
+                        if visible.is_empty() {
+                            prompt("No tables to jump to");
+                        } else if let Some(row) = choose_row_number(visible.len()) {
+                            let picked = &vpx_files_with_tableinfo[visible[row]];
+                            // the list selection moves to the table at that position
+                            selected_table = Some(picked.path.clone());
+                            last_opened_table = selected_table.clone();
+                            status = Some(format!(
+                                "[#{}: {}]",
+                                row + 1,
+                                config.display_template.render(picked)
+                            ));
+                        }
+                    }
+                    Some(entry @ (MenuEntry::NextWarning | MenuEntry::PreviousWarning)) => {
+                        let with_warnings = table_lines.with_warnings(
+                            config,
+                            warning_settings,
+                            &vpx_files_with_tableinfo,
+                        );
+                        // counted from the table the list keeps selected
+                        let current = last_opened_table.as_ref().and_then(|path| {
+                            visible
+                                .iter()
+                                .position(|i| &vpx_files_with_tableinfo[*i].path == path)
+                        });
+                        let forward = entry == MenuEntry::NextWarning;
+                        match next_with_warnings(&visible, with_warnings, current, forward) {
+                            Some(row) => {
+                                let picked = &vpx_files_with_tableinfo[visible[row]];
+                                // the list selection moves to the table
+                                selected_table = Some(picked.path.clone());
+                                last_opened_table = selected_table.clone();
+                                status = Some(format!(
+                                    "[#{}: {}]",
+                                    row + 1,
+                                    config.display_template.render(picked)
+                                ));
+                            }
+                            None => prompt("No listed tables with warnings"),
+                        }
+                    }
+                    Some(MenuEntry::WarningsReport) => {
+                        let listed = Index::new(
+                            visible
+                                .iter()
+                                .map(|i| vpx_files_with_tableinfo[*i].clone())
+                                .collect(),
                         );
+                        if let Some(picked) =
+                            choose_from_warnings_report(config, warning_settings, &listed)
+                        {
+                            // the list selection moves to the picked table
+                            selected_table = Some(picked);
+                            last_opened_table = selected_table.clone();
+                        }
+                    }
+                    Some(MenuEntry::UndoMove) => {
+                        // in read-only mode only shows what would move back
+                        let dry_run = toggles.dry_run || config.read_only;
+                        match undo_last_move(config, dry_run) {
+                            Ok(Some(moved_back)) => {
+                                match frontend_index(config, true, vec![]) {
+                                    Ok(index) => {
+                                        let mut tables = index.into_tables();
+                                        sort_tables(config, warning_settings, &mut tables, toggles);
+                                        vpx_files_with_tableinfo.clear();
+                                        vpx_files_with_tableinfo.extend(tables);
+                                    }
+                                    Err(err) => {
+                                        let msg = format!("Unable to reload tables: {:?}", err);
+                                        prompt_error(&msg);
+                                    }
+                                }
+                                status = Some(format!("[moved back: {}]", moved_back.display()));
+                                // the list selection moves to the table that moved back
+                                selected_table = Some(moved_back);
+                                last_opened_table = selected_table.clone();
+                            }
+                            Ok(None) => {}
+                            Err(err) => {
+                                let msg = format!("Unable to undo the last move: {}", err);
+                                prompt_error(&msg);
+                            }
+                        }
+                    }
+                    Some(MenuEntry::Batch) => {
+                        if visible.is_empty() {
+                            prompt("No tables to select");
+                            continue;
+                        }
+                        let mut selected = Vec::new();
+                        while let Some(chosen) = choose_batch_tables(&tables, &selected) {
+                            selected = chosen;
+                            if selected.is_empty() {
+                                break;
+                            }
+                            let picked = selected
+                                .iter()
+                                .map(|&position| {
+                                    vpx_files_with_tableinfo[visible[position]].clone()
+                                })
+                                .collect::<Vec<IndexedTable>>();
+                            match batch_menu(
+                                config,
+                                warning_settings,
+                                &mut vpx_files_with_tableinfo,
+                                &picked,
+                                toggles,
+                            ) {
+                                BatchOutcome::Done => break,
+                                BatchOutcome::Cancelled => {}
+                                BatchOutcome::InvertSelection => {
+                                    selected = inverted_selection(&selected, tables.len())
+                                }
+                                BatchOutcome::ClearSelection => selected.clear(),
+                            }
+                        }
                     }
+                    Some(MenuEntry::SavePreset) => match save_preset(&filter_input, toggles) {
+                        Ok(Some(preset)) => {
+                            match presets.iter_mut().find(|p| p.name == preset.name) {
+                                Some(existing) => *existing = preset,
+                                None => presets.push(preset),
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            let msg = format!("Unable to save preset: {}", err);
+                            prompt_error(&msg);
+                        }
+                    },
+                    // the submenus themselves are handled above
+                    Some(MenuEntry::Settings | MenuEntry::Tools) | None => {}
                 }
             }
             None => break,
@@ -252,36 +1264,145 @@ pub fn frontend(
     }
 }
 
+/// Whether both lists hold the same tables, in any order
+fn same_tables(tables: &[IndexedTable], other: &[IndexedTable]) -> bool {
+    fn by_path(tables: &[IndexedTable]) -> Vec<&IndexedTable> {
+        let mut sorted = tables.iter().collect::<Vec<&IndexedTable>>();
+        sorted.sort_by(|a, b| a.path.cmp(&b.path));
+        sorted
+    }
+    by_path(tables) == by_path(other)
+}
+
+/// Reads the index file again on a background thread whenever it changes and sends it to the
+/// returned receiver. The thread stops once the receiver is dropped.
+fn watch_index_file(config: &ResolvedConfig) -> Receiver<io::Result<TablesIndex>> {
+    let (sender, receiver) = mpsc::channel();
+    let index_path = config.tables_index_path.clone();
+    let index_folder = index_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let modified_time = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    thread::spawn(move || {
+        let mut watcher = FolderWatcher::new(&[&index_folder], false, INDEX_WATCH_SETTLE);
+        let mut modified = modified_time(&index_path);
+        loop {
+            if watcher.wait().is_err() {
+                // checking the modification time every time still works
+                thread::sleep(INDEX_WATCH_SETTLE);
+            }
+            let current = modified_time(&index_path);
+            if current == modified {
+                continue;
+            }
+            modified = current;
+            let update = match indexer::read_index(&index_path) {
+                Ok(Some(index)) => Ok(index),
+                Ok(None) => continue,
+                Err(e) => Err(e),
+            };
+            if sender.send(update).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
 fn table_menu(
     config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
     vpx_files_with_tableinfo: &mut Vec<IndexedTable>,
     vpinball_executable: &Path,
     info: &IndexedTable,
     info_str: &str,
-) {
+    toggles: MenuToggles,
+) -> Option<PathBuf> {
     let selected_path = &info.path;
     let mut exit = false;
     let mut option = None;
+    // where the table ended up if it was moved, so the table list can keep it selected
+    let mut moved_to = None;
     while !exit {
-        option = choose_table_option(info_str, option);
+        option = choose_table_option(
+            info_str,
+            option,
+            config.read_only,
+            toggles.dry_run,
+            info.archived,
+            info.staged,
+            config.resource_url.is_some(),
+        );
         match option {
-            Some(TableOption::Launch) => {
-                launch(selected_path, vpinball_executable, None);
-                exit = true;
-            }
-            Some(TableOption::LaunchFullscreen) => {
-                launch(selected_path, vpinball_executable, Some(true));
-                exit = true;
-            }
-            Some(TableOption::LaunchWindowed) => {
-                launch(selected_path, vpinball_executable, Some(false));
+            Some(
+                ref launch_option @ (TableOption::Launch
+                | TableOption::LaunchFullscreen
+                | TableOption::LaunchWindowed),
+            ) => {
+                let fullscreen = match launch_option {
+                    TableOption::LaunchFullscreen => Some(true),
+                    TableOption::LaunchWindowed => Some(false),
+                    _ => None,
+                };
+                let played = launch(
+                    selected_path,
+                    vpinball_executable,
+                    fullscreen,
+                    &config.launch_args_for(selected_path),
+                    config.launch_in_background,
+                    min_play_seconds(config, toggles),
+                );
+                // the play time is only in the index after reindexing the table
+                if played {
+                    match frontend_index(config, true, vec![selected_path.clone()]) {
+                        Ok(index) => {
+                            let mut tables = index.into_tables();
+                            sort_tables(config, warning_settings, &mut tables, toggles);
+                            vpx_files_with_tableinfo.clear();
+                            vpx_files_with_tableinfo.extend(tables);
+                        }
+                        Err(err) => {
+                            let msg = format!("Unable to reload tables: {:?}", err);
+                            prompt_error(&msg);
+                        }
+                    }
+                }
                 exit = true;
             }
+            Some(TableOption::OpenInEditor) => match open_in_table_editor(config, selected_path) {
+                Ok(()) => {
+                    // the table might have been saved
+                    match frontend_index(config, true, vec![selected_path.clone()]) {
+                        Ok(index) => {
+                            let mut tables = index.into_tables();
+                            sort_tables(config, warning_settings, &mut tables, toggles);
+                            vpx_files_with_tableinfo.clear();
+                            vpx_files_with_tableinfo.extend(tables);
+                        }
+                        Err(err) => {
+                            let msg = format!("Unable to reload tables: {:?}", err);
+                            prompt_error(&msg);
+                        }
+                    }
+                    exit = true;
+                }
+                Err(err) => {
+                    let msg = format!("Unable to open the table editor: {}", err);
+                    prompt_error(&msg);
+                }
+            },
             Some(TableOption::ForceReload) => {
                 match frontend_index(config, true, vec![selected_path.clone()]) {
                     Ok(index) => {
+                        let mut tables = index.into_tables();
+                        sort_tables(config, warning_settings, &mut tables, toggles);
                         vpx_files_with_tableinfo.clear();
-                        vpx_files_with_tableinfo.extend(index);
+                        vpx_files_with_tableinfo.extend(tables);
                         // exit to not have to
                         //  * check if the table is still in the list
                         //  * check if the info_str has changed
@@ -289,7 +1410,7 @@ fn table_menu(
                     }
                     Err(err) => {
                         let msg = format!("Unable to reload tables: {:?}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        prompt_error(&msg);
                     }
                 }
             }
@@ -298,7 +1419,7 @@ fn table_menu(
                 let result = if path.exists() {
                     open_editor(&path, Some(config))
                 } else {
-                    extractvbs(selected_path, None, false)
+                    extractvbs(selected_path, None, false, false)
                         .and_then(|_| open_editor(&path, Some(config)))
                 };
                 match result {
@@ -307,46 +1428,94 @@ fn table_menu(
                     }
                     Err(err) => {
                         let msg = format!("Unable to edit VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        prompt_error(&msg);
                     }
                 }
             }
-            Some(TableOption::ExtractVBS) => match extractvbs(selected_path, None, false) {
-                Ok(ExtractResult::Extracted(path)) => {
-                    prompt(&format!("VBS extracted to {}", path.to_string_lossy()));
-                }
-                Ok(ExtractResult::Existed(path)) => {
-                    let msg = format!("VBS already exists at {}", path.to_string_lossy());
-                    prompt(&msg.truecolor(255, 125, 0).to_string());
+            Some(TableOption::OpenResource) => {
+                if let Some(template) = &config.resource_url {
+                    let url = template.render_url(info);
+                    match open_with_default_app(&url) {
+                        Ok(()) => println!("Opened {}", url),
+                        Err(err) => {
+                            let msg = format!("Unable to open {}: {}", url, err);
+                            prompt_error(&msg);
+                        }
+                    }
                 }
+            }
+            Some(TableOption::CopyPreview) => match copy_preview_image(selected_path) {
+                Ok(msg) => prompt(&msg),
                 Err(err) => {
-                    let msg = format!("Unable to extract VBS: {}", err);
-                    prompt(&msg.truecolor(255, 125, 0).to_string());
+                    let msg = format!("Unable to copy preview image: {}", err);
+                    prompt_error(&msg);
                 }
             },
+            Some(TableOption::CopyJson) => match table_json(info, &JsonSettings::from(config)) {
+                // same schema as `info show --json`, pretty printed for pasting
+                Ok(json) => copy_with_confirmation(&format!("{:#}", json)),
+                Err(err) => {
+                    let msg = format!("Unable to serialize table info: {}", err);
+                    prompt_error(&msg);
+                }
+            },
+            Some(TableOption::ShowVBS) => {
+                if let Err(err) = script_view::show_script(selected_path) {
+                    let msg = format!("Unable to show VBS: {}", err);
+                    prompt_error(&msg);
+                }
+            }
+            Some(TableOption::ExtractVBS) => {
+                match extractvbs(selected_path, None, false, toggles.dry_run) {
+                    Ok(ExtractResult::Extracted(path)) if toggles.dry_run => {
+                        prompt(&format!("Would extract VBS to {}", path.to_string_lossy()));
+                    }
+                    Ok(ExtractResult::Extracted(path)) => {
+                        prompt(&format!("VBS extracted to {}", path.to_string_lossy()));
+                    }
+                    Ok(ExtractResult::Existed(path)) => {
+                        let msg = format!("VBS already exists at {}", path.to_string_lossy());
+                        prompt_error(&msg);
+                    }
+                    Err(err) => {
+                        let msg = format!("Unable to extract VBS: {}", err);
+                        prompt_error(&msg);
+                    }
+                }
+            }
             Some(TableOption::ShowVBSDiff) => match script_diff(selected_path) {
                 Ok(diff) => {
                     prompt(&diff);
                 }
                 Err(err) => {
                     let msg = format!("Unable to diff VBS: {}", err);
-                    prompt(&msg.truecolor(255, 125, 0).to_string());
+                    prompt_error(&msg);
                 }
             },
             Some(TableOption::PatchVBS) => {
-                let vbs_path = match extractvbs(selected_path, None, false) {
+                let vbs_path = match extractvbs(selected_path, None, false, toggles.dry_run) {
                     Ok(ExtractResult::Existed(path)) => path,
                     Ok(ExtractResult::Extracted(path)) => path,
                     Err(err) => {
                         let msg = format!("Unable to extract VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
-                        return;
+                        prompt_error(&msg);
+                        return moved_to;
                     }
                 };
-                match patch_vbs_file(&vbs_path) {
+                let result = extracted_script(selected_path, &vbs_path)
+                    .and_then(|script| patch_vbs_file(&vbs_path, script, toggles.dry_run));
+                match result {
                     Ok(applied) => {
                         if applied.is_empty() {
                             prompt("No patches applied.");
+                        } else if toggles.dry_run {
+                            applied.iter().for_each(|patch| {
+                                println!("Would apply patch: {}", patch);
+                            });
+                            prompt(&format!(
+                                "Would patch VBS file at {}",
+                                vbs_path.to_string_lossy()
+                            ));
                         } else {
                             applied.iter().for_each(|patch| {
                                 println!("Applied patch: {}", patch);
@@ -359,25 +1528,35 @@ fn table_menu(
                     }
                     Err(err) => {
                         let msg = format!("Unable to patch VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        prompt_error(&msg);
                     }
                 }
             }
             Some(TableOption::UnifyLineEndings) => {
                 let vbs_path = vbs_path_for(selected_path);
-                let vbs_path = match extractvbs(selected_path, Some(vbs_path), false) {
-                    Ok(ExtractResult::Existed(path)) => path,
-                    Ok(ExtractResult::Extracted(path)) => path,
-                    Err(err) => {
-                        let msg = format!("Unable to extract VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
-                        return;
-                    }
-                };
-                match unify_line_endings_vbs_file(&vbs_path) {
+                let vbs_path =
+                    match extractvbs(selected_path, Some(vbs_path), false, toggles.dry_run) {
+                        Ok(ExtractResult::Existed(path)) => path,
+                        Ok(ExtractResult::Extracted(path)) => path,
+                        Err(err) => {
+                            let msg = format!("Unable to extract VBS: {}", err);
+                            prompt_error(&msg);
+                            return moved_to;
+                        }
+                    };
+                let result = extracted_script(selected_path, &vbs_path).and_then(|script| {
+                    unify_line_endings_vbs_file(&vbs_path, script, toggles.dry_run)
+                });
+                match result {
                     Ok(NoChanges) => {
                         prompt("No changes applied as file has correct line endings");
                     }
+                    Ok(Unified) if toggles.dry_run => {
+                        prompt(&format!(
+                            "Would unify line endings in VBS file at {}",
+                            vbs_path.to_string_lossy()
+                        ));
+                    }
                     Ok(Unified) => {
                         prompt(&format!(
                             "Unified line endings in VBS file at {}",
@@ -386,42 +1565,58 @@ fn table_menu(
                     }
                     Err(err) => {
                         let msg = format!("Unable to patch VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        prompt_error(&msg);
                     }
                 }
             }
             Some(TableOption::CreateVBSPatch) => {
-                let vbs_path = selected_path.with_extension("vbs.original");
-                let original_path = match extractvbs(selected_path, Some(vbs_path), true) {
-                    Ok(ExtractResult::Existed(path)) => path,
-                    Ok(ExtractResult::Extracted(path)) => path,
-                    Err(err) => {
-                        let msg = format!("Unable to extract VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
-                        return;
-                    }
-                };
-                let vbs_path = vbs_path_for(selected_path);
-                let patch_path = vbs_path.with_extension("vbs.patch");
-
-                match run_diff(&original_path, &vbs_path, DiffColor::Never) {
-                    Ok(diff) => {
-                        let mut file = File::create(patch_path).unwrap();
-                        file.write_all(&diff).unwrap();
+                match create_vbs_patch(selected_path, toggles.dry_run) {
+                    Ok(patch_path) if toggles.dry_run => {
+                        prompt(&format!("Would write {}", patch_path.display()));
                     }
+                    Ok(patch_path) => prompt(&format!("Patch written to {}", patch_path.display())),
                     Err(err) => {
-                        let msg = format!("Unable to diff VBS: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        let msg = format!("Unable to create VBS patch: {}", err);
+                        prompt_error(&msg);
                     }
                 }
             }
             Some(TableOption::InfoShow) => match info_gather(selected_path) {
-                Ok(info) => {
-                    prompt(&info);
+                Ok(table_info) => {
+                    let similar = if config.info_fields.contains(&InfoField::Similar) {
+                        Index::new(vpx_files_with_tableinfo.clone())
+                            .similar(info, 3)
+                            .into_iter()
+                            .cloned()
+                            .collect::<Vec<IndexedTable>>()
+                    } else {
+                        vec![]
+                    };
+                    let info_str = info_text(config, warning_settings, info, &table_info, &similar);
+                    if let Some(jump_to) = show_info(&info_str, similar.len()) {
+                        let similar_table = &similar[jump_to];
+                        let similar_str = display_table_line_full(
+                            config,
+                            warning_settings,
+                            similar_table,
+                            None,
+                            toggles,
+                        );
+                        moved_to = table_menu(
+                            config,
+                            warning_settings,
+                            vpx_files_with_tableinfo,
+                            vpinball_executable,
+                            similar_table,
+                            &similar_str,
+                            toggles,
+                        );
+                        exit = true;
+                    }
                 }
                 Err(err) => {
                     let msg = format!("Unable to gather table info: {}", err);
-                    prompt(&msg.truecolor(255, 125, 0).to_string());
+                    prompt_error(&msg);
                 }
             },
             Some(TableOption::InfoEdit) => match info_edit(selected_path, Some(config)) {
@@ -447,7 +1642,7 @@ fn table_menu(
                     let nvram = nvram_for_rom(info);
                     if let Some(nvram) = nvram {
                         // open file in read/write mode
-                        match edit_dip_switches(nvram) {
+                        match edit_dip_switches(nvram, toggles.dry_run) {
                             Ok(_) => {
                                 // ok
                             }
@@ -463,18 +1658,196 @@ fn table_menu(
                     prompt("This table is not using used PinMAME");
                 }
             }
+            Some(TableOption::VerifyROM) => match info.rom_path() {
+                Some(rom_path) => match verify_rom_zip(rom_path) {
+                    Ok(RomIntegrity::Ok { entries }) => {
+                        prompt(&format!(
+                            "ROM {} OK ({} files)",
+                            rom_path.display(),
+                            entries
+                        ));
+                    }
+                    Ok(RomIntegrity::Corrupt(reason)) => {
+                        let msg = format!("ROM {} is corrupt: {}", rom_path.display(), reason);
+                        prompt_error(&msg);
+                    }
+                    Err(err) => {
+                        let msg = format!("Unable to verify ROM: {}", err);
+                        prompt_error(&msg);
+                    }
+                },
+                None if info.requires_pinmame => prompt("No ROM found for this table"),
+                None => prompt("This table is not using used PinMAME"),
+            },
             Some(TableOption::NVRAMClear) => {
-                clear_nvram(info);
+                clear_nvram(info, toggles.dry_run);
+            }
+            Some(TableOption::B2SAutoPositionDMD) => {
+                match auto_position_dmd(config, &info, toggles.dry_run) {
+                    Ok(msg) => {
+                        prompt(&msg);
+                    }
+                    Err(err) => {
+                        let msg = format!("Unable to auto-position DMD: {}", err);
+                        prompt_error(&msg);
+                    }
+                }
+            }
+            Some(TableOption::B2SOpenImage) => match open_backglass_image(info) {
+                Ok(image_path) => println!("Opened {}", image_path.display()),
+                Err(err) => {
+                    let msg = format!("Unable to open backglass image: {}", err);
+                    prompt_error(&msg);
+                }
+            },
+            Some(TableOption::ExtractArchive) if toggles.dry_run => {
+                match extracted_vpx_path(selected_path) {
+                    Ok(target) => prompt(&format!("Would extract table to {}", target.display())),
+                    Err(err) => {
+                        let msg = format!("Unable to extract table: {}", err);
+                        prompt_error(&msg);
+                    }
+                }
+            }
+            Some(TableOption::ExtractArchive) => match extract_archived_vpx(selected_path) {
+                Ok(extracted) => {
+                    match frontend_index(config, true, vec![]) {
+                        Ok(index) => {
+                            let mut tables = index.into_tables();
+                            sort_tables(config, warning_settings, &mut tables, toggles);
+                            vpx_files_with_tableinfo.clear();
+                            vpx_files_with_tableinfo.extend(tables);
+                        }
+                        Err(err) => {
+                            let msg = format!("Unable to reload tables: {:?}", err);
+                            prompt_error(&msg);
+                        }
+                    }
+                    prompt(&format!("Table extracted to {}", extracted.display()));
+                    exit = true;
+                }
+                Err(err) => {
+                    let msg = format!("Unable to extract table: {}", err);
+                    prompt_error(&msg);
+                }
+            },
+            Some(TableOption::SuppressWarnings) => {
+                match suppress_warnings(config, warning_settings, info, toggles.dry_run) {
+                    Ok(true) => match frontend_index(config, true, vec![selected_path.clone()]) {
+                        Ok(index) => {
+                            let mut tables = index.into_tables();
+                            sort_tables(config, warning_settings, &mut tables, toggles);
+                            vpx_files_with_tableinfo.clear();
+                            vpx_files_with_tableinfo.extend(tables);
+                            // the shown table info is outdated
+                            exit = true;
+                        }
+                        Err(err) => {
+                            let msg = format!("Unable to reload tables: {:?}", err);
+                            prompt_error(&msg);
+                        }
+                    },
+                    Ok(false) => {}
+                    Err(err) => {
+                        let msg = format!("Unable to suppress warnings: {}", err);
+                        prompt_error(&msg);
+                    }
+                }
             }
-            Some(TableOption::B2SAutoPositionDMD) => match auto_position_dmd(config, &info) {
-                Ok(msg) => {
-                    prompt(&msg);
+            Some(TableOption::EditTags) => match edit_tags(info, toggles.dry_run) {
+                Ok(true) => match frontend_index(config, true, vec![selected_path.clone()]) {
+                    Ok(index) => {
+                        let mut tables = index.into_tables();
+                        sort_tables(config, warning_settings, &mut tables, toggles);
+                        vpx_files_with_tableinfo.clear();
+                        vpx_files_with_tableinfo.extend(tables);
+                        // the shown table info is outdated
+                        exit = true;
+                    }
+                    Err(err) => {
+                        let msg = format!("Unable to reload tables: {:?}", err);
+                        prompt_error(&msg);
+                    }
+                },
+                Ok(false) => {}
+                Err(err) => {
+                    let msg = format!("Unable to edit tags: {}", err);
+                    prompt_error(&msg);
                 }
+            },
+            Some(TableOption::Rate) => match rate_table(info, toggles.dry_run) {
+                Ok(true) => match frontend_index(config, true, vec![selected_path.clone()]) {
+                    Ok(index) => {
+                        let mut tables = index.into_tables();
+                        sort_tables(config, warning_settings, &mut tables, toggles);
+                        vpx_files_with_tableinfo.clear();
+                        vpx_files_with_tableinfo.extend(tables);
+                        // the shown table info is outdated
+                        exit = true;
+                    }
+                    Err(err) => {
+                        let msg = format!("Unable to reload tables: {:?}", err);
+                        prompt_error(&msg);
+                    }
+                },
+                Ok(false) => {}
                 Err(err) => {
-                    let msg = format!("Unable to auto-position DMD: {}", err);
+                    let msg = format!("Unable to rate table: {}", err);
                     prompt_error(&msg);
                 }
             },
+            Some(TableOption::Promote) => {
+                match promote_table(config, selected_path, toggles.dry_run) {
+                    Ok(None) => {}
+                    Ok(Some(promoted)) => {
+                        match frontend_index(config, true, vec![]) {
+                            Ok(index) => {
+                                let mut tables = index.into_tables();
+                                sort_tables(config, warning_settings, &mut tables, toggles);
+                                vpx_files_with_tableinfo.clear();
+                                vpx_files_with_tableinfo.extend(tables);
+                            }
+                            Err(err) => {
+                                let msg = format!("Unable to reload tables: {:?}", err);
+                                prompt_error(&msg);
+                            }
+                        }
+                        prompt(&format!("Table moved to {}", promoted.display()));
+                        moved_to = Some(promoted);
+                        exit = true;
+                    }
+                    Err(err) => {
+                        let msg = format!("Unable to promote table: {}", err);
+                        prompt_error(&msg);
+                    }
+                }
+            }
+            Some(TableOption::MoveToFolder) => {
+                match move_to_folder(config, selected_path, toggles.dry_run) {
+                    Ok(Some(moved)) => {
+                        match frontend_index(config, true, vec![]) {
+                            Ok(index) => {
+                                let mut tables = index.into_tables();
+                                sort_tables(config, warning_settings, &mut tables, toggles);
+                                vpx_files_with_tableinfo.clear();
+                                vpx_files_with_tableinfo.extend(tables);
+                            }
+                            Err(err) => {
+                                let msg = format!("Unable to reload tables: {:?}", err);
+                                prompt_error(&msg);
+                            }
+                        }
+                        prompt(&format!("Table moved to {}", moved.display()));
+                        moved_to = Some(moved);
+                        exit = true;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        let msg = format!("Unable to move table: {}", err);
+                        prompt_error(&msg);
+                    }
+                }
+            }
             Some(TableOption::EditINI) => {
                 let path = ini_path_for(selected_path);
                 let result = if path.exists() {
@@ -488,19 +1861,61 @@ fn table_menu(
                 match result {
                     Ok(_) => {
                         println!("Launched editor for {}", path.display());
+                        // the ini settings are part of the index
+                        match frontend_index(config, true, vec![selected_path.clone()]) {
+                            Ok(index) => {
+                                let mut tables = index.into_tables();
+                                sort_tables(config, warning_settings, &mut tables, toggles);
+                                vpx_files_with_tableinfo.clear();
+                                vpx_files_with_tableinfo.extend(tables);
+                                // the shown table info is outdated
+                                exit = true;
+                            }
+                            Err(err) => {
+                                let msg = format!("Unable to reload tables: {:?}", err);
+                                prompt_error(&msg);
+                            }
+                        }
                     }
                     Err(err) => {
                         let msg = format!("Unable to edit INI: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        prompt_error(&msg);
                     }
                 }
             }
             None => exit = true,
         }
     }
+    moved_to
+}
+
+/// Writes the diff between the script in the table and the extracted script next to the table,
+/// returns the path of the patch file. With `dry_run` nothing is written.
+fn create_vbs_patch(vpx_path: &PathBuf, dry_run: bool) -> io::Result<PathBuf> {
+    let original_path = match extractvbs(
+        vpx_path,
+        Some(vpx_path.with_extension("vbs.original")),
+        true,
+        dry_run,
+    )? {
+        ExtractResult::Existed(path) | ExtractResult::Extracted(path) => path,
+    };
+    let vbs_path = vbs_path_for(vpx_path);
+    let patch_path = vbs_path.with_extension("vbs.patch");
+    if !dry_run {
+        let diff = run_diff(&original_path, &vbs_path, DiffColor::Never)?;
+        File::create(&patch_path)?.write_all(&diff)?;
+    }
+    Ok(patch_path)
 }
 
-fn auto_position_dmd(config: &ResolvedConfig, info: &&IndexedTable) -> Result<String, String> {
+/// Positions the DMD windows over the hole in the backglass DMD image, with `dry_run` the table
+/// ini is not written
+fn auto_position_dmd(
+    config: &ResolvedConfig,
+    info: &&IndexedTable,
+    dry_run: bool,
+) -> Result<String, String> {
     match &info.b2s_path {
         Some(b2s_path) => {
             // TODO move image reading parsing code to vpin
@@ -579,10 +1994,16 @@ fn auto_position_dmd(config: &ResolvedConfig, info: &&IndexedTable) -> Result<St
                                 hole.width(),
                                 hole.height(),
                             );
-                            table_config.write(&table_ini_path).unwrap();
+                            let updated = if dry_run {
+                                "would be updated"
+                            } else {
+                                table_config.write(&table_ini_path).unwrap();
+                                "updated"
+                            };
                             Ok(format!(
-                                "DMD window dimensions an position in {} updated to {}x{} at {},{}",
+                                "DMD window dimensions an position in {} {} to {}x{} at {},{}",
                                 table_ini_path.file_name().unwrap().to_string_lossy(),
+                                updated,
                                 hole.width(),
                                 hole.height(),
                                 dmd_x,
@@ -605,8 +2026,408 @@ fn auto_position_dmd(config: &ResolvedConfig, info: &&IndexedTable) -> Result<St
     }
 }
 
-fn edit_dip_switches(nvram: PathBuf) -> io::Result<()> {
-    let mut nvram_file = OpenOptions::new().read(true).write(true).open(nvram)?;
+/// Lets the user pick the warning kinds to suppress for the table.
+/// Returns true if the table notes were updated.
+fn suppress_warnings(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    info: &IndexedTable,
+    dry_run: bool,
+) -> io::Result<bool> {
+    let mut kinds: Vec<WarningKind> = Vec::new();
+    let current = info
+        .all_warnings(
+            &config.installed_components,
+            &warning_settings.chd_requirements,
+        )
+        .into_iter()
+        .map(|(kind, _)| kind)
+        .filter(|kind| !warning_settings.ignored.contains(kind));
+    for kind in current.chain(info.suppressed_warnings.iter().copied()) {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    if kinds.is_empty() {
+        prompt("This table has no warnings");
+        return Ok(false);
+    }
+    let items = kinds
+        .iter()
+        .map(|kind| kind.description())
+        .collect::<Vec<&str>>();
+    let defaults = kinds
+        .iter()
+        .map(|kind| info.suppressed_warnings.contains(kind))
+        .collect::<Vec<bool>>();
+    let help = theme::hint("(<␣> selects, <⏎> saves, <esc/q> exits)");
+    let selection = MultiSelect::with_theme(&theme::menu_theme())
+        .with_prompt(format!("Suppressed warnings {}", help))
+        .items(&items)
+        .defaults(&defaults)
+        .interact_opt()
+        .unwrap();
+    match selection {
+        Some(selection) => {
+            let mut notes = read_notes(&info.path)?;
+            notes.suppressed_warnings = selection.into_iter().map(|i| kinds[i]).collect();
+            save_notes(&info.path, &notes, dry_run)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Lets the user tick tables in the list starting from the `selected` positions, returns the
+/// positions in `tables` or `None` when the user exits
+fn choose_batch_tables(tables: &[String], selected: &[usize]) -> Option<Vec<usize>> {
+    let help = theme::hint("(<␣> selects, <a> toggles all, <⏎> continues, <esc/q> exits)");
+    let defaults = (0..tables.len())
+        .map(|position| selected.contains(&position))
+        .collect::<Vec<bool>>();
+    MultiSelect::with_theme(&theme::menu_theme())
+        .with_prompt(format!("Select tables {}", help))
+        .items(tables)
+        .defaults(&defaults)
+        .interact_opt()
+        .unwrap()
+}
+
+/// The position in `visible` of the next table with warnings after `current`, or before it when
+/// not `forward`, wrapping around at the ends. Without `current` it is the first table with
+/// warnings, or the last one when not `forward`.
+fn next_with_warnings(
+    visible: &[usize],
+    with_warnings: &[bool],
+    current: Option<usize>,
+    forward: bool,
+) -> Option<usize> {
+    let len = visible.len();
+    let start = match (current, forward) {
+        (Some(current), _) => current,
+        (None, true) => len.checked_sub(1)?,
+        (None, false) => 0,
+    };
+    (1..=len)
+        .map(|step| {
+            if forward {
+                (start + step) % len
+            } else {
+                (start + len - step % len) % len
+            }
+        })
+        .find(|&row| with_warnings[visible[row]])
+}
+
+/// The positions in `0..len` that are not in `selected`
+fn inverted_selection(selected: &[usize], len: usize) -> Vec<usize> {
+    (0..len)
+        .filter(|position| !selected.contains(position))
+        .collect()
+}
+
+/// What the batch menu leaves to the caller
+#[derive(Debug, PartialEq, Eq)]
+enum BatchOutcome {
+    /// The action ran and the tables were reloaded
+    Done,
+    /// Nothing changed, the selection is offered again
+    Cancelled,
+    InvertSelection,
+    ClearSelection,
+}
+
+/// Shows what a mutating batch action is about to do and asks to go ahead
+fn confirm_batch(action: BatchAction, paths: &[PathBuf], sizes: u64, target: &str) -> bool {
+    let summary = format!(
+        "{} on {} tables ({}) {}",
+        action.display(),
+        paths.len(),
+        format_size(sizes),
+        target
+    );
+    Confirm::with_theme(&theme::menu_theme())
+        .with_prompt(format!("{} - continue?", theme::header(&summary)))
+        .default(false)
+        .interact_opt()
+        .unwrap()
+        .unwrap_or(false)
+}
+
+/// Runs an action on all picked tables and reports what failed, tables in zip bundles are skipped
+fn batch_menu(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    vpx_files_with_tableinfo: &mut Vec<IndexedTable>,
+    picked: &[IndexedTable],
+    toggles: MenuToggles,
+) -> BatchOutcome {
+    let actions = BatchAction::ALL
+        .iter()
+        .filter(|action| !(config.read_only && action.is_mutating()))
+        .copied()
+        .collect::<Vec<BatchAction>>();
+    let mut items = actions
+        .iter()
+        .map(|action| action.display().to_string())
+        .collect::<Vec<String>>();
+    items.push("Invert selection".to_string());
+    items.push("Clear selection".to_string());
+    let Some(selection) = Select::with_theme(&theme::menu_theme())
+        .with_prompt(format!("{} tables selected", picked.len()))
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .unwrap()
+    else {
+        return BatchOutcome::Cancelled;
+    };
+    let Some(&action) = actions.get(selection) else {
+        return if selection == actions.len() {
+            BatchOutcome::InvertSelection
+        } else {
+            BatchOutcome::ClearSelection
+        };
+    };
+    let paths = picked
+        .iter()
+        .filter(|table| !table.archived)
+        .map(|table| table.path.clone())
+        .collect::<Vec<PathBuf>>();
+    let skipped = picked.len() - paths.len();
+    if paths.is_empty() {
+        prompt("Tables in zip bundles have to be extracted first");
+        return BatchOutcome::Cancelled;
+    }
+    let dry_run = toggles.dry_run;
+    // a force reload is not a change that dry-run mode holds back
+    let simulated = dry_run && action.is_mutating();
+    let sizes = picked
+        .iter()
+        .filter(|table| !table.archived)
+        .filter_map(|table| table.file_size)
+        .sum::<u64>();
+
+    let mut tags = Vec::new();
+    let mut target_folder = None;
+    let target = match action {
+        BatchAction::ExtractVBS => "next to each table".to_string(),
+        BatchAction::AddTags | BatchAction::RemoveTags => {
+            let input = Input::<String>::with_theme(&theme::menu_theme())
+                .with_prompt(format!(
+                    "{} {}",
+                    action.display(),
+                    theme::hint("(separated by spaces)")
+                ))
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+            tags = parse_tags(&input);
+            if tags.is_empty() {
+                return BatchOutcome::Cancelled;
+            }
+            format!("tags {}", tags.join(" "))
+        }
+        BatchAction::MoveToFolder => match choose_folder(config, &paths[0]) {
+            Ok(Some(folder)) => {
+                let target = format!("to {}", folder.display());
+                target_folder = Some(folder);
+                target
+            }
+            Ok(None) => return BatchOutcome::Cancelled,
+            Err(err) => {
+                prompt_error(&format!("Unable to choose a folder: {}", err));
+                return BatchOutcome::Cancelled;
+            }
+        },
+        BatchAction::ForceReload => String::new(),
+    };
+    if action.is_mutating() && !simulated && !confirm_batch(action, &paths, sizes, &target) {
+        prompt("Canceled, the selection is kept");
+        return BatchOutcome::Cancelled;
+    }
+
+    let mut changed: Vec<PathBuf> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+    let mut record = |path: &Path, result: io::Result<bool>| match result {
+        Ok(true) => changed.push(path.to_path_buf()),
+        Ok(false) => {}
+        Err(err) => failures.push(format!("{}: {}", path.display(), err)),
+    };
+    match action {
+        BatchAction::ExtractVBS => {
+            for path in &paths {
+                let result = extractvbs(path, None, false, dry_run)
+                    .map(|result| matches!(result, ExtractResult::Extracted(_)));
+                record(path, result);
+            }
+        }
+        BatchAction::AddTags | BatchAction::RemoveTags => {
+            for path in &paths {
+                record(
+                    path,
+                    change_tags(path, &tags, action == BatchAction::AddTags, dry_run),
+                );
+            }
+        }
+        BatchAction::MoveToFolder => {
+            let target_folder = target_folder.expect("folder chosen above");
+            let mut moved = Vec::new();
+            for path in &paths {
+                let result = staging::table_moves(
+                    path,
+                    &target_folder,
+                    &config.sidecar_extensions,
+                    config.include_hidden_sidecars,
+                )
+                .and_then(|moves| {
+                    if !dry_run {
+                        staging::move_files(&moves)?;
+                        moved.extend(moves);
+                    }
+                    Ok(true)
+                });
+                record(path, result);
+            }
+            if !moved.is_empty() {
+                // the whole batch is undone at once
+                remember_move(config, &moved);
+            }
+        }
+        BatchAction::ForceReload => changed = paths.clone(),
+    }
+
+    if !changed.is_empty() && !simulated {
+        // moved tables are found again under their new path by a full reindex
+        let force_reindex = if action == BatchAction::MoveToFolder {
+            vec![]
+        } else {
+            changed.clone()
+        };
+        match frontend_index(config, true, force_reindex) {
+            Ok(index) => {
+                let mut tables = index.into_tables();
+                sort_tables(config, warning_settings, &mut tables, toggles);
+                vpx_files_with_tableinfo.clear();
+                vpx_files_with_tableinfo.extend(tables);
+            }
+            Err(err) => {
+                let msg = format!("Unable to reload tables: {:?}", err);
+                prompt_error(&msg);
+            }
+        }
+    }
+    let would = if simulated { "would be " } else { "" };
+    let mut summary = format!(
+        "{}: {} tables {}changed",
+        action.display(),
+        changed.len(),
+        would
+    );
+    if skipped > 0 {
+        summary.push_str(&format!(", {} in zip bundles skipped", skipped));
+    }
+    if failures.is_empty() {
+        prompt(&summary);
+    } else {
+        summary.push_str(&format!(
+            ", {} failed:\n{}",
+            failures.len(),
+            failures.join("\n")
+        ));
+        prompt_error(&summary);
+    }
+    BatchOutcome::Done
+}
+
+/// Adds the tags to the notes of the table or removes them, returns whether the tags changed.
+/// With `dry_run` the notes are not written.
+fn change_tags(vpx_path: &Path, tags: &[String], add: bool, dry_run: bool) -> io::Result<bool> {
+    let mut notes = read_notes(vpx_path)?;
+    let new_tags = with_tags_changed(&notes.tags, tags, add);
+    if new_tags == notes.tags {
+        return Ok(false);
+    }
+    notes.tags = new_tags;
+    if !dry_run {
+        write_notes(vpx_path, &notes)?;
+    }
+    Ok(true)
+}
+
+/// Writes the notes of the table, in dry-run mode only shows which file would change.
+/// Returns whether the notes were written.
+fn save_notes(vpx_path: &Path, notes: &TableNotes, dry_run: bool) -> io::Result<bool> {
+    if dry_run {
+        prompt(&format!(
+            "Would update {}",
+            notes_path_for(vpx_path).display()
+        ));
+        return Ok(false);
+    }
+    write_notes(vpx_path, notes)?;
+    Ok(true)
+}
+
+/// The tags with the given tags appended or removed, keeping the order of the existing ones
+fn with_tags_changed(existing: &[String], tags: &[String], add: bool) -> Vec<String> {
+    let mut changed = existing
+        .iter()
+        .filter(|tag| add || !tags.contains(tag))
+        .cloned()
+        .collect::<Vec<String>>();
+    if add {
+        for tag in tags {
+            if !changed.contains(tag) {
+                changed.push(tag.clone());
+            }
+        }
+    }
+    changed
+}
+
+/// Returns true if the tags were changed
+fn edit_tags(info: &IndexedTable, dry_run: bool) -> io::Result<bool> {
+    let help = theme::hint("(separated by spaces, empty removes all)");
+    let input = Input::<String>::with_theme(&theme::menu_theme())
+        .with_prompt(format!("Tags {}", help))
+        .with_initial_text(info.tags.join(" "))
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+    let tags = parse_tags(&input);
+    if tags == info.tags {
+        return Ok(false);
+    }
+    let mut notes = read_notes(&info.path)?;
+    notes.tags = tags;
+    save_notes(&info.path, &notes, dry_run)
+}
+
+/// Asks for 1 to 5 stars or no rating, returns whether the rating changed
+fn rate_table(info: &IndexedTable, dry_run: bool) -> io::Result<bool> {
+    // index 0 clears the rating, the others are the number of stars
+    let mut items = vec!["No rating".to_string()];
+    items.extend((1..=MAX_RATING).map(format_rating));
+    let selection = Select::with_theme(&theme::menu_theme())
+        .with_prompt("Rating")
+        .default(info.rating.map_or(0, usize::from))
+        .items(&items)
+        .interact_opt()
+        .unwrap();
+    let Some(selection) = selection else {
+        return Ok(false);
+    };
+    let rating = u8::try_from(selection).ok().filter(|stars| *stars > 0);
+    if rating == info.rating {
+        return Ok(false);
+    }
+    let mut notes = read_notes(&info.path)?;
+    notes.rating = rating;
+    save_notes(&info.path, &notes, dry_run)
+}
+
+fn edit_dip_switches(nvram: PathBuf, dry_run: bool) -> io::Result<()> {
+    let mut nvram_file = OpenOptions::new().read(true).write(!dry_run).open(&nvram)?;
     let mut switches = get_all_dip_switches(&mut nvram_file)?;
 
     let items = switches
@@ -616,11 +2437,9 @@ fn edit_dip_switches(nvram: PathBuf) -> io::Result<()> {
 
     let defaults = switches.iter().map(|s| s.on).collect::<Vec<bool>>();
 
-    let help = "(<␣> selects, <⏎> saves, <esc/q> exits)"
-        .dimmed()
-        .to_string();
+    let help = theme::hint("(<␣> selects, <⏎> saves, <esc/q> exits)");
     let prompt_string = format!("Toggle switches {}", help);
-    let selection = MultiSelect::with_theme(&ColorfulTheme::default())
+    let selection = MultiSelect::with_theme(&theme::menu_theme())
         .with_prompt(prompt_string)
         .items(&items)
         .defaults(&defaults)
@@ -633,17 +2452,26 @@ fn edit_dip_switches(nvram: PathBuf) -> io::Result<()> {
             s.on = selection.contains(&i);
         });
 
-        set_dip_switches(&mut nvram_file, &switches)?;
-        prompt("DIP switches updated");
+        if dry_run {
+            prompt(&format!(
+                "Would update the DIP switches in {}",
+                nvram.display()
+            ));
+        } else {
+            set_dip_switches(&mut nvram_file, &switches)?;
+            prompt("DIP switches updated");
+        }
     }
     Ok(())
 }
 
-fn clear_nvram(info: &IndexedTable) {
+fn clear_nvram(info: &IndexedTable, dry_run: bool) {
     if info.requires_pinmame {
         let nvram_file = nvram_for_rom(info);
         if let Some(nvram_file) = nvram_file {
-            if nvram_file.exists() {
+            if nvram_file.exists() && dry_run {
+                prompt(&format!("Would remove NVRAM file {}", nvram_file.display()));
+            } else if nvram_file.exists() {
                 match confirm(
                     "This will remove the table NVRAM file and you will lose all settings / high scores!".to_string(),
                     "Are you sure?".to_string(),
@@ -655,7 +2483,7 @@ fn clear_nvram(info: &IndexedTable) {
                             }
                             Err(err) => {
                                 let msg = format!("Unable to remove NVRAM file: {}", err);
-                                prompt(&msg.truecolor(255, 125, 0).to_string());
+                                prompt_error(&msg);
                             }
                         }
                     }
@@ -664,7 +2492,7 @@ fn clear_nvram(info: &IndexedTable) {
                     }
                     Err(err) => {
                         let msg = format!("Error during confirmation: {}", err);
-                        prompt(&msg.truecolor(255, 125, 0).to_string());
+                        prompt_error(&msg);
                     }
                 }
             } else {
@@ -693,44 +2521,906 @@ fn nvram_for_rom(info: &IndexedTable) -> Option<PathBuf> {
     })
 }
 
-fn prompt(msg: &str) {
-    Input::<String>::new()
-        .with_prompt(format!("{} - Press enter to continue.", msg))
-        .default("".to_string())
-        .show_default(false)
-        .interact()
-        .unwrap();
-}
-
+/// Blocks with a single message until the terminal is at least [MIN_TERMINAL_SIZE]
+fn wait_for_terminal_size() {
+    let term = Term::stderr();
+    let mut shown_for = None;
+    // size_checked is None when not attached to a terminal
+    while let Some((rows, columns)) = term.size_checked() {
+        if columns >= MIN_TERMINAL_SIZE.0 && rows >= MIN_TERMINAL_SIZE.1 {
+            if shown_for.is_some() {
+                let _ = term.clear_screen();
+            }
+            return;
+        }
+        // only redraw on resize to avoid flickering
+        if shown_for != Some((rows, columns)) {
+            let _ = term.clear_screen();
+            let _ = term.write_line(&format!(
+                "Terminal too small - resize to at least {}x{}",
+                MIN_TERMINAL_SIZE.0, MIN_TERMINAL_SIZE.1
+            ));
+            shown_for = Some((rows, columns));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
+
+/// A summary of the tables added, removed and modified since the frontend was last opened, until
+/// any key is pressed. Nothing is shown on the first run or when nothing changed.
+fn show_changes_since_last_session(config: &ResolvedConfig, tables: &[IndexedTable]) {
+    let diff =
+        match index_diff::diff_with_snapshot(&config.last_session_snapshot_path(), tables.to_vec())
+        {
+            Ok(Some(diff)) => diff,
+            Ok(None) => return,
+            Err(err) => {
+                let msg = format!("Unable to compare with the last session: {}", err);
+                prompt_error(&msg);
+                return;
+            }
+        };
+    let modified = diff.modified().count();
+    if diff.added.is_empty() && diff.removed.is_empty() && modified == 0 {
+        return;
+    }
+    let added = diff.added.len();
+    let plural = if added == 1 { "" } else { "s" };
+    let summary = format!(
+        "Welcome back! Since last time {} table{} added, {} removed, {} modified",
+        added,
+        plural,
+        diff.removed.len(),
+        modified
+    );
+    let term = Term::stderr();
+    let _ = term.write_line(&format!(
+        "{} {}",
+        theme::header(&summary),
+        theme::hint("- Press any key to continue.")
+    ));
+    let _ = term.read_key();
+    let _ = term.clear_last_lines(1);
+}
+
+fn prompt(msg: &str) {
+    Input::<String>::new()
+        .with_prompt(format!("{} - Press enter to continue.", msg))
+        .default("".to_string())
+        .show_default(false)
+        .interact()
+        .unwrap();
+}
+
 fn prompt_error(msg: &str) {
-    prompt(&msg.truecolor(255, 125, 0).to_string());
+    prompt(&theme::error(msg).to_string());
+}
+
+/// The VPS id with the latest version and the IPDB number where known
+fn vps_summary(game: &VpsGame) -> String {
+    let mut summary = game.id.clone();
+    if let Some(latest) = game.latest_version() {
+        summary.push_str(&format!(", latest {}", latest));
+    }
+    if let Some(ipdb) = game.ipdb_number() {
+        summary.push_str(&format!(", IPDB {}", ipdb));
+    }
+    summary
 }
 
-fn choose_table_option(table_name: &str, selected: Option<TableOption>) -> Option<TableOption> {
+/// The table info with the configured fields in the configured order
+fn info_text(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    info: &IndexedTable,
+    table_info: &str,
+    similar: &[IndexedTable],
+) -> String {
+    config
+        .info_fields
+        .iter()
+        .map(|field| info_field_text(config, warning_settings, info, *field, table_info, similar))
+        .collect()
+}
+
+/// The lines of a single field of the table info, empty if there is nothing to show
+fn info_field_text(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    info: &IndexedTable,
+    field: InfoField,
+    table_info: &str,
+    similar: &[IndexedTable],
+) -> String {
+    let path_base = config.path_base();
+    let mut info_str = String::new();
+    match field {
+        InfoField::Table => info_str.push_str(table_info),
+        InfoField::Path => {
+            info_str.push_str(&format!(
+                "{:>18} {}\n",
+                theme::label("Path:"),
+                indexer::relative_path(&info.path, path_base).display()
+            ));
+        }
+        InfoField::Rom => {
+            if let Some(rom_path) = info.rom_path() {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("ROM:"),
+                    indexer::relative_path(rom_path, path_base).display()
+                ));
+            }
+        }
+        InfoField::Sidecars => {
+            let sidecars = indexer::find_sidecar_files(
+                &info.path,
+                &config.sidecar_extensions,
+                config.include_hidden_sidecars,
+            );
+            for sidecar in sidecars {
+                let file_name = sidecar.file_name().unwrap_or_default().to_string_lossy();
+                info_str.push_str(&format!("{:>18} {}\n", theme::label("Sidecar:"), file_name));
+            }
+        }
+        InfoField::Backups => {
+            let backups = indexer::find_backup_files(&info.path, &config.backup_patterns);
+            if !backups.is_empty() {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("Backups:"),
+                    backups.len()
+                ));
+            }
+        }
+        InfoField::Wheel => {
+            if !info.archived {
+                let wheel = match &info.wheel_path {
+                    Some(wheel_path) => indexer::relative_path(wheel_path, path_base)
+                        .display()
+                        .to_string(),
+                    None => theme::secondary("[missing]").to_string(),
+                };
+                info_str.push_str(&format!("{:>18} {}\n", theme::label("Wheel:"), wheel));
+            }
+        }
+        InfoField::HighScores => {
+            if info.requires_pinmame && !info.archived {
+                let saved = if info.nvram_path.is_some() {
+                    "yes"
+                } else {
+                    "no"
+                };
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("High scores saved:"),
+                    saved
+                ));
+            }
+        }
+        InfoField::Layout => {
+            if let Some(layout) = info.layout {
+                info_str.push_str(&format!(
+                    "{:>18} {}, playfield {:.0} x {:.0} (1:{:.2})\n",
+                    theme::label("Layout:"),
+                    layout.orientation(),
+                    layout.playfield_width,
+                    layout.playfield_height,
+                    layout.aspect_ratio()
+                ));
+            }
+        }
+        InfoField::Physics => {
+            if let Some(physics) = info.physics {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("Physics:"),
+                    physics.description()
+                ));
+            }
+        }
+        InfoField::Dmd => {
+            if let Some(dmd_kind) = info.dmd_kind {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("DMD:"),
+                    dmd_kind.description()
+                ));
+            }
+        }
+        InfoField::Ini => {
+            if let Some(settings) = &info.ini_settings {
+                let summary = table_ini::summary(settings);
+                let line = if summary.is_empty() {
+                    format!("{} settings", settings.len())
+                } else {
+                    summary
+                        .iter()
+                        .map(|(label, value)| format!("{} {}", label, value))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                };
+                info_str.push_str(&format!("{:>18} {}\n", theme::label("INI:"), line));
+            }
+        }
+        InfoField::Requires => {
+            if !info.required_components.is_empty() {
+                let missing = info.missing_components(&config.installed_components);
+                let components = info
+                    .required_components
+                    .iter()
+                    .map(|component| {
+                        if missing.contains(&component.as_str()) {
+                            format!("{} {}", component, theme::warning("[missing]"))
+                        } else {
+                            component.to_string()
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("Requires:"),
+                    components
+                ));
+            }
+        }
+        InfoField::FileVersion => {
+            // only known from the file name, like `v1.2` or `[5star]`
+            let patterns = &config.file_name_patterns;
+            if let Some(version) = patterns.version(&info.path) {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("File version:"),
+                    version
+                ));
+            }
+        }
+        InfoField::FileRating => {
+            let patterns = &config.file_name_patterns;
+            if let Some(rating) = patterns.rating(&info.path) {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("File rating:"),
+                    rating
+                ));
+            }
+        }
+        InfoField::Added => match info.created {
+            Some(created) => {
+                info_str.push_str(&format!("{:>18} {}\n", theme::label("Added:"), created))
+            }
+            None => info_str.push_str(&format!(
+                "{:>18} {}\n",
+                theme::label("Added:"),
+                "[creation time not available]"
+            )),
+        },
+        InfoField::Modified => {
+            info_str.push_str(&format!(
+                "{:>18} {}\n",
+                theme::label("Modified:"),
+                info.last_modified
+            ));
+        }
+        InfoField::Completeness => {
+            let warnings = table_warnings(config, warning_settings, info);
+            let completeness = table_completeness(config, info, &warnings);
+            let completeness_line = if completeness.missing.is_empty() {
+                format!("{}%", completeness.score)
+            } else {
+                format!(
+                    "{}% {}",
+                    completeness.score,
+                    theme::secondary(&format!("(missing {})", completeness.missing.join(", ")))
+                )
+            };
+            info_str.push_str(&format!(
+                "{:>18} {}\n",
+                theme::label("Completeness:"),
+                completeness_line
+            ));
+        }
+        InfoField::Warnings => {
+            let warnings = table_warnings(config, warning_settings, info);
+            for warning in warnings {
+                info_str.push_str(&format!("{:>18} {}\n", theme::warning("Warning:"), warning));
+            }
+        }
+        InfoField::Reference => {
+            if let Some(reference_path) = &config.reference_hashes {
+                let line = match ReferenceHashes::read(reference_path) {
+                    Ok(reference) => {
+                        let check = reference.check(info);
+                        match check.warning() {
+                            Some(warning) => {
+                                format!("{:>18} {}\n", theme::warning("Warning:"), warning)
+                            }
+                            None => {
+                                format!("{:>18} {}\n", theme::label("Reference:"), check.label())
+                            }
+                        }
+                    }
+                    Err(err) => format!("{:>18} {}\n", theme::warning("Warning:"), err),
+                };
+                info_str.push_str(&line);
+            }
+        }
+        InfoField::RomAudit => {
+            // opt-in as it reads the rom zip
+            if let Some(manifest_path) = &config.rom_manifest {
+                let audit = RomManifest::read(manifest_path).map(|manifest| manifest.audit(info));
+                let line = match audit {
+                    Ok(Some(audit)) => match audit.warning() {
+                        Some(warning) => {
+                            format!("{:>18} {}\n", theme::warning("Warning:"), warning)
+                        }
+                        None => format!("{:>18} {}\n", theme::label("ROM audit:"), audit.label()),
+                    },
+                    Ok(None) => String::new(),
+                    Err(err) => format!("{:>18} {}\n", theme::warning("Warning:"), err),
+                };
+                info_str.push_str(&line);
+            }
+        }
+        InfoField::Vps => {
+            if let Some(database) = warning_settings.vps.database() {
+                let line = match database {
+                    Ok(database) => {
+                        let status = database.status(info, &config.file_name_patterns);
+                        match (status.check.warning(), status.game) {
+                            (Some(warning), Some(game)) => format!(
+                                "{:>18} {} ({})\n",
+                                theme::warning("Warning:"),
+                                warning,
+                                vps_summary(game)
+                            ),
+                            (Some(warning), None) => {
+                                format!("{:>18} {}\n", theme::warning("Warning:"), warning)
+                            }
+                            (None, Some(game)) => {
+                                format!("{:>18} {}\n", theme::label("VPS:"), vps_summary(game))
+                            }
+                            (None, None) => String::new(),
+                        }
+                    }
+                    Err(err) => format!("{:>18} {}\n", theme::warning("Warning:"), err),
+                };
+                info_str.push_str(&line);
+            }
+        }
+        InfoField::Rating => {
+            if let Some(rating) = info.rating {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("Rating:"),
+                    format_rating(rating)
+                ));
+            }
+        }
+        InfoField::PlayTime => {
+            if let Some(play_time_secs) = info.play_time_secs {
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("Play time:"),
+                    format_play_time(play_time_secs)
+                ));
+            }
+        }
+        InfoField::Tags => {
+            if !info.tags.is_empty() {
+                let chips = info
+                    .tags
+                    .iter()
+                    .map(|tag| theme::secondary(&format!("[{}]", tag)).to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                info_str.push_str(&format!("{:>18} {}\n", theme::label("Tags:"), chips));
+            }
+        }
+        InfoField::Suppressed => {
+            if !info.suppressed_warnings.is_empty() {
+                let suppressed = info
+                    .suppressed_warnings
+                    .iter()
+                    .map(|kind| kind.description())
+                    .collect::<Vec<&str>>()
+                    .join(", ");
+                info_str.push_str(&format!(
+                    "{:>18} {}\n",
+                    theme::label("Suppressed:"),
+                    suppressed
+                ));
+            }
+        }
+        InfoField::RomIntegrity => {
+            if let Some(rom_path) = info.rom_path()
+                && let Ok(RomIntegrity::Corrupt(reason)) = verify_rom_zip(rom_path)
+            {
+                let warning = format!("Rom corrupt: {}", reason);
+                info_str.push_str(&format!("{:>18} {}\n", theme::warning("Warning:"), warning));
+            }
+        }
+        InfoField::Changelog => {
+            // the script of tables in zip bundles can't be read without extracting them
+            let changelog_lines = Some(&info.path)
+                .filter(|_| !info.archived)
+                .and_then(|path| script_view::script_lines(path).ok())
+                .and_then(|lines| changelog::changelog(&lines))
+                .unwrap_or_default();
+            for (i, line) in changelog_lines.iter().take(MAX_CHANGELOG_LINES).enumerate() {
+                let label = if i == 0 { "Changelog:" } else { "" };
+                info_str.push_str(&format!("{:>18} {}\n", theme::label(label), line));
+            }
+            if changelog_lines.len() > MAX_CHANGELOG_LINES {
+                let more = format!(
+                    "… {} more lines in the script",
+                    changelog_lines.len() - MAX_CHANGELOG_LINES
+                );
+                info_str.push_str(&format!("{:>18} {}\n", "", theme::secondary(&more)));
+            }
+        }
+        InfoField::Similar => {
+            for (i, table) in similar.iter().enumerate() {
+                let label = if i == 0 { "Similar:" } else { "" };
+                info_str.push_str(&format!(
+                    "{:>18} {}. {}\n",
+                    theme::label(label),
+                    i + 1,
+                    display_table_line(config, warning_settings, table)
+                ));
+            }
+        }
+    }
+    info_str
+}
+
+/// Shows the table info, returns the index of the similar table to jump to
+/// Shows the table info with options to copy it, returns the similar table to go to if any
+fn show_info(info_str: &str, similar_count: usize) -> Option<usize> {
+    const BACK: usize = 0;
+    const COPY_ALL: usize = 1;
+    const COPY_LINE: usize = 2;
+    let mut items = vec![
+        "Back".to_string(),
+        "Copy all".to_string(),
+        "Copy a line".to_string(),
+    ];
+    items.extend((1..=similar_count).map(|nr| format!("Go to similar table {}", nr)));
+    let mut default = BACK;
+    loop {
+        let selection = Select::with_theme(&theme::menu_theme())
+            .with_prompt(info_str)
+            .default(default)
+            .items(&items)
+            .interact_opt()
+            .unwrap();
+        match selection {
+            None | Some(BACK) => return None,
+            Some(COPY_ALL) => {
+                default = COPY_ALL;
+                copy_with_confirmation(info_str.trim_end());
+            }
+            Some(COPY_LINE) => {
+                default = COPY_LINE;
+                if let Some(line) = choose_info_line(info_str) {
+                    copy_with_confirmation(&line);
+                }
+            }
+            Some(selected) => return Some(selected - COPY_LINE - 1),
+        }
+    }
+}
+
+/// Lets the user pick a single line of the info, without the label padding
+fn choose_info_line(info_str: &str) -> Option<String> {
+    let lines = info_str
+        .lines()
+        .map(|line| line.trim_start().to_string())
+        .filter(|line| !console::strip_ansi_codes(line).trim().is_empty())
+        .collect::<Vec<String>>();
+    let selection = Select::with_theme(&theme::menu_theme())
+        .with_prompt("Line to copy")
+        .default(0)
+        .items(&lines)
+        .interact_opt()
+        .unwrap();
+    selection.map(|selected| lines[selected].clone())
+}
+
+fn copy_with_confirmation(text: &str) {
+    match clipboard::copy(text) {
+        Ok(()) => prompt("Copied to clipboard"),
+        Err(err) => prompt_error(&format!("Unable to copy to clipboard: {}", err)),
+    }
+}
+
+/// Copies the preview image of the table to the clipboard. Where the clipboard doesn't take
+/// images the image is written to the temp folder and its path is copied instead.
+fn copy_preview_image(vpx_path: &Path) -> io::Result<String> {
+    let Some(image) = thumbnails::table_thumbnail(vpx_path)? else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No suitable image in the table",
+        ));
+    };
+    if clipboard::copy_image(&image).is_ok() {
+        return Ok(format!(
+            "Copied {}x{} preview image to clipboard",
+            image.width(),
+            image.height()
+        ));
+    }
+    let file_stem = vpx_path.file_stem().unwrap_or_default().to_string_lossy();
+    let image_path = std::env::temp_dir().join(format!("{}.png", file_stem));
+    image.save(&image_path).map_err(|e| {
+        io::Error::other(format!("Failed to write {}: {}", image_path.display(), e))
+    })?;
+    clipboard::copy(&image_path.display().to_string())?;
+    Ok(format!(
+        "Clipboard does not take images, copied the path {}",
+        image_path.display()
+    ))
+}
+
+/// Moves the table from the staging folder to the tables folder, see [move_table]
+fn promote_table(
+    config: &ResolvedConfig,
+    vpx_path: &Path,
+    dry_run: bool,
+) -> io::Result<Option<PathBuf>> {
+    let Some(staging_folder) = &config.staging_folder else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No staging folder configured",
+        ));
+    };
+    let target = staging::promoted_path(vpx_path, staging_folder, &config.tables_folder)?;
+    let target_folder = target.parent().unwrap_or(&config.tables_folder);
+    move_table(config, vpx_path, target_folder, dry_run)
+}
+
+/// Asks for an entry of a main menu submenu, `None` if cancelled
+fn choose_menu_entry(
+    prompt: &str,
+    entries: &[MenuEntry],
+    state: &MenuState,
+    last_picked: &mut Option<MenuEntry>,
+) -> Option<MenuEntry> {
+    let items = entries
+        .iter()
+        .map(|entry| state.label(*entry))
+        .collect::<Vec<String>>();
+    let default = entries
+        .iter()
+        .position(|entry| Some(*entry) == *last_picked)
+        .unwrap_or(0);
+    let selection = Select::with_theme(&theme::menu_theme())
+        .with_prompt(prompt)
+        .default(default)
+        .items(&items)
+        .interact_opt()
+        .unwrap()?;
+    *last_picked = Some(entries[selection]);
+    *last_picked
+}
+
+/// Asks for the collection to list, `Some(None)` for all tables and `None` if cancelled
+fn choose_collection(collections: &[PathBuf], active: Option<&PathBuf>) -> Option<Option<PathBuf>> {
+    let mut items = vec!["All tables".to_string()];
+    items.extend(
+        collections
+            .iter()
+            .map(|folder| folder.display().to_string()),
+    );
+    let default = active
+        .and_then(|active| collections.iter().position(|folder| folder == active))
+        .map_or(0, |position| position + 1);
+    let selection = Select::with_theme(&theme::menu_theme())
+        .with_prompt("Collection")
+        .default(default)
+        .items(&items)
+        .interact_opt()
+        .unwrap()?;
+    Some(selection.checked_sub(1).map(|i| collections[i].clone()))
+}
+
+/// Lists the warnings of the tables grouped by table, one entry per table.
+/// Returns the path of the picked table, `None` if there are no warnings or nothing was picked.
+fn choose_from_warnings_report(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    tables: &Index,
+) -> Option<PathBuf> {
+    let report = tables.warnings_report(
+        &warning_settings.ignored,
+        &config.installed_components,
+        &warning_settings.chd_requirements,
+    );
+    if report.is_empty() {
+        prompt("No warnings for the listed tables");
+        return None;
+    }
+    let items = report
+        .iter()
+        .map(|table| {
+            let name = tables
+                .find_by_path(&table.path)
+                .map(|indexed| config.display_template.render(indexed))
+                .unwrap_or_else(|| table.path.display().to_string());
+            let warnings = table
+                .warnings
+                .iter()
+                .map(|warning| {
+                    format!(
+                        "{}{}",
+                        " ".repeat(2 * ROW_PREFIX_WIDTH),
+                        theme::warning(&warning.message)
+                    )
+                })
+                .collect::<Vec<String>>();
+            format!("{}\n{}", name, warnings.join("\n"))
+        })
+        .collect::<Vec<String>>();
+    let warnings = report
+        .iter()
+        .map(|table| table.warnings.len())
+        .sum::<usize>();
+    // paging counts items, the entries with the most warnings have to fit the terminal
+    let lines_per_item = 1 + report
+        .iter()
+        .map(|table| table.warnings.len())
+        .max()
+        .unwrap_or(0);
+    let (rows, _) = Term::stderr().size();
+    Select::with_theme(&theme::menu_theme())
+        .with_prompt(format!(
+            "{} warnings in {} tables {}",
+            warnings,
+            report.len(),
+            theme::hint("(enter jumps to the table, esc goes back)")
+        ))
+        .items(&items)
+        .max_length((usize::from(rows) / lines_per_item).max(1))
+        .default(0)
+        .interact_opt()
+        .unwrap()
+        .map(|selection| report[selection].path.clone())
+}
+
+/// Asks for a position in the list of `count` tables, counting from 1.
+/// Returns the index of the row, `None` if the input was left empty.
+fn choose_row_number(count: usize) -> Option<usize> {
+    let parse = |input: &str| {
+        input
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|number| (1..=count).contains(number))
+    };
+    let input = Input::<String>::with_theme(&theme::menu_theme())
+        .with_prompt(format!("Table number (1-{})", count))
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            if input.trim().is_empty() || parse(input).is_some() {
+                Ok(())
+            } else {
+                Err(format!("Enter a number from 1 to {}", count))
+            }
+        })
+        .interact_text()
+        .unwrap();
+    parse(&input).map(|number| number - 1)
+}
+
+/// Moves the table to a folder picked by the user, see [move_table]. `None` if cancelled.
+fn move_to_folder(
+    config: &ResolvedConfig,
+    vpx_path: &Path,
+    dry_run: bool,
+) -> io::Result<Option<PathBuf>> {
+    let Some(target_folder) = choose_folder(config, vpx_path)? else {
+        return Ok(None);
+    };
+    move_table(config, vpx_path, &target_folder, dry_run)
+}
+
+/// Moves the table and its sidecar files to `target_folder` and remembers the move for
+/// "Undo last move", in dry-run mode only shows what would move. Returns the new path of the
+/// table, `None` in dry-run mode.
+fn move_table(
+    config: &ResolvedConfig,
+    vpx_path: &Path,
+    target_folder: &Path,
+    dry_run: bool,
+) -> io::Result<Option<PathBuf>> {
+    let moves = staging::table_moves(
+        vpx_path,
+        target_folder,
+        &config.sidecar_extensions,
+        config.include_hidden_sidecars,
+    )?;
+    if dry_run {
+        prompt(&format!(
+            "Would move {} and {} sidecar file(s) to {}",
+            vpx_path.display(),
+            moves.len() - 1,
+            target_folder.display()
+        ));
+        return Ok(None);
+    }
+    staging::move_files(&moves)?;
+    remember_move(config, &moves);
+    Ok(Some(moves[0].1.clone()))
+}
+
+/// Remembers the moves for "Undo last move", a failure only loses the undo
+fn remember_move(config: &ResolvedConfig, moves: &[(PathBuf, PathBuf)]) {
+    let record_path = staging::last_move_path(&config.tables_index_path);
+    if let Err(err) = staging::record_last_move(&record_path, moves) {
+        prompt_error(&format!("Unable to remember the move for undo: {}", err));
+    }
+}
+
+/// Moves the files of the last move back, see [staging::undo_last_move]. Returns the path the
+/// table moved back to, `None` if nothing was moved back.
+fn undo_last_move(config: &ResolvedConfig, dry_run: bool) -> io::Result<Option<PathBuf>> {
+    let record_path = staging::last_move_path(&config.tables_index_path);
+    let moves = staging::undo_last_move(&record_path, dry_run)?;
+    let Some((table_path, _)) = moves
+        .iter()
+        .find(|(from, _)| from.extension().is_some_and(|ext| ext == "vpx"))
+        .or(moves.first())
+    else {
+        prompt("Nothing to undo");
+        return Ok(None);
+    };
+    if dry_run {
+        let would = moves
+            .iter()
+            .map(|(from, to)| format!("  {} -> {}", to.display(), from.display()))
+            .collect::<Vec<String>>()
+            .join("\n");
+        prompt(&format!("Would move back:\n{}", would));
+        return Ok(None);
+    }
+    Ok(Some(table_path.clone()))
+}
+
+/// Lets the user pick a folder in the tables folder or enter a new one, `None` if cancelled
+fn choose_folder(config: &ResolvedConfig, vpx_path: &Path) -> io::Result<Option<PathBuf>> {
+    const NEW_FOLDER: usize = 0;
+    const TABLES_FOLDER: usize = 1;
+    let tables_folder = &config.tables_folder;
+    let folders = staging::subfolders(tables_folder);
+    let current = vpx_path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(tables_folder).ok());
+    let mut items = vec!["[New folder]".to_string(), "./".to_string()];
+    items.extend(
+        folders
+            .iter()
+            .map(|folder| format!("{}/", folder.display())),
+    );
+    let default = current
+        .and_then(|current| folders.iter().position(|folder| folder == current))
+        .map_or(TABLES_FOLDER, |position| position + TABLES_FOLDER + 1);
+    match choose_from_list("Move to folder", &items, default) {
+        None => Ok(None),
+        Some(NEW_FOLDER) => {
+            let name: String = Input::with_theme(&theme::menu_theme())
+                .with_prompt("New folder")
+                .allow_empty(true)
+                .interact_text()
+                .map_err(io::Error::other)?;
+            if name.trim().is_empty() {
+                return Ok(None);
+            }
+            staging::folder_below(tables_folder, &name).map(Some)
+        }
+        Some(TABLES_FOLDER) => Ok(Some(tables_folder.clone())),
+        Some(selected) => Ok(Some(
+            tables_folder.join(&folders[selected - TABLES_FOLDER - 1]),
+        )),
+    }
+}
+
+/// A popup list with type-to-filter, returns the index of the picked item or `None` on escape
+fn choose_from_list(prompt: &str, items: &[String], default: usize) -> Option<usize> {
+    FuzzySelect::with_theme(&theme::menu_theme())
+        .with_prompt(prompt)
+        .default(default)
+        .items(items)
+        .interact_opt()
+        .unwrap()
+}
+
+fn choose_table_option(
+    table_name: &str,
+    selected: Option<TableOption>,
+    read_only: bool,
+    dry_run: bool,
+    archived: bool,
+    staged: bool,
+    resource_url: bool,
+) -> Option<TableOption> {
     let mut default = 0;
-    // iterate over table options
-    let selections = TableOption::ALL
+    // indices into TableOption::ALL of the options we show,
+    // archived tables can only be extracted
+    let visible = TableOption::ALL
         .iter()
         .enumerate()
-        .map(|(index, option)| {
+        .filter(|(_, option)| !(read_only && option.is_mutating()))
+        .filter(|(_, option)| !(dry_run && option.opens_editor()))
+        .filter(|(_, option)| archived == (**option == TableOption::ExtractArchive))
+        .filter(|(_, option)| resource_url || **option != TableOption::OpenResource)
+        .filter(|(_, option)| staged || **option != TableOption::Promote)
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    let selections = visible
+        .iter()
+        .enumerate()
+        .map(|(position, index)| {
+            let option = &TableOption::ALL[*index];
             if Some(option) == selected.as_ref() {
-                default = index;
+                default = position;
             }
             option.display()
         })
         .collect::<Vec<String>>();
-    let selection_opt = Select::with_theme(&ColorfulTheme::default())
+    let selection_opt = Select::with_theme(&theme::menu_theme())
         .with_prompt(table_name)
         .default(default)
         .items(&selections[..])
         .interact_opt()
         .unwrap();
 
-    selection_opt.and_then(TableOption::from_index)
+    selection_opt
+        .and_then(|position| visible.get(position).copied())
+        .and_then(TableOption::from_index)
+}
+
+/// The shortest session that counts towards the play time, `None` when play time is not recorded
+/// as nothing may be written
+fn min_play_seconds(config: &ResolvedConfig, toggles: MenuToggles) -> Option<u64> {
+    (!config.read_only && !toggles.dry_run).then_some(config.min_play_seconds)
 }
 
-fn launch(selected_path: &PathBuf, vpinball_executable: &Path, fullscreen: Option<bool>) {
+/// Adds the session to the play time in the table notes if it lasted at least `min_play_seconds`,
+/// returns whether it was added
+fn record_play_time(
+    vpx_path: &Path,
+    started: Instant,
+    min_play_seconds: Option<u64>,
+) -> io::Result<bool> {
+    let seconds = started.elapsed().as_secs();
+    match min_play_seconds {
+        Some(min_play_seconds) if seconds >= min_play_seconds => {
+            add_play_time(vpx_path, seconds)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Returns whether the play time of the table changed. Background launches are not timed, they
+/// are only seen exiting the next time the list is shown, see [background_status].
+fn launch(
+    selected_path: &PathBuf,
+    vpinball_executable: &Path,
+    fullscreen: Option<bool>,
+    extra_args: &[String],
+    background: bool,
+    min_play_seconds: Option<u64>,
+) -> bool {
     println!("{} {}", LAUNCH, selected_path.display());
+    if !extra_args.is_empty() {
+        println!("   with arguments {}", extra_args.join(" "));
+    }
 
     if !vpinball_executable.is_executable() {
         report_and_exit(format!(
@@ -739,7 +3429,29 @@ fn launch(selected_path: &PathBuf, vpinball_executable: &Path, fullscreen: Optio
         ));
     }
 
-    match launch_table(selected_path, vpinball_executable, fullscreen) {
+    if background {
+        match launch_table_background(selected_path, vpinball_executable, fullscreen, extra_args) {
+            Ok(child) => {
+                if let Ok(mut running) = RUNNING_TABLES.lock() {
+                    running.push((selected_path.clone(), child));
+                }
+            }
+            Err(e) => prompt_error(&format!("Unable to launch table: {}", e)),
+        }
+        return false;
+    }
+
+    let started = Instant::now();
+    let status = launch_table(selected_path, vpinball_executable, fullscreen, extra_args);
+    // a session that ended in a crash was still played
+    let played = match status {
+        Ok(_) => record_play_time(selected_path, started, min_play_seconds).unwrap_or_else(|e| {
+            prompt_error(&format!("Unable to save the play time: {}", e));
+            false
+        }),
+        Err(_) => false,
+    };
+    match status {
         Ok(status) => match status.code() {
             Some(0) => {
                 //println!("Table exited normally");
@@ -777,6 +3489,90 @@ fn launch(selected_path: &PathBuf, vpinball_executable: &Path, fullscreen: Optio
             }
         }
     }
+    played
+}
+
+/// Runs the configured table editor command with the table and waits for it to exit
+fn open_in_table_editor(config: &ResolvedConfig, selected_path: &Path) -> io::Result<()> {
+    let Some((program, args)) = config.table_editor_command.split_first() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No table editor configured, set table_editor_command in the config file",
+        ));
+    };
+    println!("{} {}", EDIT, selected_path.display());
+    let status = std::process::Command::new(program)
+        .args(args)
+        .arg(selected_path)
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", program, e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} exited with {}",
+            program, status
+        )))
+    }
+}
+
+/// Opens the url or file with the default application of the platform, the browser for urls and
+/// eg the image viewer for images
+fn open_with_default_app(target: impl AsRef<OsStr>) -> io::Result<()> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        // unlike `cmd /c start` this does not interpret the & in query strings
+        let mut cmd = std::process::Command::new("rundll32");
+        cmd.arg("url.dll,FileProtocolHandler");
+        cmd
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    let program = cmd.get_program().to_string_lossy().to_string();
+    cmd.arg(target)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", program, e)))?;
+    Ok(())
+}
+
+/// Writes the backglass image of the table B2S to the temp folder and opens it with the default
+/// image viewer. Returns the path of the written image.
+fn open_backglass_image(info: &IndexedTable) -> io::Result<PathBuf> {
+    let Some(b2s_path) = &info.b2s_path else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No B2S file for this table",
+        ));
+    };
+    let reader = BufReader::new(File::open(b2s_path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Unable to open B2S file {}: {}", b2s_path.display(), e),
+        )
+    })?);
+    let b2s = vpin::directb2s::read(reader)
+        .map_err(|e| io::Error::other(format!("Unable to read B2S file: {}", e)))?;
+    let Some(backglass_image) = b2s.images.backglass_image else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No backglass image in the B2S file",
+        ));
+    };
+    let decoded_data = base64::engine::general_purpose::STANDARD
+        .decode(strip_cr_lf(&backglass_image.value))
+        .map_err(|e| io::Error::other(format!("Unable to decode base64 data: {}", e)))?;
+    let extension = image::guess_format(&decoded_data)
+        .ok()
+        .and_then(|format| format.extensions_str().first())
+        .unwrap_or(&"png");
+    let file_stem = b2s_path.file_stem().unwrap_or_default().to_string_lossy();
+    let image_path = std::env::temp_dir().join(format!("{}.backglass.{}", file_stem, extension));
+    std::fs::write(&image_path, decoded_data)?;
+    open_with_default_app(&image_path)?;
+    Ok(image_path)
 }
 
 fn report_and_exit(msg: String) -> ! {
@@ -788,7 +3584,68 @@ fn launch_table(
     selected_path: &PathBuf,
     vpinball_executable: &Path,
     fullscreen: Option<bool>,
+    extra_args: &[String],
 ) -> io::Result<ExitStatus> {
+    let mut cmd = launch_command(selected_path, vpinball_executable, fullscreen, extra_args);
+    let mut child = cmd.spawn()?;
+    let result = child.wait()?;
+    Ok(result)
+}
+
+/// Starts Visual Pinball without handing it the terminal, the frontend keeps running
+fn launch_table_background(
+    selected_path: &PathBuf,
+    vpinball_executable: &Path,
+    fullscreen: Option<bool>,
+    extra_args: &[String],
+) -> io::Result<Child> {
+    launch_command(selected_path, vpinball_executable, fullscreen, extra_args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+}
+
+/// Shown next to the main prompt, the tables still running in the background and the ones that
+/// exited with an error since the last check. Tables that exited normally are dropped silently.
+fn background_status() -> Vec<String> {
+    let Ok(mut running) = RUNNING_TABLES.lock() else {
+        return Vec::new();
+    };
+    let name = |path: &Path| {
+        path.file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+    let mut status = Vec::new();
+    running.retain_mut(|(path, child)| match child.try_wait() {
+        Ok(None) => true,
+        Ok(Some(exit_status)) => {
+            if !exit_status.success() {
+                status.push(format!("[{} {}: {}]", CRASH, exit_status, name(path)));
+            }
+            false
+        }
+        Err(_) => false,
+    });
+    if !running.is_empty() {
+        let names = running
+            .iter()
+            .map(|(path, _)| name(path))
+            .collect::<Vec<String>>()
+            .join(", ");
+        status.push(format!("[running: {}]", names));
+    }
+    status
+}
+
+fn launch_command(
+    selected_path: &PathBuf,
+    vpinball_executable: &Path,
+    fullscreen: Option<bool>,
+    extra_args: &[String],
+) -> std::process::Command {
     // start process ./VPinballX_GL -play [table path]
     let mut cmd = std::process::Command::new(vpinball_executable);
     match fullscreen {
@@ -800,57 +3657,751 @@ fn launch_table(
         }
         None => (),
     }
+    cmd.args(extra_args);
     cmd.arg("-play");
     cmd.arg(selected_path);
-    let mut child = cmd.spawn()?;
-    let result = child.wait()?;
-    Ok(result)
+    cmd
 }
 
-fn display_table_line(table: &IndexedTable) -> String {
-    let file_name = table
-        .path
-        .file_stem()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string();
-    Some(table.table_info.table_name.to_owned())
-        .filter(|s| !s.clone().unwrap_or_default().is_empty())
-        .map(|s| {
-            format!(
-                "{} {}",
-                capitalize_first_letter(s.unwrap_or_default().as_str()),
-                format!("({})", file_name).dimmed()
-            )
+/// The name is colored by the state of the table, otherwise tinted by its manufacturer
+fn display_table_line(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    table: &IndexedTable,
+) -> String {
+    let state = Some(config)
+        .filter(|config| config.state_colors)
+        .and_then(|config| {
+            row_state(table, &config.row_state_priority, SystemTime::now(), || {
+                !table_warnings(config, warning_settings, table).is_empty()
+            })
+        });
+    let tint = Some(config)
+        .filter(|config| config.manufacturer_colors)
+        .and_then(|config| manufacturer_color(table, &config.manufacturer_palette));
+    config
+        .display_template
+        .render_styled(table, |token, value| match (token, state, tint) {
+            (Token::Name, Some(state), _) => theme::row_state(value, state).to_string(),
+            (Token::Name, None, Some(color)) => theme::tint(value, color).to_string(),
+            (Token::Name, None, None) => value.to_string(),
+            _ => theme::secondary(value).to_string(),
         })
-        .unwrap_or(file_name)
 }
 
-fn display_table_line_full(table: &IndexedTable) -> String {
-    let base = display_table_line(table);
+/// The filters entered in this session, newest first. Empty filters are not kept and entering a
+/// filter again moves it to the front.
+#[derive(Default)]
+struct FilterHistory(VecDeque<String>);
+
+impl History<String> for FilterHistory {
+    fn read(&self, pos: usize) -> Option<String> {
+        self.0.get(pos).cloned()
+    }
+
+    fn write(&mut self, filter: &String) {
+        let filter = filter.trim();
+        if filter.is_empty() {
+            return;
+        }
+        self.0.retain(|entry| entry != filter);
+        self.0.push_front(filter.to_string());
+        self.0.truncate(FILTER_HISTORY_SIZE);
+    }
+}
+
+/// The tables of the main menu with a version that goes up whenever they are borrowed mutably,
+/// so [TableLines] can tell they did not change without comparing them.
+#[derive(Default)]
+struct ListedTables {
+    tables: Vec<IndexedTable>,
+    version: u64,
+}
+
+impl From<Vec<IndexedTable>> for ListedTables {
+    fn from(tables: Vec<IndexedTable>) -> Self {
+        ListedTables { tables, version: 0 }
+    }
+}
+
+impl Deref for ListedTables {
+    type Target = Vec<IndexedTable>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tables
+    }
+}
+
+impl DerefMut for ListedTables {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.version += 1;
+        &mut self.tables
+    }
+}
+
+/// Rendered list lines of all tables, kept between passes through the main menu.
+///
+/// Everything is rendered again when the tables changed, see [ListedTables], when switching
+/// between names and paths, between the one and two-line view or the warning counts on and off,
+/// or when the terminal width the size column and the paths are fitted to changes.
+///
+/// With 5000 tables rendering all lines takes 20 to 40ms, comparing the tables to find the changed
+/// ones still took 10ms.
+#[derive(Default)]
+struct TableLines {
+    /// `None` until the first update
+    version: Option<u64>,
+    columns: Option<u16>,
+    show_paths: bool,
+    warning_counts: bool,
+    two_line: bool,
+    lines: Vec<String>,
+    /// Whether each table has warnings for the version of the tables, filled on first use
+    with_warnings: Option<(u64, Vec<bool>)>,
+}
+
+impl TableLines {
+    /// Whether each of the tables has warnings, only computed again when the tables changed as
+    /// the warnings check the file system
+    fn with_warnings(
+        &mut self,
+        config: &ResolvedConfig,
+        warning_settings: &WarningSettings,
+        tables: &ListedTables,
+    ) -> &[bool] {
+        if self
+            .with_warnings
+            .as_ref()
+            .is_none_or(|(version, _)| *version != tables.version)
+        {
+            let with_warnings = tables
+                .iter()
+                .map(|table| !table_warnings(config, warning_settings, table).is_empty())
+                .collect();
+            self.with_warnings = Some((tables.version, with_warnings));
+        }
+        self.with_warnings
+            .as_ref()
+            .map_or(&[], |(_, with_warnings)| with_warnings)
+    }
+
+    fn update(
+        &mut self,
+        config: &ResolvedConfig,
+        warning_settings: &WarningSettings,
+        tables: &ListedTables,
+        toggles: MenuToggles,
+    ) -> &[String] {
+        let MenuToggles {
+            show_paths,
+            warning_counts,
+            two_line,
+            ..
+        } = toggles;
+        let columns = (config.show_sizes || show_paths)
+            .then(|| Term::stderr().size_checked().map(|(_, columns)| columns))
+            .flatten();
+        if self.version == Some(tables.version)
+            && columns == self.columns
+            && show_paths == self.show_paths
+            && warning_counts == self.warning_counts
+            && two_line == self.two_line
+        {
+            return &self.lines;
+        }
+        // paths are unique already
+        let disambiguations = if show_paths {
+            HashMap::new()
+        } else {
+            name_disambiguations(tables, &config.display_template)
+        };
+        self.version = Some(tables.version);
+        self.columns = columns;
+        self.show_paths = show_paths;
+        self.warning_counts = warning_counts;
+        self.two_line = two_line;
+        self.lines = tables
+            .iter()
+            .map(|table| {
+                let disambiguation = disambiguations.get(&table.path).map(String::as_str);
+                if two_line {
+                    display_table_two_lines(
+                        config,
+                        warning_settings,
+                        table,
+                        disambiguation,
+                        toggles,
+                    )
+                } else {
+                    display_table_line_full(
+                        config,
+                        warning_settings,
+                        table,
+                        disambiguation,
+                        toggles,
+                    )
+                }
+            })
+            .collect();
+        &self.lines
+    }
+}
+
+/// The path relative to the tables or staging folder, cut off at the start to fit the row
+fn display_table_path(config: &ResolvedConfig, table: &IndexedTable, suffixes: &str) -> String {
+    let relative = [Some(&config.tables_folder), config.staging_folder.as_ref()]
+        .into_iter()
+        .flatten()
+        .find_map(|folder| table.path.strip_prefix(folder).ok())
+        .unwrap_or(&table.path);
+    let path = relative.display().to_string();
+    let Some((_, columns)) = Term::stderr().size_checked() else {
+        return path;
+    };
+    let size_column = if config.show_sizes {
+        1 + SIZE_COLUMN_WIDTH
+    } else {
+        0
+    };
+    let width = (columns as usize)
+        .saturating_sub(ROW_PREFIX_WIDTH + size_column + console::measure_text_width(suffixes));
+    truncate_left(&path, width)
+}
+
+fn display_table_line_full(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    table: &IndexedTable,
+    disambiguation: Option<&str>,
+    toggles: MenuToggles,
+) -> String {
+    let line = display_table_row(config, warning_settings, table, disambiguation, toggles);
+    if config.show_sizes {
+        with_size_column(line, table.file_size)
+    } else {
+        line
+    }
+}
+
+/// The name with the rom, backglass and wheel badges, the size goes on the status line
+fn display_table_two_lines(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    table: &IndexedTable,
+    disambiguation: Option<&str>,
+    toggles: MenuToggles,
+) -> String {
+    format!(
+        "{}\n{}{}",
+        display_table_row(config, warning_settings, table, disambiguation, toggles),
+        // below the name, past the selection marker of the first line
+        " ".repeat(2 * ROW_PREFIX_WIDTH),
+        display_table_status(config, warning_settings, table)
+    )
+}
+
+/// Compact summary of the rom, backglass, warnings and size, followed by the start of the
+/// description
+fn display_table_status(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    table: &IndexedTable,
+) -> String {
+    let mut parts = Vec::new();
+    match (&table.game_name, table.rom_path()) {
+        (Some(name), Some(_)) if table.missing_chd(&warning_settings.chd_requirements) => {
+            parts.push(theme::warning(&format!("ROM {} CHD missing", name)))
+        }
+        (Some(name), Some(_)) => parts.push(theme::secondary(&format!("ROM {}", name))),
+        (Some(name), None) if table.requires_pinmame => {
+            parts.push(theme::warning(&format!("ROM {} missing", name)))
+        }
+        (None, _) if table.requires_pinmame => parts.push(theme::warning("ROM unknown")),
+        _ => {}
+    }
+    match table.b2s_path {
+        Some(_) => parts.push(theme::secondary("B2S")),
+        None => parts.push(theme::secondary("no B2S")),
+    }
+    let warnings = table_warnings(config, warning_settings, table).len();
+    if warnings > 0 {
+        let plural = if warnings == 1 { "" } else { "s" };
+        parts.push(theme::warning(&format!("{} warning{}", warnings, plural)));
+    }
+    if let Some(size) = table.file_size {
+        parts.push(theme::secondary(&format_size(size)));
+    }
+    // the full description is in the table info
+    let description = [
+        &table.table_info.table_blurb,
+        &table.table_info.table_description,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|description| truncate_words(description, config.description_preview_length))
+    .find(|preview| !preview.is_empty());
+    if let Some(description) = description {
+        parts.push(theme::secondary(&description));
+    }
+    parts
+        .iter()
+        .map(|part| part.to_string())
+        .collect::<Vec<String>>()
+        .join(&theme::secondary(" · ").to_string())
+}
+
+/// The name or path with the badges, without the size column. The disambiguation follows the name
+/// of tables that share it with another table, see [name_disambiguations].
+fn display_table_row(
+    config: &ResolvedConfig,
+    warning_settings: &WarningSettings,
+    table: &IndexedTable,
+    disambiguation: Option<&str>,
+    toggles: MenuToggles,
+) -> String {
     let gamename_suffix = match &table.game_name {
         Some(name) => {
             let rom_found = table.rom_path().is_some();
             if rom_found {
-                format!(" - [{}]", name.dimmed())
-            } else if table.requires_pinmame {
-                format!(" - {} [{}]", Emoji("⚠️", "!"), &name)
-                    .yellow()
-                    .to_string()
+                format!(" - [{}]", theme::secondary(name))
+            } else if table.requires_pinmame
+                && !warning_settings.ignored.contains(&WarningKind::MissingRom)
+            {
+                theme::warning(&format!(" - {} [{}]", Emoji("⚠️", "!"), &name)).to_string()
             } else {
-                format!(" - [{}]", name.dimmed())
+                format!(" - [{}]", theme::secondary(name))
             }
         }
         None => "".to_string(),
     };
     let b2s_suffix = match &table.b2s_path {
-        Some(_) => " ▀".dimmed(),
+        Some(_) => theme::secondary(" ▀"),
+        None => "".into(),
+    };
+    let ini_suffix = match &table.ini_settings {
+        Some(_) => theme::secondary(" ≡"),
         None => "".into(),
     };
-    format!("{}{}{}", base, gamename_suffix, b2s_suffix)
+    let wheel_suffix = if table.wheel_path.is_none() && !table.archived {
+        theme::secondary(" ○")
+    } else {
+        "".into()
+    };
+    let archived_suffix = if table.archived {
+        theme::secondary(" [archived]")
+    } else {
+        "".into()
+    };
+    let staged_suffix = if table.staged {
+        theme::staged(" [staged]")
+    } else {
+        "".into()
+    };
+    let warnings = if toggles.warning_counts {
+        table_warnings(config, warning_settings, table).len()
+    } else {
+        0
+    };
+    let warnings_suffix = if warnings > 0 {
+        theme::warning(&format!(" ⚠{}", warnings))
+    } else {
+        "".into()
+    };
+    let suffixes = format!(
+        "{}{}{}{}{}{}{}",
+        gamename_suffix,
+        b2s_suffix,
+        ini_suffix,
+        wheel_suffix,
+        archived_suffix,
+        staged_suffix,
+        warnings_suffix
+    );
+    let base = if toggles.show_paths {
+        display_table_path(config, table, &suffixes)
+    } else {
+        match disambiguation {
+            Some(disambiguation) => format!(
+                "{} {}",
+                display_table_line(config, warning_settings, table),
+                theme::secondary(&format!("({})", disambiguation))
+            ),
+            None => display_table_line(config, warning_settings, table),
+        }
+    };
+    format!("{}{}", base, suffixes)
 }
 
-fn capitalize_first_letter(s: &str) -> String {
-    s[0..1].to_uppercase() + &s[1..]
+/// Labels for the columns of the table list, only when sizes, paths or warning counts are shown.
+/// The size has no column in the two-line view.
+fn table_list_header(config: &ResolvedConfig, toggles: MenuToggles) -> Option<String> {
+    let MenuToggles {
+        show_paths,
+        warning_counts,
+        two_line,
+        ..
+    } = toggles;
+    let size_column = config.show_sizes && !two_line;
+    if !size_column && !show_paths && !warning_counts {
+        return None;
+    }
+    let mut labels = vec![
+        if show_paths { "Path" } else { "Name" },
+        "- [ROM]",
+        "▀ B2S",
+        "≡ ini",
+        "○ no wheel",
+    ];
+    if warning_counts {
+        labels.push("⚠ warnings");
+    }
+    let header = theme::hint(&labels.join("  ")).to_string();
+    if size_column {
+        Some(with_right_column(header, "Size"))
+    } else {
+        Some(header)
+    }
+}
+
+/// Right aligns the size at the terminal edge, truncating the line to make room for it.
+/// Tables with an unknown size get an empty column.
+fn with_size_column(line: String, file_size: Option<u64>) -> String {
+    let size = file_size.map(format_size).unwrap_or_default();
+    with_right_column(line, &size)
+}
+
+fn with_right_column(line: String, column: &str) -> String {
+    let Some((_, columns)) = Term::stderr().size_checked() else {
+        // nothing to align to
+        return format!("{} {}", line, theme::secondary(column));
+    };
+    let width = (columns as usize).saturating_sub(ROW_PREFIX_WIDTH + 1 + SIZE_COLUMN_WIDTH);
+    format!(
+        "{} {}",
+        console::pad_str(&line, width, console::Alignment::Left, Some("…")),
+        theme::secondary(&format!("{:>width$}", column, width = SIZE_COLUMN_WIDTH))
+    )
+}
+
+/// Asks for a name and stores the current filter and sort order as preset in the config file
+fn save_preset(filter_input: &str, toggles: MenuToggles) -> io::Result<Option<FilterPreset>> {
+    let Some(config_path) = config::config_path() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No config file found",
+        ));
+    };
+    let name = Input::<String>::with_theme(&theme::menu_theme())
+        .with_prompt(format!(
+            "Preset name {}",
+            theme::hint("(an existing name replaces that preset)")
+        ))
+        .interact_text()
+        .unwrap();
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(None);
+    }
+    let preset = FilterPreset {
+        name: name.to_string(),
+        filter: filter_input.trim().to_string(),
+        warnings_first: toggles.warnings_first,
+        most_warnings_first: toggles.most_warnings_first,
+        rating_first: toggles.rating_first,
+        completeness_first: toggles.completeness_first,
+    };
+    config::save_filter_preset(&config_path, &preset)?;
+    Ok(Some(preset))
+}
+
+/// Adds the term to the filter input or removes it if it is already there
+fn toggle_filter_term(filter_input: &str, term: &str) -> String {
+    let terms = filter_input.split_whitespace().collect::<Vec<&str>>();
+    if terms.iter().any(|t| t.eq_ignore_ascii_case(term)) {
+        terms
+            .into_iter()
+            .filter(|t| !t.eq_ignore_ascii_case(term))
+            .collect::<Vec<&str>>()
+            .join(" ")
+    } else {
+        terms
+            .into_iter()
+            .chain([term])
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn table(path: &str, name: &str, fields: serde_json::Value) -> IndexedTable {
+        let mut table = json!({
+            "path": path,
+            "table_info": {
+                "table_name": name,
+                "properties": {}
+            },
+            "requires_pinmame": false,
+            "last_modified": "1970-01-01T00:00:00+00:00"
+        });
+        if let (Some(table), Some(fields)) = (table.as_object_mut(), fields.as_object()) {
+            table.extend(fields.clone());
+        }
+        serde_json::from_value(table).unwrap()
+    }
+
+    /// The list lines without colors, so the layout can be compared as plain text
+    fn plain_lines(lines: &[String]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| console::strip_ansi_codes(line).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_with_tags_changed() {
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<String>>();
+        let existing = tags(&["favorite", "kids"]);
+        assert_eq!(
+            with_tags_changed(&existing, &tags(&["wip", "kids"]), true),
+            tags(&["favorite", "kids", "wip"])
+        );
+        assert_eq!(
+            with_tags_changed(&existing, &tags(&["kids", "wip"]), false),
+            tags(&["favorite"])
+        );
+        assert_eq!(
+            with_tags_changed(&existing, &tags(&["wip"]), false),
+            existing
+        );
+    }
+
+    #[test]
+    fn test_filter_history() {
+        let mut history = FilterHistory::default();
+        for filter in ["+wip", " ", "rom:afm", "+wip "] {
+            history.write(&filter.to_string());
+        }
+        assert_eq!(history.read(0), Some("+wip".to_string()));
+        assert_eq!(history.read(1), Some("rom:afm".to_string()));
+        assert_eq!(history.read(2), None);
+        for i in 0..FILTER_HISTORY_SIZE {
+            history.write(&format!("author:{}", i));
+        }
+        assert_eq!(history.0.len(), FILTER_HISTORY_SIZE);
+    }
+
+    #[test]
+    fn test_next_with_warnings() {
+        // tables 1 and 4 have warnings, table 4 is filtered out
+        let with_warnings = [false, true, false, false, true];
+        let visible = [0, 1, 2, 3];
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, None, true),
+            Some(1)
+        );
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, None, false),
+            Some(1)
+        );
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, Some(1), true),
+            Some(1)
+        );
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, Some(3), true),
+            Some(1)
+        );
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, Some(0), false),
+            Some(1)
+        );
+        let visible = [0, 1, 4];
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, Some(1), true),
+            Some(2)
+        );
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, Some(1), false),
+            Some(2)
+        );
+        assert_eq!(
+            next_with_warnings(&visible, &with_warnings, Some(2), true),
+            Some(1)
+        );
+        assert_eq!(
+            next_with_warnings(&[0, 2], &with_warnings, None, true),
+            None
+        );
+        assert_eq!(next_with_warnings(&[], &with_warnings, None, false), None);
+    }
+
+    #[test]
+    fn test_inverted_selection() {
+        assert_eq!(inverted_selection(&[1, 3], 5), vec![0, 2, 4]);
+        assert_eq!(inverted_selection(&[], 2), vec![0, 1]);
+        assert_eq!(inverted_selection(&[0, 1], 2), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let config = config::default_resolved_config(
+            PathBuf::from("/vpinball/VPinballX"),
+            PathBuf::from("/tables"),
+            PathBuf::from("/tables/vpxtool_index.json"),
+        );
+        let warning_settings = WarningSettings::new(&config);
+        let tables = vec![
+            table(
+                "/tables/afm_v2.vpx",
+                "Attack from Mars",
+                json!({
+                    "game_name": "afm_113b",
+                    "rom_path": "/tables/pinmame/roms/afm_113b.zip",
+                    "requires_pinmame": true,
+                    "b2s_path": "/tables/afm_v2.directb2s",
+                    "wheel_path": "/tables/afm_v2.wheel.png",
+                    "file_size": 104857600
+                }),
+            ),
+            table("/tables/em/Fireball.vpx", "Fireball", json!({})),
+            table(
+                "/tables/ss/Fireball.vpx",
+                "Fireball",
+                json!({"staged": true, "ini_settings": {}}),
+            ),
+        ];
+        let tables = ListedTables::from(tables);
+        let mut table_lines = TableLines::default();
+        let one_line = MenuToggles::default();
+        let two_line = MenuToggles {
+            two_line: true,
+            ..MenuToggles::default()
+        };
+
+        assert_eq!(
+            plain_lines(table_lines.update(&config, &warning_settings, &tables, one_line)),
+            vec![
+                "Attack from Mars (afm_v2) - [afm_113b] ▀",
+                "Fireball (Fireball) (em/Fireball.vpx) ○",
+                "Fireball (Fireball) (ss/Fireball.vpx) ≡ ○ [staged]",
+            ]
+        );
+        assert_eq!(
+            plain_lines(table_lines.update(&config, &warning_settings, &tables, two_line)),
+            vec![
+                "Attack from Mars (afm_v2) - [afm_113b] ▀\n    ROM afm_113b · B2S · 100.0 MB",
+                "Fireball (Fireball) (em/Fireball.vpx) ○\n    no B2S",
+                "Fireball (Fireball) (ss/Fireball.vpx) ≡ ○ [staged]\n    no B2S",
+            ]
+        );
+
+        let mut broken = ListedTables::from(vec![table(
+            "/tables/tz.vpx",
+            "Twilight Zone",
+            json!({"referenced_files": ["music/theme.mp3", "sounds.wav"]}),
+        )]);
+        let with_counts = MenuToggles {
+            warning_counts: true,
+            ..MenuToggles::default()
+        };
+        assert_eq!(
+            plain_lines(table_lines.update(&config, &warning_settings, &broken, with_counts)),
+            vec!["Twilight Zone (tz) ○ ⚠2"]
+        );
+        assert_eq!(
+            plain_lines(table_lines.update(&config, &warning_settings, &broken, one_line)),
+            vec!["Twilight Zone (tz) ○"]
+        );
+
+        broken[0].table_info.table_name = Some("Twilight Zone 2".to_string());
+        assert_eq!(
+            plain_lines(table_lines.update(&config, &warning_settings, &broken, one_line)),
+            vec!["Twilight Zone 2 (tz) ○"]
+        );
+    }
+
+    #[test]
+    fn test_table_list_header() {
+        let mut config = config::default_resolved_config(
+            PathBuf::from("/vpinball/VPinballX"),
+            PathBuf::from("/tables"),
+            PathBuf::from("/tables/vpxtool_index.json"),
+        );
+        let header = |config: &ResolvedConfig, show_paths, warning_counts, two_line| {
+            let toggles = MenuToggles {
+                show_paths,
+                warning_counts,
+                two_line,
+                ..MenuToggles::default()
+            };
+            table_list_header(config, toggles)
+                .map(|header| console::strip_ansi_codes(&header).to_string())
+        };
+        assert_eq!(header(&config, false, false, false), None);
+        assert_eq!(
+            header(&config, true, false, false),
+            Some("Path  - [ROM]  ▀ B2S  ≡ ini  ○ no wheel".to_string())
+        );
+        assert_eq!(
+            header(&config, false, true, true),
+            Some("Name  - [ROM]  ▀ B2S  ≡ ini  ○ no wheel  ⚠ warnings".to_string())
+        );
+        config.show_sizes = true;
+        // the two-line view has the size on the status line
+        assert_eq!(header(&config, false, false, true), None);
+        let with_size = header(&config, false, false, false).unwrap();
+        assert!(with_size.starts_with("Name  - [ROM]"));
+        assert!(with_size.ends_with("Size"));
+    }
+
+    #[test]
+    fn test_sort_tables_most_warnings_first() {
+        let config = config::default_resolved_config(
+            PathBuf::from("/vpinball/VPinballX"),
+            PathBuf::from("/tables"),
+            PathBuf::from("/tables/vpxtool_index.json"),
+        );
+        let mut tables = vec![
+            table("/tables/a.vpx", "Addams Family", json!({})),
+            table(
+                "/tables/b.vpx",
+                "Black Knight",
+                json!({"referenced_files": ["music/theme.mp3"]}),
+            ),
+            table(
+                "/tables/c.vpx",
+                "Cyclone",
+                json!({"referenced_files": ["music/theme.mp3", "sounds.wav"]}),
+            ),
+            table(
+                "/tables/d.vpx",
+                "Diner",
+                json!({"referenced_files": ["music/theme.mp3"]}),
+            ),
+        ];
+        let toggles = MenuToggles {
+            most_warnings_first: true,
+            ..Default::default()
+        };
+        sort_tables(
+            &config,
+            &WarningSettings::new(&config),
+            &mut tables,
+            toggles,
+        );
+        let names = tables
+            .iter()
+            .map(|table| table.table_info.table_name.clone().unwrap_or_default())
+            .collect::<Vec<String>>();
+        // by name within the same number of warnings
+        assert_eq!(
+            names,
+            vec!["Cyclone", "Black Knight", "Diner", "Addams Family"]
+        );
+    }
 }
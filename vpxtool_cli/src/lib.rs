@@ -4,6 +4,7 @@
 // https://clig.dev/#arguments-and-flags
 // https://clig.dev/#subcommands
 //
+use crate::fixprint::Verbosity;
 use crate::patcher::patch_vbs_file;
 use base64::Engine;
 use clap::builder::Str;
@@ -11,8 +12,10 @@ use clap::{Arg, ArgMatches, Command, arg};
 use colored::Colorize;
 use console::Emoji;
 use git_version::git_version;
-use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use indicatif::{HumanBytes, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use pinmame_nvram::dips::get_all_dip_switches;
+use script_encoding::{extracted_script, extractvbs, importvbs, read_script, script_encoding};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::ffi::OsStr;
 use std::fmt::Display;
@@ -21,18 +24,39 @@ use std::io;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{ExitCode, exit};
+use std::time::{Duration, Instant, SystemTime};
 use vpin::directb2s::read;
 use vpin::vpx;
 use vpin::vpx::jsonmodel::{game_data_to_json, info_to_json};
-use vpin::vpx::{ExtractResult, VerifyResult, expanded, extractvbs, importvbs, verify};
+use vpin::vpx::{ExtractResult, VerifyResult, expanded, verify};
+use vpxtool_shared::completeness::{CompletenessWeights, completeness};
 use vpxtool_shared::config::{ResolvedConfig, SetupConfigResult};
-use vpxtool_shared::indexer::{IndexError, Progress};
-use vpxtool_shared::{config, indexer};
+use vpxtool_shared::inbox::{self, InboxOptions, InboxOutcome};
+use vpxtool_shared::index::{Index, TableFilter, TableWarnings};
+use vpxtool_shared::index_diff::{self, IndexDiff};
+use vpxtool_shared::indexer::{
+    IndexError, IndexOptions, IndexParallelism, IndexProgress, IndexedTable, TABLE_JSON_EXTENSION,
+    TablesIndex,
+};
+use vpxtool_shared::reference::{HashCheck, ReferenceHashes};
+use vpxtool_shared::rom_audit::{RomAudit, RomManifest};
+use vpxtool_shared::stats::{CollectionStats, collection_stats};
+use vpxtool_shared::tags_export::{self, TagsExportFormat};
+use vpxtool_shared::vps::{VPS_DATABASE_URL, VpsCheck, VpsDatabase};
+use vpxtool_shared::watch::FolderWatcher;
+use vpxtool_shared::{config, indexer, staging};
 
 mod backglass;
+mod clipboard;
+mod doctor;
 pub mod fixprint;
 mod frontend;
 pub mod patcher;
+mod roundtrip;
+mod script_encoding;
+mod script_view;
+mod theme;
+mod thumbnails;
 
 // see https://github.com/fusion-engineering/rust-git-version/issues/21
 const GIT_VERSION: &str = git_version!(args = ["--tags", "--always", "--dirty=-modified"]);
@@ -41,6 +65,7 @@ const OK: Emoji = Emoji("✅", "[launch]");
 const NOK: Emoji = Emoji("❌", "[crash]");
 
 const CMD_FRONTEND: &str = "frontend";
+const CMD_PREWARM: &str = "prewarm";
 const CMD_DIFF: &str = "diff";
 const CMD_EXTRACT: &str = "extract";
 const CMD_ASSEMBLE: &str = "assemble";
@@ -48,9 +73,25 @@ const CMD_EXTRACT_VBS: &str = "extractvbs";
 const CMD_IMPORT_VBS: &str = "importvbs";
 const CMD_PATCH: &str = "patch";
 const CMD_VERIFY: &str = "verify";
+const CMD_REPAIR_CHECKSUM: &str = "repair-checksum";
+const CMD_VERIFY_ROUNDTRIP: &str = "verify-roundtrip";
 const CMD_NEW: &str = "new";
 
 const CMD_LS: &str = "ls";
+const CMD_STATS: &str = "stats";
+const CMD_WARNINGS: &str = "warnings";
+const CMD_INDEX_DIFF: &str = "index-diff";
+const CMD_THUMBNAILS: &str = "thumbnails";
+const CMD_HASHES: &str = "hashes";
+const CMD_ROM_AUDIT: &str = "rom-audit";
+const CMD_CHECK_UPDATES: &str = "check-updates";
+const CMD_DOCTOR: &str = "doctor";
+const CMD_INBOX: &str = "inbox";
+const CMD_EXPORT_SIDECARS: &str = "export-sidecars";
+const CMD_ORPHANED_SIDECARS: &str = "orphaned-sidecars";
+const CMD_RENAME_SIDECARS: &str = "rename-sidecars";
+const CMD_EXPORT_TAGS: &str = "export-tags";
+const CMD_IMPORT_TAGS: &str = "import-tags";
 
 const CMD_CONFIG: &str = "config";
 const CMD_CONFIG_SETUP: &str = "setup";
@@ -62,6 +103,7 @@ const CMD_CONFIG_EDIT: &str = "edit";
 const CMD_SCRIPT: &str = "script";
 const CMD_SCRIPT_SHOW: &str = "show";
 const CMD_SCRIPT_EXTRACT: &str = "extract";
+const CMD_SCRIPT_EXTRACT_ALL: &str = "extract-all";
 const CMD_SCRIPT_IMPORT: &str = "import";
 const CMD_SCRIPT_PATCH: &str = "patch";
 const CMD_SCRIPT_EDIT: &str = "edit";
@@ -93,39 +135,61 @@ impl ProgressBarProgress {
     fn new(pb: ProgressBar) -> Self {
         Self { pb }
     }
-}
 
-impl Progress for ProgressBarProgress {
-    fn set_length(&self, len: u64) {
-        if len > 0 {
+    /// Callback for [indexer::index_folder]
+    fn update(&self, progress: IndexProgress) {
+        if progress.position == 1 && fixprint::verbosity() >= Verbosity::Normal {
+            // only start drawing once there is actually something to index
             self.pb.set_draw_target(ProgressDrawTarget::stdout());
-        } else {
-            self.pb.set_draw_target(ProgressDrawTarget::hidden());
+            self.pb.set_length(progress.total);
+        }
+        self.pb.set_position(progress.position);
+        if let Some(error) = progress.error {
+            self.pb.suspend(|| print_index_error(error));
         }
-        self.pb.set_length(len)
-    }
-    fn set_position(&self, pos: u64) {
-        self.pb.set_position(pos)
     }
     fn finish_and_clear(&self) {
         self.pb.finish_and_clear()
     }
 }
 
+/// Tables that could not be indexed go to stderr to keep the output on stdout parseable
+fn print_index_error(error: &IndexError) {
+    // nothing left to report to if stderr is gone
+    let _ = eprintln!("{}", error);
+}
+
+/// Callback for [indexer::index_folder] for commands that don't show progress
+fn report_index_errors(progress: IndexProgress) {
+    if let Some(error) = progress.error {
+        print_index_error(error);
+    }
+}
+
 pub fn run() -> io::Result<ExitCode> {
     let command = build_command();
     let matches = command.get_matches_from(wild::args());
+    if let Some(config_path) = matches.get_one::<String>("config") {
+        config::set_config_path_override(PathBuf::from(config_path));
+    }
+    if matches.get_flag("QUIET") {
+        fixprint::set_verbosity(Verbosity::Quiet);
+    } else if matches.get_flag("VERBOSE") {
+        fixprint::set_verbosity(Verbosity::Verbose);
+    }
+    fixprint::init_logger();
     handle_command(matches)
 }
 
 fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
+    let dry_run = matches.get_flag("DRY_RUN");
     match matches.subcommand() {
         Some((CMD_INFO, sub_matches)) => match sub_matches.subcommand() {
             Some((CMD_INFO_SHOW, sub_matches)) => {
                 let path = sub_matches.get_one::<String>("VPXPATH").map(|s| s.as_str());
                 let path = path.unwrap_or("");
                 let expanded_path = expand_path_exists(path)?;
-                println!("showing info for {}", expanded_path.display())?;
+                status!("showing info for {}", expanded_path.display())?;
                 let info = info_gather(&expanded_path)?;
                 println!("{}", info)?;
                 Ok(ExitCode::SUCCESS)
@@ -134,14 +198,14 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 let path = sub_matches.get_one::<String>("VPXPATH").map(|s| s.as_str());
                 let path = path.unwrap_or("");
                 let expanded_path = expand_path_exists(path)?;
-                println!("extracting info for {}", expanded_path.display())?;
-                info_extract(&expanded_path)
+                status!("extracting info for {}", expanded_path.display())?;
+                info_extract(&expanded_path, dry_run)
             }
             Some((CMD_INFO_IMPORT, sub_matches)) => {
                 let path = sub_matches.get_one::<String>("VPXPATH").map(|s| s.as_str());
                 let path = path.unwrap_or("");
                 let expanded_path = expand_path_exists(path)?;
-                println!("importing info for {}", expanded_path.display())?;
+                status!("importing info for {}", expanded_path.display())?;
                 info_import(&expanded_path)
             }
             Some((CMD_INFO_EDIT, sub_matches)) => {
@@ -150,7 +214,15 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 let expanded_path = expand_path_exists(path)?;
                 let loaded_config = config::load_config()?;
                 let config = loaded_config.as_ref().map(|c| &c.1);
-                println!("editing info for {}", expanded_path.display())?;
+                status!("editing info for {}", expanded_path.display())?;
+                if dry_run {
+                    let info_file_path = expanded_path.with_extension("info.json");
+                    if !info_file_path.exists() {
+                        println!("Would create {}", info_file_path.display())?;
+                    }
+                    println!("Would open editor for {}", info_file_path.display())?;
+                    return Ok(ExitCode::SUCCESS);
+                }
                 info_edit(&expanded_path, config)?;
                 Ok(ExitCode::SUCCESS)
             }
@@ -158,7 +230,7 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 let path = sub_matches.get_one::<String>("VPXPATH").map(|s| s.as_str());
                 let path = path.unwrap_or("");
                 let expanded_path = expand_path_exists(path)?;
-                println!("diffing info for {}", expanded_path.display())?;
+                status!("diffing info for {}", expanded_path.display())?;
                 let diff = info_diff(&expanded_path)?;
                 println!("{}", diff)?;
                 Ok(ExitCode::SUCCESS)
@@ -183,63 +255,78 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
             }
         }
         Some((CMD_FRONTEND, _sub_matches)) => {
-            let (config_path, config) = config::load_or_setup_config()?;
-            println!("Using config file {}", config_path.display())?;
-            println!(
-                "Using global pinmame rom folder {}",
-                config.global_pinmame_rom_folder().display()
-            )?;
-            match frontend::frontend_index(&config, true, vec![]) {
-                Ok(tables) if tables.is_empty() => {
-                    let warning =
-                        format!("No tables found in {}", config.tables_folder.display()).red();
-                    eprintln!("{}", warning)?;
-                    Ok(ExitCode::FAILURE)
-                }
-                Ok(vpx_files_with_tableinfo) => {
-                    let vpinball_executable = &config.vpx_executable;
-                    frontend::frontend(&config, vpx_files_with_tableinfo, vpinball_executable);
-                    Ok(ExitCode::SUCCESS)
-                }
-                Err(IndexError::FolderDoesNotExist(path)) => {
-                    let warning = format!(
-                        "Configured tables folder does not exist: {}",
-                        path.display()
-                    )
-                    .red();
-                    eprintln!("{}", warning)?;
-                    Ok(ExitCode::FAILURE)
-                }
-                Err(IndexError::IoError(e)) => {
-                    let warning = format!("Error running frontend: {}", e).red();
-                    eprintln!("{}", warning)?;
-                    Ok(ExitCode::FAILURE)
+            let config = match config::load_config()? {
+                Some((config_path, config)) => {
+                    status!("Using {}", config_source(config_path.as_deref()))?;
+                    config
                 }
-            }
+                None => current_dir_config()?,
+            };
+            run_frontend(&config, dry_run)
+        }
+        // without arguments we list the tables in the current directory when there is no config
+        None if config::load_config()?.is_none() => {
+            let config = current_dir_config()?;
+            run_frontend(&config, dry_run)
+        }
+        None => {
+            build_command().print_help()?;
+            Ok(ExitCode::from(2))
         }
         Some(("index", sub_matches)) => {
             let recursive = sub_matches.get_flag("RECURSIVE");
             let path = sub_matches
                 .get_one::<String>("VPXROOTPATH")
                 .map(|s| s.as_str());
+            let filter = sub_matches
+                .get_one::<String>("FILTER")
+                .map(|query| TableFilter::parse(query))
+                .unwrap_or_default();
+            if sub_matches.get_flag("WATCH") {
+                let Some((_, config)) = config::load_config()? else {
+                    return fail("No config file found, create one with `vpxtool config setup`");
+                };
+                let interval =
+                    Duration::from_secs(*sub_matches.get_one::<u64>("INTERVAL").unwrap());
+                return watch_index(&config, interval);
+            }
 
-            let (tables_folder_path, tables_index_path) = match path {
-                Some(path) => {
-                    let tables_path = expand_path_exists(path)?;
-                    let tables_index_path = config::tables_index_path(&tables_path);
-                    (tables_path, tables_index_path)
+            if let Some(path) = path {
+                let expanded_path = expand_path_exists(path)?;
+                if expanded_path.is_file() {
+                    let json_lines = sub_matches.get_flag("JSON_LINES");
+                    return index_single_file(&expanded_path, json_lines, &filter);
                 }
-                None => match config::load_config()? {
-                    Some((config_path, config)) => {
-                        println!("Using config file {}", config_path.display())?;
-                        (config.tables_folder, config.tables_index_path)
-                    }
-                    None => {
-                        eprintln!("No VPXROOTPATH provided up and no config file found")?;
-                        exit(1);
-                    }
-                },
+            }
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let options = IndexOptions {
+                recursive,
+                ..index_options(config.as_ref(), &tables_folder_path)
             };
+            if sub_matches.get_flag("JSON_LINES") {
+                let offset = sub_matches.get_one::<usize>("OFFSET").copied();
+                let limit = sub_matches.get_one::<usize>("LIMIT").copied();
+                let json_settings = config.as_ref().map(JsonSettings::from).unwrap_or_default();
+                if offset.is_some() || limit.is_some() {
+                    return index_json_lines_page(
+                        &tables_folder_path,
+                        &tables_index_path,
+                        &options,
+                        &filter,
+                        &json_settings,
+                        offset.unwrap_or(0),
+                        limit,
+                    );
+                }
+                return index_json_lines(
+                    &tables_folder_path,
+                    &tables_index_path,
+                    &options,
+                    &filter,
+                    &json_settings,
+                );
+            }
             let pb = ProgressBar::hidden();
             pb.set_style(
                 ProgressStyle::with_template(
@@ -249,21 +336,382 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
             );
             let progress = ProgressBarProgress::new(pb);
             let index = indexer::index_folder(
-                recursive,
                 &tables_folder_path,
                 &tables_index_path,
-                None,
-                &progress,
+                &options,
+                Some(&mut |p| progress.update(p)),
                 vec![],
             )?;
             progress.finish_and_clear();
-            println!(
+            status!(
                 "Indexed {} vpx files into {}",
                 index.len(),
                 &tables_index_path.display()
             )?;
             Ok(ExitCode::SUCCESS)
         }
+        Some((CMD_PREWARM, _)) => {
+            let Some((_, config)) = config::load_config()? else {
+                return fail("No config file found, create one with `vpxtool config setup`");
+            };
+            prewarm(&config)
+        }
+        Some((CMD_STATS, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let json = sub_matches.get_flag("JSON");
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let index = indexer::index_folder(
+                &tables_folder_path,
+                &tables_index_path,
+                &index_options(config.as_ref(), &tables_folder_path),
+                Some(&mut report_index_errors),
+                vec![],
+            )?;
+            let stats = collection_stats(&Index::from(&index));
+            if json {
+                let json = serde_json::to_string_pretty(&stats)?;
+                println!("{}", json)?;
+            } else {
+                println!("{}", stats_summary(&stats))?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some((CMD_WARNINGS, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let json = sub_matches.get_flag("JSON");
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let index = indexer::index_folder(
+                &tables_folder_path,
+                &tables_index_path,
+                &index_options(config.as_ref(), &tables_folder_path),
+                Some(&mut report_index_errors),
+                vec![],
+            )?;
+            let report = match &config {
+                Some(config) => Index::from(&index).warnings_report(
+                    &config.ignored_warnings(),
+                    &config.installed_components,
+                    &config.chd_requirements(),
+                ),
+                None => Index::from(&index).warnings_report(&[], &[], &Default::default()),
+            };
+            if json {
+                let json = serde_json::to_string_pretty(&report)?;
+                println!("{}", json)?;
+            } else {
+                print!("{}", warnings_report_text(&report))?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some((CMD_INDEX_DIFF, sub_matches)) => {
+            let old_path = sub_matches
+                .get_one::<String>("OLD")
+                .map(expand_path)
+                .expect("should be checked by clap");
+            let new_path = sub_matches
+                .get_one::<String>("NEW")
+                .map(expand_path)
+                .expect("should be checked by clap");
+            let old = read_index_snapshot(&old_path)?;
+            let new = read_index_snapshot(&new_path)?;
+            let diff = index_diff::diff_indexes(&old, &new)?;
+            if sub_matches.get_flag("JSON") {
+                let json = serde_json::to_string_pretty(&diff)?;
+                println!("{}", json)?;
+            } else {
+                print!("{}", index_diff_summary(&diff))?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some((CMD_HASHES, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let reference_path = match sub_matches.get_one::<String>("REFERENCE") {
+                Some(reference) => Some(expand_path_exists(reference)?),
+                None => config
+                    .as_ref()
+                    .and_then(|config| config.reference_hashes.clone()),
+            };
+            let Some(reference_path) = reference_path else {
+                return fail(
+                    "No reference hash list, pass --reference or set reference_hashes in the config",
+                );
+            };
+            let reference = ReferenceHashes::read(&reference_path)?;
+            let index = indexer::index_folder(
+                &tables_folder_path,
+                &tables_index_path,
+                &index_options(config.as_ref(), &tables_folder_path),
+                Some(&mut report_index_errors),
+                vec![],
+            )?;
+            let mut mismatches = 0;
+            for table in Index::from(&index).iter() {
+                let check = reference.check(table);
+                if check == HashCheck::Mismatch {
+                    mismatches += 1;
+                }
+                println!("{}\t{}", check.label(), table.path.display())?;
+            }
+            if mismatches > 0 {
+                Ok(ExitCode::FAILURE)
+            } else {
+                Ok(ExitCode::SUCCESS)
+            }
+        }
+        Some((CMD_ROM_AUDIT, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let manifest_path = match sub_matches.get_one::<String>("MANIFEST") {
+                Some(manifest) => Some(expand_path_exists(manifest)?),
+                None => config
+                    .as_ref()
+                    .and_then(|config| config.rom_manifest.clone()),
+            };
+            let Some(manifest_path) = manifest_path else {
+                return fail("No rom manifest, pass --manifest or set rom_manifest in the config");
+            };
+            let manifest = RomManifest::read(&manifest_path)?;
+            let index = indexer::index_folder(
+                &tables_folder_path,
+                &tables_index_path,
+                &index_options(config.as_ref(), &tables_folder_path),
+                Some(&mut report_index_errors),
+                vec![],
+            )?;
+            let mut incomplete = 0;
+            for table in Index::from(&index).iter() {
+                let Some(audit) = manifest.audit(table) else {
+                    continue;
+                };
+                if matches!(audit, RomAudit::Incomplete(_) | RomAudit::Unreadable(_)) {
+                    incomplete += 1;
+                }
+                match audit.warning() {
+                    Some(warning) => {
+                        println!("{}\t{}\t{}", audit.label(), table.path.display(), warning)?
+                    }
+                    None => println!("{}\t{}", audit.label(), table.path.display())?,
+                }
+            }
+            if incomplete > 0 {
+                Ok(ExitCode::FAILURE)
+            } else {
+                Ok(ExitCode::SUCCESS)
+            }
+        }
+        Some((CMD_CHECK_UPDATES, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let database_path = match sub_matches.get_one::<String>("DATABASE") {
+                Some(database) => Some(expand_path_exists(database)?),
+                None => config
+                    .as_ref()
+                    .and_then(|config| config.vps_database.clone()),
+            };
+            let Some(database_path) = database_path else {
+                return fail(format!(
+                    "No VPS database, download {} and pass --database or set vps_database in the config",
+                    VPS_DATABASE_URL
+                ));
+            };
+            let database = VpsDatabase::read(&database_path)?;
+            let patterns = config
+                .as_ref()
+                .map(|config| config.file_name_patterns.clone())
+                .unwrap_or_default();
+            let index = indexer::index_folder(
+                &tables_folder_path,
+                &tables_index_path,
+                &index_options(config.as_ref(), &tables_folder_path),
+                Some(&mut report_index_errors),
+                vec![],
+            )?;
+            let mut outdated = 0;
+            for table in Index::from(&index).iter() {
+                let status = database.status(table, &patterns);
+                match (status.check, status.game) {
+                    (VpsCheck::Outdated, Some(game)) => {
+                        outdated += 1;
+                        println!(
+                            "{}\t{}\t{} -> {}",
+                            status.check.label(),
+                            table.path.display(),
+                            status.local_version.unwrap_or_default(),
+                            game.latest_version().unwrap_or_default()
+                        )?
+                    }
+                    _ => println!("{}\t{}", status.check.label(), table.path.display())?,
+                }
+            }
+            if outdated > 0 {
+                Ok(ExitCode::FAILURE)
+            } else {
+                Ok(ExitCode::SUCCESS)
+            }
+        }
+        Some((CMD_INBOX, sub_matches)) => {
+            let Some((_, config)) = config::load_config()? else {
+                return fail("No config file found, create one with `vpxtool config setup`");
+            };
+            let interval = sub_matches
+                .get_flag("WATCH")
+                .then(|| Duration::from_secs(*sub_matches.get_one::<u64>("INTERVAL").unwrap()));
+            organize_inbox(&config, interval, dry_run)
+        }
+        Some((CMD_EXPORT_SIDECARS, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let (tables_folder_path, tables_index_path, config) =
+                tables_folder_and_index_path(path)?;
+            let json_settings = config.as_ref().map(JsonSettings::from).unwrap_or_default();
+            let index = indexer::index_folder(
+                &tables_folder_path,
+                &tables_index_path,
+                &index_options(config.as_ref(), &tables_folder_path),
+                Some(&mut report_index_errors),
+                vec![],
+            )?;
+            let update = sub_matches.get_flag("UPDATE");
+            export_sidecars(&Index::from(&index), &json_settings, update, dry_run)
+        }
+        Some((CMD_EXPORT_TAGS, sub_matches)) => {
+            let Some((_, config)) = config::load_config()? else {
+                return fail("No config file found, create one with `vpxtool config setup`");
+            };
+            let (path, format) = tags_export_file(&config, sub_matches.get_one::<String>("FILE"));
+            let index = frontend::index_tables_folder(
+                &config,
+                true,
+                vec![],
+                Some(&mut report_index_errors),
+            )?;
+            let export = tags_export::tags_export(&Index::from(&index), &config.tables_folder);
+            if dry_run {
+                status!(
+                    "Would write the tags of {} tables to {}",
+                    export.tables.len(),
+                    path.display()
+                )?;
+            } else {
+                tags_export::write_tags_export(&path, format, &export)?;
+                status!(
+                    "Tags of {} tables written to {}",
+                    export.tables.len(),
+                    path.display()
+                )?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some((CMD_IMPORT_TAGS, sub_matches)) => {
+            let Some((_, config)) = config::load_config()? else {
+                return fail("No config file found, create one with `vpxtool config setup`");
+            };
+            let (path, format) = tags_export_file(&config, sub_matches.get_one::<String>("FILE"));
+            let export = tags_export::read_tags_export(&path, format)?;
+            let changed = tags_export::import_tags(&export, &config.tables_folder, dry_run)?;
+            for vpx_path in &changed {
+                if dry_run {
+                    println!("Would update the tags of {}", vpx_path.display())?;
+                } else {
+                    detail!("UPDATED {}", vpx_path.display())?;
+                }
+            }
+            let would = if dry_run { "would be " } else { "" };
+            status!(
+                "Tags of {} tables {}updated from {}",
+                changed.len(),
+                would,
+                path.display()
+            )?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some((CMD_ORPHANED_SIDECARS, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let (tables_folder_path, _, config) = tables_folder_and_index_path(path)?;
+            let mut folders = vec![tables_folder_path];
+            // the staging folder only goes with the configured tables folder
+            if path.is_none()
+                && let Some(staging_folder) = config
+                    .as_ref()
+                    .and_then(|config| config.staging_folder.clone())
+            {
+                folders.push(staging_folder);
+            }
+            let (sidecar_extensions, include_hidden_sidecars) = sidecar_settings(config);
+            let delete = sub_matches.get_flag("DELETE");
+            orphaned_sidecars(
+                &folders,
+                &sidecar_extensions,
+                include_hidden_sidecars,
+                delete,
+                dry_run,
+            )
+        }
+        Some((CMD_RENAME_SIDECARS, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXPATH")
+                .expect("VPXPATH is required");
+            let vpx_path = expand_path_exists(path)?;
+            let config = config::load_config()?.map(|(_, config)| config);
+            // the frontend can undo the renames when there is an index to keep them next to
+            let record_path = config
+                .as_ref()
+                .map(|config| staging::last_move_path(&config.tables_index_path));
+            let (sidecar_extensions, include_hidden_sidecars) = sidecar_settings(config);
+            rename_sidecars(
+                &vpx_path,
+                &sidecar_extensions,
+                include_hidden_sidecars,
+                record_path.as_deref(),
+                dry_run,
+            )
+        }
+        Some((CMD_DOCTOR, _)) => {
+            let Some((config_path, config)) = config::load_config()? else {
+                return fail("No config file found, create one with `vpxtool config setup`");
+            };
+            status!("Config: {}", config_source(config_path.as_deref()))?;
+            let checks = doctor::run_checks(&config);
+            for check in &checks {
+                println!("{}", check.report())?;
+            }
+            if checks.iter().any(|check| check.is_failure()) {
+                Ok(ExitCode::FAILURE)
+            } else {
+                Ok(ExitCode::SUCCESS)
+            }
+        }
+        Some((CMD_THUMBNAILS, sub_matches)) => {
+            let path = sub_matches
+                .get_one::<String>("VPXROOTPATH")
+                .map(|s| s.as_str());
+            let max_size = sub_matches.get_one::<u32>("MAX_SIZE").copied();
+            let (tables_folder_path, _, _) = tables_folder_and_index_path(path)?;
+            let output_dir = sub_matches
+                .get_one::<String>("OUTPUT")
+                .map(expand_path)
+                .unwrap_or_else(|| tables_folder_path.join("thumbnails"));
+            extract_thumbnails(&tables_folder_path, &output_dir, max_size, dry_run)
+        }
         Some((CMD_SCRIPT, sub_matches)) => match sub_matches.subcommand() {
             Some((CMD_SCRIPT_SHOW, sub_matches)) => {
                 let path = sub_matches
@@ -272,14 +720,24 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                     .unwrap_or_default();
 
                 let expanded_path = expand_path_exists(path)?;
-                let mut vpx_file = vpx::open(expanded_path)?;
-                let game_data = vpx_file.read_gamedata()?;
-                let code = game_data.code.string;
+                let code = read_script(&expanded_path)?.string;
 
                 println!("{}", code)?;
                 Ok(ExitCode::SUCCESS)
             }
-            Some((CMD_SCRIPT_EXTRACT, sub_matches)) => handle_extractvbs(sub_matches),
+            Some((CMD_SCRIPT_EXTRACT, sub_matches)) => handle_extractvbs(sub_matches, dry_run),
+            Some((CMD_SCRIPT_EXTRACT_ALL, sub_matches)) => {
+                let path = sub_matches
+                    .get_one::<String>("VPXROOTPATH")
+                    .map(|s| s.as_str());
+                let force = sub_matches.get_flag("FORCE");
+                let (tables_folder_path, _, _) = tables_folder_and_index_path(path)?;
+                let output_dir = sub_matches
+                    .get_one::<String>("OUTPUT")
+                    .map(expand_path)
+                    .unwrap_or_else(|| tables_folder_path.join("scripts"));
+                extract_all_scripts(&tables_folder_path, &output_dir, force, dry_run)
+            }
             Some((CMD_SCRIPT_IMPORT, sub_matches)) => {
                 let path = sub_matches
                     .get_one::<String>("VPXPATH")
@@ -292,17 +750,7 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 });
 
                 let expanded_path = expand_path_exists(path)?;
-                match importvbs(&expanded_path, vbs_path_opt) {
-                    Ok(vbs_path) => {
-                        println!("IMPORTED {}", vbs_path.display())?;
-                        Ok(ExitCode::SUCCESS)
-                    }
-                    Err(e) => {
-                        let warning = format!("Error importing vbs: {}", e).red();
-                        eprintln!("{}", warning)?;
-                        Ok(ExitCode::FAILURE)
-                    }
-                }
+                handle_importvbs(&expanded_path, vbs_path_opt, dry_run)
             }
             Some((CMD_SCRIPT_EDIT, sub_matches)) => {
                 let path = sub_matches
@@ -315,10 +763,17 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 let loaded_config = config::load_config()?;
                 let config = loaded_config.as_ref().map(|c| &c.1);
                 let vbs_path = vpx::vbs_path_for(&expanded_vpx_path);
+                if dry_run {
+                    if !vbs_path.exists() {
+                        println!("Would create {}", vbs_path.display())?;
+                    }
+                    println!("Would open editor for {}", vbs_path.display())?;
+                    return Ok(ExitCode::SUCCESS);
+                }
                 if vbs_path.exists() {
                     open_or_fail(&vbs_path, config)
                 } else {
-                    extractvbs(&expanded_vpx_path, None, false)?;
+                    extractvbs(&expanded_vpx_path, None, false, false)?;
                     open_or_fail(&vbs_path, config)
                 }
             }
@@ -340,27 +795,37 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                     .unwrap_or_default();
 
                 let expanded_path = expand_path_exists(path)?;
-                let vbs_path = match extractvbs(&expanded_path, None, false) {
+                let vbs_path = match extractvbs(&expanded_path, None, false, dry_run) {
                     Ok(ExtractResult::Existed(vbs_path)) => {
                         let warning =
                             format!("EXISTED {}", vbs_path.display()).truecolor(255, 125, 0);
                         println!("{}", warning)?;
                         vbs_path
                     }
+                    Ok(ExtractResult::Extracted(vbs_path)) if dry_run => {
+                        println!("Would create {}", vbs_path.display())?;
+                        vbs_path
+                    }
                     Ok(ExtractResult::Extracted(vbs_path)) => {
-                        println!("CREATED {}", vbs_path.display())?;
+                        status!("CREATED {}", vbs_path.display())?;
                         vbs_path
                     }
                     Err(e) => return fail_with_error("Error extracting vbs", e),
                 };
 
-                let applied = patch_vbs_file(&vbs_path)?;
+                let script = extracted_script(&expanded_path, &vbs_path)?;
+                let applied = patch_vbs_file(&vbs_path, script, dry_run)?;
                 if applied.is_empty() {
-                    println!("No patches applied")?;
+                    status!("No patches applied")?;
+                } else if dry_run {
+                    println!("Would patch {}", vbs_path.display())?;
+                    applied
+                        .iter()
+                        .try_for_each(|patch| println!("Would apply patch: {}", patch))?;
                 } else {
                     applied
                         .iter()
-                        .try_for_each(|patch| println!("Applied patch: {}", patch))?;
+                        .try_for_each(|patch| status!("Applied patch: {}", patch))?;
                 }
                 Ok(ExitCode::SUCCESS)
             }
@@ -388,13 +853,13 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 let ext = expanded_path.extension().map(|e| e.to_ascii_lowercase());
                 match ext {
                     Some(ext) if ext == "directb2s" => {
-                        println!("extracting from {}", expanded_path.display())?;
-                        extract_directb2s(&expanded_path)?;
+                        status!("extracting from {}", expanded_path.display())?;
+                        extract_directb2s(&expanded_path, dry_run)?;
                         Ok(())
                     }
                     Some(ext) if ext == "vpx" => {
-                        println!("extracting from {}", expanded_path.display())?;
-                        extract(expanded_path.as_ref(), force)?;
+                        status!("extracting from {}", expanded_path.display())?;
+                        extract(expanded_path.as_ref(), force, dry_run)?;
                         Ok(())
                     }
                     _ => Err(io::Error::new(
@@ -428,6 +893,22 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                     expanded_dir_path.with_file_name(file_name)
                 }
             };
+            if dry_run {
+                // reading makes sure we report the same problems as a real run
+                expanded::read(&expanded_dir_path)?;
+                let overwrite = if vpx_path.exists() {
+                    " (overwriting existing file)"
+                } else {
+                    ""
+                };
+                println!(
+                    "Would assemble {} to {}{}",
+                    expanded_dir_path.display(),
+                    vpx_path.display(),
+                    overwrite
+                )?;
+                return Ok(ExitCode::SUCCESS);
+            }
             if vpx_path.exists() {
                 if force {
                     std::fs::remove_file(&vpx_path)?;
@@ -449,7 +930,7 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
             };
             match result {
                 Ok(_) => {
-                    println!("Successfully assembled to {}", vpx_path.display())?;
+                    status!("Successfully assembled to {}", vpx_path.display())?;
                     Ok(ExitCode::SUCCESS)
                 }
                 Err(e) => {
@@ -458,21 +939,11 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 }
             }
         }
-        Some((CMD_EXTRACT_VBS, sub_matches)) => handle_extractvbs(sub_matches),
+        Some((CMD_EXTRACT_VBS, sub_matches)) => handle_extractvbs(sub_matches, dry_run),
         Some((CMD_IMPORT_VBS, sub_matches)) => {
             let path: &str = sub_matches.get_one::<String>("VPXPATH").unwrap().as_str();
             let expanded_path = expand_path_exists(path)?;
-            match importvbs(&expanded_path, None) {
-                Ok(vbs_path) => {
-                    println!("IMPORTED {}", vbs_path.display())?;
-                    Ok(ExitCode::SUCCESS)
-                }
-                Err(e) => {
-                    let warning = format!("Error importing vbs: {}", e).red();
-                    eprintln!("{}", warning)?;
-                    Ok(ExitCode::FAILURE)
-                }
-            }
+            handle_importvbs(&expanded_path, None, dry_run)
         }
         Some((CMD_PATCH, sub_matches)) => {
             let vpx_path = sub_matches
@@ -506,6 +977,15 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                     format!("OUTVPXPATH already exists: {}", patched_vpx_path.display()),
                 ));
             }
+            if dry_run {
+                println!(
+                    "Would apply {} to {} and write {}",
+                    patch_path.display(),
+                    vpx_path.display(),
+                    patched_vpx_path.display()
+                )?;
+                return Ok(ExitCode::SUCCESS);
+            }
             let vpx_file = File::open(vpx_path)?;
             let patch_file = File::open(patch_path)?;
             let patched_vpx_file = File::create(patched_vpx_path)?;
@@ -531,7 +1011,7 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 let expanded_path = expand_path_exists(path)?;
                 match verify(&expanded_path) {
                     VerifyResult::Ok(vbs_path) => {
-                        println!("{OK} {}", vbs_path.display())?;
+                        status!("{OK} {}", vbs_path.display())?;
                     }
                     VerifyResult::Failed(vbs_path, msg) => {
                         let warning =
@@ -542,6 +1022,33 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
             }
             Ok(ExitCode::SUCCESS)
         }
+        Some((CMD_REPAIR_CHECKSUM, sub_matches)) => {
+            let backup = sub_matches.get_flag("BACKUP");
+            let mut exit_code = ExitCode::SUCCESS;
+            for path in sub_matches
+                .get_many::<String>("VPXPATH")
+                .unwrap_or_default()
+            {
+                let expanded_path = expand_path_exists(path)?;
+                if handle_repair_checksum(&expanded_path, backup, dry_run)? != ExitCode::SUCCESS {
+                    exit_code = ExitCode::FAILURE;
+                }
+            }
+            Ok(exit_code)
+        }
+        Some((CMD_VERIFY_ROUNDTRIP, sub_matches)) => {
+            let mut exit_code = ExitCode::SUCCESS;
+            for path in sub_matches
+                .get_many::<String>("VPXPATH")
+                .unwrap_or_default()
+            {
+                let expanded_path = expand_path_exists(path)?;
+                if verify_roundtrip(&expanded_path)? != ExitCode::SUCCESS {
+                    exit_code = ExitCode::FAILURE;
+                }
+            }
+            Ok(exit_code)
+        }
         Some((CMD_NEW, sub_matches)) => {
             let path = {
                 let this = sub_matches.get_one::<String>("VPXPATH").map(|v| v.as_str());
@@ -552,18 +1059,22 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
             };
 
             let expanded_path = shellexpand::tilde(path);
-            println!("creating new vpx file at {}", expanded_path)?;
+            if dry_run {
+                println!("Would create new vpx file at {}", expanded_path)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            status!("creating new vpx file at {}", expanded_path)?;
             new(expanded_path.as_ref())?;
             Ok(ExitCode::SUCCESS)
         }
         Some((CMD_CONFIG, sub_matches)) => match sub_matches.subcommand() {
             Some((CMD_CONFIG_SETUP, _)) => match config::setup_config() {
                 Ok(SetupConfigResult::Configured(config_path)) => {
-                    println!("Created config file {}", config_path.display())?;
+                    status!("Created config file {}", config_path.display())?;
                     Ok(ExitCode::SUCCESS)
                 }
                 Ok(SetupConfigResult::Existing(config_path)) => {
-                    println!(
+                    status!(
                         "Config file already exists at \"{}\"",
                         config_path.display()
                     )?;
@@ -599,7 +1110,7 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
             },
             Some((CMD_CONFIG_CLEAR, _)) => match config::clear_config() {
                 Ok(Some(config_path)) => {
-                    println!("Cleared config file {}", config_path.display())?;
+                    status!("Cleared config file {}", config_path.display())?;
                     Ok(ExitCode::SUCCESS)
                 }
                 Ok(None) => {
@@ -626,19 +1137,28 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                     .map(|s| s.as_str())
                     .unwrap_or_default();
                 let expanded_path = expand_path_exists(path)?;
+                if dry_run {
+                    println!(
+                        "Would convert lossless images in {} to webp and compact the file",
+                        expanded_path.display()
+                    )?;
+                    return Ok(ExitCode::SUCCESS);
+                }
                 let mut vpx_file = vpx::open_rw(&expanded_path)?;
                 let images = vpx_file.images_to_webp()?;
                 if !images.is_empty() {
                     for image in images.iter() {
-                        println!(
+                        status!(
                             "Updated {} from {} to {}",
-                            image.name, image.old_extension, image.new_extension
+                            image.name,
+                            image.old_extension,
+                            image.new_extension
                         )?;
                     }
-                    println!("Compacting vpx file")?;
+                    status!("Compacting vpx file")?;
                     vpx::compact(&expanded_path)?;
                 } else {
-                    println!("No images to update")?;
+                    status!("No images to update")?;
                 }
                 Ok(ExitCode::SUCCESS)
             }
@@ -679,6 +1199,11 @@ fn handle_command(matches: ArgMatches) -> io::Result<ExitCode> {
                 .map(|s| s.as_str())
                 .unwrap_or_default();
             let expanded_path = expand_path_exists(path)?;
+            if sub_matches.get_flag("WRITE") {
+                let backup = sub_matches.get_flag("BACKUP");
+                let yes = sub_matches.get_flag("YES");
+                return handle_write_romname(&expanded_path, backup, yes, dry_run);
+            }
             if let Some(rom_name) = indexer::get_romname_from_vpx(&expanded_path)? {
                 println!("{rom_name}")?;
             }
@@ -696,8 +1221,36 @@ fn build_command() -> Command {
         .version(GIT_VERSION)
         .author("Francis DB")
         .about("Extracts and assembles vpx files")
-        .arg_required_else_help(true)
         .before_help(format!("Vpxtool {GIT_VERSION}"))
+        .arg(
+            Arg::new("DRY_RUN")
+                .long("dry-run")
+                .num_args(0)
+                .global(true)
+                .help("Print what commands that write files would do without changing anything"),
+        )
+        .arg(
+            Arg::new("QUIET")
+                .short('q')
+                .long("quiet")
+                .num_args(0)
+                .global(true)
+                .conflicts_with("VERBOSE")
+                .help("Only print errors and the requested data, no progress, summaries or info lines"),
+        )
+        .arg(
+            Arg::new("VERBOSE")
+                .short('v')
+                .long("verbose")
+                .num_args(0)
+                .global(true)
+                .help("Also print extra details, like the encoding of the script in the vpx file"),
+        )
+        .arg(
+            arg!(--config <CONFIGPATH> "Use this config file instead of the default location, can also be set through the VPXTOOL_CONFIG environment variable")
+                .required(false)
+                .global(true),
+        )
         .subcommand(
             Command::new(CMD_INFO)
                 .subcommand_required(true)
@@ -761,9 +1314,14 @@ fn build_command() -> Command {
                         .default_value("true"),
                 )
         )
+        .subcommand(
+            Command::new(CMD_PREWARM)
+                .about("Brings the index up to date without starting the frontend")
+                .long_about("Indexes the configured tables folder like the frontend does when it starts, parsing only new and changed tables and dropping the ones that are gone, so the next frontend start is fast. Meant for boot scripts, prints a one line summary.")
+        )
         .subcommand(
             Command::new("index")
-                .about("Indexes a directory of vpx files")
+                .about("Indexes a directory of vpx files or parses a single vpx file")
                 .arg(
                     Arg::new("RECURSIVE")
                         .short('r')
@@ -773,14 +1331,241 @@ fn build_command() -> Command {
                         .default_value("true"),
                 )
                 .arg(
-                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                    Arg::new("JSON_LINES")
+                        .long("json-lines")
+                        .num_args(0)
+                        .help("Stream each table as a json object per line instead of showing progress"),
+                )
+                .arg(
+                    arg!(--filter <QUERY> "With --json-lines, only write the tables matching the query, using the same syntax as the frontend filter.")
+                        .id("FILTER")
+                        .required(false)
+                        .requires("JSON_LINES"),
+                )
+                .arg(
+                    arg!(--offset <N> "With --json-lines, skip the first N tables. Tables are sorted by path, the output then follows once indexing is done.")
+                        .id("OFFSET")
+                        .required(false)
+                        .requires("JSON_LINES")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    arg!(--limit <N> "With --json-lines, write at most N tables. Applied after sorting by path and after --offset.")
+                        .id("LIMIT")
+                        .required(false)
+                        .requires("JSON_LINES")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("WATCH")
+                        .long("watch")
+                        .num_args(0)
+                        .conflicts_with_all(["JSON_LINES", "VPXROOTPATH"])
+                        .help("Keep indexing the configured tables folder and print what changed, a frontend running at the same time picks up the changes"),
+                )
+                .arg(
+                    arg!(--interval <SECONDS> "With --watch, the seconds without changes before indexing, so tables that are still being copied are skipped. Where there are no change notifications the seconds between checks.")
+                        .id("INTERVAL")
+                        .required(false)
+                        .requires("WATCH")
+                        .default_value("5")
+                        .value_parser(clap::value_parser!(u64).range(1..)),
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files or to a single vpx file, which is printed as json without updating the index. Defaults to what is set up in the config file.")
                         .required(false)
                 ),
         )
         .subcommand(
-            Command::new(CMD_SCRIPT)
-                .subcommand_required(true)
-                .about("Vpx script code related commands")
+            Command::new(CMD_STATS)
+                .about("Shows a summary of the indexed tables collection")
+                .arg(
+                    Arg::new("JSON")
+                        .long("json")
+                        .num_args(0)
+                        .help("Output the summary as json"),
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_WARNINGS)
+                .about("Lists the warnings of all indexed tables, grouped by table")
+                .long_about("Lists every warning of the indexed tables, like missing roms or referenced files, grouped by table. Suppressed warnings are left out, like in the frontend.")
+                .arg(
+                    Arg::new("JSON")
+                        .long("json")
+                        .num_args(0)
+                        .help("Output the report as json"),
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_INDEX_DIFF)
+                .about("Shows the tables that were added, removed or changed between two index files")
+                .long_about("Shows the tables that were added, removed or changed between two saved index files. Prints ADDED, REMOVED or CHANGED followed by a tab and the table path, changed tables are followed by the fields that changed with their old and new value.")
+                .arg(
+                    Arg::new("JSON")
+                        .long("json")
+                        .num_args(0)
+                        .help("Output the differences as json"),
+                )
+                .arg(arg!(<OLD> "The older index file").required(true))
+                .arg(arg!(<NEW> "The newer index file").required(true)),
+        )
+        .subcommand(
+            Command::new(CMD_HASHES)
+                .about("Compares the table content hashes against a list of trusted hashes")
+                .long_about("Compares the table content hashes against a list of trusted hashes in the sha256sum format. Prints MATCHED, MISMATCH or UNKNOWN followed by a tab and the table path for every table. Exits with an error if any table does not match.")
+                .arg(
+                    arg!(-r --reference <FILE> "The list of trusted hashes. Defaults to reference_hashes in the config file.")
+                        .id("REFERENCE")
+                        .required(false)
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_ROM_AUDIT)
+                .about("Checks that the rom zips of the tables contain all required files")
+                .long_about("Checks the rom zips of the tables against a manifest of required files per rom. Prints COMPLETE, INCOMPLETE, UNLISTED or UNREADABLE followed by a tab and the table path for every table with a rom. Exits with an error if any rom set is incomplete or unreadable.")
+                .arg(
+                    arg!(-m --manifest <FILE> "The required files per rom. Defaults to rom_manifest in the config file.")
+                        .id("MANIFEST")
+                        .required(false)
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_CHECK_UPDATES)
+                .about("Checks the VPS database for newer versions of the tables")
+                .long_about("Matches the tables by name against a downloaded copy of the Virtual Pinball Spreadsheet database and compares their version with the latest VPX release. Prints UP-TO-DATE, OUTDATED, UNKNOWN or UNMATCHED followed by a tab and the table path for every table, outdated tables also get the local and the latest version. Exits with an error if any table is outdated.")
+                .arg(
+                    arg!(-d --database <FILE> "The downloaded vpsdb.json. Defaults to vps_database in the config file.")
+                        .id("DATABASE")
+                        .required(false)
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_DOCTOR)
+                .about("Checks the config for common setup problems")
+                .long_about("Checks that the configured Visual Pinball executable, tables folder and rom folder exist and are usable. Prints a report with hints for every problem found and exits with an error if any check failed."),
+        )
+        .subcommand(
+            Command::new(CMD_INBOX)
+                .about("Moves new tables from the inbox folder to the tables folder")
+                .long_about("Moves the tables in the configured inbox_folder, and the tables in zip bundles there, with their sidecar files to the tables folder. With inbox_name_template the files are renamed after the table. Tables that conflict with existing files stay in the inbox. Use --dry-run to only report what would be moved.")
+                .arg(
+                    Arg::new("WATCH")
+                        .short('w')
+                        .long("watch")
+                        .num_args(0)
+                        .help("Keep running and check the inbox for new downloads at every interval"),
+                )
+                .arg(
+                    arg!(--interval <SECONDS> "With --watch, the seconds between checks. Files changed more recently are considered still downloading.")
+                        .id("INTERVAL")
+                        .required(false)
+                        .requires("WATCH")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(u64).range(1..)),
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_EXPORT_SIDECARS)
+                .about("Writes the indexed info of every table to a .vpxtool.json file next to it")
+                .long_about("Writes a <table>.vpxtool.json file next to every table with the same json as `index --json-lines`, so other tools can use it without running vpxtool. Existing files are left alone unless --update is set. Tables in zip bundles are skipped. Use --dry-run to only report what would be written.")
+                .arg(
+                    Arg::new("UPDATE")
+                        .short('u')
+                        .long("update")
+                        .num_args(0)
+                        .help("Rewrite existing files that are out of date"),
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_EXPORT_TAGS)
+                .about("Writes the favorites and tags of all tables to a file other frontends can read")
+                .long_about("Writes the favorites and tags of all tables to tags_export_path, or FILE if given, as json or ini depending on tags_export_format or the extension of the file. Tables with the `favorite` tag are the favorites. Use --dry-run to only report what would be written.")
+                .arg(
+                    arg!(<FILE> "The file to write, defaults to tags_export_path from the config file")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_IMPORT_TAGS)
+                .about("Reads favorites and tags back from a file written by export-tags")
+                .long_about("Replaces the tags of the tables listed in tags_export_path, or FILE if given, with the favorites and tags in it. Tables that are not listed are left alone. Use --dry-run to only report which tables would change.")
+                .arg(
+                    arg!(<FILE> "The file to read, defaults to tags_export_path from the config file")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_ORPHANED_SIDECARS)
+                .about("Lists sidecar files whose table is gone")
+                .long_about("Lists the files in the tables folder, and the staging folder if configured, that look like sidecar files (.vbs, .directb2s, .ini, ... and the extra_sidecar_extensions) but have no table with the same name next to them. With --delete they are deleted after confirmation, use --dry-run to only report what would be deleted.")
+                .arg(
+                    Arg::new("DELETE")
+                        .long("delete")
+                        .num_args(0)
+                        .help("Delete the listed files after confirmation"),
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_RENAME_SIDECARS)
+                .about("Renames sidecar files left with an old table name to the table name")
+                .long_about("Finds the sidecar files next to the table that have no table of their own but a name similar to the table, like `Table v1.1.directb2s` for `Table (Bally 1995).vpx`. These are usually left behind when the table was renamed outside vpxtool. They are renamed after the table after confirmation, files that already exist are never replaced. Use --dry-run to only list the renames.")
+                .arg(
+                    arg!(<VPXPATH> "The path to the vpx file")
+                        .required(true)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_THUMBNAILS)
+                .about("Exports a preview image for each table to a thumbnails folder")
+                .arg(
+                    arg!(-o --output <OUTPUTDIR> "The folder to write the thumbnails to. Defaults to a thumbnails folder in the tables folder.")
+                        .id("OUTPUT")
+                        .required(false)
+                )
+                .arg(
+                    arg!(--"max-size" <PIXELS> "Scale images down so neither side exceeds this size")
+                        .id("MAX_SIZE")
+                        .required(false)
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                )
+                .arg(
+                    arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                        .required(false)
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_SCRIPT)
+                .subcommand_required(true)
+                .about("Vpx script code related commands")
                 .subcommand(
                     Command::new(CMD_SCRIPT_SHOW)
                         .about("Show a vpx script")
@@ -792,6 +1577,27 @@ fn build_command() -> Command {
                 .subcommand(
                     extract_script_command(CMD_SCRIPT_EXTRACT),
                 )
+                .subcommand(
+                    Command::new(CMD_SCRIPT_EXTRACT_ALL)
+                        .about("Extracts the scripts of all tables into a folder, keeping the folder structure")
+                        .arg(
+                            arg!(-o --output <OUTPUTDIR> "The folder to write the scripts to. Defaults to a scripts folder in the tables folder.")
+                                .id("OUTPUT")
+                                .required(false)
+                        )
+                        .arg(
+                            Arg::new("FORCE")
+                                .short('f')
+                                .long("force")
+                                .num_args(0)
+                                .default_value("false")
+                                .help("Will overwrite existing .vbs files if set."),
+                        )
+                        .arg(
+                            arg!(<VPXROOTPATH> "The path to the root directory of vpx files. Defaults to what is set up in the config file.")
+                                .required(false)
+                        ),
+                )
                 .subcommand(
                     Command::new(CMD_SCRIPT_IMPORT)
                         .about("Import the table vpx script")
@@ -894,6 +1700,31 @@ fn build_command() -> Command {
                 .arg(arg!(<PATCHPATH> "The path to the dif file").required(true))
                 .arg(arg!(<OUTVPXPATH> "The path to the output vpx file. Defaults to <VPXPATH>.patched.vpx").required(false))
         )
+        .subcommand(
+            Command::new(CMD_VERIFY_ROUNDTRIP)
+                .about("Extracts and assembles a vpx file in a temporary folder and reports the streams that changed")
+                .arg(
+                    arg!(<VPXPATH> "The path(s) to the vpx file(s)")
+                        .required(true)
+                        .num_args(1..),
+                ),
+        )
+        .subcommand(
+            Command::new(CMD_REPAIR_CHECKSUM)
+                .about("Rewrites the checksum of a vpx file that fails verification")
+                .arg(
+                    Arg::new("BACKUP")
+                        .short('b')
+                        .long("backup")
+                        .num_args(0)
+                        .help("Keep a copy of the original file as <file>.vpx.bak"),
+                )
+                .arg(
+                    arg!(<VPXPATH> "The path(s) to the vpx file(s)")
+                        .required(true)
+                        .num_args(1..),
+                ),
+        )
         .subcommand(
             Command::new(CMD_NEW)
                 .about("Creates a minimal empty new vpx file")
@@ -966,7 +1797,30 @@ fn build_command() -> Command {
         .subcommand(
             Command::new(CMD_ROMNAME)
                 .about("Prints the PinMAME ROM name from a vpx file")
-                .long_about("Extracts the PinMAME ROM name from a vpx file by searching for specific patterns in the table script. If the table is not PinMAME based, no output is produced.")
+                .long_about("Extracts the PinMAME ROM name from a vpx file by searching for specific patterns in the table script. If the table is not PinMAME based, no output is produced. With --write the ROM name is stored in the table info as the RomName custom property.")
+                .arg(
+                    Arg::new("WRITE")
+                        .short('w')
+                        .long("write")
+                        .num_args(0)
+                        .help("Write the ROM name into the table info of the vpx file"),
+                )
+                .arg(
+                    Arg::new("BACKUP")
+                        .short('b')
+                        .long("backup")
+                        .num_args(0)
+                        .requires("WRITE")
+                        .help("Keep a copy of the original file as <file>.vpx.bak"),
+                )
+                .arg(
+                    Arg::new("YES")
+                        .short('y')
+                        .long("yes")
+                        .num_args(0)
+                        .requires("WRITE")
+                        .help("Do not ask for confirmation before writing the ROM name"),
+                )
                 .arg(arg!(<VPXPATH> "The path to the vpx file").required(true)),
         )
 }
@@ -1026,7 +1880,196 @@ fn new(vpx_file_path: &str) -> io::Result<()> {
     vpx::new_minimal_vpx(vpx_file_path)
 }
 
-fn handle_extractvbs(sub_matches: &ArgMatches) -> io::Result<ExitCode> {
+fn handle_importvbs(
+    vpx_path: &PathBuf,
+    vbs_path_opt: Option<PathBuf>,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    match importvbs(vpx_path, vbs_path_opt, dry_run) {
+        Ok((vbs_path, encoding)) if dry_run => {
+            println!(
+                "Would import {} into {}",
+                vbs_path.display(),
+                vpx_path.display()
+            )?;
+            detail!("Encoding: {}", encoding)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Ok((vbs_path, encoding)) => {
+            status!("IMPORTED {}", vbs_path.display())?;
+            detail!("Encoding: {}", encoding)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(e) => {
+            let warning = format!("Error importing vbs: {}", e).red();
+            eprintln!("{}", warning)?;
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum ChecksumRepair {
+    AlreadyValid,
+    Repaired { backup_path: Option<PathBuf> },
+}
+
+/// Rewrites the table so its checksum (the MAC stream) matches the content again.
+///
+/// The table is parsed before anything is written, a table that can't be read is left untouched.
+/// With `dry_run` nothing is written, the result tells what would happen.
+fn repair_checksum(vpx_path: &PathBuf, backup: bool, dry_run: bool) -> io::Result<ChecksumRepair> {
+    let vpx = vpx::read(vpx_path)?;
+    if let VerifyResult::Ok(_) = verify(vpx_path) {
+        return Ok(ChecksumRepair::AlreadyValid);
+    }
+    let backup_path = backup.then(|| PathBuf::from(format!("{}.bak", vpx_path.display())));
+    if !dry_run {
+        if let Some(backup_path) = &backup_path {
+            std::fs::copy(vpx_path, backup_path)?;
+        }
+        vpx::write(vpx_path, &vpx)?;
+    }
+    Ok(ChecksumRepair::Repaired { backup_path })
+}
+
+fn handle_repair_checksum(vpx_path: &PathBuf, backup: bool, dry_run: bool) -> io::Result<ExitCode> {
+    match repair_checksum(vpx_path, backup, dry_run) {
+        Ok(ChecksumRepair::AlreadyValid) => {
+            status!("{OK} {} checksum already correct", vpx_path.display())?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Ok(ChecksumRepair::Repaired { backup_path }) if dry_run => {
+            println!("Would repair checksum of {}", vpx_path.display())?;
+            if let Some(backup_path) = backup_path {
+                println!("Would back up to {}", backup_path.display())?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Ok(ChecksumRepair::Repaired { backup_path }) => {
+            status!("REPAIRED {}", vpx_path.display())?;
+            if let Some(backup_path) = backup_path {
+                status!("Backup: {}", backup_path.display())?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(e) => {
+            let warning = format!("{NOK} {} not repaired: {}", vpx_path.display(), e).red();
+            eprintln!("{}", warning)?;
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Custom table info property the ROM name is written to
+const ROM_NAME_PROPERTY: &str = "RomName";
+
+#[derive(PartialEq, Debug)]
+enum RomNameWrite {
+    AlreadySet,
+    Written { backup_path: Option<PathBuf> },
+}
+
+/// Stores the ROM name as custom property in the table info.
+///
+/// The custom info tags keep their order, a new property is added at the end.
+fn write_romname(vpx_path: &PathBuf, rom_name: &str, backup: bool) -> io::Result<RomNameWrite> {
+    let mut vpx = vpx::read(vpx_path)?;
+    if vpx
+        .info
+        .properties
+        .get(ROM_NAME_PROPERTY)
+        .map(|s| s.as_str())
+        == Some(rom_name)
+    {
+        return Ok(RomNameWrite::AlreadySet);
+    }
+    let backup_path = if backup {
+        let backup_path = PathBuf::from(format!("{}.bak", vpx_path.display()));
+        std::fs::copy(vpx_path, &backup_path)?;
+        Some(backup_path)
+    } else {
+        None
+    };
+    vpx.info
+        .properties
+        .insert(ROM_NAME_PROPERTY.to_string(), rom_name.to_string());
+    if !vpx
+        .custominfotags
+        .iter()
+        .any(|tag| tag == ROM_NAME_PROPERTY)
+    {
+        vpx.custominfotags.push(ROM_NAME_PROPERTY.to_string());
+    }
+    vpx::write(vpx_path, &vpx)?;
+    Ok(RomNameWrite::Written { backup_path })
+}
+
+fn handle_write_romname(
+    vpx_path: &PathBuf,
+    backup: bool,
+    yes: bool,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let Some(rom_name) = indexer::get_romname_from_vpx(vpx_path)? else {
+        let warning = format!("{NOK} No ROM name found in {}", vpx_path.display()).red();
+        eprintln!("{}", warning)?;
+        return Ok(ExitCode::FAILURE);
+    };
+    let current = vpx::open(vpx_path)?
+        .read_tableinfo()?
+        .properties
+        .remove(ROM_NAME_PROPERTY);
+    if current.as_deref() == Some(rom_name.as_str()) {
+        status!(
+            "{OK} {} already has ROM name {}",
+            vpx_path.display(),
+            rom_name
+        )?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if dry_run {
+        println!(
+            "Would write ROM name {} to {}",
+            rom_name,
+            vpx_path.display()
+        )?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !yes {
+        let warning = match current {
+            Some(current) => format!(
+                "{} has ROM name {} in its table info",
+                vpx_path.display(),
+                current
+            ),
+            None => format!("{} has no ROM name in its table info", vpx_path.display()),
+        };
+        let question = format!("Write ROM name {} found in the script?", rom_name);
+        if !confirm(warning, question)? {
+            status!("Aborted")?;
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
+    match write_romname(vpx_path, &rom_name, backup)? {
+        RomNameWrite::AlreadySet => {
+            status!(
+                "{OK} {} already has ROM name {}",
+                vpx_path.display(),
+                rom_name
+            )?;
+        }
+        RomNameWrite::Written { backup_path } => {
+            status!("Wrote ROM name {} to {}", rom_name, vpx_path.display())?;
+            if let Some(backup_path) = backup_path {
+                status!("Backup: {}", backup_path.display())?;
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn handle_extractvbs(sub_matches: &ArgMatches, dry_run: bool) -> io::Result<ExitCode> {
     let force = sub_matches.get_flag("FORCE");
     let vpx_path = sub_matches.get_one::<String>("VPXPATH").map(expand_path);
     let vbs_path = sub_matches.get_one::<String>("VBSPATH").map(expand_path);
@@ -1047,13 +2090,21 @@ fn handle_extractvbs(sub_matches: &ArgMatches) -> io::Result<ExitCode> {
         })
     });
 
-    match extractvbs(&expanded_vpx_path, vbs_path_opt, force) {
+    match extractvbs(&expanded_vpx_path, vbs_path_opt, force, dry_run) {
         Ok(ExtractResult::Existed(vbs_path)) => {
             let warning = format!("EXISTED {}", vbs_path.display()).truecolor(255, 125, 0);
             println!("{}", warning)?;
         }
+        Ok(ExtractResult::Extracted(vbs_path)) if dry_run => {
+            println!("Would create {}", vbs_path.display())?;
+        }
         Ok(ExtractResult::Extracted(vbs_path)) => {
-            println!("CREATED {}", vbs_path.display())?;
+            status!("CREATED {}", vbs_path.display())?;
+            if fixprint::verbosity() >= Verbosity::Verbose {
+                // the extracted file is always UTF-8 without BOM
+                let encoding = script_encoding(&expanded_vpx_path)?;
+                detail!("Encoding: {} (written as UTF-8)", encoding)?;
+            }
         }
         Err(e) => {
             let warning = format!("Error extracting vbs: {}", e).red();
@@ -1064,19 +2115,23 @@ fn handle_extractvbs(sub_matches: &ArgMatches) -> io::Result<ExitCode> {
     Ok(ExitCode::SUCCESS)
 }
 
-fn extract_directb2s(expanded_path: &PathBuf) -> io::Result<()> {
+fn extract_directb2s(expanded_path: &PathBuf, dry_run: bool) -> io::Result<()> {
     let file = File::open(expanded_path)?;
     let reader = BufReader::new(file);
     match read(reader) {
         Ok(b2s) => {
-            println!("DirectB2S file version {}", b2s.version)?;
+            status!("DirectB2S file version {}", b2s.version)?;
             let root_dir_path = expanded_path.with_extension("directb2s.extracted");
+            if dry_run {
+                println!("Would write to {}", root_dir_path.display())?;
+                return Ok(());
+            }
 
             let mut root_dir = std::fs::DirBuilder::new();
             root_dir.recursive(true);
             root_dir.create(&root_dir_path)?;
 
-            println!("Writing to {}", root_dir_path.display())?;
+            status!("Writing to {}", root_dir_path.display())?;
             wite_images(b2s, root_dir_path.as_path());
         }
         Err(msg) => {
@@ -1318,6 +2373,12 @@ fn info_gather(vpx_file_path: &PathBuf) -> io::Result<String> {
         table_info.table_rules.unwrap_or("[not set]".to_string())
     ));
 
+    buffer.push_str(&format!(
+        "{:>18} {}\n",
+        "SHA-256:".green(),
+        indexer::content_hash(vpx_file_path)?
+    ));
+
     for (prop, value) in &table_info.properties {
         buffer.push_str(&format!("{:>18}: {}\n", prop.green(), value));
     }
@@ -1325,9 +2386,800 @@ fn info_gather(vpx_file_path: &PathBuf) -> io::Result<String> {
     Ok(buffer)
 }
 
-fn info_extract(vpx_file_path: &Path) -> io::Result<ExitCode> {
+/// Where the config comes from, the config file or only the environment
+fn config_source(config_path: Option<&Path>) -> String {
+    match config_path {
+        Some(config_path) => format!("config file {}", config_path.display()),
+        None => format!(
+            "config from the {}* environment variables",
+            config::ENV_PREFIX
+        ),
+    }
+}
+
+/// Config for the tables in the current directory, with a hint on setting up a config file
+fn current_dir_config() -> io::Result<ResolvedConfig> {
+    let config = config::current_dir_config()?;
+    let hint = format!(
+        "No config file found, showing the tables in {}. Run `vpxtool config setup` to set up \
+         your Visual Pinball installation, tables folder and roms.",
+        config.tables_folder.display()
+    );
+    status!("{}", hint.yellow())?;
+    Ok(config)
+}
+
+fn run_frontend(config: &ResolvedConfig, dry_run: bool) -> io::Result<ExitCode> {
+    status!(
+        "Using global pinmame rom folder {}",
+        config.global_pinmame_rom_folder().display()
+    )?;
+    match frontend::frontend_index(config, true, vec![]) {
+        Ok(index) if index.is_empty() => {
+            let warning = format!("No tables found in {}", config.tables_folder.display()).red();
+            eprintln!("{}", warning)?;
+            Ok(ExitCode::FAILURE)
+        }
+        Ok(index) => {
+            let vpinball_executable = &config.vpx_executable;
+            frontend::frontend(config, index.into_tables(), vpinball_executable, dry_run);
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(IndexError::FolderDoesNotExist(path)) => {
+            let warning = format!(
+                "Configured tables folder does not exist: {}",
+                path.display()
+            )
+            .red();
+            eprintln!("{}", warning)?;
+            Ok(ExitCode::FAILURE)
+        }
+        Err(e) => {
+            let warning = format!("Error running frontend: {}", e).red();
+            eprintln!("{}", warning)?;
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Brings the index up to date like the frontend does when it starts, without showing it
+fn prewarm(config: &ResolvedConfig) -> io::Result<ExitCode> {
+    let start = Instant::now();
+    let paths = |index: &TablesIndex| {
+        index
+            .tables()
+            .into_iter()
+            .map(|table| table.path)
+            .collect::<HashSet<PathBuf>>()
+    };
+    let before = indexer::read_index(&config.tables_index_path)?
+        .map(|index| paths(&index))
+        .unwrap_or_default();
+    let mut parsed = 0;
+    let mut failed = 0;
+    let index = match frontend::index_tables_folder(
+        config,
+        true,
+        vec![],
+        Some(&mut |progress: IndexProgress| {
+            report_index_errors(progress);
+            parsed += 1;
+            if progress.table.is_none() {
+                failed += 1;
+            }
+        }),
+    ) {
+        Ok(index) => index,
+        Err(IndexError::FolderDoesNotExist(path)) => {
+            return fail(format!(
+                "Configured tables folder does not exist: {}",
+                path.display()
+            ));
+        }
+        Err(e) => return fail(format!("Error indexing tables: {}", e)),
+    };
+    let after = paths(&index);
+    status!(
+        "{} tables in {}, {} parsed ({} new, {} failed), {} removed, in {:.1}s",
+        index.len(),
+        config.tables_index_path.display(),
+        parsed,
+        after.difference(&before).count(),
+        failed,
+        before.difference(&after).count(),
+        start.elapsed().as_secs_f64()
+    )?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The given file or the configured one, with the format of its extension or the configured one
+fn tags_export_file(config: &ResolvedConfig, file: Option<&String>) -> (PathBuf, TagsExportFormat) {
+    let path = file
+        .map(expand_path)
+        .unwrap_or_else(|| config.tags_export_file());
+    let format = TagsExportFormat::from_path(&path).unwrap_or(config.tags_export_format);
+    (path, format)
+}
+
+/// Resolves the tables folder and index file from the given path or the config file. The config
+/// is returned as well so commands only load it, and warn about its settings, once.
+fn tables_folder_and_index_path(
+    path: Option<&str>,
+) -> io::Result<(PathBuf, PathBuf, Option<ResolvedConfig>)> {
+    let loaded_config = config::load_config()?;
+    match path {
+        Some(path) => {
+            let tables_path = expand_path_exists(path)?;
+            let tables_index_path = config::tables_index_path(&tables_path);
+            let config = loaded_config.map(|(_, config)| config);
+            Ok((tables_path, tables_index_path, config))
+        }
+        None => match loaded_config {
+            Some((config_path, config)) => {
+                if fixprint::verbosity() >= Verbosity::Normal {
+                    eprintln!("Using {}", config_source(config_path.as_deref()))?;
+                }
+                Ok((
+                    config.tables_folder.clone(),
+                    config.tables_index_path.clone(),
+                    Some(config),
+                ))
+            }
+            None => {
+                eprintln!("No VPXROOTPATH provided up and no config file found")?;
+                exit(1);
+            }
+        },
+    }
+}
+
+/// The configured index options when indexing the configured tables folder, so the shared index
+/// file looks the same whichever command updated it. Another folder gets its own index file and
+/// is indexed without the staging folder. Without a config only the defaults apply, the threads
+/// are picked by the disk of the tables folder.
+fn index_options(config: Option<&ResolvedConfig>, tables_folder: &Path) -> IndexOptions {
+    match config {
+        Some(config) if config.tables_folder == tables_folder => config.index_options(),
+        Some(config) => IndexOptions {
+            staging_folder: None,
+            threads: config
+                .index_parallelism
+                .threads(tables_folder, config.index_threads),
+            ..config.index_options()
+        },
+        None => IndexOptions {
+            threads: IndexParallelism::default().threads(tables_folder, None),
+            ..IndexOptions::default()
+        },
+    }
+}
+
+/// Writes every table as a single line of json (NDJSON) to stdout.
+///
+/// Tables that need parsing are written as soon as they are parsed, unchanged tables from the
+/// existing index follow once indexing is done. Only tables matching the filter are written.
+fn index_json_lines(
+    tables_folder_path: &Path,
+    tables_index_path: &Path,
+    options: &IndexOptions,
+    filter: &TableFilter,
+    json_settings: &JsonSettings,
+) -> io::Result<ExitCode> {
+    let mut written = std::collections::HashSet::new();
+    let mut write_result = Ok(());
+    let index = indexer::index_folder(
+        tables_folder_path,
+        tables_index_path,
+        options,
+        Some(&mut |p| {
+            report_index_errors(p);
+            if let Some(table) = p.table
+                && write_result.is_ok()
+            {
+                if table.matches(filter) {
+                    write_result = write_json_line(table, json_settings);
+                }
+                written.insert(table.path.clone());
+            }
+        }),
+        vec![],
+    )?;
+    write_result?;
+    for table in Index::from(&index).iter() {
+        if !written.contains(&table.path) && table.matches(filter) {
+            write_json_line(table, json_settings)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parses a single vpx file without touching the index. Writes the table as a json line with
+/// `json_lines` when it matches the filter, as pretty printed json otherwise.
+fn index_single_file(
+    vpx_path: &Path,
+    json_lines: bool,
+    filter: &TableFilter,
+) -> io::Result<ExitCode> {
+    let config = config::load_config()?.map(|(_, config)| config);
+    let global_roms = match &config {
+        Some(config) => indexer::find_roms(&config.global_pinmame_rom_folder())?,
+        None => HashMap::new(),
+    };
+    let json_settings = config.as_ref().map(JsonSettings::from).unwrap_or_default();
+    let table = match indexer::index_file(vpx_path, &global_roms) {
+        Ok(table) => table,
+        Err(e) => return fail(e.to_string()),
+    };
+    if json_lines {
+        if table.matches(filter) {
+            write_json_line(&table, &json_settings)?;
+        }
+    } else {
+        println!("{:#}", table_json(&table, &json_settings)?)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Like [index_json_lines] but only writes a page of the tables sorted by path, which can't be
+/// streamed as the order is only known once indexing is done. The filter applies before paging.
+fn index_json_lines_page(
+    tables_folder_path: &Path,
+    tables_index_path: &Path,
+    options: &IndexOptions,
+    filter: &TableFilter,
+    json_settings: &JsonSettings,
+    offset: usize,
+    limit: Option<usize>,
+) -> io::Result<ExitCode> {
+    let index = indexer::index_folder(
+        tables_folder_path,
+        tables_index_path,
+        options,
+        Some(&mut report_index_errors),
+        vec![],
+    )?;
+    Index::from(&index)
+        .iter()
+        .filter(|table| table.matches(filter))
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .try_for_each(|table| write_json_line(table, json_settings))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The config settings that change how tables are written as json
+#[derive(Default)]
+pub(crate) struct JsonSettings {
+    weights: CompletenessWeights,
+    /// Paths are written relative to this folder when set, see [ResolvedConfig::path_base]
+    path_base: Option<PathBuf>,
+}
+
+impl From<&ResolvedConfig> for JsonSettings {
+    fn from(config: &ResolvedConfig) -> Self {
+        JsonSettings {
+            weights: config.completeness_weights,
+            path_base: config.path_base().map(Path::to_path_buf),
+        }
+    }
+}
+
+fn write_json_line(table: &IndexedTable, json_settings: &JsonSettings) -> io::Result<()> {
+    println!("{}", table_json(table, json_settings)?)
+}
+
+/// The table with its completeness score, see [completeness]
+pub(crate) fn table_json(
+    table: &IndexedTable,
+    json_settings: &JsonSettings,
+) -> io::Result<serde_json::Value> {
+    let mut json = match &json_settings.path_base {
+        Some(base) => serde_json::to_value(table.relative_to(base))?,
+        None => serde_json::to_value(table)?,
+    };
+    let score = completeness(table, &json_settings.weights, &table.warnings()).score;
+    if let Some(object) = json.as_object_mut() {
+        object.insert("completeness".to_string(), score.into());
+    }
+    Ok(json)
+}
+
+/// Writes every table as json to a sidecar next to it, see [table_json]. Existing sidecars are
+/// only rewritten with `update` and when their content changed.
+fn export_sidecars(
+    index: &Index,
+    json_settings: &JsonSettings,
+    update: bool,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let mut written = 0;
+    let mut updated = 0;
+    let mut unchanged = 0;
+    let mut failed = Vec::new();
+    // there is no folder to write to for tables in zip bundles
+    for table in index.iter().filter(|table| !table.archived) {
+        let sidecar_path = table.path.with_extension(TABLE_JSON_EXTENSION);
+        let json = format!(
+            "{}\n",
+            serde_json::to_string_pretty(&table_json(table, json_settings)?)?
+        );
+        let exists = sidecar_path.exists();
+        if exists
+            && (!update || std::fs::read_to_string(&sidecar_path).is_ok_and(|old| old == json))
+        {
+            unchanged += 1;
+            continue;
+        }
+        if dry_run {
+            println!("Would write {}", sidecar_path.display())?;
+        } else if let Err(e) = std::fs::write(&sidecar_path, json) {
+            failed.push((sidecar_path, e));
+            continue;
+        } else {
+            detail!("WRITTEN {}", sidecar_path.display())?;
+        }
+        if exists {
+            updated += 1;
+        } else {
+            written += 1;
+        }
+    }
+    let would = if dry_run { "would be " } else { "" };
+    status!(
+        "{} sidecars {}written, {} {}updated, {} left as they were",
+        written,
+        would,
+        updated,
+        would,
+        unchanged
+    )?;
+    for (path, e) in &failed {
+        let warning = format!("Error writing {}: {}", path.display(), e).red();
+        eprintln!("{}", warning)?;
+    }
+    if failed.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// The sidecar extensions and whether hidden sidecars count, the defaults without config
+fn sidecar_settings(config: Option<ResolvedConfig>) -> (Vec<String>, bool) {
+    match config {
+        Some(config) => (config.sidecar_extensions, config.include_hidden_sidecars),
+        None => (
+            indexer::DEFAULT_SIDECAR_EXTENSIONS
+                .map(String::from)
+                .to_vec(),
+            false,
+        ),
+    }
+}
+
+/// Renames the sidecar files with the old name of the table after confirmation, see
+/// [staging::sidecar_renames]. The renames are remembered in `record_path` for "Undo last move".
+fn rename_sidecars(
+    vpx_path: &Path,
+    sidecar_extensions: &[String],
+    include_hidden_sidecars: bool,
+    record_path: Option<&Path>,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let renames = staging::sidecar_renames(vpx_path, sidecar_extensions, include_hidden_sidecars)?;
+    if renames.is_empty() {
+        status!("No sidecar files to rename")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let file_name = |path: &Path| {
+        path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+    for (from, to) in &renames {
+        println!("{} -> {}", file_name(from), file_name(to))?;
+    }
+    if dry_run {
+        println!("Would rename {} files", renames.len())?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let msg = format!("{} files will be renamed", renames.len());
+    if !confirm(msg, "Rename them?".to_string())? {
+        println!("Aborted")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut failed = 0;
+    let mut renamed = Vec::new();
+    for (from, to) in renames {
+        match std::fs::rename(&from, &to) {
+            Ok(()) => renamed.push((from, to)),
+            Err(e) => {
+                failed += 1;
+                let warning = format!("Error renaming {}: {}", from.display(), e).red();
+                eprintln!("{}", warning)?;
+            }
+        }
+    }
+    if let Some(record_path) = record_path.filter(|_| !renamed.is_empty())
+        && let Err(e) = staging::record_last_move(record_path, &renamed)
+    {
+        log::warn!("Unable to remember the renames for undo: {}", e);
+    }
+    status!("Renamed {} files", renamed.len())?;
+    if failed == 0 {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Lists the sidecar files without table in the folders, see [indexer::find_orphaned_sidecars],
+/// and deletes them after confirmation with `delete`
+fn orphaned_sidecars(
+    folders: &[PathBuf],
+    sidecar_extensions: &[String],
+    include_hidden_sidecars: bool,
+    delete: bool,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let mut orphans = Vec::new();
+    for folder in folders {
+        orphans.extend(indexer::find_orphaned_sidecars(
+            folder,
+            sidecar_extensions,
+            include_hidden_sidecars,
+        )?);
+    }
+    if orphans.is_empty() {
+        status!("No orphaned sidecar files found")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    for orphan in &orphans {
+        println!("{}", orphan.display())?;
+    }
+    if !delete {
+        return Ok(ExitCode::SUCCESS);
+    }
+    if dry_run {
+        println!("Would delete {} files", orphans.len())?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let msg = format!("{} files will be deleted", orphans.len());
+    if !confirm(msg, "Delete them?".to_string())? {
+        println!("Aborted")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut failed = 0;
+    for orphan in &orphans {
+        if let Err(e) = std::fs::remove_file(orphan) {
+            failed += 1;
+            let warning = format!("Error deleting {}: {}", orphan.display(), e).red();
+            eprintln!("{}", warning)?;
+        }
+    }
+    status!("Deleted {} files", orphans.len() - failed)?;
+    if failed == 0 {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Indexes the tables folder like the frontend does whenever files in it change and prints the
+/// changes, until interrupted. Where there are no change notifications the folder is indexed
+/// every interval instead, see [FolderWatcher].
+fn watch_index(config: &ResolvedConfig, interval: Duration) -> io::Result<ExitCode> {
+    let mut folders = vec![config.tables_folder.as_path()];
+    folders.extend(config.staging_folder.as_deref());
+    let mut watcher =
+        FolderWatcher::new(&folders, true, interval).ignore(&config.tables_index_path);
+    if !watcher.has_notifications() {
+        status!("No change notifications, checking every {:?}", interval)?;
+    }
+    let mut previous: Option<TablesIndex> = None;
+    loop {
+        match frontend::index_tables_folder(config, true, vec![], Some(&mut report_index_errors)) {
+            Ok(index) => {
+                match &previous {
+                    Some(previous) => {
+                        print!(
+                            "{}",
+                            index_diff_summary(&index_diff::diff_indexes(previous, &index)?)
+                        )?;
+                    }
+                    None => status!(
+                        "Watching {} with {} tables",
+                        config.tables_folder.display(),
+                        index.len()
+                    )?,
+                }
+                previous = Some(index);
+            }
+            // a network share might be gone for a moment, the next round tries again
+            Err(e) => {
+                let warning = format!("Error indexing tables: {}", e).truecolor(255, 125, 0);
+                eprintln!("{}", warning)?;
+            }
+        }
+        watcher.wait()?;
+    }
+}
+
+/// Moves the tables in the inbox to the tables folder. With an interval the inbox is checked
+/// again until the process is stopped, otherwise the inbox is organized once.
+fn organize_inbox(
+    config: &ResolvedConfig,
+    interval: Option<Duration>,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let Some(inbox_folder) = &config.inbox_folder else {
+        return fail("No inbox folder, set inbox_folder in the config");
+    };
+    let options = InboxOptions {
+        tables_folder: &config.tables_folder,
+        name_template: config.inbox_name_template.as_ref(),
+        sidecar_extensions: &config.sidecar_extensions,
+        include_hidden_sidecars: config.include_hidden_sidecars,
+        dry_run,
+    };
+    // tables left in the inbox are only handled again once they change
+    let mut handled: HashMap<PathBuf, SystemTime> = HashMap::new();
+    let mut left_in_inbox = 0;
+    loop {
+        for vpx_file in inbox::inbox_tables(inbox_folder)? {
+            if handled.get(&vpx_file.path) == Some(&vpx_file.last_modified) {
+                continue;
+            }
+            // still downloading
+            if let Some(interval) = interval
+                && vpx_file
+                    .last_modified
+                    .elapsed()
+                    .is_ok_and(|age| age < interval)
+            {
+                continue;
+            }
+            handled.insert(vpx_file.path.clone(), vpx_file.last_modified);
+            let path = vpx_file.path.display();
+            match inbox::organize(&vpx_file, &options) {
+                InboxOutcome::Moved(moved) => println!("Moved {} to {}", path, moved.display())?,
+                InboxOutcome::WouldMove(target) => {
+                    println!("Would move {} to {}", path, target.display())?
+                }
+                InboxOutcome::Conflict(reason) | InboxOutcome::Failed(reason) => {
+                    left_in_inbox += 1;
+                    let warning =
+                        format!("Left in inbox {}: {}", path, reason).truecolor(255, 125, 0);
+                    eprintln!("{}", warning)?;
+                }
+            }
+        }
+        match interval {
+            Some(interval) => std::thread::sleep(interval),
+            None if left_in_inbox > 0 => return Ok(ExitCode::FAILURE),
+            None => return Ok(ExitCode::SUCCESS),
+        }
+    }
+}
+
+fn extract_thumbnails(
+    tables_folder_path: &Path,
+    output_dir: &Path,
+    max_size: Option<u32>,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let vpx_files = indexer::find_vpx_files(true, tables_folder_path)?;
+    if !dry_run {
+        std::fs::create_dir_all(output_dir)?;
+    }
+    let mut skipped = Vec::new();
+    for vpx_file in vpx_files {
+        let thumbnail = match thumbnails::table_thumbnail(&vpx_file.path) {
+            Ok(Some(thumbnail)) => thumbnail,
+            Ok(None) => {
+                skipped.push(vpx_file.path);
+                continue;
+            }
+            Err(e) => {
+                let warning = format!("Error reading {}: {}", vpx_file.path.display(), e)
+                    .truecolor(255, 125, 0);
+                eprintln!("{}", warning)?;
+                skipped.push(vpx_file.path);
+                continue;
+            }
+        };
+        let file_stem = vpx_file.path.file_stem().unwrap_or_default();
+        let thumbnail_path = output_dir.join(file_stem).with_extension("png");
+        if dry_run {
+            println!("Would write {}", thumbnail_path.display())?;
+            continue;
+        }
+        let thumbnail = match max_size {
+            Some(max_size) => thumbnails::limit_size(thumbnail, max_size),
+            None => thumbnail,
+        };
+        thumbnail.save(&thumbnail_path).map_err(|e| {
+            io::Error::other(format!(
+                "Failed to write {}: {}",
+                thumbnail_path.display(),
+                e
+            ))
+        })?;
+        println!("{}", thumbnail_path.display())?;
+    }
+    for path in skipped {
+        let warning = format!("No suitable image in {}", path.display()).truecolor(255, 125, 0);
+        println!("{}", warning)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Extracts the script of every table to `output_dir`, mirroring the folders in the tables folder.
+/// Tables without a script are skipped, failures are reported at the end.
+fn extract_all_scripts(
+    tables_folder_path: &Path,
+    output_dir: &Path,
+    force: bool,
+    dry_run: bool,
+) -> io::Result<ExitCode> {
+    let vpx_files = indexer::find_vpx_files(true, tables_folder_path)?;
+    let mut extracted = 0;
+    let mut existed = 0;
+    let mut without_script = 0;
+    let mut failed = Vec::new();
+    for vpx_file in vpx_files {
+        let relative = vpx_file
+            .path
+            .strip_prefix(tables_folder_path)
+            .unwrap_or(&vpx_file.path);
+        let vbs_path = output_dir.join(relative).with_extension("vbs");
+        let has_script = read_script(&vpx_file.path).map(|code| !code.string.trim().is_empty());
+        match has_script {
+            Ok(true) => {}
+            Ok(false) => {
+                without_script += 1;
+                continue;
+            }
+            Err(e) => {
+                failed.push((vpx_file.path, e));
+                continue;
+            }
+        }
+        if vbs_path.exists() && !force {
+            existed += 1;
+            continue;
+        }
+        let result = vbs_path
+            .parent()
+            .filter(|_| !dry_run)
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| extractvbs(&vpx_file.path, Some(vbs_path), true, dry_run));
+        match result {
+            Ok(ExtractResult::Extracted(vbs_path)) | Ok(ExtractResult::Existed(vbs_path)) => {
+                if dry_run {
+                    println!("Would create {}", vbs_path.display())?;
+                } else {
+                    status!("CREATED {}", vbs_path.display())?;
+                }
+                extracted += 1;
+            }
+            Err(e) => failed.push((vpx_file.path, e)),
+        }
+    }
+    status!(
+        "{} scripts extracted, {} already existed, {} tables without script, {} failed",
+        extracted,
+        existed,
+        without_script,
+        failed.len()
+    )?;
+    for (path, e) in &failed {
+        let warning = format!("Error extracting vbs from {}: {}", path.display(), e).red();
+        eprintln!("{}", warning)?;
+    }
+    if failed.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn read_index_snapshot(path: &Path) -> io::Result<indexer::TablesIndex> {
+    indexer::read_index(path)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unable to read index file {}", path.display()),
+        )
+    })
+}
+
+fn index_diff_summary(diff: &IndexDiff) -> String {
+    let mut buffer = String::new();
+    for path in &diff.added {
+        buffer.push_str(&format!("{}\t{}\n", "ADDED".green(), path.display()));
+    }
+    for path in &diff.removed {
+        buffer.push_str(&format!("{}\t{}\n", "REMOVED".red(), path.display()));
+    }
+    for table in &diff.changed {
+        buffer.push_str(&format!(
+            "{}\t{}\n",
+            "CHANGED".yellow(),
+            table.path.display()
+        ));
+        for change in &table.changes {
+            buffer.push_str(&format!(
+                "  {}: {} -> {}\n",
+                change.field, change.old, change.new
+            ));
+        }
+    }
+    buffer
+}
+
+/// Every table path followed by its indented warnings, and the totals
+fn warnings_report_text(report: &[TableWarnings]) -> String {
+    let mut buffer = String::new();
+    for table in report {
+        buffer.push_str(&format!("{}\n", table.path.display()));
+        for warning in &table.warnings {
+            buffer.push_str(&format!("  {}\n", warning.message.truecolor(255, 125, 0)));
+        }
+    }
+    let warnings = report
+        .iter()
+        .map(|table| table.warnings.len())
+        .sum::<usize>();
+    buffer.push_str(&format!(
+        "{} warnings in {} tables\n",
+        warnings,
+        report.len()
+    ));
+    buffer
+}
+
+fn stats_summary(stats: &CollectionStats) -> String {
+    let mut buffer = String::new();
+    buffer.push_str(&format!("{:>18} {}\n", "Tables:".green(), stats.tables));
+    buffer.push_str(&format!(
+        "{:>18} {}\n",
+        "Total size:".green(),
+        HumanBytes(stats.total_size)
+    ));
+    buffer.push_str(&format!(
+        "{:>18} {} ({} found, {} missing)\n",
+        "Requires rom:".green(),
+        stats.requires_rom,
+        stats.rom_found,
+        stats.rom_missing
+    ));
+    buffer.push_str(&format!(
+        "{:>18} {} ({} without)\n",
+        "With backglass:".green(),
+        stats.with_b2s,
+        stats.without_b2s
+    ));
+    buffer.push_str(&format!(
+        "{:>18} {}\n",
+        "With warnings:".green(),
+        stats.with_warnings
+    ));
+    buffer.push_str(&format!("{:>18}\n", "Manufacturers:".green()));
+    for (manufacturer, count) in &stats.per_manufacturer {
+        buffer.push_str(&format!("{:>18} {}\n", manufacturer, count));
+    }
+    buffer.push_str(&format!("{:>18}\n", "Decades:".green()));
+    for (decade, count) in &stats.per_decade {
+        buffer.push_str(&format!("{:>18} {}\n", decade, count));
+    }
+    buffer
+}
+
+fn info_extract(vpx_file_path: &Path, dry_run: bool) -> io::Result<ExitCode> {
     let info_file_path = vpx_file_path.with_extension("info.json");
-    if info_file_path.exists() {
+    let exists = info_file_path.exists();
+    if exists && !dry_run {
         let confirmed = confirm(
             format!("File \"{}\" already exists", info_file_path.display()),
             "Do you want to overwrite the existing file?".to_string(),
@@ -1337,25 +3189,41 @@ fn info_extract(vpx_file_path: &Path) -> io::Result<ExitCode> {
             return Ok(ExitCode::SUCCESS);
         }
     }
-    write_info_json(vpx_file_path, &info_file_path)?;
-    println!("Extracted table info to {}", info_file_path.display())?;
+    write_info_json(vpx_file_path, &info_file_path, dry_run)?;
+    if dry_run {
+        let overwrite = if exists {
+            " (overwriting existing file)"
+        } else {
+            ""
+        };
+        println!(
+            "Would extract table info to {}{}",
+            info_file_path.display(),
+            overwrite
+        )?;
+    } else {
+        status!("Extracted table info to {}", info_file_path.display())?;
+    }
     Ok(ExitCode::SUCCESS)
 }
 
-fn write_info_json(vpx_file_path: &Path, info_file_path: &Path) -> io::Result<()> {
+/// Writes the table info as json, with `dry_run` the table info is only read
+fn write_info_json(vpx_file_path: &Path, info_file_path: &Path, dry_run: bool) -> io::Result<()> {
     let mut vpx_file = vpx::open(vpx_file_path)?;
     let table_info = vpx_file.read_tableinfo()?;
     let custom_info_tags = vpx_file.read_custominfotags()?;
     let table_info_json = info_to_json(&table_info, &custom_info_tags);
-    let info_file = File::create(info_file_path)?;
-    serde_json::to_writer_pretty(info_file, &table_info_json)?;
+    if !dry_run {
+        let info_file = File::create(info_file_path)?;
+        serde_json::to_writer_pretty(info_file, &table_info_json)?;
+    }
     Ok(())
 }
 
 fn info_edit(vpx_file_path: &Path, config: Option<&ResolvedConfig>) -> io::Result<PathBuf> {
     let info_file_path = vpx_file_path.with_extension("info.json");
     if !info_file_path.exists() {
-        write_info_json(vpx_file_path, &info_file_path)?;
+        write_info_json(vpx_file_path, &info_file_path, false)?;
     }
     open_editor(&info_file_path, config)?;
     Ok(info_file_path)
@@ -1379,12 +3247,12 @@ fn open_configured_editor(file_to_edit: &Path, editor: &String) -> io::Result<()
                 Ok(())
             } else {
                 let warning = format!("Failed to open editor {}: {}", editor, status);
-                Err(io::Error::new(io::ErrorKind::Other, warning))
+                Err(io::Error::other(warning))
             }
         }
         Err(e) => {
             let warning = format!("Failed to open editor {}: {}", &editor, e);
-            Err(io::Error::new(io::ErrorKind::Other, warning))
+            Err(io::Error::other(warning))
         }
     }
 }
@@ -1425,7 +3293,7 @@ pub fn ls(vpx_file_path: &Path) -> io::Result<()> {
 pub fn confirm(msg: String, yes_no_question: String) -> io::Result<bool> {
     // TODO do we need to check for terminal here?
     //   let use_color = stdout().is_terminal();
-    let warning = msg.truecolor(255, 125, 0);
+    let warning = theme::error(&msg);
     println!("{}", warning)?;
     println!("{} (y/n)", yes_no_question)?;
     let mut input = String::new();
@@ -1433,12 +3301,38 @@ pub fn confirm(msg: String, yes_no_question: String) -> io::Result<bool> {
     Ok(input.trim() == "y")
 }
 
-pub fn extract(vpx_file_path: &Path, yes: bool) -> io::Result<ExitCode> {
+/// Extracts and assembles the table in the temp folder, fails if any stream of the assembled table
+/// differs from the original
+fn verify_roundtrip(vpx_path: &Path) -> io::Result<ExitCode> {
+    let work_dir = std::env::temp_dir().join(format!("vpxtool-roundtrip-{}", std::process::id()));
+    match roundtrip::roundtrip(vpx_path, &work_dir) {
+        Ok(differences) if differences.is_empty() => {
+            status!("{OK} {}", vpx_path.display())?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Ok(differences) => {
+            let warning = format!("{NOK} {}", vpx_path.display()).truecolor(255, 125, 0);
+            eprintln!("{}", warning)?;
+            for difference in differences {
+                eprintln!("  {}", difference)?;
+            }
+            Ok(ExitCode::FAILURE)
+        }
+        Err(e) => {
+            let warning = format!("{NOK} {} {}", vpx_path.display(), e).red();
+            eprintln!("{}", warning)?;
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+pub fn extract(vpx_file_path: &Path, yes: bool, dry_run: bool) -> io::Result<ExitCode> {
     let root_dir_path_str = vpx_file_path.with_extension("");
     let root_dir_path = Path::new(&root_dir_path_str);
+    let exists = root_dir_path.exists();
 
     // ask for confirmation if the directory exists
-    if root_dir_path.exists() && !yes {
+    if exists && !yes && !dry_run {
         let confirmed = confirm(
             format!("Directory \"{}\" already exists", root_dir_path.display()),
             "Do you want to remove the existing directory?".to_string(),
@@ -1448,19 +3342,29 @@ pub fn extract(vpx_file_path: &Path, yes: bool) -> io::Result<ExitCode> {
             return Ok(ExitCode::SUCCESS);
         }
     }
-    if root_dir_path.exists() {
+    let vpx = vpx::read(&vpx_file_path.to_path_buf())?;
+    if dry_run {
+        let replace = if exists {
+            " (replacing existing directory)"
+        } else {
+            ""
+        };
+        println!(
+            "Would extract to \"{}\"{}",
+            root_dir_path.display(),
+            replace
+        )?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    if exists {
         std::fs::remove_dir_all(root_dir_path)?;
     }
     let mut root_dir = std::fs::DirBuilder::new();
     root_dir.recursive(true);
     root_dir.create(root_dir_path)?;
-    let result = {
-        let vpx = vpx::read(&vpx_file_path.to_path_buf())?;
-        expanded::write(&vpx, &root_dir_path)
-    };
-    match result {
+    match expanded::write(&vpx, &root_dir_path) {
         Ok(_) => {
-            println!("Successfully extracted to \"{}\"", root_dir_path.display())?;
+            status!("Successfully extracted to \"{}\"", root_dir_path.display())?;
             Ok(ExitCode::SUCCESS)
         }
         Err(e) => fail(format!("Failed to extract: {}", e)),
@@ -1473,7 +3377,7 @@ pub fn info_diff(vpx_file_path: &Path) -> io::Result<String> {
     if info_file_path.exists() {
         let original_info_path =
             RemoveOnDrop::new(vpx_file_path.with_extension("info.original.tmp"));
-        write_info_json(&expanded_vpx_path, original_info_path.path())?;
+        write_info_json(&expanded_vpx_path, original_info_path.path(), false)?;
         let diff_color = if colored::control::SHOULD_COLORIZE.should_colorize() {
             DiffColor::Always
         } else {
@@ -1491,26 +3395,16 @@ pub fn script_diff(vpx_file_path: &Path) -> io::Result<String> {
     // set extension for PathBuf
     let vbs_path = vpx_file_path.with_extension("vbs");
     if vbs_path.exists() {
-        match vpx::open(vpx_file_path) {
-            Ok(mut vpx_file) => {
-                let gamedata = vpx_file.read_gamedata()?;
-                let script = gamedata.code;
-                let original_vbs_path =
-                    RemoveOnDrop::new(vpx_file_path.with_extension("vbs.original.tmp"));
-                std::fs::write(original_vbs_path.path(), script.string)?;
-                let diff_color = if colored::control::SHOULD_COLORIZE.should_colorize() {
-                    DiffColor::Always
-                } else {
-                    DiffColor::Never
-                };
-                let output = run_diff(original_vbs_path.path(), &vbs_path, diff_color)?;
-                Ok(String::from_utf8_lossy(&output).to_string())
-            }
-            Err(e) => {
-                let msg = format!("Not a valid vpx file {}: {}", vpx_file_path.display(), e);
-                Err(io::Error::new(io::ErrorKind::InvalidData, msg))
-            }
-        }
+        let script = read_script(vpx_file_path)?;
+        let original_vbs_path = RemoveOnDrop::new(vpx_file_path.with_extension("vbs.original.tmp"));
+        std::fs::write(original_vbs_path.path(), script.string)?;
+        let diff_color = if colored::control::SHOULD_COLORIZE.should_colorize() {
+            DiffColor::Always
+        } else {
+            DiffColor::Never
+        };
+        let output = run_diff(original_vbs_path.path(), &vbs_path, diff_color)?;
+        Ok(String::from_utf8_lossy(&output).to_string())
     } else {
         // wrap the error
         let msg = format!("No sidecar vbs file found: {}", vbs_path.display());
@@ -1586,7 +3480,7 @@ pub fn run_diff(
             "Failed to run 'diff'. Is it installed on your system? {}",
             e
         );
-        io::Error::new(io::ErrorKind::Other, msg)
+        io::Error::other(msg)
     })
 }
 
@@ -1610,6 +3504,7 @@ fn show_dip_switches(nvram: &PathBuf) -> io::Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use testdir::testdir;
 
     #[test]
     fn test_os_independent_file_name_windows() {
@@ -1652,4 +3547,140 @@ mod tests {
         let result = os_independent_file_name(file_path.to_string());
         assert_eq!(result, None);
     }
+
+    #[test]
+    fn test_extract_dry_run_writes_nothing() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = dir.join("test.vpx");
+        vpx::new_minimal_vpx(&vpx_path)?;
+        extract(&vpx_path, true, true)?;
+        assert!(!vpx_path.with_extension("").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_all_scripts() -> io::Result<()> {
+        let tables_dir = testdir!();
+        let sub_dir = tables_dir.join("sub");
+        std::fs::create_dir_all(&sub_dir)?;
+        vpxtool_shared::fixtures::VpxFixture::new()
+            .file_name("scripted.vpx")
+            .script("Option Explicit")
+            .build_in(&sub_dir)?;
+        vpxtool_shared::fixtures::VpxFixture::new()
+            .file_name("empty.vpx")
+            .build_in(&tables_dir)?;
+        let output_dir = tables_dir.join("scripts");
+        let exit_code = extract_all_scripts(&tables_dir, &output_dir, false, false)?;
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+        assert!(output_dir.join("sub").join("scripted.vbs").exists());
+        assert!(!output_dir.join("empty.vbs").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_sidecars() -> io::Result<()> {
+        let tables_dir = testdir!();
+        let vpx_path = vpxtool_shared::fixtures::VpxFixture::new()
+            .file_name("Table.vpx")
+            .table_name("Attack from Mars")
+            .build_in(&tables_dir)?;
+        let index = indexer::index_folder(
+            &tables_dir,
+            &tables_dir.join("vpxtool_index.json"),
+            &IndexOptions::default(),
+            None,
+            vec![],
+        )?;
+        let index = Index::from(&index);
+        let json_settings = JsonSettings::default();
+        let sidecar_path = vpx_path.with_extension(TABLE_JSON_EXTENSION);
+
+        export_sidecars(&index, &json_settings, false, true)?;
+        let written_in_dry_run = sidecar_path.exists();
+        export_sidecars(&index, &json_settings, false, false)?;
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&sidecar_path)?)?;
+        std::fs::write(&sidecar_path, "{}")?;
+        export_sidecars(&index, &json_settings, false, false)?;
+        let kept = std::fs::read_to_string(&sidecar_path)?;
+        export_sidecars(&index, &json_settings, true, false)?;
+        let updated = std::fs::read_to_string(&sidecar_path)?;
+        let relative_settings = JsonSettings {
+            path_base: Some(tables_dir.clone()),
+            ..JsonSettings::default()
+        };
+        let relative_json = table_json(index.iter().next().unwrap(), &relative_settings)?;
+
+        assert!(!written_in_dry_run);
+        assert_eq!(json["table_info"]["table_name"], "Attack from Mars");
+        assert!(json["completeness"].is_u64());
+        assert_eq!(kept, "{}");
+        assert_ne!(updated, "{}");
+        assert_eq!(json["path"], vpx_path.to_string_lossy().as_ref());
+        assert_eq!(
+            relative_json["path"],
+            vpx_path.file_name().unwrap().to_string_lossy().as_ref()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_repair_checksum() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = vpxtool_shared::fixtures::VpxFixture::new()
+            .file_name("broken.vpx")
+            .script("Option Explicit")
+            .build_in(&dir)?;
+        let already_valid = repair_checksum(&vpx_path, true, false)?;
+        {
+            let mut comp = cfb::open_rw(&vpx_path)?;
+            let mut mac = comp.open_stream("/GameStg/MAC")?;
+            mac.write_all(&[0u8; 16])?;
+        }
+        let planned = repair_checksum(&vpx_path, true, true)?;
+        let broken = verify(&vpx_path);
+        let backup_in_dry_run = dir.join("broken.vpx.bak").exists();
+        let repaired = repair_checksum(&vpx_path, true, false)?;
+
+        assert_eq!(already_valid, ChecksumRepair::AlreadyValid);
+        assert!(matches!(broken, VerifyResult::Failed(..)));
+        assert!(!backup_in_dry_run);
+        assert_eq!(planned, repaired);
+        assert_eq!(
+            repaired,
+            ChecksumRepair::Repaired {
+                backup_path: Some(dir.join("broken.vpx.bak"))
+            }
+        );
+        assert_eq!(verify(&vpx_path), VerifyResult::Ok(vpx_path.clone()));
+        assert!(dir.join("broken.vpx.bak").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_romname() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = vpxtool_shared::fixtures::VpxFixture::new()
+            .file_name("rom.vpx")
+            .build_in(&dir)?;
+        let written = write_romname(&vpx_path, "afm_113b", true)?;
+        let again = write_romname(&vpx_path, "afm_113b", false)?;
+        let vpx = vpx::read(&vpx_path)?;
+
+        assert_eq!(
+            written,
+            RomNameWrite::Written {
+                backup_path: Some(dir.join("rom.vpx.bak"))
+            }
+        );
+        assert_eq!(again, RomNameWrite::AlreadySet);
+        assert_eq!(
+            vpx.info.properties.get(ROM_NAME_PROPERTY),
+            Some(&"afm_113b".to_string())
+        );
+        assert_eq!(vpx.custominfotags, vec![ROM_NAME_PROPERTY.to_string()]);
+        assert_eq!(verify(&vpx_path), VerifyResult::Ok(vpx_path.clone()));
+        Ok(())
+    }
 }
@@ -5,7 +5,7 @@ use std::collections::HashSet;
 use std::fmt::Display;
 use std::fs::File;
 use std::io;
-use std::io::{Read, Write};
+use std::io::Write;
 use std::path::Path;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -28,16 +28,19 @@ impl Display for PatchType {
     }
 }
 
-pub fn patch_vbs_file(vbs_path: &Path) -> io::Result<HashSet<PatchType>> {
-    // TODO we probably need to ensure proper encoding here in stead of going for utf8
-    let mut file = File::open(vbs_path)?;
-    let mut text = String::new();
-    file.read_to_string(&mut text)?;
-
-    let (patched_text, applied) = patch_script(text);
-
-    let mut file = File::create(vbs_path)?;
-    file.write_all(patched_text.as_bytes())?;
+/// Patches the script and writes it to `vbs_path`, with `dry_run` only the patches that would be
+/// applied are returned
+pub fn patch_vbs_file(
+    vbs_path: &Path,
+    script: String,
+    dry_run: bool,
+) -> io::Result<HashSet<PatchType>> {
+    let (patched_text, applied) = patch_script(script);
+
+    if !dry_run {
+        let mut file = File::create(vbs_path)?;
+        file.write_all(patched_text.as_bytes())?;
+    }
     Ok(applied)
 }
 
@@ -46,17 +49,19 @@ pub fn patch_vbs_file(vbs_path: &Path) -> io::Result<HashSet<PatchType>> {
  * This is needed because some vbs files have mixed line endings, which causes
  * the vbs parser to fail.
  * One example is [Aztec (Williams 1976) 1.3 by jipeji16](https://www.vpforums.org/index.php?app=downloads&showfile=15768)
+ * With `dry_run` nothing is written.
  */
-pub fn unify_line_endings_vbs_file(vbs_path: &Path) -> io::Result<LineEndingsResult> {
-    // TODO we probably need to ensure proper encoding here in stead of going for utf8
-    let mut file = File::open(vbs_path)?;
-    let mut text = String::new();
-    file.read_to_string(&mut text)?;
-
+pub fn unify_line_endings_vbs_file(
+    vbs_path: &Path,
+    text: String,
+    dry_run: bool,
+) -> io::Result<LineEndingsResult> {
     let patched_text = unify_line_endings(&text);
 
-    let mut file = File::create(vbs_path)?;
-    file.write_all(patched_text.as_bytes())?;
+    if !dry_run {
+        let mut file = File::create(vbs_path)?;
+        file.write_all(patched_text.as_bytes())?;
+    }
 
     if text != patched_text {
         Ok(LineEndingsResult::Unified)
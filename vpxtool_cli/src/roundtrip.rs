@@ -0,0 +1,143 @@
+//! Extracts a table and assembles it again to check that nothing gets lost on the way.
+//!
+//! The assembled file is compared to the original stream by stream, the layout of the compound
+//! file itself is allowed to differ.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use vpin::vpx;
+use vpin::vpx::expanded;
+
+#[derive(PartialEq, Debug)]
+pub(crate) enum StreamDifference {
+    /// The stream is in the original but not in the assembled table
+    Missing(String),
+    /// The stream is only in the assembled table
+    Added(String),
+    /// The stream content differs
+    Changed(String),
+}
+
+impl std::fmt::Display for StreamDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamDifference::Missing(stream) => write!(f, "{} missing", stream),
+            StreamDifference::Added(stream) => write!(f, "{} added", stream),
+            StreamDifference::Changed(stream) => write!(f, "{} changed", stream),
+        }
+    }
+}
+
+/// Extracts the table to `work_dir`, assembles it from there and compares the streams of the
+/// result with the original. `work_dir` is removed afterwards.
+pub(crate) fn roundtrip(vpx_path: &Path, work_dir: &Path) -> io::Result<Vec<StreamDifference>> {
+    if work_dir.exists() {
+        std::fs::remove_dir_all(work_dir)?;
+    }
+    std::fs::create_dir_all(work_dir)?;
+    let result = roundtrip_in(vpx_path, work_dir);
+    let _ = std::fs::remove_dir_all(work_dir);
+    result
+}
+
+fn roundtrip_in(vpx_path: &Path, work_dir: &Path) -> io::Result<Vec<StreamDifference>> {
+    let expanded_dir = work_dir.join("expanded");
+    std::fs::create_dir_all(&expanded_dir)?;
+    let vpx = vpx::read(&vpx_path.to_path_buf())?;
+    expanded::write(&vpx, &expanded_dir).map_err(|e| io::Error::other(e.to_string()))?;
+    let assembled = expanded::read(&expanded_dir)?;
+    let assembled_path = work_dir.join("assembled.vpx");
+    vpx::write(&assembled_path, &assembled)?;
+    Ok(compare_streams(
+        &read_streams(vpx_path)?,
+        &read_streams(&assembled_path)?,
+    ))
+}
+
+/// All streams in the compound file keyed by their path
+fn read_streams(path: &Path) -> io::Result<BTreeMap<String, Vec<u8>>> {
+    let mut comp = cfb::CompoundFile::open(File::open(path)?)?;
+    let stream_paths = comp
+        .walk()
+        .filter(|entry| entry.is_stream())
+        .map(|entry| entry.path().to_path_buf())
+        .collect::<Vec<PathBuf>>();
+    let mut streams = BTreeMap::new();
+    for stream_path in stream_paths {
+        let mut data = Vec::new();
+        comp.open_stream(&stream_path)?.read_to_end(&mut data)?;
+        streams.insert(stream_path.to_string_lossy().to_string(), data);
+    }
+    Ok(streams)
+}
+
+fn compare_streams(
+    original: &BTreeMap<String, Vec<u8>>,
+    assembled: &BTreeMap<String, Vec<u8>>,
+) -> Vec<StreamDifference> {
+    let mut differences = Vec::new();
+    for (path, data) in original {
+        match assembled.get(path) {
+            None => differences.push(StreamDifference::Missing(path.clone())),
+            Some(assembled_data) if assembled_data != data => {
+                differences.push(StreamDifference::Changed(path.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    differences.extend(
+        assembled
+            .keys()
+            .filter(|path| !original.contains_key(*path))
+            .map(|path| StreamDifference::Added(path.clone())),
+    );
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use testdir::testdir;
+    use vpxtool_shared::fixtures::VpxFixture;
+
+    #[test]
+    fn test_roundtrip() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = VpxFixture::new()
+            .file_name("roundtrip.vpx")
+            .script("Option Explicit")
+            .wall("Wall1")
+            .image("playfield")
+            .build_in(&dir)?;
+        let work_dir = dir.join("work");
+        let unchanged = roundtrip(&vpx_path, &work_dir)?;
+
+        assert_eq!(unchanged, Vec::new());
+        assert!(!work_dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_streams() -> io::Result<()> {
+        let original = BTreeMap::from([
+            ("/GameStg/GameData".to_string(), vec![1, 2]),
+            ("/GameStg/Image0".to_string(), vec![3]),
+        ]);
+        let mut assembled = original.clone();
+        assembled.remove("/GameStg/Image0");
+        assembled.insert("/GameStg/GameData".to_string(), vec![1]);
+        assembled.insert("/GameStg/Sound0".to_string(), vec![4]);
+        let mut text = Vec::new();
+        for difference in compare_streams(&original, &assembled) {
+            writeln!(text, "{}", difference)?;
+        }
+        assert_eq!(
+            String::from_utf8_lossy(&text),
+            "/GameStg/GameData changed\n/GameStg/Image0 missing\n/GameStg/Sound0 added\n"
+        );
+        Ok(())
+    }
+}
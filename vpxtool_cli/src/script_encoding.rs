@@ -0,0 +1,334 @@
+//! Script extraction and import that keep the original encoding of the table script.
+//!
+//! Older tables store their script in Latin-1 and some scripts start with a UTF-8 byte order
+//! mark. Extracted `.vbs` files are always UTF-8 without BOM. On import the script is written
+//! back in the encoding the table used, as long as the edited script can be represented in it.
+//!
+//! In every VPX version the script is the CODE record of the `GameStg/GameData` stream, next to
+//! the other table settings. The version in `GameStg/Version` tells how to parse that stream.
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use vpin::vpx;
+use vpin::vpx::ExtractResult;
+use vpin::vpx::model::{StringEncoding, StringWithEncoding};
+
+const BOM: char = '\u{feff}';
+
+/// The streams needed to read the script, stream names in compound files are case-insensitive
+const SCRIPT_STREAMS: [&str; 2] = ["/GameStg/Version", "/GameStg/GameData"];
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) struct ScriptEncoding {
+    pub latin1: bool,
+    pub bom: bool,
+}
+
+impl ScriptEncoding {
+    pub fn detect(code: &StringWithEncoding) -> ScriptEncoding {
+        ScriptEncoding {
+            latin1: code.encoding == StringEncoding::Latin1,
+            bom: code.string.starts_with(BOM),
+        }
+    }
+}
+
+impl Display for ScriptEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.latin1, self.bom) {
+            (true, true) => write!(f, "Latin-1 with BOM"),
+            (true, false) => write!(f, "Latin-1"),
+            (false, true) => write!(f, "UTF-8 with BOM"),
+            (false, false) => write!(f, "UTF-8"),
+        }
+    }
+}
+
+/// Decodes a script file as UTF-8, falling back to Latin-1, without the BOM
+pub(crate) fn decode_script_file(bytes: &[u8]) -> (String, ScriptEncoding) {
+    let (script, latin1) = match std::str::from_utf8(bytes) {
+        Ok(script) => (script.to_string(), false),
+        // Latin-1 maps every byte to the code point with the same value
+        Err(_) => (bytes.iter().map(|&b| b as char).collect(), true),
+    };
+    let bom = script.starts_with(BOM);
+    let script = script.trim_start_matches(BOM).to_string();
+    (script, ScriptEncoding { latin1, bom })
+}
+
+/// The script as it should be stored in a table that used the `original` encoding.
+///
+/// Falls back to UTF-8 if the script contains characters Latin-1 can't represent.
+pub(crate) fn encode_for_table(script: &str, original: ScriptEncoding) -> StringWithEncoding {
+    let script = script.trim_start_matches(BOM);
+    let string = if original.bom {
+        format!("{}{}", BOM, script)
+    } else {
+        script.to_string()
+    };
+    let fits_latin1 = string.chars().all(|c| (c as u32) <= 0xff);
+    let encoding = if original.latin1 && fits_latin1 {
+        StringEncoding::Latin1
+    } else {
+        StringEncoding::Utf8
+    };
+    StringWithEncoding { encoding, string }
+}
+
+/// The script of the table. Files without the streams holding the script, like other compound
+/// files or damaged tables, fail with the list of streams they do have.
+pub(crate) fn read_script(vpx_file_path: &Path) -> io::Result<StringWithEncoding> {
+    let streams = stream_paths(vpx_file_path)?;
+    let missing = SCRIPT_STREAMS
+        .iter()
+        .filter(|name| {
+            !streams
+                .iter()
+                .any(|stream| stream.eq_ignore_ascii_case(name))
+        })
+        .copied()
+        .collect::<Vec<&str>>();
+    if !missing.is_empty() {
+        let found = if streams.is_empty() {
+            "none".to_string()
+        } else {
+            streams.join(", ")
+        };
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "No table script in {}, missing {}. Streams found: {}",
+                vpx_file_path.display(),
+                missing.join(", "),
+                found
+            ),
+        ));
+    }
+    Ok(vpx::open(vpx_file_path)?.read_gamedata()?.code)
+}
+
+/// All streams in the compound file, like `/GameStg/GameData`
+fn stream_paths(vpx_file_path: &Path) -> io::Result<Vec<String>> {
+    let comp = cfb::CompoundFile::open(File::open(vpx_file_path)?).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Not a vpx file {}: {}", vpx_file_path.display(), e),
+        )
+    })?;
+    Ok(comp
+        .walk()
+        .filter(|entry| entry.is_stream())
+        .map(|entry| entry.path().to_string_lossy().replace('\\', "/"))
+        .collect())
+}
+
+pub(crate) fn script_encoding(vpx_file_path: &Path) -> io::Result<ScriptEncoding> {
+    Ok(ScriptEncoding::detect(&read_script(vpx_file_path)?))
+}
+
+/// Like [vpx::extractvbs] but always writes UTF-8 without BOM.
+/// With `dry_run` the script is read but the file is not written.
+pub(crate) fn extractvbs(
+    vpx_file_path: &PathBuf,
+    vbs_file_path: Option<PathBuf>,
+    overwrite: bool,
+    dry_run: bool,
+) -> io::Result<ExtractResult> {
+    let script_path = vbs_file_path.unwrap_or_else(|| vpx::vbs_path_for(vpx_file_path));
+    if script_path.exists() && !overwrite {
+        return Ok(ExtractResult::Existed(script_path));
+    }
+    let code = read_script(vpx_file_path)?;
+    if !dry_run {
+        std::fs::write(&script_path, code.string.trim_start_matches(BOM))?;
+    }
+    Ok(ExtractResult::Extracted(script_path))
+}
+
+/// The script [extractvbs] leaves in `vbs_file_path`, the file itself if it already exists. In a
+/// dry run the file might not have been written, then the script comes from the table.
+pub(crate) fn extracted_script(vpx_file_path: &Path, vbs_file_path: &Path) -> io::Result<String> {
+    if vbs_file_path.exists() {
+        std::fs::read_to_string(vbs_file_path)
+    } else {
+        let code = read_script(vpx_file_path)?;
+        Ok(code.string.trim_start_matches(BOM).to_string())
+    }
+}
+
+/// Like [vpx::importvbs] but keeps the encoding and BOM of the script in the table.
+/// Returns the path of the imported script and the encoding it was stored in, or would be stored
+/// in with `dry_run`.
+pub(crate) fn importvbs(
+    vpx_file_path: &PathBuf,
+    vbs_file_path: Option<PathBuf>,
+    dry_run: bool,
+) -> io::Result<(PathBuf, ScriptEncoding)> {
+    let script_path = vbs_file_path.unwrap_or_else(|| vpx::vbs_path_for(vpx_file_path));
+    if !script_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Script file not found: {}", script_path.display()),
+        ));
+    }
+    let (script, file_encoding) = decode_script_file(&std::fs::read(&script_path)?);
+    let original = script_encoding(vpx_file_path)?;
+    let plain_utf8 = ScriptEncoding {
+        latin1: false,
+        bom: false,
+    };
+    if original == plain_utf8 && file_encoding == plain_utf8 {
+        // nothing to convert, only the script stream needs updating
+        if !dry_run {
+            vpx::importvbs(vpx_file_path, Some(script_path.clone()))?;
+        }
+        return Ok((script_path, plain_utf8));
+    }
+    let mut vpx = vpx::read(vpx_file_path)?;
+    vpx.gamedata.code = encode_for_table(&script, original);
+    let stored = ScriptEncoding::detect(&vpx.gamedata.code);
+    if !dry_run {
+        vpx::write(vpx_file_path, &vpx)?;
+    }
+    Ok((script_path, stored))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use testdir::testdir;
+    use vpxtool_shared::fixtures::VpxFixture;
+
+    #[test]
+    fn test_decode_script_file() {
+        let utf8_bom = "\u{feff}' café".as_bytes();
+        assert_eq!(
+            decode_script_file(utf8_bom),
+            (
+                "' café".to_string(),
+                ScriptEncoding {
+                    latin1: false,
+                    bom: true
+                }
+            )
+        );
+        assert_eq!(
+            decode_script_file(b"' caf\xe9"),
+            (
+                "' café".to_string(),
+                ScriptEncoding {
+                    latin1: true,
+                    bom: false
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_for_table() {
+        let latin1 = ScriptEncoding {
+            latin1: true,
+            bom: false,
+        };
+        assert_eq!(
+            encode_for_table("' café", latin1).encoding,
+            StringEncoding::Latin1
+        );
+        // can't be represented in Latin-1
+        assert_eq!(
+            encode_for_table("' ★", latin1).encoding,
+            StringEncoding::Utf8
+        );
+        let bom = ScriptEncoding {
+            latin1: false,
+            bom: true,
+        };
+        assert_eq!(encode_for_table("' x", bom).string, "\u{feff}' x");
+    }
+
+    #[test]
+    fn test_extract_import_keeps_latin1() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = VpxFixture::new().file_name("latin1.vpx").build_in(&dir)?;
+        let mut vpx = vpx::read(&vpx_path)?;
+        vpx.gamedata.code = StringWithEncoding {
+            encoding: StringEncoding::Latin1,
+            string: "' café".to_string(),
+        };
+        vpx::write(&vpx_path, &vpx)?;
+
+        extractvbs(&vpx_path, None, true, false)?;
+        let vbs_path = vpx::vbs_path_for(&vpx_path);
+        let extracted = std::fs::read_to_string(&vbs_path)?;
+        std::fs::write(&vbs_path, "' crème brûlée")?;
+        let (_, stored) = importvbs(&vpx_path, None, false)?;
+        let code = vpx::read(&vpx_path)?.gamedata.code;
+
+        assert_eq!(extracted, "' café");
+        assert_eq!(
+            stored,
+            ScriptEncoding {
+                latin1: true,
+                bom: false
+            }
+        );
+        assert_eq!(code.encoding, StringEncoding::Latin1);
+        assert_eq!(code.string, "' crème brûlée");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_script_versions() -> io::Result<()> {
+        let dir = testdir!();
+        // 10.6 and 10.8 tables, the game data records differ between them
+        let mut scripts = Vec::new();
+        for version in [1060, 1080] {
+            let vpx_path = VpxFixture::new()
+                .file_name(format!("v{}.vpx", version))
+                .version(version)
+                .script(format!("' table version {}", version))
+                .build_in(&dir)?;
+            scripts.push(read_script(&vpx_path)?.string);
+        }
+
+        assert_eq!(
+            scripts,
+            vec!["' table version 1060", "' table version 1080"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_script_missing_stream() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = dir.join("no_script.vpx");
+        let mut comp = cfb::create(&vpx_path)?;
+        comp.create_storage("/GameStg")?;
+        comp.create_stream("/GameStg/Version")?
+            .write_all(&1080u32.to_le_bytes())?;
+        comp.create_storage("/TableInfo")?;
+        comp.create_stream("/TableInfo/TableName")?;
+        comp.flush()?;
+        drop(comp);
+        let not_vpx_path = dir.join("not_vpx.vpx");
+        std::fs::write(&not_vpx_path, "not a compound file")?;
+
+        let missing = read_script(&vpx_path).unwrap_err();
+        let not_vpx = read_script(&not_vpx_path).unwrap_err();
+
+        assert_eq!(missing.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            missing.to_string(),
+            format!(
+                "No table script in {}, missing /GameStg/GameData. \
+                 Streams found: /GameStg/Version, /TableInfo/TableName",
+                vpx_path.display()
+            )
+        );
+        assert_eq!(not_vpx.kind(), io::ErrorKind::InvalidData);
+        Ok(())
+    }
+}
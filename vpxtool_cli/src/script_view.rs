@@ -0,0 +1,206 @@
+//! Read-only view of a table script in the frontend, with basic VBScript highlighting.
+//!
+//! Scripts are read from the table on first use and cached for the session, the cache entry is
+//! dropped when the table file changes, for example after importing an edited script.
+use crate::script_encoding::read_script;
+use crate::theme;
+use dialoguer::{Input, Select};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// Script lines per table, with the modification time of the table they were read at
+type ScriptCache = HashMap<PathBuf, (SystemTime, Arc<Vec<String>>)>;
+
+static SCRIPT_CACHE: LazyLock<Mutex<ScriptCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const KEYWORDS: [&str; 54] = [
+    "and", "as", "byref", "byval", "call", "case", "class", "const", "dim", "do", "each", "else",
+    "elseif", "empty", "end", "error", "exit", "explicit", "false", "for", "function", "get",
+    "goto", "if", "in", "is", "let", "loop", "me", "mod", "new", "next", "not", "nothing", "null",
+    "on", "option", "or", "preserve", "private", "property", "public", "redim", "resume", "select",
+    "set", "step", "sub", "then", "to", "true", "until", "wend", "while",
+];
+
+/// Shows the script of the table until the user goes back
+pub(crate) fn show_script(vpx_path: &Path) -> io::Result<()> {
+    let lines = script_lines(vpx_path)?;
+    let width = lines.len().to_string().len();
+    let mut items = vec!["Back".to_string(), "Search".to_string()];
+    items.extend(lines.iter().enumerate().map(|(nr, line)| {
+        let nr = format!("{:>width$}", nr + 1, width = width);
+        format!("{} {}", theme::secondary(&nr), highlight_line(line))
+    }));
+    let file_name = vpx_path.file_name().unwrap_or_default().to_string_lossy();
+    let mut status = String::new();
+    let mut query = String::new();
+    let mut default = 0;
+    loop {
+        let selection = Select::with_theme(&theme::menu_theme())
+            .with_prompt(format!("{} ({} lines){}", file_name, lines.len(), status))
+            .default(default)
+            .items(&items)
+            .interact_opt()
+            .map_err(io::Error::other)?;
+        match selection {
+            None | Some(0) => return Ok(()),
+            Some(1) => {
+                query = Input::with_theme(&theme::menu_theme())
+                    .with_prompt("Search")
+                    .with_initial_text(&query)
+                    .allow_empty(true)
+                    .interact_text()
+                    .map_err(io::Error::other)?;
+                // from the selected line, or from the top
+                let from = default
+                    .checked_sub(2)
+                    .unwrap_or(lines.len().saturating_sub(1));
+                match find_next(&lines, &query, from) {
+                    Some(found) => {
+                        default = found + 2;
+                        status = format!(" [{}: line {}]", query, found + 1);
+                    }
+                    None => {
+                        default = 1;
+                        status = if query.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" [{}: not found]", query)
+                        };
+                    }
+                }
+            }
+            // selecting a line searches again from there
+            Some(selected) => {
+                default = selected;
+                if let Some(found) = find_next(&lines, &query, selected - 2) {
+                    default = found + 2;
+                    status = format!(" [{}: line {}]", query, found + 1);
+                }
+            }
+        }
+    }
+}
+
+/// The index of the first line after `from` containing `query`, ignoring case and wrapping
+/// around at the end of the script
+fn find_next(lines: &[String], query: &str, from: usize) -> Option<usize> {
+    if query.is_empty() || lines.is_empty() {
+        return None;
+    }
+    let query = query.to_lowercase();
+    (1..=lines.len())
+        .map(|offset| (from + offset) % lines.len())
+        .find(|&index| lines[index].to_lowercase().contains(&query))
+}
+
+/// The script lines of the table, from the cache if the table didn't change
+pub(crate) fn script_lines(vpx_path: &Path) -> io::Result<Arc<Vec<String>>> {
+    let modified = vpx_path.metadata()?.modified()?;
+    let mut cache = SCRIPT_CACHE
+        .lock()
+        .map_err(|_| io::Error::other("Script cache lock poisoned"))?;
+    if let Some((cached_modified, lines)) = cache.get(vpx_path)
+        && *cached_modified == modified
+    {
+        return Ok(lines.clone());
+    }
+    let code = read_script(vpx_path)?.string;
+    let lines = Arc::new(
+        code.trim_start_matches('\u{feff}')
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect::<Vec<String>>(),
+    );
+    cache.insert(vpx_path.to_path_buf(), (modified, lines.clone()));
+    Ok(lines)
+}
+
+/// Colors keywords, string literals and comments of a single VBScript line
+pub(crate) fn highlight_line(line: &str) -> String {
+    let mut highlighted = String::new();
+    let mut word = String::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            let word_ends = chars
+                .peek()
+                .is_none_or(|(_, next)| !(next.is_alphanumeric() || *next == '_'));
+            if !word_ends {
+                continue;
+            }
+            let lower = word.to_lowercase();
+            let start = index + c.len_utf8() - word.len();
+            if lower == "rem" && is_statement_start(line, start) {
+                highlighted.push_str(&theme::secondary(&line[start..]).to_string());
+                return highlighted;
+            }
+            if KEYWORDS.contains(&lower.as_str()) {
+                highlighted.push_str(&theme::code_keyword(&word).to_string());
+            } else {
+                highlighted.push_str(&word);
+            }
+            word.clear();
+        } else if c == '\'' {
+            highlighted.push_str(&theme::secondary(&line[index..]).to_string());
+            return highlighted;
+        } else if c == '"' {
+            // a doubled quote inside a string is an escaped quote, which the next literal
+            // picks up, so the end result is the same
+            let end = line[index + 1..]
+                .find('"')
+                .map(|end| index + 1 + end + 1)
+                .unwrap_or(line.len());
+            highlighted.push_str(&theme::code_string(&line[index..end]).to_string());
+            while chars.peek().is_some_and(|(next, _)| *next < end) {
+                chars.next();
+            }
+        } else {
+            highlighted.push(c);
+        }
+    }
+    highlighted
+}
+
+/// `Rem` only starts a comment at the start of a statement
+fn is_statement_start(line: &str, index: usize) -> bool {
+    let before = line[..index].trim_end();
+    before.is_empty() || before.ends_with(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_highlight_line_keeps_text() {
+        let lines = [
+            "Sub Table1_Init : Dim x : x = \"a 'quoted' \"\"value\"\"\" ' comment",
+            "  Rem old code",
+            "Const cGameName = \"afm_113b\"",
+            "unterminated = \"oops",
+        ];
+        for line in lines {
+            assert_eq!(console::strip_ansi_codes(&highlight_line(line)), line);
+        }
+    }
+
+    #[test]
+    fn test_find_next() {
+        let lines = vec![
+            "Option Explicit".to_string(),
+            "Sub Table1_Init".to_string(),
+            "End Sub".to_string(),
+        ];
+        assert_eq!(find_next(&lines, "sub", 0), Some(1));
+        assert_eq!(find_next(&lines, "sub", 1), Some(2));
+        // wraps around
+        assert_eq!(find_next(&lines, "sub", 2), Some(1));
+        assert_eq!(find_next(&lines, "missing", 0), None);
+        assert_eq!(find_next(&lines, "", 0), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Styles used by the text based frontend.
+//!
+//! The high contrast variant only uses black, white and bright yellow. Selection is shown
+//! reversed (black on yellow) so it stays distinguishable from yellow warnings.
+use colored::{ColoredString, Colorize};
+use console::{Style, style};
+use dialoguer::theme::ColorfulTheme;
+use std::sync::atomic::{AtomicBool, Ordering};
+use vpxtool_shared::display::{Rgb, RowState};
+
+static HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_high_contrast(high_contrast: bool) {
+    HIGH_CONTRAST.store(high_contrast, Ordering::Relaxed);
+}
+
+fn high_contrast() -> bool {
+    HIGH_CONTRAST.load(Ordering::Relaxed)
+}
+
+pub(crate) fn menu_theme() -> ColorfulTheme {
+    if !high_contrast() {
+        return ColorfulTheme::default();
+    }
+    let white = Style::new().for_stderr().white().bright();
+    let accent = Style::new().for_stderr().yellow().bright().bold();
+    ColorfulTheme {
+        defaults_style: white.clone(),
+        prompt_style: white.clone().bold(),
+        prompt_prefix: style("?".to_string()).for_stderr().yellow().bright(),
+        prompt_suffix: style("›".to_string()).for_stderr().white().bright(),
+        success_prefix: style("✔".to_string()).for_stderr().white().bright(),
+        success_suffix: style("·".to_string()).for_stderr().white().bright(),
+        error_prefix: style("✘".to_string()).for_stderr().yellow().bright(),
+        error_style: accent.clone(),
+        hint_style: white.clone(),
+        values_style: white.clone().bold(),
+        active_item_style: Style::new().for_stderr().black().on_yellow().bold(),
+        inactive_item_style: white.clone(),
+        active_item_prefix: style("❯".to_string()).for_stderr().yellow().bright(),
+        inactive_item_prefix: style(" ".to_string()).for_stderr(),
+        checked_item_prefix: style("✔".to_string()).for_stderr().yellow().bright(),
+        unchecked_item_prefix: style("⬚".to_string()).for_stderr().white().bright(),
+        picked_item_prefix: style("❯".to_string()).for_stderr().yellow().bright(),
+        unpicked_item_prefix: style(" ".to_string()).for_stderr(),
+        fuzzy_cursor_style: Style::new().for_stderr().black().on_white(),
+        fuzzy_match_highlight_style: accent.underlined(),
+    }
+}
+
+/// Main menu entries that are not tables
+pub(crate) fn header(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_white().bold().underline()
+    } else {
+        text.bold()
+    }
+}
+
+/// Field names in the table info
+pub(crate) fn label(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_white().bold()
+    } else {
+        text.green()
+    }
+}
+
+pub(crate) fn warning(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_yellow().bold()
+    } else {
+        text.yellow()
+    }
+}
+
+/// Errors reported after an action failed
+pub(crate) fn error(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_yellow().bold()
+    } else {
+        text.truecolor(255, 125, 0)
+    }
+}
+
+/// Secondary information, not dimmed in high contrast mode as that lowers the contrast
+pub(crate) fn secondary(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_white()
+    } else {
+        text.dimmed()
+    }
+}
+
+/// Marker of tables in the staging folder
+pub(crate) fn staged(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_white().italic()
+    } else {
+        text.cyan()
+    }
+}
+
+/// Key binding hints
+pub(crate) fn hint(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_white().italic()
+    } else {
+        text.dimmed()
+    }
+}
+
+/// Table names tinted by manufacturer, left alone in high contrast mode
+pub(crate) fn tint(text: &str, color: Rgb) -> ColoredString {
+    if high_contrast() {
+        text.normal()
+    } else {
+        text.truecolor(color.0, color.1, color.2)
+    }
+}
+
+/// Table names colored by the state of the table, only errors stand out in high contrast mode
+pub(crate) fn row_state(text: &str, state: RowState) -> ColoredString {
+    match state {
+        RowState::Errors if high_contrast() => text.bright_yellow(),
+        _ if high_contrast() => text.normal(),
+        RowState::Errors => text.red(),
+        RowState::Favorite => text.magenta(),
+        RowState::Archived => text.bright_black(),
+        RowState::RecentlyAdded => text.green(),
+    }
+}
+
+/// VBScript keywords in the script view
+pub(crate) fn code_keyword(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_white().bold()
+    } else {
+        text.blue().bold()
+    }
+}
+
+/// VBScript string literals in the script view
+pub(crate) fn code_string(text: &str) -> ColoredString {
+    if high_contrast() {
+        text.bright_yellow()
+    } else {
+        text.green()
+    }
+}
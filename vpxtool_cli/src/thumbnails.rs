@@ -0,0 +1,96 @@
+use image::{DynamicImage, RgbaImage};
+use std::io;
+use std::path::Path;
+use vpin::vpx;
+use vpin::vpx::image::ImageData;
+use vpin::vpx::lzw::from_lzw_blocks;
+
+/// Picks the image that best represents a table.
+///
+/// In order of preference:
+/// * the screenshot stored in the table info
+/// * the image configured as table screenshot in the gamedata
+/// * the playfield image
+pub(crate) fn table_thumbnail(vpx_path: &Path) -> io::Result<Option<DynamicImage>> {
+    let mut vpx_file = vpx::open(vpx_path)?;
+    let table_info = vpx_file.read_tableinfo()?;
+    let screenshot = table_info
+        .screenshot
+        .and_then(|screenshot| image::load_from_memory(&screenshot).ok());
+    if screenshot.is_some() {
+        return Ok(screenshot);
+    }
+    let gamedata = vpx_file.read_gamedata()?;
+    let candidates = [gamedata.screen_shot, gamedata.image]
+        .into_iter()
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<String>>();
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+    let images = vpx_file.read_images()?;
+    let thumbnail = candidates.iter().find_map(|name| {
+        images
+            .iter()
+            .find(|image| image.name.eq_ignore_ascii_case(name))
+            .and_then(decode_image)
+    });
+    Ok(thumbnail)
+}
+
+/// Scales the image down so neither side exceeds `max_size`, keeping the aspect ratio.
+pub(crate) fn limit_size(image: DynamicImage, max_size: u32) -> DynamicImage {
+    if image.width() <= max_size && image.height() <= max_size {
+        image
+    } else {
+        image.thumbnail(max_size, max_size)
+    }
+}
+
+fn decode_image(image_data: &ImageData) -> Option<DynamicImage> {
+    if let Some(jpeg) = &image_data.jpeg {
+        return image::load_from_memory(&jpeg.data).ok();
+    }
+    let bits = image_data.bits.as_ref()?;
+    // bitmaps are stored as lzw compressed BGRA
+    let rgba = from_lzw_blocks(&bits.lzw_compressed_data)
+        .chunks_exact(4)
+        .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+        .collect::<Vec<u8>>();
+    RgbaImage::from_raw(image_data.width, image_data.height, rgba).map(DynamicImage::ImageRgba8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testdir::testdir;
+    use vpxtool_shared::fixtures::VpxFixture;
+
+    #[test]
+    fn test_table_thumbnail_playfield_image() -> io::Result<()> {
+        let mut vpx = VpxFixture::new().image("playfield").to_vpx();
+        vpx.gamedata.image = "Playfield".to_string();
+        let vpx_path = testdir!().join("test.vpx");
+        vpx::write(&vpx_path, &vpx)?;
+        let thumbnail = table_thumbnail(&vpx_path)?.expect("no thumbnail found");
+        assert_eq!((thumbnail.width(), thumbnail.height()), (1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_thumbnail_no_image() -> io::Result<()> {
+        let vpx_path = VpxFixture::new().image("unused").build_in(&testdir!())?;
+        assert!(table_thumbnail(&vpx_path)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_size() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(400, 200));
+        let limited = limit_size(image, 100);
+        assert_eq!((limited.width(), limited.height()), (100, 50));
+        let small = DynamicImage::ImageRgba8(RgbaImage::new(40, 20));
+        let limited = limit_size(small, 100);
+        assert_eq!((limited.width(), limited.height()), (40, 20));
+    }
+}
@@ -23,12 +23,27 @@ pub(crate) fn show_info(
         let selected_item = selected_item_res.index.unwrap_or(0);
         let table = &tables.indexed_tables[selected_item];
 
-        let gametext = table
-            .table_info
-            .table_description
-            .clone()
-            .filter(|x| !x.trim().is_empty())
-            .unwrap_or("[no description]".to_string());
+        let mut gametext = String::new();
+        if let Some(author) = non_empty(&table.table_info.author_name) {
+            gametext.push_str(&format!("Author: {}\n", author));
+        }
+        if let Some(released) = non_empty(&table.table_info.release_date) {
+            gametext.push_str(&format!("Released: {}\n", released));
+        }
+        if !gametext.is_empty() {
+            gametext.push('\n');
+        }
+        gametext
+            .push_str(non_empty(&table.table_info.table_description).unwrap_or("[no description]"));
+        // blurb and rules are separate texts in the table info, only shown when set
+        for (label, text) in [
+            ("Blurb", &table.table_info.table_blurb),
+            ("Rules", &table.table_info.table_rules),
+        ] {
+            if let Some(text) = non_empty(text) {
+                gametext.push_str(&format!("\n\n{}:\n{}", label, text));
+            }
+        }
         let wtitle = display_table_line(table);
 
         // FIXME, this keeps creating windows???
@@ -38,6 +53,10 @@ pub(crate) fn show_info(
     }
 }
 
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|x| !x.is_empty())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn create_info_box(window: &Window, mut contexts: EguiContexts, wtitle: String, wtext: String) {
     let width = window.resolution.width();
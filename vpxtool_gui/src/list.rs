@@ -5,6 +5,7 @@ use bevy::input::ButtonInput;
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
 use std::cmp::Ordering;
+use vpxtool_shared::display::Capitalization;
 use vpxtool_shared::indexer::IndexedTable;
 
 #[derive(Component, Debug)]
@@ -189,18 +190,14 @@ pub(crate) fn display_table_line(table: &IndexedTable) -> String {
         .filter(|s| !s.clone().unwrap_or_default().trim().is_empty())
         .map(|s| {
             match s {
-                Some(name) => capitalize_first_letter(&name),
-                None => capitalize_first_letter(&file_name),
+                Some(name) => Capitalization::default().apply(&name),
+                None => Capitalization::default().apply(&file_name),
             }
             // TODO we probably want to show both the file name and the table name
         })
         .unwrap_or(file_name)
 }
 
-fn capitalize_first_letter(s: &str) -> String {
-    s[0..1].to_uppercase() + &s[1..]
-}
-
 fn generate_table_indices(max_index: usize, selected_index: usize) -> [usize; ITEMS_SHOWN] {
     let mut table_indices = [0; ITEMS_SHOWN];
     for (i, item) in table_indices.iter_mut().enumerate() {
@@ -8,7 +8,7 @@ use bevy_asset::{AssetServer, RecursiveDependencyLoadState, UntypedHandle};
 use crossbeam_channel::Sender;
 use std::thread;
 use vpxtool_shared::indexer;
-use vpxtool_shared::indexer::Progress;
+use vpxtool_shared::indexer::IndexProgress;
 
 const SLOW_LOADING: bool = false;
 
@@ -295,16 +295,15 @@ fn despawn_loading_screen(mut commands: Commands, query: Query<Entity, With<Part
 struct EventSendingProgress {
     sender: Sender<ChannelExternalEvent>,
 }
-impl Progress for EventSendingProgress {
-    fn set_length(&self, len: u64) {
-        self.sender
-            .send(ChannelExternalEvent::ProgressLength(len))
-            .unwrap();
-    }
-
-    fn set_position(&self, i: u64) {
+impl EventSendingProgress {
+    fn update(&self, progress: IndexProgress) {
+        if progress.position == 1 {
+            self.sender
+                .send(ChannelExternalEvent::ProgressLength(progress.total))
+                .unwrap();
+        }
         self.sender
-            .send(ChannelExternalEvent::ProgressPosition(i))
+            .send(ChannelExternalEvent::ProgressPosition(progress.position))
             .unwrap();
     }
 
@@ -326,23 +325,26 @@ fn load_tables(
     let tx = stream_sender.clone();
     let resolved_config = resolved_config.config.clone();
     let _vpinball_thread = thread::spawn(move || {
-        let recursive = true;
         // TODO make a progress that sends events and update loading gui
         let progress = EventSendingProgress { sender: tx.clone() };
         let index_result = indexer::index_folder(
-            recursive,
             &resolved_config.tables_folder,
             &resolved_config.tables_index_path,
-            Some(&resolved_config.global_pinmame_rom_folder()),
-            &progress,
+            &resolved_config.index_options(),
+            Some(&mut |p| progress.update(p)),
             Vec::new(),
         );
         progress.finish_and_clear();
         match index_result {
             Ok(index) => {
                 info!("{} tables loaded", index.len());
-                tx.send(ChannelExternalEvent::TablesLoaded(index.tables()))
-                    .unwrap();
+                // archived tables can't be launched until extracted
+                let tables = index
+                    .tables()
+                    .into_iter()
+                    .filter(|table| !table.archived)
+                    .collect();
+                tx.send(ChannelExternalEvent::TablesLoaded(tables)).unwrap();
             }
             Err(e) => {
                 error!("Error loading tables: {:?}", e);
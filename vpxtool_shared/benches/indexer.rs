@@ -0,0 +1,119 @@
+//! Benchmarks for the indexer hot path, run with `cargo bench -p vpxtool_shared`.
+//!
+//! The tables are synthetic fixtures so the numbers are reproducible on any machine.
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use vpxtool_shared::fixtures::VpxFixture;
+use vpxtool_shared::indexer::{self, IndexOptions, IndexedTable};
+
+const SCRIPT: &str = r#"Option Explicit
+Const cGameName = "bench"
+LoadVPM "01120100", "sega.vbs", 3.02
+Sub Table1_Init
+    PlaySound "fx_bench"
+    LoadImage "bench.png"
+End Sub
+"#;
+
+fn fixture(name: &str) -> VpxFixture {
+    VpxFixture::new()
+        .file_name(format!("{}.vpx", name))
+        .table_name(name)
+        .script(SCRIPT)
+        .wall("Wall1")
+        .flipper("LeftFlipper")
+        .image("Playfield")
+}
+
+fn fixtures_dir(name: &str, count: usize) -> PathBuf {
+    let dir =
+        std::env::temp_dir()
+            .join("vpxtool-bench")
+            .join(format!("{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).expect("bench dir");
+    for i in 0..count {
+        fixture(&format!("table_{:04}", i))
+            .build_in(&dir)
+            .expect("bench fixture");
+    }
+    dir
+}
+
+/// Index of `count` tables, made by copying a single indexed fixture
+fn large_index(dir: &Path, count: usize) -> Vec<IndexedTable> {
+    let vpx_files = indexer::find_vpx_files(false, dir).expect("find fixtures");
+    let table = indexer::index_vpx_files(&vpx_files[..1], &HashMap::new(), None)
+        .tables()
+        .pop()
+        .expect("indexed fixture");
+    (0..count)
+        .map(|i| {
+            let mut copy = table.clone();
+            copy.path = dir.join(format!("copy_{:05}.vpx", i));
+            copy
+        })
+        .collect()
+}
+
+fn bench_single_file(c: &mut Criterion) {
+    let dir = fixtures_dir("single", 1);
+    let vpx_files = indexer::find_vpx_files(false, &dir).expect("find fixtures");
+    let no_roms = HashMap::new();
+    c.bench_function("index single file", |b| {
+        b.iter(|| indexer::index_vpx_files(black_box(&vpx_files), &no_roms, None))
+    });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+fn bench_index_folder(c: &mut Criterion) {
+    let mut group = c.benchmark_group("index folder");
+    group.sample_size(10);
+    for count in [10, 100] {
+        let dir = fixtures_dir(&format!("folder_{}", count), count);
+        let index_path = dir.join("vpxtool_index.json");
+        let options = IndexOptions {
+            recursive: false,
+            ..IndexOptions::default()
+        };
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &dir, |b, dir| {
+            b.iter(|| {
+                // a fresh index every time, otherwise only the first iteration does any work
+                let _ = std::fs::remove_file(&index_path);
+                indexer::index_folder(dir, &index_path, &options, None, Vec::new())
+                    .expect("index folder")
+            })
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    group.finish();
+}
+
+fn bench_warnings(c: &mut Criterion) {
+    let dir = fixtures_dir("warnings", 1);
+    let mut group = c.benchmark_group("warnings");
+    for count in [1_000, 10_000] {
+        let tables = large_index(&dir, count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &tables, |b, tables| {
+            b.iter(|| {
+                tables
+                    .iter()
+                    .map(|table| table.warnings().len())
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+criterion_group!(
+    benches,
+    bench_single_file,
+    bench_index_folder,
+    bench_warnings
+);
+criterion_main!(benches);
@@ -0,0 +1,108 @@
+//! Release notes that authors keep as comments at the top of the table script.
+//!
+//! Scripts often start with a block like
+//!
+//! ```text
+//! '*******************************
+//! ' Attack from Mars
+//! ' Changelog:
+//! ' 1.1 - fixed the ramps
+//! '*******************************
+//! Option Explicit
+//! ```
+//!
+//! The leading comment block is the changelog. If it has a line like `Changelog`, `Release notes`
+//! or `Version history` only the part from that line on is taken. Lines that are only decoration,
+//! like `'*****` or `'=====`, are left out.
+
+/// Words starting the changelog part of the leading comment block, lowercase
+const MARKERS: [&str; 6] = [
+    "changelog",
+    "change log",
+    "changes",
+    "release notes",
+    "version history",
+    "revisions",
+];
+
+/// The changelog lines without comment markers, `None` if the script doesn't start with a
+/// comment block that has any text
+pub fn changelog<S: AsRef<str>>(script_lines: &[S]) -> Option<Vec<String>> {
+    let block = script_lines
+        .iter()
+        .map(|line| line.as_ref().trim_start_matches('\u{feff}').trim())
+        .skip_while(|line| line.is_empty())
+        .map_while(comment_text)
+        .filter(|text| !is_decoration(text))
+        .collect::<Vec<&str>>();
+    let start = block
+        .iter()
+        .position(|text| {
+            let text = text.to_lowercase();
+            MARKERS.iter().any(|marker| text.starts_with(marker))
+        })
+        .unwrap_or(0);
+    let mut lines = block[start..]
+        .iter()
+        .map(|text| text.to_string())
+        .skip_while(|text| text.is_empty())
+        .collect::<Vec<String>>();
+    while lines.last().is_some_and(|text| text.is_empty()) {
+        lines.pop();
+    }
+    (!lines.is_empty()).then_some(lines)
+}
+
+/// The text of a `'` or `Rem` comment line, `None` for code
+fn comment_text(line: &str) -> Option<&str> {
+    if let Some(text) = line.strip_prefix('\'') {
+        return Some(text.trim_start_matches('\'').trim());
+    }
+    let rem = line.get(..3)?;
+    let rest = &line[3..];
+    (rem.eq_ignore_ascii_case("rem") && (rest.is_empty() || rest.starts_with(char::is_whitespace)))
+        .then(|| rest.trim())
+}
+
+/// Lines like `*****`, `-----` or `=====`, empty lines are kept as paragraph breaks
+fn is_decoration(text: &str) -> bool {
+    !text.is_empty() && !text.chars().any(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_changelog() {
+        let script = [
+            "",
+            "'*******************************",
+            "' Attack from Mars by JP",
+            "'",
+            "' Changelog:",
+            "' 1.0 - first release",
+            "REM 1.1 - fixed the ramps",
+            "'",
+            "'*******************************",
+            "Option Explicit",
+            "' not part of the changelog",
+        ];
+        assert_eq!(
+            changelog(&script),
+            Some(vec![
+                "Changelog:".to_string(),
+                "1.0 - first release".to_string(),
+                "1.1 - fixed the ramps".to_string(),
+            ])
+        );
+        // without marker the whole block is taken
+        assert_eq!(
+            changelog(&["' Table by JP", "' v2 new lights", "Option Explicit"]),
+            Some(vec!["Table by JP".to_string(), "v2 new lights".to_string()])
+        );
+        assert_eq!(changelog(&["Option Explicit", "' comment"]), None);
+        assert_eq!(changelog(&["'********", "Remove = 1"]), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! A 0 to 100 score of how complete the setup of a table is, to find the tables that need
+//! attention first.
+//!
+//! Every check has a weight, see [CompletenessWeights]. Checks that don't apply to a table are left
+//! out, a table without PinMAME doesn't need a rom and still scores 100 when everything else is
+//! there.
+use crate::indexer::IndexedTable;
+use serde::{Deserialize, Serialize};
+
+/// How much each check counts, a weight of 0 leaves the check out
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct CompletenessWeights {
+    /// The rom of a PinMAME table is found
+    pub rom: u32,
+    /// There is a `.directb2s` backglass
+    pub backglass: u32,
+    /// There is a wheel image
+    pub wheel: u32,
+    /// The view is set up in a `.pov` file or the table ini
+    pub pov: u32,
+    /// The table has no unsuppressed warnings
+    pub no_warnings: u32,
+}
+
+impl Default for CompletenessWeights {
+    fn default() -> Self {
+        CompletenessWeights {
+            rom: 3,
+            backglass: 2,
+            wheel: 1,
+            pov: 1,
+            no_warnings: 3,
+        }
+    }
+}
+
+impl CompletenessWeights {
+    pub fn is_default(&self) -> bool {
+        *self == CompletenessWeights::default()
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Completeness {
+    /// 0 to 100, 100 if no check applies
+    pub score: u8,
+    /// The checks that failed, eg `backglass`
+    pub missing: Vec<&'static str>,
+}
+
+/// Scores the table, `warnings` are the warnings shown for it, without the suppressed ones
+pub fn completeness(
+    table: &IndexedTable,
+    weights: &CompletenessWeights,
+    warnings: &[String],
+) -> Completeness {
+    let pov = table.ini_settings.is_some()
+        || (!table.archived && table.path.with_extension("pov").is_file());
+    let checks = [
+        // only PinMAME tables need a rom
+        (
+            "rom",
+            weights.rom,
+            table.requires_pinmame,
+            table.rom_path().is_some(),
+        ),
+        (
+            "backglass",
+            weights.backglass,
+            true,
+            table.b2s_path.is_some(),
+        ),
+        ("wheel", weights.wheel, true, table.wheel_path.is_some()),
+        ("pov", weights.pov, true, pov),
+        (
+            "no warnings",
+            weights.no_warnings,
+            true,
+            warnings.is_empty(),
+        ),
+    ];
+    let mut total = 0;
+    let mut met = 0;
+    let mut missing = Vec::new();
+    for (name, weight, applies, passed) in checks {
+        if !applies || weight == 0 {
+            continue;
+        }
+        total += weight;
+        if passed {
+            met += weight;
+        } else {
+            missing.push(name);
+        }
+    }
+    let score = (met * 100).checked_div(total).unwrap_or(100) as u8;
+    Completeness { score, missing }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_completeness() {
+        let mut table: IndexedTable = serde_json::from_value(json!({
+            "path": "/tables/missing/Table.vpx",
+            "table_info": {
+                "properties": {}
+            },
+            "game_name": "afm_113b",
+            "b2s_path": "/tables/missing/Table.directb2s",
+            "requires_pinmame": true,
+            "last_modified": "1970-01-01T00:00:00+00:00"
+        }))
+        .unwrap();
+        let weights = CompletenessWeights::default();
+        let warnings = vec!["Rom not found: afm_113b".to_string()];
+
+        assert_eq!(
+            completeness(&table, &weights, &warnings),
+            Completeness {
+                score: 20,
+                missing: vec!["rom", "wheel", "pov", "no warnings"],
+            }
+        );
+        // the rom only counts for PinMAME tables
+        table.requires_pinmame = false;
+        assert_eq!(completeness(&table, &weights, &[]).score, 71);
+        let only_wheel = CompletenessWeights {
+            rom: 0,
+            backglass: 0,
+            wheel: 1,
+            pov: 0,
+            no_warnings: 0,
+        };
+        assert_eq!(completeness(&table, &only_wheel, &[]).score, 0);
+        table.wheel_path = Some(PathBuf::from("/tables/missing/Table.wheel.png"));
+        assert_eq!(completeness(&table, &only_wheel, &[]).score, 100);
+    }
+}
@@ -4,22 +4,214 @@ use dialoguer::Select;
 use dialoguer::theme::ColorfulTheme;
 use figment::{
     Figment,
-    providers::{Format, Toml},
+    providers::{Format, Serialized, Toml},
 };
+use log::warn;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fs::File;
+use std::sync::OnceLock;
 use std::{env, io};
 
+use crate::completeness::CompletenessWeights;
+use crate::display::{
+    Capitalization, DEFAULT_DISPLAY_TEMPLATE, DisplayTemplate, InfoField, Rgb, RowState,
+    default_info_field_names, default_manufacturer_palette, default_row_state_priority,
+    parse_info_fields,
+};
+use crate::file_name_tags::{
+    self, DEFAULT_RATING_PATTERN, DEFAULT_VERSION_PATTERN, FileNamePatterns, pattern_setting,
+};
+use crate::indexer::{
+    ChdRequirements, DEFAULT_BACKUP_PATTERNS, DEFAULT_SIDECAR_EXTENSIONS, IndexOptions,
+    IndexParallelism, WarningKind, is_sqlite_index,
+};
+use crate::tags_export::TagsExportFormat;
 use crate::vpinball_config::VPinballConfig;
+use sha2::{Digest, Sha256};
 use std::io::Write;
 
 const CONFIGURATION_FILE_NAME: &str = "vpxtool.cfg";
 
+/// Environment variable that points to a config file to use instead of the default locations
+pub const CONFIG_PATH_ENV: &str = "VPXTOOL_CONFIG";
+
+/// Prefix of the environment variables that override settings, eg `VPXTOOL_TABLES_FOLDER`
+pub const ENV_PREFIX: &str = "VPXTOOL_";
+
+/// Settings that can be set through environment variables, named like the setting in upper case
+/// after [ENV_PREFIX]. They take precedence over the config file and are enough to run vpxtool
+/// without one.
+pub const ENV_SETTINGS: [&str; 3] = ["vpx_executable", "tables_folder", "rom_folder"];
+
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// A named frontend filter and sort order, see [crate::index::TableFilter]
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct FilterPreset {
+    pub name: String,
+    #[serde(default)]
+    pub filter: String,
+    #[serde(default)]
+    pub warnings_first: bool,
+    /// Tables with the most warnings first
+    #[serde(default)]
+    pub most_warnings_first: bool,
+    /// Highest rated tables first, after the tables with warnings if both are on
+    #[serde(default)]
+    pub rating_first: bool,
+    /// Least complete tables first, see [crate::completeness]
+    #[serde(default)]
+    pub completeness_first: bool,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct Config {
     pub vpx_executable: PathBuf,
     pub tables_folder: Option<PathBuf>,
     pub editor: Option<String>,
+    /// Disables all actions that modify tables or their sidecar files
+    #[serde(default)]
+    pub read_only: bool,
+    /// How tables are shown in the frontend, see [DisplayTemplate]
+    pub display_template: Option<String>,
+    /// Page opened from the frontend to look up a table, like [DisplayTemplate] with url-encoded
+    /// values, eg `https://www.ipdb.org/search.pl?name={name}&searchtype=advanced`
+    pub resource_url: Option<String>,
+    /// Leave the file stem out of the table list for tables that have a name
+    #[serde(default)]
+    pub hide_stem: bool,
+    /// How table names are capitalized in the table list
+    #[serde(default)]
+    pub name_capitalization: Capitalization,
+    /// Sidecar file extensions to look for on top of [DEFAULT_SIDECAR_EXTENSIONS]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_sidecar_extensions: Vec<String>,
+    /// Also detect hidden sidecar files like `.Table.ini`
+    #[serde(default)]
+    pub include_hidden_sidecars: bool,
+    /// Extra arguments passed to vpinball for every table
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub launch_args: Vec<String>,
+    /// Extra arguments per table, keyed by vpx file name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub table_launch_args: BTreeMap<String, Vec<String>>,
+    /// Keep the frontend usable while a table runs instead of waiting for Visual Pinball to exit
+    #[serde(default)]
+    pub launch_in_background: bool,
+    /// Command and arguments that open a table in the VPinball editor, the table path is added last
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub table_editor_command: Vec<String>,
+    /// External components installed on this machine, eg `VPinMAME` or `B2SBackglassServer`.
+    /// Tables requiring other components get a warning, nothing is checked when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub installed_components: Vec<String>,
+    /// Leave backup and autosave files out of the index
+    #[serde(default)]
+    pub exclude_backups: bool,
+    /// Backup file name patterns to look for on top of [DEFAULT_BACKUP_PATTERNS]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_backup_patterns: Vec<String>,
+    /// Also index the vpx files inside zip archives in the tables folder
+    #[serde(default)]
+    pub index_archives: bool,
+    /// How many tables are parsed at the same time, `auto` goes one at a time on network shares,
+    /// removable and spinning disks
+    #[serde(default)]
+    pub index_parallelism: IndexParallelism,
+    /// Threads for parsing tables in parallel, all cores if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_threads: Option<usize>,
+    /// Black and white frontend with a single accent color
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// Tint table names in the frontend by their manufacturer
+    #[serde(default = "default_manufacturer_colors")]
+    pub manufacturer_colors: bool,
+    /// `#rrggbb` colors to pick manufacturer colors from, replaces the default palette
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manufacturer_palette: Vec<String>,
+    /// Color table names in the frontend by their state, like favorites or tables with warnings
+    #[serde(default = "default_state_colors")]
+    pub state_colors: bool,
+    /// The state that colors a table when it is in several, first wins
+    #[serde(default = "default_row_state_priority")]
+    pub row_state_priority: Vec<RowState>,
+    /// Fields shown in the table info, in this order
+    #[serde(default = "default_info_field_names")]
+    pub info_fields: Vec<String>,
+    /// Move the frontend selection to the first table when the filter changes, instead of keeping
+    /// the last opened table selected if it is still listed
+    #[serde(default)]
+    pub auto_select_first: bool,
+    /// Show the file size of each table in the frontend list
+    #[serde(default)]
+    pub show_sizes: bool,
+    /// List tables by their path relative to the tables folder instead of their name
+    #[serde(default)]
+    pub show_paths: bool,
+    /// Show the number of warnings at the end of the table rows in the frontend list
+    #[serde(default)]
+    pub warning_counts: bool,
+    /// Blank lines between the tables in the frontend list, for large screens
+    #[serde(default)]
+    pub row_spacing: u8,
+    /// Folder with the PinMAME roms, defaults to the `roms` folder in the PinMAME folder
+    pub rom_folder: Option<PathBuf>,
+    /// Folder with the PinMAME NVRAM files that hold the high scores, defaults to the `nvram`
+    /// folder in the PinMAME folder
+    pub nvram_folder: Option<PathBuf>,
+    /// `sha256sum` style list of trusted table hashes, see [crate::reference]
+    pub reference_hashes: Option<PathBuf>,
+    /// Required files per rom, enables the rom audit, see [crate::rom_audit]
+    pub rom_manifest: Option<PathBuf>,
+    /// Downloaded copy of the VPS database to check for newer table versions, see [crate::vps]
+    pub vps_database: Option<PathBuf>,
+    /// Folder with new tables that are not yet in the tables folder, see [crate::staging]
+    pub staging_folder: Option<PathBuf>,
+    /// Folder watched for new downloads that are moved to the tables folder, see [crate::inbox]
+    pub inbox_folder: Option<PathBuf>,
+    /// Renames tables moved from the inbox, like [DisplayTemplate], eg
+    /// `{name} ({manufacturer} {year})`
+    pub inbox_name_template: Option<String>,
+    /// Store the index in a SQLite database instead of a json file, needs the sqlite feature
+    #[serde(default)]
+    pub sqlite_index: bool,
+    /// Folder with wheel images named after the table, its rom or its table name
+    pub wheel_folder: Option<PathBuf>,
+    /// Filters saved from the frontend, in the order they are cycled through
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter_presets: Vec<FilterPreset>,
+    /// How much each check counts in the completeness score, see [crate::completeness]
+    #[serde(default, skip_serializing_if = "CompletenessWeights::is_default")]
+    pub completeness_weights: CompletenessWeights,
+    /// Finds the table version in the file name, empty disables it, see [crate::file_name_tags]
+    pub file_name_version_pattern: Option<String>,
+    /// Finds the table rating in the file name, empty disables it
+    pub file_name_rating_pattern: Option<String>,
+    /// Show table, sidecar and rom paths relative to [Config::relative_paths_base] in the table
+    /// info and exports
+    #[serde(default)]
+    pub relative_paths: bool,
+    /// Base folder for relative paths, defaults to the tables folder
+    pub relative_paths_base: Option<PathBuf>,
+    /// Shorter sessions don't count towards the play time, like accidental launches
+    #[serde(default = "default_min_play_seconds")]
+    pub min_play_seconds: u64,
+    /// Most terminal columns of the description shown in the two-line view, 0 hides it
+    #[serde(default = "default_description_preview_length")]
+    pub description_preview_length: usize,
+    /// File the favorites and tags are exported to for other frontends, see [crate::tags_export]
+    pub tags_export_path: Option<PathBuf>,
+    #[serde(default)]
+    pub tags_export_format: TagsExportFormat,
+    /// Roms that also need a CHD disk image, see [crate::indexer::ChdRequirements]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chd_games: Vec<String>,
+    /// Folder with a folder per rom holding its CHD files, defaults to the rom folder
+    pub chd_folder: Option<PathBuf>,
 }
 impl Config {
     fn from(resolved_config: &ResolvedConfig) -> Self {
@@ -27,6 +219,93 @@ impl Config {
             vpx_executable: resolved_config.vpx_executable.clone(),
             tables_folder: Some(resolved_config.tables_folder.clone()),
             editor: resolved_config.editor.clone(),
+            read_only: resolved_config.read_only,
+            display_template: Some(resolved_config.display_template.to_string())
+                .filter(|template| template != DEFAULT_DISPLAY_TEMPLATE),
+            resource_url: resolved_config
+                .resource_url
+                .as_ref()
+                .map(|template| template.to_string()),
+            hide_stem: resolved_config.display_template.hides_stem(),
+            name_capitalization: resolved_config.display_template.capitalization(),
+            extra_sidecar_extensions: resolved_config
+                .sidecar_extensions
+                .iter()
+                .filter(|extension| !DEFAULT_SIDECAR_EXTENSIONS.contains(&extension.as_str()))
+                .cloned()
+                .collect(),
+            include_hidden_sidecars: resolved_config.include_hidden_sidecars,
+            launch_args: resolved_config.launch_args.clone(),
+            table_launch_args: resolved_config.table_launch_args.clone(),
+            launch_in_background: resolved_config.launch_in_background,
+            table_editor_command: resolved_config.table_editor_command.clone(),
+            installed_components: resolved_config.installed_components.clone(),
+            exclude_backups: resolved_config.exclude_backups,
+            extra_backup_patterns: resolved_config
+                .backup_patterns
+                .iter()
+                .filter(|pattern| !DEFAULT_BACKUP_PATTERNS.contains(&pattern.as_str()))
+                .cloned()
+                .collect(),
+            index_archives: resolved_config.index_archives,
+            index_parallelism: resolved_config.index_parallelism,
+            index_threads: resolved_config.index_threads,
+            high_contrast: resolved_config.high_contrast,
+            manufacturer_colors: resolved_config.manufacturer_colors,
+            state_colors: resolved_config.state_colors,
+            row_state_priority: resolved_config.row_state_priority.clone(),
+            info_fields: resolved_config
+                .info_fields
+                .iter()
+                .map(|field| field.name().to_string())
+                .collect(),
+            manufacturer_palette: if resolved_config.manufacturer_palette
+                == default_manufacturer_palette()
+            {
+                Vec::new()
+            } else {
+                resolved_config
+                    .manufacturer_palette
+                    .iter()
+                    .map(Rgb::to_string)
+                    .collect()
+            },
+            auto_select_first: resolved_config.auto_select_first,
+            show_sizes: resolved_config.show_sizes,
+            warning_counts: resolved_config.warning_counts,
+            show_paths: resolved_config.show_paths,
+            row_spacing: resolved_config.row_spacing,
+            rom_folder: resolved_config.rom_folder.clone(),
+            nvram_folder: resolved_config.nvram_folder.clone(),
+            reference_hashes: resolved_config.reference_hashes.clone(),
+            rom_manifest: resolved_config.rom_manifest.clone(),
+            vps_database: resolved_config.vps_database.clone(),
+            staging_folder: resolved_config.staging_folder.clone(),
+            inbox_folder: resolved_config.inbox_folder.clone(),
+            inbox_name_template: resolved_config
+                .inbox_name_template
+                .as_ref()
+                .map(|template| template.to_string()),
+            sqlite_index: is_sqlite_index(&resolved_config.tables_index_path),
+            wheel_folder: resolved_config.wheel_folder.clone(),
+            filter_presets: resolved_config.filter_presets.clone(),
+            completeness_weights: resolved_config.completeness_weights,
+            file_name_version_pattern: pattern_setting(
+                resolved_config.file_name_patterns.version.as_ref(),
+                DEFAULT_VERSION_PATTERN,
+            ),
+            file_name_rating_pattern: pattern_setting(
+                resolved_config.file_name_patterns.rating.as_ref(),
+                DEFAULT_RATING_PATTERN,
+            ),
+            relative_paths: resolved_config.relative_paths,
+            relative_paths_base: resolved_config.relative_paths_base.clone(),
+            min_play_seconds: resolved_config.min_play_seconds,
+            description_preview_length: resolved_config.description_preview_length,
+            tags_export_path: resolved_config.tags_export_path.clone(),
+            tags_export_format: resolved_config.tags_export_format,
+            chd_games: resolved_config.chd_games.clone(),
+            chd_folder: resolved_config.chd_folder.clone(),
         }
     }
 }
@@ -37,9 +316,109 @@ pub struct ResolvedConfig {
     pub tables_folder: PathBuf,
     pub tables_index_path: PathBuf,
     pub editor: Option<String>,
+    pub read_only: bool,
+    pub display_template: DisplayTemplate,
+    pub resource_url: Option<DisplayTemplate>,
+    /// Defaults extended with the configured extra extensions
+    pub sidecar_extensions: Vec<String>,
+    pub include_hidden_sidecars: bool,
+    pub launch_args: Vec<String>,
+    pub table_launch_args: BTreeMap<String, Vec<String>>,
+    pub launch_in_background: bool,
+    pub table_editor_command: Vec<String>,
+    pub installed_components: Vec<String>,
+    pub exclude_backups: bool,
+    /// Defaults extended with the configured extra patterns
+    pub backup_patterns: Vec<String>,
+    pub index_archives: bool,
+    pub index_parallelism: IndexParallelism,
+    pub index_threads: Option<usize>,
+    pub high_contrast: bool,
+    pub manufacturer_colors: bool,
+    pub manufacturer_palette: Vec<Rgb>,
+    pub state_colors: bool,
+    pub row_state_priority: Vec<RowState>,
+    pub info_fields: Vec<InfoField>,
+    pub auto_select_first: bool,
+    pub show_sizes: bool,
+    pub warning_counts: bool,
+    pub show_paths: bool,
+    pub row_spacing: u8,
+    pub rom_folder: Option<PathBuf>,
+    pub nvram_folder: Option<PathBuf>,
+    pub reference_hashes: Option<PathBuf>,
+    pub rom_manifest: Option<PathBuf>,
+    pub vps_database: Option<PathBuf>,
+    pub staging_folder: Option<PathBuf>,
+    pub inbox_folder: Option<PathBuf>,
+    pub inbox_name_template: Option<DisplayTemplate>,
+    pub wheel_folder: Option<PathBuf>,
+    pub filter_presets: Vec<FilterPreset>,
+    pub completeness_weights: CompletenessWeights,
+    pub file_name_patterns: FileNamePatterns,
+    pub relative_paths: bool,
+    pub relative_paths_base: Option<PathBuf>,
+    pub min_play_seconds: u64,
+    pub description_preview_length: usize,
+    pub tags_export_path: Option<PathBuf>,
+    pub tags_export_format: TagsExportFormat,
+    pub chd_games: Vec<String>,
+    pub chd_folder: Option<PathBuf>,
 }
 
 impl ResolvedConfig {
+    /// The folder paths are shown relative to, `None` when paths are shown absolute
+    pub fn path_base(&self) -> Option<&Path> {
+        self.relative_paths.then(|| {
+            self.relative_paths_base
+                .as_deref()
+                .unwrap_or(&self.tables_folder)
+        })
+    }
+
+    /// Patterns of files the indexer should skip, empty unless backups are excluded
+    pub fn index_excluded_patterns(&self) -> &[String] {
+        if self.exclude_backups {
+            &self.backup_patterns
+        } else {
+            &[]
+        }
+    }
+
+    /// Threads for indexing the tables folder, see [IndexParallelism]
+    pub fn index_thread_count(&self) -> usize {
+        self.index_parallelism
+            .threads(&self.tables_folder, self.index_threads)
+    }
+
+    /// Everything the index of the tables folder is built with, every command updating the
+    /// index file should use these so the index does not depend on which command ran last
+    pub fn index_options(&self) -> IndexOptions {
+        IndexOptions {
+            recursive: true,
+            global_roms_path: Some(self.global_pinmame_rom_folder()),
+            excluded_patterns: self.index_excluded_patterns().to_vec(),
+            include_archives: self.index_archives,
+            wheel_folder: self.wheel_folder.clone(),
+            staging_folder: self.staging_folder.clone(),
+            nvram_folder: Some(self.global_pinmame_nvram_folder()),
+            threads: self.index_thread_count(),
+        }
+    }
+
+    /// The global launch arguments followed by the ones configured for this table
+    pub fn launch_args_for(&self, vpx_file_path: &Path) -> Vec<String> {
+        let mut args = self.launch_args.clone();
+        let table_args = vpx_file_path.file_name().and_then(|file_name| {
+            self.table_launch_args
+                .get(file_name.to_string_lossy().as_ref())
+        });
+        if let Some(table_args) = table_args {
+            args.extend(table_args.iter().cloned());
+        }
+        args
+    }
+
     pub fn global_pinmame_folder(&self) -> PathBuf {
         // first we try to read the ini file
         let ini_file = self.vpinball_ini_file();
@@ -62,9 +441,69 @@ impl ResolvedConfig {
     }
 
     pub fn global_pinmame_rom_folder(&self) -> PathBuf {
+        if let Some(rom_folder) = &self.rom_folder {
+            return rom_folder.clone();
+        }
         self.global_pinmame_folder().join("roms")
     }
 
+    pub fn global_pinmame_nvram_folder(&self) -> PathBuf {
+        if let Some(nvram_folder) = &self.nvram_folder {
+            return nvram_folder.clone();
+        }
+        self.global_pinmame_folder().join("nvram")
+    }
+
+    /// Defaults to `vpxtool_tags.json` or `.ini` in the tables folder
+    pub fn tags_export_file(&self) -> PathBuf {
+        if let Some(path) = &self.tags_export_path {
+            return path.clone();
+        }
+        self.tables_folder.join(format!(
+            "vpxtool_tags.{}",
+            self.tags_export_format.extension()
+        ))
+    }
+
+    /// The tables as the frontend last listed them, next to the index file
+    pub fn last_session_snapshot_path(&self) -> PathBuf {
+        self.tables_index_path
+            .with_file_name("vpxtool_last_session.json")
+    }
+
+    /// The configured CHD roms, empty when CHDs are not checked
+    pub fn chd_requirements(&self) -> ChdRequirements {
+        if self.chd_games.is_empty() {
+            return ChdRequirements::default();
+        }
+        ChdRequirements {
+            games: self
+                .chd_games
+                .iter()
+                .map(|game| game.to_lowercase())
+                .collect(),
+            folder: self
+                .chd_folder
+                .clone()
+                .unwrap_or_else(|| self.global_pinmame_rom_folder()),
+        }
+    }
+
+    /// Missing roms are only worth reporting when there is a rom folder to keep them in, either
+    /// configured or the one of PinMAME.
+    pub fn has_rom_folder(&self) -> bool {
+        self.rom_folder.is_some() || self.global_pinmame_rom_folder().is_dir()
+    }
+
+    /// Warning kinds that don't apply to this setup
+    pub fn ignored_warnings(&self) -> Vec<WarningKind> {
+        if self.has_rom_folder() {
+            Vec::new()
+        } else {
+            vec![WarningKind::MissingRom]
+        }
+    }
+
     pub fn vpinball_ini_file(&self) -> PathBuf {
         if cfg!(target_os = "windows") {
             // in the same directory as the vpx executable
@@ -78,7 +517,25 @@ impl ResolvedConfig {
     }
 }
 
+/// Use this config file instead of the default locations, takes precedence over [CONFIG_PATH_ENV].
+/// Only the first call has an effect.
+pub fn set_config_path_override(path: PathBuf) {
+    let _ = CONFIG_PATH_OVERRIDE.set(path);
+}
+
+/// The config file explicitly requested through [set_config_path_override] or [CONFIG_PATH_ENV]
+fn explicit_config_path() -> Option<PathBuf> {
+    CONFIG_PATH_OVERRIDE.get().cloned().or_else(|| {
+        env::var_os(CONFIG_PATH_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
 pub fn config_path() -> Option<PathBuf> {
+    if let Some(explicit_path) = explicit_config_path() {
+        return Some(explicit_path).filter(|path| path.exists());
+    }
     let home_directory_configuration_path = home_config_path();
     if home_directory_configuration_path.exists() {
         return Some(home_directory_configuration_path);
@@ -110,29 +567,80 @@ pub fn setup_config() -> io::Result<SetupConfigResult> {
     }
 }
 
-pub fn load_or_setup_config() -> io::Result<(PathBuf, ResolvedConfig)> {
+pub fn load_or_setup_config() -> io::Result<(Option<PathBuf>, ResolvedConfig)> {
     match load_config()? {
         Some(loaded) => Ok(loaded),
         None => {
             // TODO avoid stdout interaction here
             println!("Warning: Failed find a config file.");
-            create_default_config()
+            let (config_path, config) = create_default_config()?;
+            Ok((Some(config_path), config))
         }
     }
 }
 
-pub fn load_config() -> io::Result<Option<(PathBuf, ResolvedConfig)>> {
+/// The config file with the [ENV_SETTINGS] applied on top. Without config file the config only
+/// comes from the environment, the path is `None` then. `None` if there is neither.
+pub fn load_config() -> io::Result<Option<(Option<PathBuf>, ResolvedConfig)>> {
+    if let Some(explicit_path) = explicit_config_path() {
+        if !explicit_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Config file not found: {}", explicit_path.display()),
+            ));
+        }
+        let config = read_config(&explicit_path)?;
+        return Ok(Some((Some(explicit_path), config)));
+    }
     match config_path() {
         Some(config_path) => {
             let config = read_config(&config_path)?;
-            Ok(Some((config_path, config)))
+            Ok(Some((Some(config_path), config)))
+        }
+        None => {
+            let env_settings = env_settings(env::vars_os());
+            if env_settings.is_empty() {
+                return Ok(None);
+            }
+            let config = read_config_with_env(None, &env_settings)?;
+            Ok(Some((None, config)))
         }
-        None => Ok(None),
     }
 }
 
+/// The [ENV_SETTINGS] that are set, keyed by setting name. Empty values are left out.
+fn env_settings<I: IntoIterator<Item = (OsString, OsString)>>(vars: I) -> BTreeMap<String, String> {
+    vars.into_iter()
+        .filter_map(|(name, value)| {
+            let setting = name.to_str()?.strip_prefix(ENV_PREFIX)?.to_lowercase();
+            let value = value.into_string().ok().filter(|value| !value.is_empty())?;
+            ENV_SETTINGS
+                .contains(&setting.as_str())
+                .then_some((setting, value))
+        })
+        .collect()
+}
+
 fn read_config(config_path: &Path) -> io::Result<ResolvedConfig> {
-    let figment = Figment::new().merge(Toml::file(config_path));
+    read_config_with_env(Some(config_path), &env_settings(env::vars_os()))
+}
+
+/// Reads the config file with the environment settings on top, or only the environment settings
+/// when there is no config file
+fn read_config_with_env(
+    config_path: Option<&Path>,
+    env_settings: &BTreeMap<String, String>,
+) -> io::Result<ResolvedConfig> {
+    let figment = match config_path {
+        Some(config_path) => Figment::new().merge(Toml::file(config_path)),
+        // the file would have it, without one we look in the default locations
+        None if !env_settings.contains_key("vpx_executable") => Figment::new().merge(
+            Serialized::default("vpx_executable", default_vpinball_executable_detection()),
+        ),
+        None => Figment::new(),
+    }
+    // as strings, the values are not parsed like in the toml file
+    .merge(Serialized::defaults(env_settings));
     // TODO avoid unwrap
     let config: Config = figment.extract().map_err(|e| {
         io::Error::new(
@@ -145,15 +653,179 @@ fn read_config(config_path: &Path) -> io::Result<ResolvedConfig> {
     let tables_folder = config
         .tables_folder
         .unwrap_or(default_tables_root(&config.vpx_executable));
+    let display_template = match config.display_template {
+        Some(template) => template.parse().unwrap_or_else(|e| {
+            warn!(
+                "Invalid display_template \"{}\": {}. Using the default \"{}\".",
+                template, e, DEFAULT_DISPLAY_TEMPLATE
+            );
+            DisplayTemplate::default()
+        }),
+        None => DisplayTemplate::default(),
+    }
+    .hide_stem(config.hide_stem)
+    .capitalize(config.name_capitalization);
+    let resource_url = config.resource_url.and_then(|template| {
+        template
+            .parse()
+            .map_err(|e| {
+                warn!(
+                    "Invalid resource_url \"{}\": {}. Opening resource pages is disabled.",
+                    template, e
+                );
+            })
+            .ok()
+    });
+    let inbox_name_template = config.inbox_name_template.and_then(|template| {
+        template
+            .parse()
+            .map_err(|e| {
+                warn!(
+                    "Invalid inbox_name_template \"{}\": {}. Tables keep their file name.",
+                    template, e
+                );
+            })
+            .ok()
+    });
+    let mut sidecar_extensions = default_sidecar_extensions();
+    for extension in config.extra_sidecar_extensions {
+        let extension = extension.trim_start_matches('.').to_string();
+        if !sidecar_extensions.contains(&extension) {
+            sidecar_extensions.push(extension);
+        }
+    }
+    let mut backup_patterns = default_backup_patterns();
+    for pattern in config.extra_backup_patterns {
+        if !backup_patterns.contains(&pattern) {
+            backup_patterns.push(pattern);
+        }
+    }
+    let mut manufacturer_palette = config
+        .manufacturer_palette
+        .iter()
+        .filter_map(|color| match color.parse::<Rgb>() {
+            Ok(rgb) => Some(rgb),
+            Err(e) => {
+                warn!("Invalid manufacturer_palette color: {}", e);
+                None
+            }
+        })
+        .collect::<Vec<Rgb>>();
+    if manufacturer_palette.is_empty() {
+        manufacturer_palette = default_manufacturer_palette();
+    }
+    let (info_fields, unknown_info_fields) = parse_info_fields(&config.info_fields);
+    for name in unknown_info_fields {
+        warn!("Unknown info_fields entry \"{}\", ignoring it", name);
+    }
+    let mut index_path = tables_index_path(&tables_folder);
+    if config.sqlite_index {
+        index_path.set_extension("sqlite");
+    }
     let resolved_config = ResolvedConfig {
         vpx_executable: config.vpx_executable,
         tables_folder: tables_folder.clone(),
-        tables_index_path: tables_index_path(&tables_folder),
+        tables_index_path: index_path,
         editor: config.editor,
+        read_only: config.read_only,
+        display_template,
+        resource_url,
+        sidecar_extensions,
+        include_hidden_sidecars: config.include_hidden_sidecars,
+        launch_args: config.launch_args,
+        table_launch_args: config.table_launch_args,
+        launch_in_background: config.launch_in_background,
+        table_editor_command: config.table_editor_command,
+        installed_components: config.installed_components,
+        exclude_backups: config.exclude_backups,
+        backup_patterns,
+        index_archives: config.index_archives,
+        index_parallelism: config.index_parallelism,
+        index_threads: config.index_threads,
+        high_contrast: config.high_contrast,
+        manufacturer_colors: config.manufacturer_colors,
+        manufacturer_palette,
+        state_colors: config.state_colors,
+        row_state_priority: config.row_state_priority,
+        info_fields,
+        auto_select_first: config.auto_select_first,
+        show_sizes: config.show_sizes,
+        warning_counts: config.warning_counts,
+        show_paths: config.show_paths,
+        row_spacing: config.row_spacing,
+        rom_folder: config.rom_folder,
+        nvram_folder: config.nvram_folder,
+        reference_hashes: config.reference_hashes,
+        rom_manifest: config.rom_manifest,
+        vps_database: config.vps_database,
+        staging_folder: config.staging_folder,
+        inbox_folder: config.inbox_folder,
+        inbox_name_template,
+        wheel_folder: config.wheel_folder,
+        filter_presets: config.filter_presets,
+        completeness_weights: config.completeness_weights,
+        file_name_patterns: FileNamePatterns {
+            version: file_name_pattern(
+                "file_name_version_pattern",
+                config.file_name_version_pattern.as_deref(),
+                DEFAULT_VERSION_PATTERN,
+            ),
+            rating: file_name_pattern(
+                "file_name_rating_pattern",
+                config.file_name_rating_pattern.as_deref(),
+                DEFAULT_RATING_PATTERN,
+            ),
+        },
+        relative_paths: config.relative_paths,
+        relative_paths_base: config.relative_paths_base,
+        min_play_seconds: config.min_play_seconds,
+        description_preview_length: config.description_preview_length,
+        tags_export_path: config.tags_export_path,
+        tags_export_format: config.tags_export_format,
+        chd_games: config.chd_games,
+        chd_folder: config.chd_folder,
     };
     Ok(resolved_config)
 }
 
+/// The pattern from the config, the default if it is invalid
+fn file_name_pattern(name: &str, setting: Option<&str>, default: &str) -> Option<Regex> {
+    file_name_tags::parse_pattern(setting, default).unwrap_or_else(|e| {
+        warn!(
+            "Invalid {} \"{}\": {}. Using the default \"{}\".",
+            name,
+            setting.unwrap_or_default(),
+            e,
+            default
+        );
+        Regex::new(default).ok()
+    })
+}
+
+fn default_sidecar_extensions() -> Vec<String> {
+    DEFAULT_SIDECAR_EXTENSIONS.map(String::from).to_vec()
+}
+
+fn default_manufacturer_colors() -> bool {
+    true
+}
+
+fn default_state_colors() -> bool {
+    true
+}
+
+fn default_min_play_seconds() -> u64 {
+    60
+}
+
+fn default_description_preview_length() -> usize {
+    60
+}
+
+fn default_backup_patterns() -> Vec<String> {
+    DEFAULT_BACKUP_PATTERNS.map(String::from).to_vec()
+}
+
 pub fn tables_index_path(tables_folder: &Path) -> PathBuf {
     tables_folder.join("vpxtool_index.json")
 }
@@ -177,7 +849,7 @@ fn home_config_path() -> PathBuf {
     dirs::config_dir().unwrap().join(CONFIGURATION_FILE_NAME)
 }
 
-fn create_default_config() -> io::Result<(PathBuf, ResolvedConfig)> {
+fn choose_config_path() -> PathBuf {
     let local_configuration_path = local_config_path();
     let home_directory_configuration_path = home_config_path();
     let choices: Vec<(&str, String)> = vec![
@@ -205,11 +877,18 @@ fn create_default_config() -> io::Result<(PathBuf, ResolvedConfig)> {
         .interact_opt()
         .unwrap();
 
-    let config_file = if let Some(index) = selection_opt {
+    if let Some(index) = selection_opt {
         let (_selected_choice, path) = (&choices[index].0, &choices[index].1);
         PathBuf::from(path)
     } else {
         unreachable!("Failed to select a configuration file path.");
+    }
+}
+
+fn create_default_config() -> io::Result<(PathBuf, ResolvedConfig)> {
+    let config_file = match explicit_config_path() {
+        Some(explicit_path) => explicit_path,
+        None => choose_config_path(),
     };
 
     let mut vpx_executable = default_vpinball_executable_detection();
@@ -237,12 +916,7 @@ fn create_default_config() -> io::Result<(PathBuf, ResolvedConfig)> {
     let tables_root = default_tables_root(&vpx_executable);
     let index_path = tables_index_path(&tables_root);
 
-    let resolved_config = ResolvedConfig {
-        vpx_executable,
-        tables_folder: tables_root,
-        tables_index_path: index_path,
-        editor: None,
-    };
+    let resolved_config = default_resolved_config(vpx_executable, tables_root, index_path);
     let config = Config::from(&resolved_config);
 
     // write config to config_file
@@ -250,6 +924,126 @@ fn create_default_config() -> io::Result<(PathBuf, ResolvedConfig)> {
     Ok((config_file, resolved_config))
 }
 
+/// Config for trying vpxtool without setting it up, used when there is no config file.
+///
+/// Lists the tables in the current directory and looks for Visual Pinball and PinMAME in their
+/// default locations. The index is kept in the cache directory to leave the folder untouched.
+pub fn current_dir_config() -> io::Result<ResolvedConfig> {
+    let cache_dir = dirs::cache_dir().unwrap_or_else(env::temp_dir);
+    folder_config(env::current_dir()?, &cache_dir)
+}
+
+/// [current_dir_config] for any tables folder, with the index in `vpxtool` in the `cache_dir`
+fn folder_config(tables_folder: PathBuf, cache_dir: &Path) -> io::Result<ResolvedConfig> {
+    let index_folder = cache_dir.join("vpxtool");
+    std::fs::create_dir_all(&index_folder)?;
+    // one index per folder
+    let digest = Sha256::digest(tables_folder.to_string_lossy().as_bytes());
+    let folder_id = digest
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    let index_path = index_folder.join(format!("vpxtool_index_{}.json", folder_id));
+    Ok(default_resolved_config(
+        default_vpinball_executable_detection(),
+        tables_folder,
+        index_path,
+    ))
+}
+
+/// The config with every setting at its default, nothing is read from or written to disk
+pub fn default_resolved_config(
+    vpx_executable: PathBuf,
+    tables_folder: PathBuf,
+    tables_index_path: PathBuf,
+) -> ResolvedConfig {
+    ResolvedConfig {
+        vpx_executable,
+        tables_folder,
+        tables_index_path,
+        editor: None,
+        read_only: false,
+        display_template: DisplayTemplate::default(),
+        resource_url: None,
+        sidecar_extensions: default_sidecar_extensions(),
+        include_hidden_sidecars: false,
+        launch_args: Vec::new(),
+        table_launch_args: BTreeMap::new(),
+        launch_in_background: false,
+        table_editor_command: Vec::new(),
+        installed_components: Vec::new(),
+        exclude_backups: false,
+        backup_patterns: default_backup_patterns(),
+        index_archives: false,
+        index_parallelism: IndexParallelism::Auto,
+        index_threads: None,
+        high_contrast: false,
+        manufacturer_colors: true,
+        manufacturer_palette: default_manufacturer_palette(),
+        state_colors: true,
+        row_state_priority: default_row_state_priority(),
+        info_fields: InfoField::ALL.to_vec(),
+        auto_select_first: false,
+        show_sizes: false,
+        warning_counts: false,
+        show_paths: false,
+        row_spacing: 0,
+        rom_folder: None,
+        nvram_folder: None,
+        reference_hashes: None,
+        rom_manifest: None,
+        vps_database: None,
+        staging_folder: None,
+        inbox_folder: None,
+        inbox_name_template: None,
+        wheel_folder: None,
+        filter_presets: Vec::new(),
+        completeness_weights: CompletenessWeights::default(),
+        file_name_patterns: FileNamePatterns::default(),
+        relative_paths: false,
+        relative_paths_base: None,
+        min_play_seconds: 60,
+        description_preview_length: 60,
+        tags_export_path: None,
+        tags_export_format: TagsExportFormat::Json,
+        chd_games: vec![],
+        chd_folder: None,
+    }
+}
+
+/// Adds the preset to the config file, replacing a preset with the same name.
+///
+/// Only the presets are touched, other settings are kept as they are. Comments are lost.
+pub fn save_filter_preset(config_file: &Path, preset: &FilterPreset) -> io::Result<()> {
+    let text = std::fs::read_to_string(config_file)?;
+    let mut table: toml::Table = text.parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Failed to load config file: {}", e),
+        )
+    })?;
+    let presets = table
+        .entry("filter_presets")
+        .or_insert_with(|| toml::Value::Array(Vec::new()));
+    let Some(presets) = presets.as_array_mut() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "filter_presets in the config file is not a list",
+        ));
+    };
+    let value = toml::Value::try_from(preset).map_err(io::Error::other)?;
+    let existing = presets.iter().position(|existing| {
+        existing.get("name").and_then(toml::Value::as_str) == Some(preset.name.as_str())
+    });
+    match existing {
+        Some(position) => presets[position] = value,
+        None => presets.push(value),
+    }
+    let toml = toml::to_string(&table).map_err(io::Error::other)?;
+    std::fs::write(config_file, toml)
+}
+
 fn write_config(config_file: &Path, config: &Config) -> io::Result<()> {
     let toml = toml::to_string(&config).unwrap();
     let mut file = File::create(config_file)?;
@@ -311,6 +1105,26 @@ mod tests {
     use pretty_assertions::assert_eq;
     use testdir::testdir;
 
+    #[test]
+    fn test_folder_config() -> io::Result<()> {
+        let test_dir = testdir!();
+        let cache_dir = test_dir.join("cache");
+        let tables_folder = test_dir.join("tables");
+        let config = folder_config(tables_folder.clone(), &cache_dir)?;
+        assert_eq!(config.tables_folder, tables_folder);
+        // the index is not written to the tables folder
+        assert_eq!(
+            config.tables_index_path.parent(),
+            Some(cache_dir.join("vpxtool").as_path())
+        );
+        assert!(cache_dir.join("vpxtool").is_dir());
+        assert_eq!(config, folder_config(tables_folder, &cache_dir)?);
+        // one index per folder
+        let other = folder_config(test_dir.join("other"), &cache_dir)?;
+        assert_ne!(other.tables_index_path, config.tables_index_path);
+        Ok(())
+    }
+
     // test that we can read a incomplete config file with missing tables_folder
     #[test]
     fn test_read_incomplete_config() -> io::Result<()> {
@@ -321,7 +1135,7 @@ mod tests {
         let mut file = File::create(&config_file)?;
         file.write_all(b"vpx_executable = \"/tmp/test/vpinball\"")?;
 
-        let config = read_config(&config_file)?;
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
 
         if cfg!(target_os = "macos") {
             let expected_tables_dir = dirs::home_dir().unwrap().join(".vpinball").join("tables");
@@ -332,6 +1146,52 @@ mod tests {
                     tables_folder: expected_tables_dir.clone(),
                     tables_index_path: expected_tables_dir.join("vpxtool_index.json"),
                     editor: None,
+                    read_only: false,
+                    display_template: DisplayTemplate::default(),
+                    resource_url: None,
+                    sidecar_extensions: default_sidecar_extensions(),
+                    include_hidden_sidecars: false,
+                    launch_args: Vec::new(),
+                    table_launch_args: BTreeMap::new(),
+                    launch_in_background: false,
+                    table_editor_command: Vec::new(),
+                    installed_components: Vec::new(),
+                    exclude_backups: false,
+                    backup_patterns: default_backup_patterns(),
+                    index_archives: false,
+                    index_parallelism: IndexParallelism::Auto,
+                    index_threads: None,
+                    high_contrast: false,
+                    manufacturer_colors: true,
+                    manufacturer_palette: default_manufacturer_palette(),
+                    state_colors: true,
+                    row_state_priority: default_row_state_priority(),
+                    info_fields: InfoField::ALL.to_vec(),
+                    auto_select_first: false,
+                    show_sizes: false,
+                    warning_counts: false,
+                    show_paths: false,
+                    row_spacing: 0,
+                    rom_folder: None,
+                    nvram_folder: None,
+                    reference_hashes: None,
+                    rom_manifest: None,
+                    vps_database: None,
+                    staging_folder: None,
+                    inbox_folder: None,
+                    inbox_name_template: None,
+                    wheel_folder: None,
+                    filter_presets: Vec::new(),
+                    completeness_weights: CompletenessWeights::default(),
+                    file_name_patterns: FileNamePatterns::default(),
+                    relative_paths: false,
+                    relative_paths_base: None,
+                    min_play_seconds: 60,
+                    description_preview_length: 60,
+                    tags_export_path: None,
+                    tags_export_format: TagsExportFormat::Json,
+                    chd_games: vec![],
+                    chd_folder: None,
                 }
             );
         } else {
@@ -342,9 +1202,327 @@ mod tests {
                     tables_folder: PathBuf::from("/tmp/test/tables"),
                     tables_index_path: PathBuf::from("/tmp/test/tables/vpxtool_index.json"),
                     editor: None,
+                    read_only: false,
+                    display_template: DisplayTemplate::default(),
+                    resource_url: None,
+                    sidecar_extensions: default_sidecar_extensions(),
+                    include_hidden_sidecars: false,
+                    launch_args: Vec::new(),
+                    table_launch_args: BTreeMap::new(),
+                    launch_in_background: false,
+                    table_editor_command: Vec::new(),
+                    installed_components: Vec::new(),
+                    exclude_backups: false,
+                    backup_patterns: default_backup_patterns(),
+                    index_archives: false,
+                    index_parallelism: IndexParallelism::Auto,
+                    index_threads: None,
+                    high_contrast: false,
+                    manufacturer_colors: true,
+                    manufacturer_palette: default_manufacturer_palette(),
+                    state_colors: true,
+                    row_state_priority: default_row_state_priority(),
+                    info_fields: InfoField::ALL.to_vec(),
+                    auto_select_first: false,
+                    show_sizes: false,
+                    warning_counts: false,
+                    show_paths: false,
+                    row_spacing: 0,
+                    rom_folder: None,
+                    nvram_folder: None,
+                    reference_hashes: None,
+                    rom_manifest: None,
+                    vps_database: None,
+                    staging_folder: None,
+                    inbox_folder: None,
+                    inbox_name_template: None,
+                    wheel_folder: None,
+                    filter_presets: Vec::new(),
+                    completeness_weights: CompletenessWeights::default(),
+                    file_name_patterns: FileNamePatterns::default(),
+                    relative_paths: false,
+                    relative_paths_base: None,
+                    min_play_seconds: 60,
+                    description_preview_length: 60,
+                    tags_export_path: None,
+                    tags_export_format: TagsExportFormat::Json,
+                    chd_games: vec![],
+                    chd_folder: None,
                 }
             );
         }
         Ok(())
     }
+
+    #[test]
+    fn test_read_config_read_only() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(b"vpx_executable = \"/tmp/test/vpinball\"\nread_only = true")?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert!(config.read_only);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_high_contrast() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(b"vpx_executable = \"/tmp/test/vpinball\"\nhigh_contrast = true")?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert!(config.high_contrast);
+        assert!(Config::from(&config).high_contrast);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_sqlite_index() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            b"vpx_executable = \"/tmp/test/vpinball\"\ntables_folder = \"/tmp/test/tables\"\nsqlite_index = true",
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert_eq!(
+            config.tables_index_path,
+            PathBuf::from("/tmp/test/tables/vpxtool_index.sqlite")
+        );
+        assert!(Config::from(&config).sqlite_index);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_filter_preset() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(b"vpx_executable = \"/tmp/test/vpinball\"\nhigh_contrast = true")?;
+
+        let williams = FilterPreset {
+            name: "williams".to_string(),
+            filter: "williams".to_string(),
+            warnings_first: true,
+            most_warnings_first: false,
+            rating_first: false,
+            completeness_first: true,
+        };
+        let mut kids = FilterPreset {
+            name: "kids".to_string(),
+            filter: "+kids".to_string(),
+            warnings_first: false,
+            most_warnings_first: true,
+            rating_first: true,
+            completeness_first: false,
+        };
+        save_filter_preset(&config_file, &williams)?;
+        save_filter_preset(&config_file, &kids)?;
+        kids.filter = "+kids -broken".to_string();
+        save_filter_preset(&config_file, &kids)?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+        assert!(config.high_contrast);
+        assert_eq!(config.filter_presets, vec![williams, kids]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_manufacturer_colors() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            b"vpx_executable = \"/tmp/test/vpinball\"\nmanufacturer_colors = false\nmanufacturer_palette = [\"#ff0000\", \"oops\"]",
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert!(!config.manufacturer_colors);
+        assert_eq!(config.manufacturer_palette, vec![Rgb(255, 0, 0)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_invalid_display_template() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            b"vpx_executable = \"/tmp/test/vpinball\"\ndisplay_template = \"{name} {oops}\"",
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert_eq!(config.display_template, DisplayTemplate::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_extra_sidecar_extensions() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            b"vpx_executable = \"/tmp/test/vpinball\"\nextra_sidecar_extensions = [\".cfg\", \"vbs\"]",
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert_eq!(
+            config.sidecar_extensions,
+            vec![
+                "vbs",
+                "ini",
+                "directb2s",
+                "info.json",
+                "notes.json",
+                "wheel.png",
+                "vpxtool.json",
+                "cfg"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_launch_args() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            br#"vpx_executable = "/tmp/test/vpinball"
+launch_args = ["-Minimized"]
+table_editor_command = ["/tmp/test/vpinball", "-edit"]
+installed_components = ["VPinMAME", "B2SBackglassServer"]
+launch_in_background = true
+
+[table_launch_args]
+"Table (Bally 1980).vpx" = ["-DisableTrueFullscreen", "-ini", "custom.ini"]
+"#,
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert_eq!(
+            config.launch_args_for(Path::new("/tables/Table (Bally 1980).vpx")),
+            vec!["-Minimized", "-DisableTrueFullscreen", "-ini", "custom.ini"]
+        );
+        assert_eq!(
+            config.launch_args_for(Path::new("/tables/Other.vpx")),
+            vec!["-Minimized"]
+        );
+        assert_eq!(
+            config.table_editor_command,
+            vec!["/tmp/test/vpinball", "-edit"]
+        );
+        assert_eq!(
+            config.installed_components,
+            vec!["VPinMAME", "B2SBackglassServer"]
+        );
+        assert!(config.launch_in_background);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_inbox() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            br#"vpx_executable = "/tmp/test/vpinball"
+inbox_folder = "/tmp/test/downloads"
+inbox_name_template = "{name} ({manufacturer} {year})"
+"#,
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert_eq!(
+            config.inbox_folder,
+            Some(PathBuf::from("/tmp/test/downloads"))
+        );
+        assert_eq!(
+            config
+                .inbox_name_template
+                .map(|template| template.to_string()),
+            Some("{name} ({manufacturer} {year})".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_env() -> io::Result<()> {
+        let settings = env_settings(
+            [
+                ("VPXTOOL_TABLES_FOLDER", "/cabinet/tables"),
+                ("VPXTOOL_ROM_FOLDER", ""),
+                ("VPXTOOL_EDITOR", "vim"),
+                ("TABLES_FOLDER", "/other"),
+            ]
+            .map(|(name, value)| (OsString::from(name), OsString::from(value))),
+        );
+        assert_eq!(
+            settings,
+            BTreeMap::from([("tables_folder".to_string(), "/cabinet/tables".to_string())])
+        );
+
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        std::fs::write(
+            &config_file,
+            "vpx_executable = \"/tmp/test/vpinball\"\ntables_folder = \"/tmp/test/tables\"",
+        )?;
+        let config = read_config_with_env(Some(&config_file), &settings)?;
+        assert_eq!(config.vpx_executable, PathBuf::from("/tmp/test/vpinball"));
+        assert_eq!(config.tables_folder, PathBuf::from("/cabinet/tables"));
+        assert_eq!(
+            config.tables_index_path,
+            tables_index_path(Path::new("/cabinet/tables"))
+        );
+
+        // no config file
+        let settings = BTreeMap::from([
+            (
+                "vpx_executable".to_string(),
+                "/cabinet/VPinballX_GL".to_string(),
+            ),
+            ("tables_folder".to_string(), "/cabinet/tables".to_string()),
+            ("rom_folder".to_string(), "1995".to_string()),
+        ]);
+        let config = read_config_with_env(None, &settings)?;
+        assert_eq!(
+            config.vpx_executable,
+            PathBuf::from("/cabinet/VPinballX_GL")
+        );
+        assert_eq!(config.tables_folder, PathBuf::from("/cabinet/tables"));
+        assert_eq!(config.rom_folder, Some(PathBuf::from("1995")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_config_backups() -> io::Result<()> {
+        let temp_dir = testdir!();
+        let config_file = temp_dir.join(CONFIGURATION_FILE_NAME);
+        let mut file = File::create(&config_file)?;
+        file.write_all(
+            b"vpx_executable = \"/tmp/test/vpinball\"\nexclude_backups = true\nextra_backup_patterns = [\"*.old.vpx\"]",
+        )?;
+
+        let config = read_config_with_env(Some(&config_file), &BTreeMap::new())?;
+
+        assert!(config.exclude_backups);
+        assert_eq!(
+            config.index_excluded_patterns().last(),
+            Some(&"*.old.vpx".to_string())
+        );
+        Ok(())
+    }
 }
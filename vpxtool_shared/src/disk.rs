@@ -0,0 +1,171 @@
+//! Guesses what kind of disk a folder is on, so the indexer can go easy on slow disks.
+//!
+//! Only implemented for Linux, where the mount table and the block device attributes are readable
+//! from `/proc` and `/sys`. Elsewhere the disk kind is unknown.
+use std::path::{Path, PathBuf};
+
+/// File systems that are mounted over the network
+const NETWORK_FS_TYPES: [&str; 12] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskKind {
+    /// SSD or NVMe, fine with many reads at the same time
+    Solid,
+    /// Spinning disk where parallel reads make the heads seek back and forth
+    Rotational,
+    /// USB sticks and card readers
+    Removable,
+    /// NAS and other network shares
+    Network,
+}
+
+impl DiskKind {
+    /// Whether reading many files at the same time is likely slower than one after the other
+    pub fn is_slow(self) -> bool {
+        self != DiskKind::Solid
+    }
+}
+
+/// A line of `/proc/self/mountinfo`
+#[derive(Debug, PartialEq)]
+struct Mount {
+    mount_point: PathBuf,
+    fs_type: String,
+    source: String,
+}
+
+/// The kind of disk the folder is on, `None` where that can't be found out
+pub fn disk_kind(folder: &Path) -> Option<DiskKind> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let folder = folder.canonicalize().ok()?;
+    let mount_info = std::fs::read_to_string("/proc/self/mountinfo").ok()?;
+    let mount = find_mount(&mount_info, &folder)?;
+    if NETWORK_FS_TYPES.contains(&mount.fs_type.as_str()) {
+        return Some(DiskKind::Network);
+    }
+    block_device_kind(Path::new(&mount.source))
+}
+
+/// The mount with the longest mount point that contains the path
+fn find_mount(mount_info: &str, path: &Path) -> Option<Mount> {
+    mount_info
+        .lines()
+        .filter_map(parse_mount)
+        .filter(|mount| path.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.components().count())
+}
+
+/// Parses a mountinfo line like
+/// `36 35 98:0 / /mnt/tables rw,noatime master:1 - ext4 /dev/sda1 rw`
+fn parse_mount(line: &str) -> Option<Mount> {
+    let (mount_fields, fs_fields) = line.split_once(" - ")?;
+    let mount_point = mount_fields.split(' ').nth(4)?;
+    let mut fs_fields = fs_fields.split(' ');
+    let fs_type = fs_fields.next()?;
+    let source = fs_fields.next()?;
+    Some(Mount {
+        mount_point: PathBuf::from(unescape_octal(mount_point)),
+        fs_type: fs_type.to_string(),
+        source: unescape_octal(source),
+    })
+}
+
+/// Mountinfo escapes spaces, tabs, newlines and backslashes like `\040`
+fn unescape_octal(field: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = field;
+    while let Some(backslash) = rest.find('\\') {
+        unescaped.push_str(&rest[..backslash]);
+        let code = rest.get(backslash + 1..backslash + 4);
+        match code.and_then(|code| u8::from_str_radix(code, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(byte as char);
+                rest = &rest[backslash + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[backslash + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Looks up the device in `/sys/class/block`, partitions take the attributes of their disk
+fn block_device_kind(device: &Path) -> Option<DiskKind> {
+    // /dev/disk/by-uuid/... and /dev/mapper/... are links to the actual device
+    let device = device.canonicalize().ok()?;
+    let name = device.strip_prefix("/dev").ok()?.file_name()?;
+    let mut sys_device = Path::new("/sys/class/block")
+        .join(name)
+        .canonicalize()
+        .ok()?;
+    if sys_device.join("partition").exists() {
+        sys_device = sys_device.parent()?.to_path_buf();
+    }
+    let read_flag = |file: &str| {
+        std::fs::read_to_string(sys_device.join(file))
+            .ok()
+            .map(|value| value.trim() == "1")
+    };
+    if read_flag("removable")? {
+        Some(DiskKind::Removable)
+    } else if read_flag("queue/rotational")? {
+        Some(DiskKind::Rotational)
+    } else {
+        Some(DiskKind::Solid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const MOUNT_INFO: &str = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+45 22 0:40 / /mnt/nas rw,relatime shared:30 - cifs //nas/pinball rw,vers=3.1.1
+46 22 8:17 / /media/usb\\040stick rw,nosuid shared:31 - vfat /dev/sdb1 rw
+";
+
+    #[test]
+    fn test_find_mount() {
+        let mount = |path: &str| find_mount(MOUNT_INFO, Path::new(path)).unwrap();
+        assert_eq!(mount("/home/pinball/tables").source, "/dev/nvme0n1p2");
+        assert_eq!(
+            mount("/mnt/nas/tables"),
+            Mount {
+                mount_point: PathBuf::from("/mnt/nas"),
+                fs_type: "cifs".to_string(),
+                source: "//nas/pinball".to_string(),
+            }
+        );
+        // only whole path components match
+        assert_eq!(mount("/mnt/nasty").source, "/dev/nvme0n1p2");
+        assert_eq!(mount("/media/usb stick/tables").source, "/dev/sdb1");
+        assert_eq!(find_mount("", Path::new("/tables")), None);
+    }
+
+    #[test]
+    fn test_unescape_octal() {
+        assert_eq!(unescape_octal("/media/usb\\040stick"), "/media/usb stick");
+        assert_eq!(unescape_octal("back\\134slash"), "back\\slash");
+        assert_eq!(unescape_octal("odd\\"), "odd\\");
+    }
+}
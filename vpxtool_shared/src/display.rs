@@ -0,0 +1,971 @@
+//! Configurable rendering of table names in table lists.
+//!
+//! A template mixes literal text with the tokens `{name}`, `{stem}`, `{manufacturer}` and
+//! `{year}`. Manufacturer and year are taken from the common `Title (Manufacturer Year)` file
+//! naming convention.
+//!
+//! Tokens without a value render empty and brackets left empty are dropped. When the table has
+//! no name `{name}` falls back to the file stem and `{stem}` renders empty, this way the default
+//! template shows just the file stem for tables without a name. With [DisplayTemplate::hide_stem]
+//! `{stem}` always renders empty.
+//!
+//! The same tokens fill in the url of the table resource page, see [DisplayTemplate::render_url].
+//!
+//! Table names are capitalized as configured with [Capitalization].
+use crate::indexer::IndexedTable;
+use crate::notes::MAX_RATING;
+use crate::tags_export::FAVORITE_TAG;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub const DEFAULT_DISPLAY_TEMPLATE: &str = "{name} ({stem})";
+
+static MANUFACTURER_YEAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(([^()]*?)\s*(\d{4})\)").unwrap());
+static EMPTY_BRACKETS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(\s*\)|\[\s*\]").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Name,
+    Stem,
+    Manufacturer,
+    Year,
+}
+
+impl Token {
+    fn parse(s: &str) -> Option<Token> {
+        match s {
+            "name" => Some(Token::Name),
+            "stem" => Some(Token::Stem),
+            "manufacturer" => Some(Token::Manufacturer),
+            "year" => Some(Token::Year),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Token(Token),
+}
+
+/// How `{name}` is capitalized. Names with uppercase letters are left alone so acronyms and
+/// names like `iCarly` keep their spelling.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capitalization {
+    /// Names are shown as they are
+    Off,
+    /// The first letter of a name without uppercase letters is capitalized, `attack` becomes
+    /// `Attack`
+    #[default]
+    FirstLetter,
+    /// The first letter of every word without uppercase letters is capitalized, `attack from
+    /// mars` becomes `Attack From Mars`
+    TitleCase,
+}
+
+impl Capitalization {
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            Capitalization::Off => name.to_string(),
+            Capitalization::FirstLetter => capitalize_first_letter(name),
+            Capitalization::TitleCase => name
+                .split(' ')
+                .map(capitalize_first_letter)
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayTemplate {
+    source: String,
+    segments: Vec<Segment>,
+    hide_stem: bool,
+    capitalization: Capitalization,
+}
+
+impl Default for DisplayTemplate {
+    fn default() -> Self {
+        DEFAULT_DISPLAY_TEMPLATE.parse().unwrap()
+    }
+}
+
+impl Display for DisplayTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for DisplayTemplate {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let after = &rest[start + 1..];
+            let end = after.find('}').ok_or_else(|| {
+                format!(
+                    "Unclosed '{{' at position {}",
+                    source.len() - rest.len() + start
+                )
+            })?;
+            let name = &after[..end];
+            let token = Token::parse(name).ok_or_else(|| format!("Unknown token {{{}}}", name))?;
+            segments.push(Segment::Token(token));
+            rest = &after[end + 1..];
+        }
+        if rest.contains('}') {
+            return Err("Unmatched '}'".to_string());
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        Ok(DisplayTemplate {
+            source: source.to_string(),
+            segments,
+            hide_stem: false,
+            capitalization: Capitalization::default(),
+        })
+    }
+}
+
+impl DisplayTemplate {
+    /// Leaves out the file stem, named tables show just their name
+    pub fn hide_stem(mut self, hide_stem: bool) -> Self {
+        self.hide_stem = hide_stem;
+        self
+    }
+
+    pub fn hides_stem(&self) -> bool {
+        self.hide_stem
+    }
+
+    /// Changes how table names are capitalized
+    pub fn capitalize(mut self, capitalization: Capitalization) -> Self {
+        self.capitalization = capitalization;
+        self
+    }
+
+    pub fn capitalization(&self) -> Capitalization {
+        self.capitalization
+    }
+
+    pub fn render(&self, table: &IndexedTable) -> String {
+        self.render_styled(table, |_, value| value.to_string())
+    }
+
+    /// Renders the template, passing each non-empty token value through `style`
+    pub fn render_styled<F>(&self, table: &IndexedTable, style: F) -> String
+    where
+        F: Fn(Token, &str) -> String,
+    {
+        let stem = table
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let table_name = table
+            .table_info
+            .table_name
+            .as_ref()
+            .filter(|name| !name.trim().is_empty());
+        let (manufacturer, year) = manufacturer_and_year(&stem);
+
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Token(token) => {
+                    let value = match token {
+                        Token::Name => match table_name {
+                            Some(name) => self.capitalization.apply(name),
+                            None => stem.clone(),
+                        },
+                        Token::Stem if table_name.is_none() || self.hide_stem => String::new(),
+                        Token::Stem => stem.clone(),
+                        Token::Manufacturer => manufacturer.clone().unwrap_or_default(),
+                        Token::Year => year.clone().unwrap_or_default(),
+                    };
+                    if !value.is_empty() {
+                        rendered.push_str(&style(*token, &value));
+                    }
+                }
+            }
+        }
+        let rendered = EMPTY_BRACKETS_RE.replace_all(&rendered, "");
+        rendered.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Renders the template as url, token values are percent-encoded and tokens without a value
+    /// are left out
+    pub fn render_url(&self, table: &IndexedTable) -> String {
+        let stem = table
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let table_name = table
+            .table_info
+            .table_name
+            .as_ref()
+            .filter(|name| !name.trim().is_empty());
+        let (manufacturer, year) = manufacturer_and_year(&stem);
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Token(token) => {
+                    let value = match token {
+                        Token::Name => table_name.cloned().unwrap_or_else(|| stem.clone()),
+                        Token::Stem => stem.clone(),
+                        Token::Manufacturer => manufacturer.clone().unwrap_or_default(),
+                        Token::Year => year.clone().unwrap_or_default(),
+                    };
+                    rendered.push_str(&percent_encode(value.trim()));
+                }
+            }
+        }
+        rendered
+    }
+}
+
+/// Percent-encodes everything except the unreserved url characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// The tables that render to the same name, ignoring case, mapped to the shortest end of their
+/// path that tells them apart, eg `afm_v2.vpx` or `bally/afm.vpx`. Tables with a unique name are
+/// left out.
+pub fn name_disambiguations(
+    tables: &[IndexedTable],
+    template: &DisplayTemplate,
+) -> HashMap<PathBuf, String> {
+    let mut by_name: HashMap<String, Vec<&Path>> = HashMap::new();
+    for table in tables {
+        by_name
+            .entry(template.render(table).to_lowercase())
+            .or_default()
+            .push(&table.path);
+    }
+    let mut disambiguations = HashMap::new();
+    for paths in by_name.values().filter(|paths| paths.len() > 1) {
+        let max_depth = paths
+            .iter()
+            .map(|path| path.components().count())
+            .max()
+            .unwrap_or(1);
+        let depth = (1..=max_depth)
+            .find(|depth| {
+                let tails: HashSet<String> =
+                    paths.iter().map(|path| path_tail(path, *depth)).collect();
+                tails.len() == paths.len()
+            })
+            .unwrap_or(max_depth);
+        for path in paths {
+            disambiguations.insert(path.to_path_buf(), path_tail(path, depth));
+        }
+    }
+    disambiguations
+}
+
+/// The last `depth` components of the path, joined with `/`
+fn path_tail(path: &Path, depth: usize) -> String {
+    let components = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+    components[components.len().saturating_sub(depth)..].join("/")
+}
+
+/// Extracts manufacturer and year from a `Title (Manufacturer Year)` file stem
+pub fn manufacturer_and_year(stem: &str) -> (Option<String>, Option<String>) {
+    match MANUFACTURER_YEAR_RE.captures_iter(stem).last() {
+        Some(captures) => {
+            let manufacturer = captures
+                .get(1)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|m| !m.is_empty());
+            let year = captures.get(2).map(|m| m.as_str().to_string());
+            (manufacturer, year)
+        }
+        None => (None, None),
+    }
+}
+
+/// Colors manufacturers are tinted with when no palette is configured, readable on dark and
+/// light terminals.
+pub const DEFAULT_MANUFACTURER_PALETTE: [&str; 8] = [
+    "#e06c75", "#98c379", "#e5c07b", "#61afef", "#c678dd", "#56b6c2", "#d19a66", "#be5046",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    /// Parses `#rrggbb`, the `#` is optional
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let hex = source.trim_start_matches('#');
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Expected a #rrggbb color but got \"{}\"", source));
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        Ok(Rgb(channel(0), channel(2), channel(4)))
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+pub fn default_manufacturer_palette() -> Vec<Rgb> {
+    DEFAULT_MANUFACTURER_PALETTE
+        .iter()
+        .map(|color| color.parse().unwrap())
+        .collect()
+}
+
+/// Picks a color for the manufacturer of the table, `None` if the file name does not follow the
+/// `Title (Manufacturer Year)` convention.
+///
+/// The same manufacturer always gets the same color, regardless of casing.
+pub fn manufacturer_color(table: &IndexedTable, palette: &[Rgb]) -> Option<Rgb> {
+    if palette.is_empty() {
+        return None;
+    }
+    let stem = table.path.file_stem()?.to_string_lossy();
+    let (manufacturer, _) = manufacturer_and_year(&stem);
+    // FNV-1a, std hashers are not guaranteed to be stable across releases
+    let hash = manufacturer?
+        .to_lowercase()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    Some(palette[(hash % palette.len() as u64) as usize])
+}
+
+/// Tables added less than this long ago are [RowState::RecentlyAdded]
+const RECENTLY_ADDED: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A field of the table info in the frontend, the order and selection is configurable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfoField {
+    /// Version, name, author and description from the vpx file
+    Table,
+    Path,
+    /// Path of the rom zip
+    Rom,
+    /// Files next to the table sharing its name
+    Sidecars,
+    /// Number of backup files
+    Backups,
+    Wheel,
+    /// Whether the NVRAM with the high scores exists
+    HighScores,
+    Layout,
+    Physics,
+    Dmd,
+    /// Summary of the table ini
+    Ini,
+    /// Components the table needs
+    Requires,
+    /// Version from the file name
+    FileVersion,
+    /// Rating from the file name
+    FileRating,
+    Added,
+    Modified,
+    Completeness,
+    Warnings,
+    /// Check against the reference hashes
+    Reference,
+    /// Check against the rom manifest
+    RomAudit,
+    /// Match in the VPS database and whether there is a newer version
+    Vps,
+    Rating,
+    PlayTime,
+    Tags,
+    /// Suppressed warnings
+    Suppressed,
+    /// Warning for a corrupt rom zip
+    RomIntegrity,
+    /// Changelog from the script
+    Changelog,
+    /// Similar tables to jump to
+    Similar,
+}
+
+impl InfoField {
+    /// All fields in the default order
+    pub const ALL: [InfoField; 28] = [
+        InfoField::Table,
+        InfoField::Path,
+        InfoField::Rom,
+        InfoField::Sidecars,
+        InfoField::Backups,
+        InfoField::Wheel,
+        InfoField::HighScores,
+        InfoField::Layout,
+        InfoField::Physics,
+        InfoField::Dmd,
+        InfoField::Ini,
+        InfoField::Requires,
+        InfoField::FileVersion,
+        InfoField::FileRating,
+        InfoField::Added,
+        InfoField::Modified,
+        InfoField::Completeness,
+        InfoField::Warnings,
+        InfoField::Reference,
+        InfoField::RomAudit,
+        InfoField::Vps,
+        InfoField::Rating,
+        InfoField::PlayTime,
+        InfoField::Tags,
+        InfoField::Suppressed,
+        InfoField::RomIntegrity,
+        InfoField::Changelog,
+        InfoField::Similar,
+    ];
+
+    /// The name in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            InfoField::Table => "table",
+            InfoField::Path => "path",
+            InfoField::Rom => "rom",
+            InfoField::Sidecars => "sidecars",
+            InfoField::Backups => "backups",
+            InfoField::Wheel => "wheel",
+            InfoField::HighScores => "high_scores",
+            InfoField::Layout => "layout",
+            InfoField::Physics => "physics",
+            InfoField::Dmd => "dmd",
+            InfoField::Ini => "ini",
+            InfoField::Requires => "requires",
+            InfoField::FileVersion => "file_version",
+            InfoField::FileRating => "file_rating",
+            InfoField::Added => "added",
+            InfoField::Modified => "modified",
+            InfoField::Completeness => "completeness",
+            InfoField::Warnings => "warnings",
+            InfoField::Reference => "reference",
+            InfoField::RomAudit => "rom_audit",
+            InfoField::Vps => "vps",
+            InfoField::Rating => "rating",
+            InfoField::PlayTime => "play_time",
+            InfoField::Tags => "tags",
+            InfoField::Suppressed => "suppressed",
+            InfoField::RomIntegrity => "rom_integrity",
+            InfoField::Changelog => "changelog",
+            InfoField::Similar => "similar",
+        }
+    }
+}
+
+impl FromStr for InfoField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        InfoField::ALL
+            .into_iter()
+            .find(|field| field.name() == s.trim())
+            .ok_or_else(|| format!("Unknown info field \"{}\"", s))
+    }
+}
+
+pub fn default_info_field_names() -> Vec<String> {
+    InfoField::ALL
+        .iter()
+        .map(|field| field.name().to_string())
+        .collect()
+}
+
+/// The known fields in the given order, unknown names are returned separately
+pub fn parse_info_fields(names: &[String]) -> (Vec<InfoField>, Vec<String>) {
+    let mut fields = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names {
+        match name.parse::<InfoField>() {
+            Ok(field) if !fields.contains(&field) => fields.push(field),
+            Ok(_) => {}
+            Err(_) => unknown.push(name.clone()),
+        }
+    }
+    (fields, unknown)
+}
+
+/// States that color the name of a table in the list, the first one in the configured priority
+/// that applies wins over the manufacturer tint, see [row_state]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowState {
+    /// The table has warnings
+    Errors,
+    /// The table has the [FAVORITE_TAG]
+    Favorite,
+    /// The table is in a zip bundle
+    Archived,
+    /// The table was added in the last week
+    RecentlyAdded,
+}
+
+pub fn default_row_state_priority() -> Vec<RowState> {
+    vec![
+        RowState::Errors,
+        RowState::Favorite,
+        RowState::Archived,
+        RowState::RecentlyAdded,
+    ]
+}
+
+/// The first state in `priority` the table is in. `has_warnings` is only asked when errors are
+/// in the priority and no earlier state applies, as warnings check the file system.
+pub fn row_state(
+    table: &IndexedTable,
+    priority: &[RowState],
+    now: SystemTime,
+    has_warnings: impl Fn() -> bool,
+) -> Option<RowState> {
+    priority.iter().copied().find(|state| match state {
+        RowState::Errors => has_warnings(),
+        RowState::Favorite => table.tags.iter().any(|tag| tag == FAVORITE_TAG),
+        RowState::Archived => table.archived,
+        RowState::RecentlyAdded => now
+            .duration_since(table.added().into())
+            .is_ok_and(|age| age < RECENTLY_ADDED),
+    })
+}
+
+/// File size with binary units and one decimal, eg `1.5 MB` for 1572864 bytes
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// The file stem of the table if the list line doesn't show it already, ignoring case. Added to
+/// the search items so a table can be found by its file name as well as by its table name.
+pub fn search_stem(line: &str, table: &IndexedTable) -> Option<String> {
+    let stem = table.path.file_stem()?.to_string_lossy().to_string();
+    if line.to_lowercase().contains(&stem.to_lowercase()) {
+        None
+    } else {
+        Some(stem)
+    }
+}
+
+/// Shortens the text to `width` terminal columns by cutting off the start, so the end of a path
+/// with the file name stays visible, eg `…/Table (Bally 1995).vpx`. Wide characters like CJK
+/// and emoji take two columns.
+pub fn truncate_left(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    // room for the ellipsis
+    let mut tail = Vec::new();
+    let mut tail_width = 0;
+    for c in text.chars().rev() {
+        tail_width += c.width().unwrap_or(0);
+        if tail_width > width - 1 {
+            break;
+        }
+        tail.push(c);
+    }
+    format!("…{}", tail.into_iter().rev().collect::<String>())
+}
+
+/// The text on a single line, cut off after at most `max_width` terminal columns with an
+/// ellipsis. The cut is made between words unless that would drop more than half of the text.
+pub fn truncate_words(text: &str, max_width: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.width() <= max_width {
+        return text;
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    // room for the ellipsis
+    let mut cut = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next_if(|c| cut.width() + c.width().unwrap_or(0) < max_width) {
+        cut.push(c);
+    }
+    let ends_on_word = chars.peek() == Some(&' ');
+    let cut = match cut.rfind(' ') {
+        Some(space) if !ends_on_word && cut[..space].width() >= max_width / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end())
+}
+
+/// Hours and minutes, eg `3h 12m`, or only minutes below an hour
+pub fn format_play_time(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Filled and empty stars, eg `★★★☆☆` for a rating of 3
+pub fn format_rating(rating: u8) -> String {
+    let filled = rating.min(MAX_RATING) as usize;
+    format!(
+        "{}{}",
+        "★".repeat(filled),
+        "☆".repeat(MAX_RATING as usize - filled)
+    )
+}
+
+/// Leaves words that already have an uppercase letter alone
+fn capitalize_first_letter(s: &str) -> String {
+    if s.chars().any(char::is_uppercase) {
+        return s.to_string();
+    }
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn table(path: &str, table_name: Option<&str>) -> IndexedTable {
+        serde_json::from_value(json!({
+            "path": path,
+            "table_info": {
+                "table_name": table_name,
+                "properties": {}
+            },
+            "requires_pinmame": false,
+            "last_modified": "1970-01-01T00:00:00+00:00"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_default_template() {
+        let template = DisplayTemplate::default();
+        let named = table("/tables/Attack (Bally 1980).vpx", Some("attack"));
+        assert_eq!(template.render(&named), "Attack (Attack (Bally 1980))");
+        let unnamed = table("/tables/Attack (Bally 1980).vpx", None);
+        assert_eq!(template.render(&unnamed), "Attack (Bally 1980)");
+    }
+
+    #[test]
+    fn test_hide_stem() {
+        let template = DisplayTemplate::default().hide_stem(true);
+        let named = table("/tables/Attack (Bally 1980).vpx", Some("attack"));
+        assert_eq!(template.render(&named), "Attack");
+        let unnamed = table("/tables/Attack (Bally 1980).vpx", None);
+        assert_eq!(template.render(&unnamed), "Attack (Bally 1980)");
+    }
+
+    #[test]
+    fn test_parse_info_fields() {
+        let names = ["rom", "path", "nonsense", "rom", " tags"].map(String::from);
+        assert_eq!(
+            parse_info_fields(&names),
+            (
+                vec![InfoField::Rom, InfoField::Path, InfoField::Tags],
+                vec!["nonsense".to_string()]
+            )
+        );
+        let (defaults, unknown) = parse_info_fields(&default_info_field_names());
+        assert_eq!(defaults, InfoField::ALL.to_vec());
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn test_row_state() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+        let mut table = table("/tables/attack.vpx", Some("Attack from Mars"));
+        let priority = default_row_state_priority();
+        assert_eq!(row_state(&table, &priority, now, || false), None);
+
+        table.archived = true;
+        assert_eq!(
+            row_state(&table, &priority, now, || false),
+            Some(RowState::Archived)
+        );
+        table.tags = vec!["favorite".to_string()];
+        assert_eq!(
+            row_state(&table, &priority, now, || false),
+            Some(RowState::Favorite)
+        );
+        assert_eq!(
+            row_state(&table, &priority, now, || true),
+            Some(RowState::Errors)
+        );
+        // the configured order decides
+        let archived_first = [RowState::Archived, RowState::Errors];
+        assert_eq!(
+            row_state(&table, &archived_first, now, || true),
+            Some(RowState::Archived)
+        );
+        assert_eq!(row_state(&table, &[], now, || true), None);
+
+        table.created = Some((now - Duration::from_secs(60)).into());
+        assert_eq!(
+            row_state(&table, &[RowState::RecentlyAdded], now, || false),
+            Some(RowState::RecentlyAdded)
+        );
+        table.created = Some((now - RECENTLY_ADDED).into());
+        assert_eq!(
+            row_state(&table, &[RowState::RecentlyAdded], now, || false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_capitalization() {
+        let first_letter = Capitalization::FirstLetter;
+        assert_eq!(first_letter.apply("attack from mars"), "Attack from mars");
+        // acronyms and mixed case are kept
+        assert_eq!(first_letter.apply("AFM"), "AFM");
+        assert_eq!(first_letter.apply("iCarly"), "iCarly");
+        assert_eq!(first_letter.apply("tron: LEGACY"), "tron: LEGACY");
+        // unicode first letters
+        assert_eq!(first_letter.apply("élan"), "Élan");
+        assert_eq!(first_letter.apply("ßeta"), "SSeta");
+        assert_eq!(first_letter.apply("1941"), "1941");
+        assert_eq!(first_letter.apply(""), "");
+
+        let title_case = Capitalization::TitleCase;
+        assert_eq!(title_case.apply("attack from mars"), "Attack From Mars");
+        assert_eq!(title_case.apply("the AFM  edition"), "The AFM  Edition");
+        assert_eq!(title_case.apply("über iCarly"), "Über iCarly");
+
+        assert_eq!(Capitalization::Off.apply("attack"), "attack");
+
+        let named = table("/tables/attack.vpx", Some("attack from mars"));
+        let template = DisplayTemplate::default().hide_stem(true);
+        assert_eq!(template.render(&named), "Attack from mars");
+        let template = template.capitalize(Capitalization::Off);
+        assert_eq!(template.render(&named), "attack from mars");
+    }
+
+    #[test]
+    fn test_manufacturer_year_template() {
+        let template: DisplayTemplate = "{name} [{manufacturer} {year}]".parse().unwrap();
+        let named = table("/tables/Attack from Mars (Bally 1995) v2.vpx", Some("AFM"));
+        assert_eq!(template.render(&named), "AFM [Bally 1995]");
+        let plain = table("/tables/afm.vpx", Some("AFM"));
+        assert_eq!(template.render(&plain), "AFM");
+    }
+
+    #[test]
+    fn test_render_url() {
+        let template: DisplayTemplate =
+            "https://example.org/search?q={name}&m={manufacturer}&y={year}"
+                .parse()
+                .unwrap();
+        let named = table(
+            "/tables/Attack from Mars (Bally 1995).vpx",
+            Some("AFM & co"),
+        );
+        assert_eq!(
+            template.render_url(&named),
+            "https://example.org/search?q=AFM%20%26%20co&m=Bally&y=1995"
+        );
+        let plain = table("/tables/afm.vpx", None);
+        assert_eq!(
+            template.render_url(&plain),
+            "https://example.org/search?q=afm&m=&y="
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert_eq!(
+            "{name} {foo}".parse::<DisplayTemplate>(),
+            Err("Unknown token {foo}".to_string())
+        );
+        assert_eq!(
+            "{name".parse::<DisplayTemplate>(),
+            Err("Unclosed '{' at position 0".to_string())
+        );
+        assert_eq!(
+            "name}".parse::<DisplayTemplate>(),
+            Err("Unmatched '}'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_rgb() {
+        assert_eq!("#ff8000".parse::<Rgb>(), Ok(Rgb(255, 128, 0)));
+        assert_eq!("00FF10".parse::<Rgb>(), Ok(Rgb(0, 255, 16)));
+        assert!("#fff".parse::<Rgb>().is_err());
+        assert!("#gg0000".parse::<Rgb>().is_err());
+        assert_eq!(Rgb(255, 128, 0).to_string(), "#ff8000");
+    }
+
+    #[test]
+    fn test_manufacturer_color() {
+        let palette = default_manufacturer_palette();
+        let afm = table("/tables/Attack from Mars (Bally 1995).vpx", None);
+        let eight_ball = table("/tables/Eight Ball (bally 1977).vpx", None);
+        let plain = table("/tables/afm.vpx", None);
+        assert!(manufacturer_color(&afm, &palette).is_some());
+        assert_eq!(
+            manufacturer_color(&afm, &palette),
+            manufacturer_color(&eight_ball, &palette)
+        );
+        assert_eq!(manufacturer_color(&plain, &palette), None);
+        assert_eq!(manufacturer_color(&afm, &[]), None);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(245 * 1024 * 1024), "245.0 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_name_disambiguations() {
+        let tables = vec![
+            table("/tables/afm.vpx", Some("Attack from Mars")),
+            table("/tables/afm_v2.vpx", Some("attack from mars")),
+            table("/tables/bally/tz.vpx", Some("Twilight Zone")),
+            table("/tables/mods/tz.vpx", Some("Twilight Zone")),
+            table("/tables/mm.vpx", Some("Medieval Madness")),
+        ];
+        let template = DisplayTemplate::default().hide_stem(true);
+
+        let disambiguations = name_disambiguations(&tables, &template);
+
+        assert_eq!(
+            disambiguations,
+            HashMap::from([
+                (PathBuf::from("/tables/afm.vpx"), "afm.vpx".to_string()),
+                (
+                    PathBuf::from("/tables/afm_v2.vpx"),
+                    "afm_v2.vpx".to_string()
+                ),
+                (
+                    PathBuf::from("/tables/bally/tz.vpx"),
+                    "bally/tz.vpx".to_string()
+                ),
+                (
+                    PathBuf::from("/tables/mods/tz.vpx"),
+                    "mods/tz.vpx".to_string()
+                ),
+            ])
+        );
+        // the stem already tells them apart
+        let disambiguations = name_disambiguations(&tables, &DisplayTemplate::default());
+        assert_eq!(disambiguations.len(), 2);
+    }
+
+    #[test]
+    fn test_search_stem() {
+        let table = table("/tables/TMNT (Data East 1991).vpx", Some("Teenage Mutant"));
+        assert_eq!(
+            search_stem("Teenage Mutant", &table),
+            Some("TMNT (Data East 1991)".to_string())
+        );
+        assert_eq!(
+            search_stem("tmnt (data east 1991) [tmnt_104]", &table),
+            None
+        );
+    }
+
+    #[test]
+    fn test_truncate_left() {
+        assert_eq!(truncate_left("bally/afm.vpx", 20), "bally/afm.vpx");
+        assert_eq!(truncate_left("bally/afm.vpx", 9), "…/afm.vpx");
+        assert_eq!(truncate_left("bally/afm.vpx", 1), "…");
+        assert_eq!(truncate_left("bally/afm.vpx", 0), "");
+        // wide characters take two columns
+        assert_eq!(truncate_left("日本/パチンコ.vpx", 14), "…/パチンコ.vpx");
+        assert_eq!(truncate_left("日本/パチンコ.vpx", 11), "…チンコ.vpx");
+        // a wide character that does not fit is left out entirely
+        assert_eq!(truncate_left("日本/パチンコ.vpx", 10), "…ンコ.vpx");
+        assert_eq!(truncate_left("🎱🎱🎱.vpx", 7), "…🎱.vpx");
+    }
+
+    #[test]
+    fn test_truncate_words() {
+        assert_eq!(truncate_words("Attack from Mars", 20), "Attack from Mars");
+        assert_eq!(truncate_words("Attack\nfrom  Mars", 20), "Attack from Mars");
+        assert_eq!(truncate_words("Attack from Mars", 15), "Attack from…");
+        assert_eq!(truncate_words("Attack from Mars", 12), "Attack from…");
+        // a cut between words would drop too much
+        assert_eq!(
+            truncate_words("Attack frommarsanddecay", 16),
+            "Attack frommars…"
+        );
+        assert_eq!(truncate_words("Attack from Mars", 1), "…");
+        assert_eq!(truncate_words("Attack from Mars", 0), "");
+        // wide characters take two columns
+        assert_eq!(truncate_words("火星からの 攻撃", 15), "火星からの 攻撃");
+        assert_eq!(truncate_words("火星からの 攻撃", 12), "火星からの…");
+        assert_eq!(truncate_words("🎱 Pinball Party", 8), "🎱 Pinb…");
+        assert_eq!(truncate_words("🎱🎱🎱🎱", 6), "🎱🎱…");
+    }
+
+    #[test]
+    fn test_format_rating() {
+        assert_eq!(format_rating(3), "★★★☆☆");
+        assert_eq!(format_rating(0), "☆☆☆☆☆");
+        assert_eq!(format_rating(9), "★★★★★");
+    }
+
+    #[test]
+    fn test_format_play_time() {
+        assert_eq!(format_play_time(59), "0m");
+        assert_eq!(format_play_time(45 * 60), "45m");
+        assert_eq!(format_play_time(3 * 3600 + 12 * 60 + 30), "3h 12m");
+    }
+}
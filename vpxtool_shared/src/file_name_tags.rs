@@ -0,0 +1,106 @@
+//! Version and rating that downloads often carry in their file name, like
+//! `Attack from Mars v1.2 [5star].vpx`.
+//!
+//! Both are found with a configurable regular expression on the file stem. The first capture group
+//! is the value, patterns without a group take the whole match. The tags are derived when shown,
+//! changing the patterns doesn't need a reindex.
+use regex::Regex;
+use std::path::Path;
+
+/// `v1.2`, `V2` or `v1.0.3`
+pub const DEFAULT_VERSION_PATTERN: &str = r"(?i)\bv(\d+(?:\.\d+)*)\b";
+/// `[5star]`, `[4 stars]` or `[3-star]`
+pub const DEFAULT_RATING_PATTERN: &str = r"(?i)\[(\d)[\s-]*stars?\]";
+
+#[derive(Debug, Clone)]
+pub struct FileNamePatterns {
+    /// `None` when disabled
+    pub version: Option<Regex>,
+    /// `None` when disabled
+    pub rating: Option<Regex>,
+}
+
+impl Default for FileNamePatterns {
+    fn default() -> Self {
+        FileNamePatterns {
+            version: Some(Regex::new(DEFAULT_VERSION_PATTERN).unwrap()),
+            rating: Some(Regex::new(DEFAULT_RATING_PATTERN).unwrap()),
+        }
+    }
+}
+
+impl PartialEq for FileNamePatterns {
+    fn eq(&self, other: &Self) -> bool {
+        let source = |pattern: &Option<Regex>| pattern.as_ref().map(|re| re.as_str().to_string());
+        source(&self.version) == source(&other.version)
+            && source(&self.rating) == source(&other.rating)
+    }
+}
+
+impl FileNamePatterns {
+    pub fn version(&self, vpx_path: &Path) -> Option<String> {
+        find(self.version.as_ref(), vpx_path)
+    }
+
+    pub fn rating(&self, vpx_path: &Path) -> Option<String> {
+        find(self.rating.as_ref(), vpx_path)
+    }
+}
+
+/// The pattern from the config, the default when not set and `None` when set empty
+pub fn parse_pattern(setting: Option<&str>, default: &str) -> Result<Option<Regex>, String> {
+    match setting.map(str::trim) {
+        None => Ok(Some(Regex::new(default).unwrap())),
+        Some("") => Ok(None),
+        Some(pattern) => Regex::new(pattern).map(Some).map_err(|e| e.to_string()),
+    }
+}
+
+/// The config setting for the pattern, the reverse of [parse_pattern]
+pub fn pattern_setting(pattern: Option<&Regex>, default: &str) -> Option<String> {
+    match pattern {
+        None => Some(String::new()),
+        Some(re) if re.as_str() == default => None,
+        Some(re) => Some(re.as_str().to_string()),
+    }
+}
+
+fn find(pattern: Option<&Regex>, vpx_path: &Path) -> Option<String> {
+    let stem = vpx_path.file_stem()?.to_string_lossy();
+    let captures = pattern?.captures(&stem)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map(|value| value.as_str().trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_file_name_tags() {
+        let patterns = FileNamePatterns::default();
+        let path = Path::new("/tables/Attack from Mars (Bally 1995) v1.2 [5star].vpx");
+        assert_eq!(patterns.version(path), Some("1.2".to_string()));
+        assert_eq!(patterns.rating(path), Some("5".to_string()));
+        let path = Path::new("/tables/Medieval Madness VPW.vpx");
+        assert_eq!(patterns.version(path), None);
+        assert_eq!(patterns.rating(path), None);
+
+        let patterns = FileNamePatterns {
+            version: parse_pattern(Some(r"_rev\d+"), DEFAULT_VERSION_PATTERN).unwrap(),
+            rating: parse_pattern(Some(""), DEFAULT_RATING_PATTERN).unwrap(),
+        };
+        let path = Path::new("/tables/afm_rev3 [4 stars].vpx");
+        assert_eq!(patterns.version(path), Some("_rev3".to_string()));
+        assert_eq!(patterns.rating(path), None);
+        assert!(parse_pattern(Some("v("), DEFAULT_VERSION_PATTERN).is_err());
+        assert_eq!(
+            pattern_setting(patterns.rating.as_ref(), DEFAULT_RATING_PATTERN),
+            Some(String::new())
+        );
+    }
+}
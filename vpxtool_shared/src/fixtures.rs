@@ -0,0 +1,206 @@
+//! Builders for small synthetic vpx files.
+//!
+//! Real tables are large and can't be part of the repository, these fixtures allow tests to
+//! create a minimal but valid vpx file with just the parts they care about.
+//!
+//! Only available to tests, other crates enable the `test-fixtures` feature in their
+//! dev-dependencies.
+//!
+//! ```
+//! use testdir::testdir;
+//! use vpxtool_shared::fixtures::VpxFixture;
+//!
+//! let path = VpxFixture::new()
+//!     .table_name("Test Table")
+//!     .script("Const cGameName = \"testrom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+//!     .wall("Wall1")
+//!     .build_in(&testdir!())
+//!     .unwrap();
+//! assert!(path.exists());
+//! ```
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vpin::vpx;
+use vpin::vpx::VPX;
+use vpin::vpx::gameitem::GameItemEnum;
+use vpin::vpx::gameitem::flipper::Flipper;
+use vpin::vpx::gameitem::wall::Wall;
+use vpin::vpx::image::{ImageData, ImageDataJpeg};
+use vpin::vpx::version::Version;
+
+/// A valid 1x1 transparent png
+pub const PNG_1X1: [u8; 67] = [
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F, 0x15, 0xC4,
+    0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00, 0x01, 0x00, 0x00,
+    0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+    0x42, 0x60, 0x82,
+];
+
+static FIXTURE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// `GameItemEnum` is not `Clone` so we keep a description and create the items when building
+enum FixtureGameItem {
+    Wall(String),
+    Flipper(String),
+}
+
+impl FixtureGameItem {
+    fn to_game_item(&self) -> GameItemEnum {
+        match self {
+            FixtureGameItem::Wall(name) => {
+                let mut wall = Wall::default();
+                wall.name = name.clone();
+                GameItemEnum::Wall(wall)
+            }
+            FixtureGameItem::Flipper(name) => {
+                let mut flipper = Flipper::default();
+                flipper.name = name.clone();
+                GameItemEnum::Flipper(flipper)
+            }
+        }
+    }
+}
+
+/// Builder for a minimal vpx file
+#[derive(Default)]
+pub struct VpxFixture {
+    file_name: Option<String>,
+    table_name: Option<String>,
+    script: Option<String>,
+    version: Option<u32>,
+    game_items: Vec<FixtureGameItem>,
+    images: Vec<ImageData>,
+}
+
+impl VpxFixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The file name to use, defaults to a unique name ending in `.vpx`
+    pub fn file_name<S: Into<String>>(mut self, file_name: S) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    pub fn table_name<S: Into<String>>(mut self, table_name: S) -> Self {
+        self.table_name = Some(table_name.into());
+        self
+    }
+
+    pub fn script<S: Into<String>>(mut self, script: S) -> Self {
+        self.script = Some(script.into());
+        self
+    }
+
+    /// The file format version, like `1080` for VPX 10.8, defaults to `1072`
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn wall<S: Into<String>>(mut self, name: S) -> Self {
+        self.game_items.push(FixtureGameItem::Wall(name.into()));
+        self
+    }
+
+    pub fn flipper<S: Into<String>>(mut self, name: S) -> Self {
+        self.game_items.push(FixtureGameItem::Flipper(name.into()));
+        self
+    }
+
+    /// Adds a 1x1 png image with the given name
+    pub fn image<S: Into<String>>(mut self, name: S) -> Self {
+        let name = name.into();
+        let path = format!("{}.png", name);
+        let image = ImageData {
+            name: name.clone(),
+            path: path.clone(),
+            width: 1,
+            height: 1,
+            jpeg: Some(ImageDataJpeg {
+                path,
+                name,
+                internal_name: None,
+                data: PNG_1X1.to_vec(),
+            }),
+            ..Default::default()
+        };
+        self.images.push(image);
+        self
+    }
+
+    /// Builds the in-memory representation without writing it to disk
+    pub fn to_vpx(&self) -> VPX {
+        let mut vpx = VPX {
+            version: Version::new(self.version.unwrap_or(1072)),
+            ..Default::default()
+        };
+        vpx.info.table_name = self.table_name.clone();
+        if let Some(script) = &self.script {
+            vpx.set_script(script.clone());
+        }
+        for game_item in &self.game_items {
+            vpx.add_game_item(game_item.to_game_item());
+        }
+        for image in &self.images {
+            vpx.add_or_replace_image(image.clone());
+        }
+        vpx
+    }
+
+    /// Writes the vpx file into the given directory and returns its path.
+    pub fn build_in(&self, dir: &Path) -> io::Result<PathBuf> {
+        let file_name = self.file_name.clone().unwrap_or_else(|| {
+            let id = FIXTURE_COUNTER.fetch_add(1, Ordering::Relaxed);
+            format!("fixture_{}.vpx", id)
+        });
+        let path = dir.join(file_name);
+        vpx::write(&path, &self.to_vpx())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use testdir::testdir;
+    use vpin::vpx::VerifyResult;
+
+    #[test]
+    fn test_fixture_roundtrip() -> io::Result<()> {
+        let dir = testdir!();
+        let path = VpxFixture::new()
+            .file_name("fixture.vpx")
+            .table_name("Fixture")
+            .script("Const cGameName = \"fixture\"")
+            .wall("Wall1")
+            .flipper("LeftFlipper")
+            .image("playfield")
+            .build_in(&dir)?;
+        assert_eq!(path, dir.join("fixture.vpx"));
+        assert_eq!(vpx::verify(&path), VerifyResult::Ok(path.clone()));
+
+        let vpx = vpx::read(&path)?;
+        assert_eq!(vpx.info.table_name, Some("Fixture".to_string()));
+        assert_eq!(vpx.gamedata.code.string, "Const cGameName = \"fixture\"");
+        assert_eq!(vpx.gameitems.len(), 2);
+        assert_eq!(vpx.images.len(), 1);
+        assert_eq!(vpx.images[0].name, "playfield");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixture_build_unique_paths() -> io::Result<()> {
+        let dir = testdir!();
+        let first = VpxFixture::new().build_in(&dir)?;
+        let second = VpxFixture::new().build_in(&dir)?;
+        assert_ne!(first, second);
+        assert!(first.exists());
+        assert!(second.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Organizes new downloads that land in an inbox folder.
+//!
+//! Tables in the inbox, and tables in zip bundles there, are parsed and moved with their sidecar
+//! files to the tables folder, see [crate::staging]. With a name template the files are renamed
+//! after the table, like `Attack from Mars (Bally 1995).vpx`. A table is left in the inbox when
+//! one of its files already exists in the tables folder.
+//!
+//! Tables in zip bundles are extracted next to the bundle first, the bundle itself stays in the
+//! inbox.
+use crate::display::DisplayTemplate;
+use crate::indexer::{
+    IndexedTable, PathWithMetadata, archive_path, extract_archived_vpx, find_archived_vpx_files,
+    find_vpx_files, index_vpx_file,
+};
+use crate::staging;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Characters that are not allowed in file names on at least one platform
+const INVALID_FILE_NAME_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+pub struct InboxOptions<'a> {
+    pub tables_folder: &'a Path,
+    /// Renames the table and its sidecar files, the file names are kept when `None`
+    pub name_template: Option<&'a DisplayTemplate>,
+    pub sidecar_extensions: &'a [String],
+    pub include_hidden_sidecars: bool,
+    /// Only report where the tables would go
+    pub dry_run: bool,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum InboxOutcome {
+    /// The new path of the table
+    Moved(PathBuf),
+    /// Where the table would be moved to in dry-run mode
+    WouldMove(PathBuf),
+    /// Left in the inbox as one of the files already exists
+    Conflict(String),
+    /// Left in the inbox as it could not be parsed or moved
+    Failed(String),
+}
+
+/// The tables directly in the inbox and in zip bundles there, subfolders are left alone
+pub fn inbox_tables(inbox_folder: &Path) -> io::Result<Vec<PathWithMetadata>> {
+    let mut tables = find_vpx_files(false, inbox_folder)?;
+    tables.extend(find_archived_vpx_files(false, inbox_folder)?);
+    tables.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(tables)
+}
+
+/// The file stem for the table, the rendered template without characters that are not allowed in
+/// file names. Falls back to the current file stem if nothing is left.
+pub fn canonical_stem(table: &IndexedTable, name_template: &DisplayTemplate) -> String {
+    let rendered = name_template
+        .render(table)
+        .replace(INVALID_FILE_NAME_CHARS, "-")
+        .trim_matches(|c: char| c == '.' || c.is_whitespace())
+        .to_string();
+    if rendered.is_empty() {
+        table
+            .path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    } else {
+        rendered
+    }
+}
+
+/// Parses the table and moves it with its sidecar files to the tables folder
+pub fn organize(vpx_file: &PathWithMetadata, options: &InboxOptions) -> InboxOutcome {
+    let table = match index_vpx_file(vpx_file, &HashMap::new()) {
+        Ok((_, table)) => table,
+        Err(e) => return InboxOutcome::Failed(e.to_string()),
+    };
+    let stem = match options.name_template {
+        Some(name_template) => canonical_stem(&table, name_template),
+        None => table
+            .path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+    };
+    // checked up front so nothing is extracted for a table that can't be moved
+    let moves = staging::table_moves_as(
+        &table.path,
+        options.tables_folder,
+        &stem,
+        options.sidecar_extensions,
+        options.include_hidden_sidecars,
+    );
+    match moves {
+        Err(e) => return outcome_for_error(e),
+        Ok(moves) if options.dry_run => return InboxOutcome::WouldMove(moves[0].1.clone()),
+        Ok(_) => {}
+    }
+    let vpx_path = if archive_path(&table.path).is_some() {
+        match extract_archived_vpx(&table.path) {
+            Ok(extracted) => extracted,
+            Err(e) => return outcome_for_error(e),
+        }
+    } else {
+        table.path
+    };
+    match staging::move_table_as(
+        &vpx_path,
+        options.tables_folder,
+        &stem,
+        options.sidecar_extensions,
+        options.include_hidden_sidecars,
+    ) {
+        Ok(moved) => InboxOutcome::Moved(moved),
+        Err(e) => outcome_for_error(e),
+    }
+}
+
+fn outcome_for_error(e: io::Error) -> InboxOutcome {
+    if e.kind() == io::ErrorKind::AlreadyExists {
+        InboxOutcome::Conflict(e.to_string())
+    } else {
+        InboxOutcome::Failed(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::VpxFixture;
+    use pretty_assertions::assert_eq;
+    use std::fs::File;
+    use testdir::testdir;
+
+    #[test]
+    fn test_organize() -> io::Result<()> {
+        let dir = testdir!();
+        let inbox = dir.join("inbox");
+        let tables = dir.join("tables");
+        std::fs::create_dir_all(&inbox)?;
+        VpxFixture::new()
+            .file_name("afm_v2 (Bally 1995).vpx")
+            .table_name("Attack from Mars")
+            .build_in(&inbox)?;
+        File::create(inbox.join("afm_v2 (Bally 1995).directb2s"))?;
+        let name_template = "{name} ({manufacturer} {year})".parse().unwrap();
+        let sidecar_extensions = vec!["directb2s".to_string()];
+        let mut options = InboxOptions {
+            tables_folder: &tables,
+            name_template: Some(&name_template),
+            sidecar_extensions: &sidecar_extensions,
+            include_hidden_sidecars: false,
+            dry_run: true,
+        };
+        let expected = tables.join("Attack from Mars (Bally 1995).vpx");
+
+        let found = inbox_tables(&inbox)?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            organize(&found[0], &options),
+            InboxOutcome::WouldMove(expected.clone())
+        );
+        assert!(!tables.exists());
+
+        options.dry_run = false;
+        assert_eq!(
+            organize(&found[0], &options),
+            InboxOutcome::Moved(expected.clone())
+        );
+        assert!(expected.with_extension("directb2s").exists());
+        assert_eq!(inbox_tables(&inbox)?.len(), 0);
+
+        // the same table again stays in the inbox
+        VpxFixture::new()
+            .file_name("afm_v2 (Bally 1995).vpx")
+            .table_name("Attack from Mars")
+            .build_in(&inbox)?;
+        let found = inbox_tables(&inbox)?;
+        assert!(matches!(
+            organize(&found[0], &options),
+            InboxOutcome::Conflict(_)
+        ));
+        assert!(found[0].path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_stem() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = VpxFixture::new()
+            .file_name("t2.vpx")
+            .table_name("Terminator 2: Judgment Day")
+            .build_in(&dir)?;
+        let (_, table) = index_vpx_file(
+            &PathWithMetadata {
+                path: vpx_path,
+                last_modified: std::time::SystemTime::now(),
+                file_size: None,
+            },
+            &HashMap::new(),
+        )?;
+        let name_template = "{name}".parse().unwrap();
+        assert_eq!(
+            canonical_stem(&table, &name_template),
+            "Terminator 2- Judgment Day"
+        );
+        let name_template = "{manufacturer}".parse().unwrap();
+        assert_eq!(canonical_stem(&table, &name_template), "t2");
+        Ok(())
+    }
+}
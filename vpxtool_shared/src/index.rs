@@ -0,0 +1,917 @@
+//! Queries over a collection of indexed tables.
+use crate::display::manufacturer_and_year;
+use crate::indexer::{
+    ChdRequirements, IndexedTable, Orientation, TablesIndex, WarningKind, matches_any_pattern,
+};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use regex::Regex;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static BRACKETED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\([^)]*\)|\[[^]]*\]").unwrap());
+
+/// The warnings of one table, see [Index::warnings_report]
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct TableWarnings {
+    pub path: PathBuf,
+    pub warnings: Vec<TableWarning>,
+}
+
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct TableWarning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+/// A list of indexed tables with convenience queries, in a stable order.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Index {
+    tables: Vec<IndexedTable>,
+}
+
+impl Index {
+    pub fn new(tables: Vec<IndexedTable>) -> Index {
+        Index { tables }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn tables(&self) -> &[IndexedTable] {
+        &self.tables
+    }
+
+    pub fn into_tables(self) -> Vec<IndexedTable> {
+        self.tables
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedTable> {
+        self.tables.iter()
+    }
+
+    pub fn find_by_path(&self, path: &Path) -> Option<&IndexedTable> {
+        self.tables.iter().find(|table| table.path == path)
+    }
+
+    /// Tables that require PinMAME but whose rom is not in `roms`.
+    ///
+    /// The keys of `roms` are lowercase rom names as returned by [crate::indexer::find_roms].
+    pub fn missing_roms(&self, roms: &HashMap<String, PathBuf>) -> Vec<&IndexedTable> {
+        self.tables
+            .iter()
+            .filter(|table| table.requires_pinmame)
+            .filter(|table| match &table.game_name {
+                Some(game_name) => !roms.contains_key(&game_name.to_lowercase()),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Groups of tables that have identical file contents, tables without a content hash are
+    /// never considered duplicates.
+    pub fn duplicates(&self) -> Vec<Vec<&IndexedTable>> {
+        let mut by_hash: BTreeMap<&str, Vec<&IndexedTable>> = BTreeMap::new();
+        for table in &self.tables {
+            if let Some(hash) = &table.content_hash {
+                by_hash.entry(hash).or_default().push(table);
+            }
+        }
+        by_hash
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect()
+    }
+
+    /// The collections with tables, sorted, see [collection]
+    pub fn collections(&self, root: &Path) -> Vec<PathBuf> {
+        let mut collections = self
+            .tables
+            .iter()
+            .filter_map(|table| collection(&table.path, root))
+            .collect::<Vec<PathBuf>>();
+        collections.sort();
+        collections.dedup();
+        collections
+    }
+
+    /// Tables grouped by the manufacturer in their `Title (Manufacturer Year)` file name,
+    /// `None` holds the tables not following that convention.
+    pub fn by_manufacturer(&self) -> BTreeMap<Option<String>, Vec<&IndexedTable>> {
+        let mut by_manufacturer: BTreeMap<Option<String>, Vec<&IndexedTable>> = BTreeMap::new();
+        for table in &self.tables {
+            let stem = table
+                .path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let (manufacturer, _) = manufacturer_and_year(&stem);
+            by_manufacturer.entry(manufacturer).or_default().push(table);
+        }
+        by_manufacturer
+    }
+
+    /// Other builds of the same table, at most `limit`.
+    ///
+    /// Tables using the same rom come first, followed by tables with a similar name using the
+    /// same fuzzy matcher as the frontend search.
+    pub fn similar(&self, table: &IndexedTable, limit: usize) -> Vec<&IndexedTable> {
+        let game_name = table.game_name.as_ref().map(|name| name.to_lowercase());
+        let others = self.tables.iter().filter(|other| other.path != table.path);
+        let (mut same_rom, rest): (Vec<&IndexedTable>, Vec<&IndexedTable>) =
+            others.partition(|other| {
+                game_name.is_some()
+                    && other.game_name.as_ref().map(|name| name.to_lowercase()) == game_name
+            });
+        let matcher = SkimMatcherV2::default();
+        let title = comparable_title(table);
+        let mut similar_name = rest
+            .into_iter()
+            .filter_map(|other| {
+                name_similarity(&matcher, &title, &comparable_title(other))
+                    .map(|score| (score, other))
+            })
+            .collect::<Vec<_>>();
+        similar_name.sort_by_key(|(score, other)| (Reverse(*score), &other.path));
+        same_rom.extend(similar_name.into_iter().map(|(_, other)| other));
+        same_rom.truncate(limit);
+        same_rom
+    }
+
+    /// The unsuppressed warnings of every table that has any, in index order, see
+    /// [IndexedTable::warnings_ignoring]
+    pub fn warnings_report(
+        &self,
+        ignored: &[WarningKind],
+        installed_components: &[String],
+        chds: &ChdRequirements,
+    ) -> Vec<TableWarnings> {
+        self.tables
+            .iter()
+            .map(|table| TableWarnings {
+                path: table.path.clone(),
+                warnings: table
+                    .warning_kinds_ignoring(ignored, installed_components, chds)
+                    .into_iter()
+                    .map(|(kind, message)| TableWarning { kind, message })
+                    .collect(),
+            })
+            .filter(|table| !table.warnings.is_empty())
+            .collect()
+    }
+
+    /// A random table, `None` if the index is empty
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&IndexedTable> {
+        self.tables.choose(rng)
+    }
+
+    pub fn filter<P>(&self, predicate: P) -> Index
+    where
+        P: Fn(&IndexedTable) -> bool,
+    {
+        Index {
+            tables: self
+                .tables
+                .iter()
+                .filter(|table| predicate(table))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// The folder directly below `root` the table is in, also when it is in a deeper subfolder of it,
+/// eg `em` for `/tables/em/gottlieb/Table.vpx`. Tables directly in `root` or outside of it are in
+/// no collection.
+pub fn collection(vpx_path: &Path, root: &Path) -> Option<PathBuf> {
+    let relative = vpx_path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?;
+    components.next()?;
+    Some(PathBuf::from(first.as_os_str()))
+}
+
+/// Lowercase table name without bracketed parts like `(Bally 1995)` or `[VR]`, falls back to
+/// the file name.
+fn comparable_title(table: &IndexedTable) -> String {
+    let name = table
+        .table_info
+        .table_name
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| {
+            table
+                .path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+    comparable_name(&name)
+}
+
+/// Lowercase name without bracketed parts and repeated spaces
+pub(crate) fn comparable_name(name: &str) -> String {
+    BRACKETED_RE
+        .replace_all(name, "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Fuzzy score of two comparable names, `None` if they are not similar
+fn name_similarity(matcher: &SkimMatcherV2, name: &str, other_name: &str) -> Option<i64> {
+    let (shorter, longer) = if name.len() <= other_name.len() {
+        (name, other_name)
+    } else {
+        (other_name, name)
+    };
+    // avoid short names matching anything that contains their letters
+    if shorter.is_empty() || shorter.len() * 10 < longer.len() * 6 {
+        return None;
+    }
+    matcher.fuzzy_match(longer, shorter)
+}
+
+/// Whether the names look like the same table, eg `Attack from Mars v1.1` and
+/// `Attack from Mars (Bally 1995)`, with the same fuzzy matching as [Index::similar]
+pub fn similar_names(name: &str, other_name: &str) -> bool {
+    name_similarity(
+        &SkimMatcherV2::default(),
+        &comparable_name(name),
+        &comparable_name(other_name),
+    )
+    .is_some()
+}
+
+/// Filter term that only keeps tables without a wheel image
+pub const MISSING_WHEEL_TERM: &str = "missing:wheel";
+/// Filter term that only keeps tables in the staging folder
+pub const STAGED_TERM: &str = "is:staged";
+/// Filter term that only keeps tables with saved high scores, an NVRAM file for their rom
+pub const NVRAM_TERM: &str = "has:nvram";
+/// Filter term that only keeps tables that can be launched right away, see
+/// [IndexedTable::launchable]
+pub const LAUNCHABLE_TERM: &str = "is:launchable";
+/// Filter terms that only keep tables with that fullscreen orientation, see [Orientation]
+pub const PORTRAIT_TERM: &str = "orientation:portrait";
+pub const LANDSCAPE_TERM: &str = "orientation:landscape";
+/// Prefix of the filter term that only keeps tables rated at least that many stars, eg `rating:4`
+pub const RATING_TERM_PREFIX: &str = "rating:";
+/// Prefix of the filter term that only keeps tables using that rom, eg `rom:afm_113b`
+pub const ROM_TERM_PREFIX: &str = "rom:";
+/// Prefix of the filter term that only keeps tables by that author, eg `author:salas` or
+/// `author:"jp salas"`
+pub const AUTHOR_TERM_PREFIX: &str = "author:";
+/// Filter terms that only keep tables using their own physics or a global physics set, see
+/// [crate::indexer::TablePhysics]
+pub const TABLE_PHYSICS_TERM: &str = "physics:table";
+pub const GLOBAL_PHYSICS_TERM: &str = "physics:global";
+/// Prefix of the filter term that only keeps tables with at least that difficulty in percent, eg
+/// `difficulty:50`
+pub const DIFFICULTY_TERM_PREFIX: &str = "difficulty:";
+
+/// Filter on table tags and names, parsed from terms like `+kids -broken attack`.
+///
+/// A table matches when it has all `+tag` tags, none of the `-tag` tags and every remaining
+/// term is part of its name, file name or manufacturer. Terms can be combined with `OR`, eg
+/// `williams 1992 OR gottlieb`, an explicit `AND` binds stronger and is the same as leaving it
+/// out. A `"quoted phrase"` is matched literally, spaces and operators included. Case is
+/// ignored, except for the operators. [MISSING_WHEEL_TERM] only keeps tables without a wheel
+/// image, [STAGED_TERM] only staged tables, [NVRAM_TERM] only tables with saved high scores,
+/// [LAUNCHABLE_TERM] only tables that are on disk and not in a zip bundle.
+/// [PORTRAIT_TERM] and [LANDSCAPE_TERM] only keep tables with a known layout in that orientation.
+/// `rating:4` only keeps tables with 4 stars or more. `rom:afm_113b` only keeps tables using that
+/// rom, `*` matches any characters, eg `rom:afm_*`. [TABLE_PHYSICS_TERM] and
+/// [GLOBAL_PHYSICS_TERM] only keep tables with known physics of that kind, `difficulty:50` only
+/// tables with a known difficulty of 50% or more. `author:salas` only keeps tables whose author
+/// contains that name, spaces are ignored.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct TableFilter {
+    include_tags: HashSet<String>,
+    exclude_tags: HashSet<String>,
+    missing_wheel: bool,
+    staged: bool,
+    nvram: bool,
+    launchable: bool,
+    orientation: Option<Orientation>,
+    min_rating: Option<u8>,
+    /// Lowercase rom name pattern
+    rom: Option<String>,
+    /// `true` for tables using a global physics set, `false` for tables using their own physics
+    global_physics: Option<bool>,
+    /// In percent
+    min_difficulty: Option<u8>,
+    /// Lowercase part of the author name without spaces
+    author: Option<String>,
+    /// Alternatives separated by `OR`, each with the terms that all have to match
+    alternatives: Vec<Vec<String>>,
+}
+
+impl TableFilter {
+    pub fn parse(input: &str) -> TableFilter {
+        let mut filter = TableFilter::default();
+        let mut terms = Vec::new();
+        // `author:` followed by a quoted name
+        let mut author_pending = false;
+        for (token, quoted) in tokenize(input) {
+            if std::mem::take(&mut author_pending) {
+                filter.author = Some(without_spaces(&token)).filter(|author| !author.is_empty());
+                continue;
+            }
+            if quoted {
+                if !token.is_empty() {
+                    terms.push(token.to_lowercase());
+                }
+                continue;
+            }
+            match token.as_str() {
+                "OR" => {
+                    if !terms.is_empty() {
+                        filter.alternatives.push(std::mem::take(&mut terms));
+                    }
+                    continue;
+                }
+                "AND" => continue,
+                _ => {}
+            }
+            let term = token.to_lowercase();
+            if term == MISSING_WHEEL_TERM {
+                filter.missing_wheel = true;
+            } else if term == STAGED_TERM {
+                filter.staged = true;
+            } else if term == NVRAM_TERM {
+                filter.nvram = true;
+            } else if term == LAUNCHABLE_TERM {
+                filter.launchable = true;
+            } else if term == PORTRAIT_TERM {
+                filter.orientation = Some(Orientation::Portrait);
+            } else if term == LANDSCAPE_TERM {
+                filter.orientation = Some(Orientation::Landscape);
+            } else if term == AUTHOR_TERM_PREFIX {
+                author_pending = true;
+            } else if let Some(author) = term.strip_prefix(AUTHOR_TERM_PREFIX) {
+                filter.author = Some(without_spaces(author));
+            } else if term == TABLE_PHYSICS_TERM {
+                filter.global_physics = Some(false);
+            } else if term == GLOBAL_PHYSICS_TERM {
+                filter.global_physics = Some(true);
+            } else if let Some(difficulty) = term
+                .strip_prefix(DIFFICULTY_TERM_PREFIX)
+                .and_then(|difficulty| difficulty.parse::<u8>().ok())
+            {
+                filter.min_difficulty = Some(difficulty);
+            } else if let Some(rating) = term
+                .strip_prefix(RATING_TERM_PREFIX)
+                .and_then(|rating| rating.parse::<u8>().ok())
+            {
+                filter.min_rating = Some(rating);
+            } else if let Some(rom) = term
+                .strip_prefix(ROM_TERM_PREFIX)
+                .filter(|rom| !rom.is_empty())
+            {
+                filter.rom = Some(rom.to_string());
+            } else if let Some(tag) = term.strip_prefix('+') {
+                if !tag.is_empty() {
+                    filter.include_tags.insert(tag.to_string());
+                }
+            } else if let Some(tag) = term.strip_prefix('-') {
+                if !tag.is_empty() {
+                    filter.exclude_tags.insert(tag.to_string());
+                }
+            } else {
+                terms.push(term);
+            }
+        }
+        if !terms.is_empty() {
+            filter.alternatives.push(terms);
+        }
+        filter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include_tags.is_empty()
+            && self.exclude_tags.is_empty()
+            && !self.missing_wheel
+            && !self.staged
+            && !self.nvram
+            && !self.launchable
+            && self.orientation.is_none()
+            && self.min_rating.is_none()
+            && self.rom.is_none()
+            && self.global_physics.is_none()
+            && self.min_difficulty.is_none()
+            && self.author.is_none()
+            && self.alternatives.is_empty()
+    }
+
+    pub fn missing_wheel(&self) -> bool {
+        self.missing_wheel
+    }
+
+    pub fn launchable(&self) -> bool {
+        self.launchable
+    }
+
+    pub fn matches(&self, table: &IndexedTable) -> bool {
+        let tags: HashSet<String> = table.tags.iter().map(|tag| tag.to_lowercase()).collect();
+        if !self.include_tags.is_subset(&tags) || !self.exclude_tags.is_disjoint(&tags) {
+            return false;
+        }
+        if self.missing_wheel && table.wheel_path.is_some() {
+            return false;
+        }
+        if self.staged && !table.staged {
+            return false;
+        }
+        if self.nvram && table.nvram_path.is_none() {
+            return false;
+        }
+        if self.launchable && !table.launchable() {
+            return false;
+        }
+        if let Some(orientation) = self.orientation
+            && table.layout.map(|layout| layout.orientation()) != Some(orientation)
+        {
+            return false;
+        }
+        if let Some(min_rating) = self.min_rating
+            && table.rating.unwrap_or(0) < min_rating
+        {
+            return false;
+        }
+        if let Some(rom) = &self.rom
+            && !table
+                .game_name
+                .as_ref()
+                .is_some_and(|game_name| matches_any_pattern(game_name, &[rom]))
+        {
+            return false;
+        }
+        if let Some(global_physics) = self.global_physics
+            && table.physics.map(|physics| physics.physics_set.is_some()) != Some(global_physics)
+        {
+            return false;
+        }
+        if let Some(min_difficulty) = self.min_difficulty
+            && table
+                .physics
+                .is_none_or(|physics| physics.difficulty_percent() < min_difficulty)
+        {
+            return false;
+        }
+        if let Some(author) = &self.author
+            && !table
+                .table_info
+                .author_name
+                .as_ref()
+                .is_some_and(|author_name| without_spaces(author_name).contains(author))
+        {
+            return false;
+        }
+        if self.alternatives.is_empty() {
+            return true;
+        }
+        let name = table
+            .table_info
+            .table_name
+            .clone()
+            .unwrap_or_default()
+            .to_lowercase();
+        let file_name = table.path.to_string_lossy().to_lowercase();
+        let stem = table
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let manufacturer = manufacturer_and_year(&stem)
+            .0
+            .unwrap_or_default()
+            .to_lowercase();
+        self.alternatives.iter().any(|terms| {
+            terms.iter().all(|term| {
+                name.contains(term) || file_name.contains(term) || manufacturer.contains(term)
+            })
+        })
+    }
+}
+
+impl IndexedTable {
+    /// Whether the table passes the filter, the same check the frontend list and
+    /// `index --json-lines --filter` use
+    pub fn matches(&self, filter: &TableFilter) -> bool {
+        filter.matches(self)
+    }
+}
+
+/// Lowercase, so `JP Salas` and `jpsalas` compare equal
+fn without_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<String>().to_lowercase()
+}
+
+/// Splits the input on whitespace outside of double quotes. Returns the tokens and whether they
+/// were quoted, an unterminated quote runs to the end of the input.
+fn tokenize(input: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        if c == '"' {
+            if quoted || !current.is_empty() {
+                tokens.push((std::mem::take(&mut current), quoted));
+            }
+            quoted = !quoted;
+        } else if c.is_whitespace() && !quoted {
+            if !current.is_empty() {
+                tokens.push((std::mem::take(&mut current), false));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push((current, quoted));
+    }
+    tokens
+}
+
+impl From<Vec<IndexedTable>> for Index {
+    fn from(tables: Vec<IndexedTable>) -> Self {
+        Index::new(tables)
+    }
+}
+
+impl From<&TablesIndex> for Index {
+    /// Tables are ordered by path as the underlying index has no order
+    fn from(index: &TablesIndex) -> Self {
+        let mut tables = index.tables();
+        tables.sort_by(|a, b| a.path.cmp(&b.path));
+        Index::new(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::{TableLayout, TablePhysics};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn test_index() -> Index {
+        let tables: Vec<IndexedTable> = serde_json::from_value(json!([
+            {
+                "path": "/tables/Attack from Mars (Bally 1995).vpx",
+                "table_info": {"properties": {}},
+                "game_name": "afm_113b",
+                "requires_pinmame": true,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "content_hash": "aaa"
+            },
+            {
+                "path": "/tables/Medieval Madness (Williams 1997).vpx",
+                "table_info": {"properties": {}},
+                "game_name": "MM_109c",
+                "requires_pinmame": true,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "content_hash": "bbb"
+            },
+            {
+                "path": "/tables/copy/Attack from Mars (Bally 1995).vpx",
+                "table_info": {"properties": {}},
+                "game_name": "afm_113b",
+                "requires_pinmame": true,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "content_hash": "aaa"
+            },
+            {
+                "path": "/tables/original.vpx",
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            }
+        ]))
+        .unwrap();
+        Index::new(tables)
+    }
+
+    fn paths(tables: &[&IndexedTable]) -> Vec<String> {
+        tables
+            .iter()
+            .map(|table| table.path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_find_by_path() {
+        let index = test_index();
+        let found = index.find_by_path(Path::new("/tables/original.vpx"));
+        assert_eq!(found.map(|t| t.requires_pinmame), Some(false));
+        assert_eq!(index.find_by_path(Path::new("/tables/other.vpx")), None);
+    }
+
+    #[test]
+    fn test_missing_roms() {
+        let index = test_index();
+        let roms = HashMap::from([("mm_109c".to_string(), PathBuf::from("/roms/mm_109c.zip"))]);
+        assert_eq!(
+            paths(&index.missing_roms(&roms)),
+            vec![
+                "/tables/Attack from Mars (Bally 1995).vpx",
+                "/tables/copy/Attack from Mars (Bally 1995).vpx",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let index = test_index();
+        let duplicates = index.duplicates();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            paths(&duplicates[0]),
+            vec![
+                "/tables/Attack from Mars (Bally 1995).vpx",
+                "/tables/copy/Attack from Mars (Bally 1995).vpx",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collections() {
+        let root = Path::new("/tables");
+        assert_eq!(test_index().collections(root), vec![PathBuf::from("copy")]);
+        assert_eq!(
+            collection(Path::new("/tables/em/gottlieb/Table.vpx"), root),
+            Some(PathBuf::from("em"))
+        );
+        assert_eq!(collection(Path::new("/tables/Table.vpx"), root), None);
+        assert_eq!(collection(Path::new("/staging/new/Table.vpx"), root), None);
+    }
+
+    #[test]
+    fn test_by_manufacturer() {
+        let index = test_index();
+        let counts = index
+            .by_manufacturer()
+            .into_iter()
+            .map(|(manufacturer, tables)| (manufacturer, tables.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![
+                (None, 1),
+                (Some("Bally".to_string()), 2),
+                (Some("Williams".to_string()), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let index = test_index();
+        let filtered = index.filter(|table| !table.requires_pinmame);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(
+            filtered.tables()[0].path,
+            PathBuf::from("/tables/original.vpx")
+        );
+    }
+
+    #[test]
+    fn test_table_filter() {
+        let mut tables = test_index().into_tables();
+        tables[0].tags = vec!["kids".to_string(), "wip".to_string()];
+        tables[1].tags = vec!["kids".to_string()];
+        tables[3].tags = vec!["broken".to_string()];
+        tables[3].wheel_path = Some(PathBuf::from("/tables/wheels/original.png"));
+        tables[2].staged = true;
+        tables[1].nvram_path = Some(PathBuf::from("/pinmame/nvram/afm_113b.nv"));
+        tables[0].rating = Some(4);
+        tables[1].rating = Some(2);
+        tables[1].layout = Some(TableLayout {
+            playfield_width: 952.0,
+            playfield_height: 2162.0,
+            fullscreen_rotation: 270.0,
+        });
+        tables[0].physics = Some(TablePhysics {
+            physics_set: None,
+            difficulty: 0.2,
+        });
+        tables[2].physics = Some(TablePhysics {
+            physics_set: Some(2),
+            difficulty: 0.6,
+        });
+        tables[0].table_info.author_name = Some("JP Salas".to_string());
+        tables[2].table_info.author_name = Some("Dark & Friends".to_string());
+        let index = Index::new(tables);
+        let matching = |input: &str| {
+            let filter = TableFilter::parse(input);
+            index.filter(|table| table.matches(&filter)).len()
+        };
+        assert!(TableFilter::parse("  ").is_empty());
+        assert_eq!(matching(""), 4);
+        assert_eq!(matching("+Kids"), 2);
+        assert_eq!(matching("+kids -wip"), 1);
+        assert_eq!(matching("-broken"), 3);
+        assert_eq!(matching("+kids mars"), 1);
+        assert_eq!(matching("attack mars"), 2);
+        assert_eq!(matching("+unknown"), 0);
+        assert_eq!(matching("missing:wheel"), 3);
+        assert_eq!(matching("missing:wheel -kids"), 1);
+        assert_eq!(matching("is:staged"), 1);
+        assert_eq!(matching("has:nvram"), 1);
+        // none of the tables are on disk
+        assert_eq!(matching("is:launchable"), 0);
+        assert_eq!(matching("orientation:landscape"), 1);
+        // unknown layouts never match
+        assert_eq!(matching("orientation:portrait"), 0);
+        assert_eq!(matching("rating:3"), 1);
+        assert_eq!(matching("rating:1 +kids"), 2);
+        // not a number, searched for in the names
+        assert_eq!(matching("rating:high"), 0);
+        assert_eq!(matching("rom:AFM_113B"), 2);
+        assert_eq!(matching("rom:mm_*"), 1);
+        assert_eq!(matching("rom:afm_113b +kids"), 1);
+        assert_eq!(matching("rom:tz_94h"), 0);
+        // unknown physics never match
+        assert_eq!(matching("physics:table"), 1);
+        assert_eq!(matching("physics:global"), 1);
+        assert_eq!(matching("difficulty:20"), 2);
+        assert_eq!(matching("difficulty:50 physics:global"), 1);
+        assert_eq!(matching("difficulty:70"), 0);
+        assert_eq!(matching("author:salas"), 1);
+        assert_eq!(matching("author:jpsalas"), 1);
+        assert_eq!(matching("author:\"dark & friends\""), 1);
+        assert_eq!(matching("author:\"jp salas\" mars"), 1);
+        // tables without author never match
+        assert_eq!(matching("author:a"), 2);
+        assert_eq!(matching("williams OR original"), 2);
+        // tags apply to all alternatives
+        assert_eq!(matching("mars AND +wip OR original"), 1);
+        assert_eq!(matching("bally OR"), 2);
+        assert_eq!(matching("+kids williams OR mars"), 2);
+        assert_eq!(matching("\"from mars\""), 2);
+        assert_eq!(matching("\"mars from\""), 0);
+        assert_eq!(matching("\"madness (williams\""), 1);
+    }
+
+    #[test]
+    fn test_warnings_report() {
+        let mut index = test_index();
+        index.tables[1].suppressed_warnings = vec![WarningKind::MissingRom];
+        index.tables[3].required_components = vec!["FlexDMD".to_string()];
+        let chds = ChdRequirements::default();
+        let report = index.warnings_report(&[], &["VPinMAME".to_string()], &chds);
+        assert_eq!(
+            report,
+            vec![
+                TableWarnings {
+                    path: PathBuf::from("/tables/Attack from Mars (Bally 1995).vpx"),
+                    warnings: vec![TableWarning {
+                        kind: WarningKind::MissingRom,
+                        message: "Rom not found: afm_113b".to_string(),
+                    }],
+                },
+                TableWarnings {
+                    path: PathBuf::from("/tables/copy/Attack from Mars (Bally 1995).vpx"),
+                    warnings: vec![TableWarning {
+                        kind: WarningKind::MissingRom,
+                        message: "Rom not found: afm_113b".to_string(),
+                    }],
+                },
+                TableWarnings {
+                    path: PathBuf::from("/tables/original.vpx"),
+                    warnings: vec![TableWarning {
+                        kind: WarningKind::MissingComponent,
+                        message: "Requires FlexDMD, which is not installed".to_string(),
+                    }],
+                },
+            ]
+        );
+        assert_eq!(
+            index
+                .warnings_report(&[WarningKind::MissingRom], &[], &chds)
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_launchable_filter() -> std::io::Result<()> {
+        let dir = testdir::testdir!();
+        let on_disk = dir.join("Fireball.vpx");
+        std::fs::write(&on_disk, "")?;
+        let tables: Vec<IndexedTable> = serde_json::from_value(json!([
+            {
+                "path": on_disk,
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            },
+            {
+                "path": dir.join("Deleted.vpx"),
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            },
+            {
+                "path": dir.join("bundle.zip/Bundled.vpx"),
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "archived": true
+            }
+        ]))
+        .unwrap();
+        let index = Index::new(tables);
+        let filter = TableFilter::parse(LAUNCHABLE_TERM);
+        assert!(filter.launchable());
+        let launchable = index.filter(|table| table.matches(&filter));
+        assert_eq!(
+            launchable
+                .iter()
+                .map(|table| &table.path)
+                .collect::<Vec<_>>(),
+            vec![&on_disk]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("a \"b c\"d OR \"e"),
+            vec![
+                ("a".to_string(), false),
+                ("b c".to_string(), true),
+                ("d".to_string(), false),
+                ("OR".to_string(), false),
+                ("e".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+        let index = test_index();
+        let mut rng = StdRng::seed_from_u64(42);
+        let picked = (0..20)
+            .filter_map(|_| index.random(&mut rng))
+            .map(|table| table.path.clone())
+            .collect::<std::collections::HashSet<PathBuf>>();
+        // all picks are from the index and they are not all the same
+        assert!(picked.iter().all(|path| index.find_by_path(path).is_some()));
+        assert!(picked.len() > 1);
+        // the same seed gives the same pick
+        let first = index.random(&mut StdRng::seed_from_u64(7));
+        assert_eq!(index.random(&mut StdRng::seed_from_u64(7)), first);
+        let filtered = index.filter(|table| !table.requires_pinmame);
+        assert_eq!(
+            filtered.random(&mut rng).map(|table| table.path.clone()),
+            Some(PathBuf::from("/tables/original.vpx"))
+        );
+        assert_eq!(Index::default().random(&mut rng), None);
+    }
+
+    #[test]
+    fn test_similar() {
+        let mut index = test_index();
+        let mut tables = index.into_tables();
+        tables.push(
+            serde_json::from_value(json!({
+                "path": "/tables/Attack from Mars 2.0 (Bally 1995) VR.vpx",
+                "table_info": {"table_name": "Attack from Mars 2.0", "properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            }))
+            .unwrap(),
+        );
+        index = Index::new(tables);
+        let afm = &index.tables()[0];
+        assert_eq!(
+            paths(&index.similar(afm, 3)),
+            vec![
+                "/tables/copy/Attack from Mars (Bally 1995).vpx",
+                "/tables/Attack from Mars 2.0 (Bally 1995) VR.vpx",
+            ]
+        );
+        assert_eq!(index.similar(afm, 1).len(), 1);
+        let original = index
+            .find_by_path(Path::new("/tables/original.vpx"))
+            .unwrap();
+        assert!(index.similar(original, 3).is_empty());
+    }
+}
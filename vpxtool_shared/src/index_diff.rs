@@ -0,0 +1,223 @@
+//! Differences between two snapshots of the tables index.
+//!
+//! Tables are matched by path, a renamed or moved table shows up as removed and added. Changes
+//! are reported per field of the indexed table, as it is stored in the index file.
+use crate::indexer::{IndexedTable, TablesIndex, read_index, write_index_json};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Fields that change when the table file itself changes, unlike eg the play count
+const FILE_FIELDS: [&str; 2] = ["last_modified", "content_hash"];
+
+#[derive(Serialize, PartialEq, Debug, Default)]
+pub struct IndexDiff {
+    pub added: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub changed: Vec<ChangedTable>,
+}
+
+impl IndexDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The changed tables whose file changed
+    pub fn modified(&self) -> impl Iterator<Item = &ChangedTable> {
+        self.changed.iter().filter(|table| {
+            table
+                .changes
+                .iter()
+                .any(|change| FILE_FIELDS.contains(&change.field.as_str()))
+        })
+    }
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct ChangedTable {
+    pub path: PathBuf,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct FieldChange {
+    /// The field name in the index file, nested fields are joined with a `.`
+    pub field: String,
+    /// `null` when the field was not set
+    pub old: Value,
+    /// `null` when the field is no longer set
+    pub new: Value,
+}
+
+/// Compares the tables in both snapshots, all lists are sorted by path
+pub fn diff_indexes(old: &TablesIndex, new: &TablesIndex) -> io::Result<IndexDiff> {
+    let old_tables = by_path(old);
+    let new_tables = by_path(new);
+    let mut diff = IndexDiff::default();
+    for (path, old_table) in &old_tables {
+        match new_tables.get(path) {
+            None => diff.removed.push(path.clone()),
+            Some(new_table) if old_table != new_table => {
+                let changes = field_changes(
+                    "",
+                    &serde_json::to_value(old_table)?,
+                    &serde_json::to_value(new_table)?,
+                );
+                diff.changed.push(ChangedTable {
+                    path: path.clone(),
+                    changes,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    diff.added = new_tables
+        .keys()
+        .filter(|path| !old_tables.contains_key(*path))
+        .cloned()
+        .collect();
+    Ok(diff)
+}
+
+/// Compares the tables with the snapshot at `snapshot_path`, which is then replaced by the tables.
+/// `None` if there was no snapshot yet.
+pub fn diff_with_snapshot(
+    snapshot_path: &Path,
+    tables: Vec<IndexedTable>,
+) -> io::Result<Option<IndexDiff>> {
+    let previous = read_index(snapshot_path)?;
+    let mut current = TablesIndex::empty();
+    current.insert_all(tables);
+    let diff = previous
+        .map(|previous| diff_indexes(&previous, &current))
+        .transpose()?;
+    write_index_json(&current, snapshot_path)?;
+    Ok(diff)
+}
+
+fn by_path(index: &TablesIndex) -> BTreeMap<PathBuf, IndexedTable> {
+    index
+        .tables()
+        .into_iter()
+        .map(|table| (table.path.clone(), table))
+        .collect()
+}
+
+/// Walks nested objects, other values like lists are compared as a whole
+fn field_changes(prefix: &str, old: &Value, new: &Value) -> Vec<FieldChange> {
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields)) => {
+            let new_only = new_fields
+                .keys()
+                .filter(|key| !old_fields.contains_key(*key));
+            old_fields
+                .keys()
+                .chain(new_only)
+                .flat_map(|key| {
+                    let field = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    field_changes(
+                        &field,
+                        old_fields.get(key).unwrap_or(&Value::Null),
+                        new_fields.get(key).unwrap_or(&Value::Null),
+                    )
+                })
+                .collect()
+        }
+        (old, new) if old != new => vec![FieldChange {
+            field: prefix.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use testdir::testdir;
+
+    fn table(path: &str, content_hash: &str, table_name: Option<&str>) -> IndexedTable {
+        serde_json::from_value(json!({
+            "path": path,
+            "table_info": {"table_name": table_name, "properties": {}},
+            "requires_pinmame": false,
+            "last_modified": "2024-01-01T00:00:00+00:00",
+            "content_hash": content_hash,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_indexes() -> io::Result<()> {
+        let mut old = TablesIndex::empty();
+        old.insert(table("/tables/a.vpx", "aaa", Some("A")));
+        old.insert(table("/tables/b.vpx", "bbb", None));
+        old.insert(table("/tables/c.vpx", "ccc", None));
+        let mut new = TablesIndex::empty();
+        new.insert(table("/tables/a.vpx", "aaa", Some("A")));
+        new.insert(table("/tables/b.vpx", "bbb2", Some("B")));
+        new.insert(table("/tables/d.vpx", "ddd", None));
+
+        let diff = diff_indexes(&old, &new)?;
+
+        assert_eq!(
+            diff,
+            IndexDiff {
+                added: vec![PathBuf::from("/tables/d.vpx")],
+                removed: vec![PathBuf::from("/tables/c.vpx")],
+                changed: vec![ChangedTable {
+                    path: PathBuf::from("/tables/b.vpx"),
+                    changes: vec![
+                        FieldChange {
+                            field: "table_info.table_name".to_string(),
+                            old: Value::Null,
+                            new: json!("B"),
+                        },
+                        FieldChange {
+                            field: "content_hash".to_string(),
+                            old: json!("bbb"),
+                            new: json!("bbb2"),
+                        },
+                    ],
+                }],
+            }
+        );
+        assert!(diff_indexes(&new, &new)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_with_snapshot() -> io::Result<()> {
+        let snapshot_path = testdir!().join("snapshot.json");
+        let tables = vec![
+            table("/tables/a.vpx", "aaa", Some("A")),
+            table("/tables/b.vpx", "bbb", None),
+        ];
+        assert_eq!(diff_with_snapshot(&snapshot_path, tables)?, None);
+
+        let mut renamed = table("/tables/a.vpx", "aaa", Some("Renamed"));
+        renamed.rating = Some(4);
+        let tables = vec![
+            renamed,
+            table("/tables/b.vpx", "bbb2", None),
+            table("/tables/c.vpx", "ccc", None),
+        ];
+        let diff = diff_with_snapshot(&snapshot_path, tables)?.unwrap();
+        assert_eq!(diff.added, vec![PathBuf::from("/tables/c.vpx")]);
+        assert_eq!(diff.changed.len(), 2);
+        assert_eq!(
+            diff.modified().map(|table| &table.path).collect::<Vec<_>>(),
+            vec![&PathBuf::from("/tables/b.vpx")]
+        );
+        Ok(())
+    }
+}
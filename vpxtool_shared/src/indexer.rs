@@ -1,10 +1,17 @@
-use chrono::{DateTime, Utc};
-use log::info;
+use crate::disk::disk_kind;
+use crate::notes::{notes_path_for, read_notes};
+use crate::table_ini::{ini_path_for, read_table_ini};
+use chrono::{DateTime, Local, Utc};
+use log::{info, warn};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::{HashMap, HashSet};
-use std::fmt::Debug;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Debug, Display};
 use std::fs::Metadata;
 use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 use std::time::SystemTime;
 use std::{
     ffi::OsStr,
@@ -60,6 +67,8 @@ impl From<TableInfo> for IndexedTableInfo {
 pub struct PathWithMetadata {
     pub path: PathBuf,
     pub last_modified: SystemTime,
+    /// `None` for tables in zip archives, where the size of the archive says little
+    pub file_size: Option<u64>,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug, Eq, Ord, PartialOrd)]
@@ -74,6 +83,12 @@ impl From<IsoSystemTime> for SystemTime {
         iso_system_time.0
     }
 }
+impl Display for IsoSystemTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let local: DateTime<Local> = self.0.into();
+        write!(f, "{}", local.format("%Y-%m-%d %H:%M"))
+    }
+}
 impl Serialize for IsoSystemTime {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -106,17 +121,463 @@ pub struct IndexedTable {
     #[serde(skip_serializing_if = "Option::is_none")]
     local_rom_path: Option<PathBuf>,
     pub wheel_path: Option<PathBuf>,
+    /// The NVRAM file of the rom that holds the high scores, in the table folder or in the
+    /// global NVRAM folder. `None` when the rom was never played.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nvram_path: Option<PathBuf>,
     pub requires_pinmame: bool,
+    /// How the script drives the DMD, `None` for tables without DMD or indexed by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dmd_kind: Option<DmdKind>,
+    /// Playfield size and view rotation, `None` for tables indexed by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<TableLayout>,
+    /// Physics and difficulty set in the table, `None` for tables indexed by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physics: Option<TablePhysics>,
     pub last_modified: IsoSystemTime,
+    /// Creation time of the file, not available on all platforms and file systems
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<IsoSystemTime>,
+    /// Lowercase hex SHA-256 of the vpx file contents
+    pub content_hash: Option<String>,
+    /// Size of the vpx file in bytes, unknown for tables indexed by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+    /// Files the script loads by relative path, eg through `PlayMusic`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub referenced_files: Vec<String>,
+    /// External components the table needs, eg `VPinMAME` or ActiveX objects the script creates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_components: Vec<String>,
+    /// Settings from the ini file next to the table keyed by `Section.Key`, `None` without ini
+    /// file, see [crate::table_ini]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ini_settings: Option<BTreeMap<String, String>>,
+    /// The table is stored inside a zip archive, `path` points to the entry within the archive.
+    /// It has to be extracted before it can be launched.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// The table is in the staging folder, see [crate::staging]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub staged: bool,
+    /// Warning kinds suppressed in the table notes, see [crate::notes]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed_warnings: Vec<WarningKind>,
+    /// Tags from the table notes, see [crate::notes]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Stars from the table notes, see [crate::notes]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Total play time in seconds from the table notes, see [crate::notes]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub play_time_secs: Option<u64>,
+    /// When the notes and ini file were last changed, a changed sidecar gets the table indexed
+    /// again like a changed table
+    #[serde(default, skip_serializing_if = "SidecarTimes::is_empty")]
+    pub sidecars_modified: SidecarTimes,
+}
+
+/// Modification times of the sidecar files whose contents end up in the index, `None` for
+/// missing files
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub struct SidecarTimes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<IsoSystemTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ini: Option<IsoSystemTime>,
+}
+
+impl SidecarTimes {
+    pub fn read(vpx_path: &Path) -> SidecarTimes {
+        let modified = |path: PathBuf| last_modified(&path).ok().map(IsoSystemTime);
+        SidecarTimes {
+            notes: modified(notes_path_for(vpx_path)),
+            ini: modified(ini_path_for(vpx_path)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.notes.is_none() && self.ini.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    MissingRom,
+    MissingReferencedFile,
+    MissingComponent,
+    MissingChd,
+}
+
+/// Roms that also need a CHD disk image, checked when configured, see
+/// [IndexedTable::missing_chd]
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct ChdRequirements {
+    /// Lowercase rom names
+    pub games: Vec<String>,
+    /// Holds a folder per rom with its `.chd` files, like the PinMAME `roms` folder
+    pub folder: PathBuf,
+}
+
+/// The way a table shows its DMD, detected from the script
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum DmdKind {
+    /// Rendered by the script through FlexDMD
+    FlexDmd,
+    /// Rendered by the script through UltraDMD
+    UltraDmd,
+    /// The display of the emulated machine, shown by PinMAME on a real or virtual DMD
+    #[serde(rename = "pinmame")]
+    PinMame,
+}
+
+/// How the playfield is laid out, read from the table game data.
+///
+/// VPX files don't store a rendering resolution, that is up to the player settings.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct TableLayout {
+    /// Playfield width in VPX units
+    pub playfield_width: f32,
+    /// Playfield height in VPX units
+    pub playfield_height: f32,
+    /// Rotation of the fullscreen (cabinet) view in degrees
+    pub fullscreen_rotation: f32,
+}
+
+/// The physics and difficulty the table author set, read from the table game data.
+///
+/// Players can override both in their VPinball settings or the table ini.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct TablePhysics {
+    /// The global physics set 1 to 8 used instead of the table physics, `None` when the table
+    /// uses its own physics
+    pub physics_set: Option<u32>,
+    /// From 0 to 1, how steep and lively the table plays
+    pub difficulty: f32,
+}
+
+impl TablePhysics {
+    fn from_game_data(game_data: &GameData) -> TablePhysics {
+        TablePhysics {
+            physics_set: Some(game_data.override_physics).filter(|set| *set > 0),
+            difficulty: game_data.global_difficulty,
+        }
+    }
+
+    /// Difficulty in percent, like the VPinball editor shows it
+    pub fn difficulty_percent(&self) -> u8 {
+        (self.difficulty.clamp(0.0, 1.0) * 100.0).round() as u8
+    }
+
+    /// Eg `table physics, difficulty 20%` or `global set 3, difficulty 50%`
+    pub fn description(&self) -> String {
+        let physics = match self.physics_set {
+            Some(set) => format!("global set {}", set),
+            None => "table physics".to_string(),
+        };
+        format!("{}, difficulty {}%", physics, self.difficulty_percent())
+    }
+}
+
+/// Direction the playfield runs on the screen in the fullscreen view
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Orientation {
+    /// Along the screen height, for desktops and portrait mounted screens
+    Portrait,
+    /// Along the screen width, the view is rotated for landscape mounted cabinet screens
+    Landscape,
+}
+
+impl Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Orientation::Portrait => write!(f, "portrait"),
+            Orientation::Landscape => write!(f, "landscape"),
+        }
+    }
+}
+
+impl TableLayout {
+    /// `None` if the playfield has no size
+    fn from_game_data(game_data: &GameData) -> Option<TableLayout> {
+        let playfield_width = game_data.right - game_data.left;
+        let playfield_height = game_data.bottom - game_data.top;
+        (playfield_width > 0.0 && playfield_height > 0.0).then_some(TableLayout {
+            playfield_width,
+            playfield_height,
+            fullscreen_rotation: game_data.bg_rotation_fullscreen,
+        })
+    }
+
+    /// Views turned by 90 or 270 degrees are landscape, anything closer to 0 or 180 portrait
+    pub fn orientation(&self) -> Orientation {
+        let quarter_turns = (self.fullscreen_rotation / 90.0).round() as i64;
+        if quarter_turns.rem_euclid(2) == 1 {
+            Orientation::Landscape
+        } else {
+            Orientation::Portrait
+        }
+    }
+
+    /// Playfield height relative to its width, eg 2.0 for a playfield twice as long as wide
+    pub fn aspect_ratio(&self) -> f32 {
+        self.playfield_height / self.playfield_width
+    }
+}
+
+impl DmdKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            DmdKind::FlexDmd => "FlexDMD",
+            DmdKind::UltraDmd => "UltraDMD",
+            DmdKind::PinMame => "PinMAME (real or virtual DMD)",
+        }
+    }
+}
+
+impl WarningKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            WarningKind::MissingRom => "Missing rom",
+            WarningKind::MissingReferencedFile => "Missing referenced files",
+            WarningKind::MissingComponent => "Missing external components",
+            WarningKind::MissingChd => "Missing CHD disk image",
+        }
+    }
 }
 
 impl IndexedTable {
     pub fn rom_path(&self) -> Option<&PathBuf> {
         self.rom_path.as_ref().or(self.local_rom_path.as_ref())
     }
+
+    /// Tables in zip bundles have to be extracted first and tables deleted since they were indexed
+    /// are gone
+    pub fn launchable(&self) -> bool {
+        !self.archived && self.path.is_file()
+    }
+
+    /// The table with its file paths relative to `base`, see [relative_path]
+    pub fn relative_to(&self, base: &Path) -> IndexedTable {
+        let relative = |path: &PathBuf| relative_path(path, Some(base)).to_path_buf();
+        IndexedTable {
+            path: relative(&self.path),
+            b2s_path: self.b2s_path.as_ref().map(relative),
+            rom_path: self.rom_path.as_ref().map(relative),
+            local_rom_path: self.local_rom_path.as_ref().map(relative),
+            wheel_path: self.wheel_path.as_ref().map(relative),
+            nvram_path: self.nvram_path.as_ref().map(relative),
+            ..self.clone()
+        }
+    }
+
+    /// When the table was added to the folder, falls back to the last modified time if the
+    /// creation time is not available.
+    pub fn added(&self) -> IsoSystemTime {
+        self.created.unwrap_or(self.last_modified)
+    }
+
+    /// Referenced files that can't be found next to the table or in its `music` folder
+    pub fn missing_referenced_files(&self) -> Vec<&str> {
+        if self.archived {
+            // nothing has been extracted yet
+            return Vec::new();
+        }
+        let table_dir = self.path.parent().unwrap_or(Path::new(""));
+        self.referenced_files
+            .iter()
+            .filter(|file| {
+                let relative = PathBuf::from(file.replace('\\', std::path::MAIN_SEPARATOR_STR));
+                !table_dir.join(&relative).exists()
+                    && !table_dir.join("music").join(&relative).exists()
+            })
+            .map(|file| file.as_str())
+            .collect()
+    }
+
+    /// Required components that are not in `installed`, ignoring case.
+    /// Nothing is reported missing when `installed` is empty.
+    pub fn missing_components(&self, installed: &[String]) -> Vec<&str> {
+        if installed.is_empty() {
+            return Vec::new();
+        }
+        self.required_components
+            .iter()
+            .filter(|component| {
+                !installed
+                    .iter()
+                    .any(|installed| installed.eq_ignore_ascii_case(component))
+            })
+            .map(|component| component.as_str())
+            .collect()
+    }
+
+    /// The table uses one of the roms in `chds` and there is no `.chd` file in the folder of that
+    /// rom, next to the table or in the CHD folder. Nothing is missing when no roms are configured.
+    pub fn missing_chd(&self, chds: &ChdRequirements) -> bool {
+        let Some(game_name) = &self.game_name else {
+            return false;
+        };
+        let game_name = game_name.to_lowercase();
+        if !chds.games.contains(&game_name) {
+            return false;
+        }
+        let local_roms = self
+            .path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join("pinmame")
+            .join("roms");
+        let has_chd = |folder: PathBuf| {
+            fs::read_dir(folder).is_ok_and(|entries| {
+                entries.flatten().any(|entry| {
+                    entry
+                        .path()
+                        .extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("chd"))
+                })
+            })
+        };
+        !has_chd(local_roms.join(&game_name)) && !has_chd(chds.folder.join(&game_name))
+    }
+
+    /// Looks up the rom again, in the table folder or in `global_roms`.
+    /// Returns true if the rom path changed.
+    pub fn relink_rom(&mut self, global_roms: &HashMap<String, PathBuf>) -> bool {
+        let rom_path = find_rom_path(&self.path, &self.game_name, global_roms);
+        let changed = rom_path.as_ref() != self.rom_path();
+        self.rom_path = rom_path;
+        self.local_rom_path = None;
+        changed
+    }
+
+    /// Looks up the NVRAM file of the rom, in the table folder or in `nvrams`.
+    /// Returns true if the NVRAM path changed.
+    pub fn relink_nvram(&mut self, nvrams: &HashMap<String, PathBuf>) -> bool {
+        let nvram_path = self
+            .game_name
+            .as_ref()
+            .filter(|_| !self.archived)
+            .and_then(|game_name| {
+                let game_name = game_name.to_lowercase();
+                let local_nvram_path = self
+                    .path
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join("pinmame")
+                    .join("nvram")
+                    .join(format!("{}.nv", game_name));
+                if local_nvram_path.exists() {
+                    Some(local_nvram_path)
+                } else {
+                    nvrams.get(&game_name).cloned()
+                }
+            });
+        let changed = nvram_path != self.nvram_path;
+        self.nvram_path = nvram_path;
+        changed
+    }
+
+    /// Looks for a wheel image in `wheels` if the current one is missing, matching the file
+    /// name, rom name or table name. Returns true if the wheel path changed.
+    pub fn relink_wheel(&mut self, wheels: &HashMap<String, PathBuf>) -> bool {
+        if self.archived || self.wheel_path.as_ref().is_some_and(|path| path.exists()) {
+            return false;
+        }
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
+        let wheel_path = [
+            stem.as_ref(),
+            self.game_name.as_ref(),
+            self.table_info.table_name.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|name| wheels.get(&name.to_lowercase()))
+        .cloned();
+        let changed = wheel_path != self.wheel_path;
+        self.wheel_path = wheel_path;
+        changed
+    }
+
+    /// Problems that will likely prevent the table from working as intended,
+    /// leaving out the suppressed ones
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings_ignoring(&[], &[], &ChdRequirements::default())
+    }
+
+    /// The unsuppressed warnings, leaving out the `ignored` kinds, eg missing roms when there is
+    /// no rom folder. Components are checked against `installed_components`, see
+    /// [IndexedTable::missing_components], CHD disk images against `chds`.
+    pub fn warnings_ignoring(
+        &self,
+        ignored: &[WarningKind],
+        installed_components: &[String],
+        chds: &ChdRequirements,
+    ) -> Vec<String> {
+        self.warning_kinds_ignoring(ignored, installed_components, chds)
+            .into_iter()
+            .map(|(_, warning)| warning)
+            .collect()
+    }
+
+    /// Like [IndexedTable::warnings_ignoring], with the kind of every warning
+    pub fn warning_kinds_ignoring(
+        &self,
+        ignored: &[WarningKind],
+        installed_components: &[String],
+        chds: &ChdRequirements,
+    ) -> Vec<(WarningKind, String)> {
+        self.all_warnings(installed_components, chds)
+            .into_iter()
+            .filter(|(kind, _)| !self.suppressed_warnings.contains(kind) && !ignored.contains(kind))
+            .collect()
+    }
+
+    /// All problems, including the suppressed ones
+    pub fn all_warnings(
+        &self,
+        installed_components: &[String],
+        chds: &ChdRequirements,
+    ) -> Vec<(WarningKind, String)> {
+        let mut warnings = Vec::new();
+        if self.requires_pinmame && self.rom_path().is_none() {
+            let warning = match &self.game_name {
+                Some(game_name) => format!("Rom not found: {}", game_name),
+                None => "Requires PinMAME but no rom name found".to_string(),
+            };
+            warnings.push((WarningKind::MissingRom, warning));
+        }
+        for file in self.missing_referenced_files() {
+            warnings.push((
+                WarningKind::MissingReferencedFile,
+                format!("Referenced file not found: {}", file),
+            ));
+        }
+        for component in self.missing_components(installed_components) {
+            warnings.push((
+                WarningKind::MissingComponent,
+                format!("Requires {}, which is not installed", component),
+            ));
+        }
+        if self.missing_chd(chds) {
+            let game_name = self.game_name.as_deref().unwrap_or_default();
+            warnings.push((
+                WarningKind::MissingChd,
+                format!("CHD not found: {}", game_name),
+            ));
+        }
+        warnings
+    }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct TablesIndex {
     tables: HashMap<PathBuf, IndexedTable>,
 }
@@ -155,16 +616,85 @@ impl TablesIndex {
     }
 
     pub(crate) fn should_index(&self, path_with_metadata: &PathWithMetadata) -> bool {
-        // if exists with different last modified or missing
+        // if exists with different last modified or size, or missing
         match self.tables.get(&path_with_metadata.path) {
             Some(existing) => {
                 let existing_last_modified: SystemTime = existing.last_modified.into();
+                // tools that restore the modification time still change the size
+                let size_changed = matches!(
+                    (existing.file_size, path_with_metadata.file_size),
+                    (Some(existing_size), Some(size)) if existing_size != size
+                );
                 existing_last_modified != path_with_metadata.last_modified
+                    || size_changed
+                    || (!existing.archived
+                        && existing.sidecars_modified
+                            != SidecarTimes::read(&path_with_metadata.path))
             }
             None => true,
         }
     }
 
+    /// A table that was only touched, like restored from a backup, still has the same size and
+    /// content hash. Returns the indexed hash when the size and sidecars are unchanged, so the
+    /// index threads can compare it instead of parsing the table.
+    pub(crate) fn unchanged_hash(&self, vpx_file: &PathWithMetadata) -> Option<String> {
+        let existing = self.tables.get(&vpx_file.path)?;
+        let size = vpx_file.file_size?;
+        if existing.archived
+            || existing.file_size != Some(size)
+            || existing.sidecars_modified != SidecarTimes::read(&vpx_file.path)
+        {
+            return None;
+        }
+        existing.content_hash.clone()
+    }
+
+    /// Updates the modification time of a table that turned out to be unchanged, so it is not
+    /// hashed again next time
+    pub(crate) fn touch(&mut self, vpx_file: &PathWithMetadata) {
+        if let Some(existing) = self.tables.get_mut(&vpx_file.path) {
+            existing.last_modified = vpx_file.last_modified.into();
+        }
+    }
+
+    /// Updates the rom paths of all tables without parsing the vpx files again.
+    /// Returns the number of tables whose rom path changed.
+    pub fn relink_roms(&mut self, global_roms: &HashMap<String, PathBuf>) -> usize {
+        self.tables
+            .values_mut()
+            .map(|table| table.relink_rom(global_roms))
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    /// Updates the NVRAM paths of all tables, see [IndexedTable::relink_nvram].
+    /// Returns the number of tables whose NVRAM path changed.
+    pub fn relink_nvrams(&mut self, nvrams: &HashMap<String, PathBuf>) -> usize {
+        self.tables
+            .values_mut()
+            .map(|table| table.relink_nvram(nvrams))
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    /// Updates the wheel paths of all tables, see [IndexedTable::relink_wheel].
+    /// Returns the number of tables whose wheel path changed.
+    pub fn relink_wheels(&mut self, wheels: &HashMap<String, PathBuf>) -> usize {
+        self.tables
+            .values_mut()
+            .map(|table| table.relink_wheel(wheels))
+            .filter(|changed| *changed)
+            .count()
+    }
+
+    /// Flags the tables in the staging folder, see [IndexedTable::staged]
+    pub(crate) fn mark_staged(&mut self, staging_folder: Option<&Path>) {
+        for table in self.tables.values_mut() {
+            table.staged = staging_folder.is_some_and(|folder| table.path.starts_with(folder));
+        }
+    }
+
     pub(crate) fn remove_missing(&mut self, paths: &[PathWithMetadata]) -> usize {
         // create a hashset with the paths
         let len = self.tables.len();
@@ -220,23 +750,100 @@ pub fn find_roms(rom_path: &Path) -> io::Result<HashMap<String, PathBuf>> {
     entries.try_for_each(|entry| {
         let dir_entry = entry?;
         let path = dir_entry.path();
-        if path.is_file() {
-            if let Some("zip") = path.extension().and_then(OsStr::to_str) {
-                let rom_name = path
-                    .file_stem()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string()
-                    .to_lowercase();
-                roms.insert(rom_name, path);
-            }
+        if path.is_file()
+            && let Some("zip") = path.extension().and_then(OsStr::to_str)
+        {
+            let rom_name = path
+                .file_stem()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string()
+                .to_lowercase();
+            roms.insert(rom_name, path);
         }
         Ok::<(), io::Error>(())
     })?;
     Ok(roms)
 }
 
+/// Returns the png images in the given folder keyed by their lowercase name without extension
+pub fn find_wheels(wheel_folder: &Path) -> io::Result<HashMap<String, PathBuf>> {
+    if !wheel_folder.exists() {
+        return Ok(HashMap::new());
+    }
+    let mut wheels = HashMap::new();
+    for entry in fs::read_dir(wheel_folder)? {
+        let path = entry?.path();
+        let is_png = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+        if path.is_file()
+            && is_png
+            && let Some(stem) = path.file_stem()
+        {
+            wheels.insert(stem.to_string_lossy().to_lowercase(), path);
+        }
+    }
+    Ok(wheels)
+}
+
+/// The NVRAM files in the folder keyed by lowercase rom name
+pub fn find_nvrams(nvram_folder: &Path) -> io::Result<HashMap<String, PathBuf>> {
+    if !nvram_folder.exists() {
+        return Ok(HashMap::new());
+    }
+    let mut nvrams = HashMap::new();
+    for entry in fs::read_dir(nvram_folder)? {
+        let path = entry?.path();
+        let is_nv = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("nv"));
+        if path.is_file()
+            && is_nv
+            && let Some(stem) = path.file_stem()
+        {
+            nvrams.insert(stem.to_string_lossy().to_lowercase(), path);
+        }
+    }
+    Ok(nvrams)
+}
+
+#[derive(PartialEq, Debug)]
+pub enum RomIntegrity {
+    Ok { entries: usize },
+    Corrupt(String),
+}
+
+/// Checks that the rom zip can be read and that all entries decompress with a matching CRC
+pub fn verify_rom_zip(rom_path: &Path) -> io::Result<RomIntegrity> {
+    let file = File::open(rom_path)?;
+    let mut archive = match zip::ZipArchive::new(file) {
+        Ok(archive) => archive,
+        Err(e) => return Ok(RomIntegrity::Corrupt(e.to_string())),
+    };
+    for index in 0..archive.len() {
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => return Ok(RomIntegrity::Corrupt(e.to_string())),
+        };
+        let name = entry.name().map(|n| n.to_string()).unwrap_or_default();
+        // the zip reader validates the CRC once the entry is fully read
+        if let Err(e) = io::copy(&mut entry, &mut io::sink()) {
+            return Ok(RomIntegrity::Corrupt(format!("{}: {}", name, e)));
+        }
+    }
+    Ok(RomIntegrity::Ok {
+        entries: archive.len(),
+    })
+}
+
+/// Tables copied from case-insensitive file systems can have an extension like `.VPX`
+fn is_vpx_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("vpx"))
+}
+
 pub fn find_vpx_files(recursive: bool, tables_path: &Path) -> io::Result<Vec<PathWithMetadata>> {
     if recursive {
         let mut vpx_files = Vec::new();
@@ -244,14 +851,13 @@ pub fn find_vpx_files(recursive: bool, tables_path: &Path) -> io::Result<Vec<Pat
         entries.try_for_each(|entry| {
             let dir_entry = entry?;
             let path = dir_entry.path();
-            if path.is_file() {
-                if let Some("vpx") = path.extension().and_then(OsStr::to_str) {
-                    let last_modified = last_modified(path)?;
-                    vpx_files.push(PathWithMetadata {
-                        path: path.to_path_buf(),
-                        last_modified,
-                    });
-                }
+            if path.is_file() && is_vpx_file(path) {
+                let metadata = path.metadata()?;
+                vpx_files.push(PathWithMetadata {
+                    path: path.to_path_buf(),
+                    last_modified: metadata.modified()?,
+                    file_size: Some(metadata.len()),
+                });
             }
             Ok::<(), io::Error>(())
         })?;
@@ -263,14 +869,13 @@ pub fn find_vpx_files(recursive: bool, tables_path: &Path) -> io::Result<Vec<Pat
         dirs.try_for_each(|entry| {
             let dir_entry = entry?;
             let path = dir_entry.path();
-            if path.is_file() {
-                if let Some("vpx") = path.extension().and_then(OsStr::to_str) {
-                    let last_modified = last_modified(&path)?;
-                    vpx_files.push(PathWithMetadata {
-                        path: path.to_path_buf(),
-                        last_modified,
-                    });
-                }
+            if path.is_file() && is_vpx_file(&path) {
+                let metadata = path.metadata()?;
+                vpx_files.push(PathWithMetadata {
+                    path: path.to_path_buf(),
+                    last_modified: metadata.modified()?,
+                    file_size: Some(metadata.len()),
+                });
             }
             Ok::<(), io::Error>(())
         })?;
@@ -278,6 +883,113 @@ pub fn find_vpx_files(recursive: bool, tables_path: &Path) -> io::Result<Vec<Pat
     }
 }
 
+/// Lists the vpx files stored inside zip archives in the tables folder.
+///
+/// The returned paths are the archive path joined with the entry name, eg
+/// `tables/Bundle.zip/Table.vpx`. Only the zip central directory is read.
+pub fn find_archived_vpx_files(
+    recursive: bool,
+    tables_path: &Path,
+) -> io::Result<Vec<PathWithMetadata>> {
+    let zip_files: Vec<PathBuf> = if recursive {
+        walk_dir_filtered(tables_path)
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .collect()
+    } else {
+        fs::read_dir(tables_path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect()
+    };
+    let mut vpx_files = Vec::new();
+    for zip_path in zip_files.iter().filter(|path| is_zip_file(path)) {
+        let last_modified = last_modified(zip_path)?;
+        let archive = match zip::ZipArchive::new(File::open(zip_path)?) {
+            Ok(archive) => archive,
+            Err(e) => {
+                warn!("Not a valid zip file {}: {}", zip_path.display(), e);
+                continue;
+            }
+        };
+        for name in archive.file_names().filter_map(|name| name.ok()) {
+            if name.to_lowercase().ends_with(".vpx") {
+                vpx_files.push(PathWithMetadata {
+                    path: zip_path.join(name.as_ref()),
+                    last_modified,
+                    file_size: None,
+                });
+            }
+        }
+    }
+    vpx_files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(vpx_files)
+}
+
+fn is_zip_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// The zip archive containing the vpx file, `None` if the file is not inside an archive.
+pub fn archive_path(vpx_path: &Path) -> Option<&Path> {
+    vpx_path.ancestors().skip(1).find(|path| is_zip_file(path))
+}
+
+/// Reads the vpx file for a path returned by [find_archived_vpx_files] into memory.
+pub fn read_archived_vpx(vpx_path: &Path) -> io::Result<Vec<u8>> {
+    let (zip_path, entry_name) = split_archived_path(vpx_path)?;
+    let mut archive = zip::ZipArchive::new(File::open(zip_path)?).map_err(io::Error::other)?;
+    let mut entry = archive.by_name(&entry_name).map_err(io::Error::other)?;
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Where [extract_archived_vpx] puts the archived vpx file, next to its zip archive.
+///
+/// Fails if that file already exists.
+pub fn extracted_vpx_path(vpx_path: &Path) -> io::Result<PathBuf> {
+    let (zip_path, _) = split_archived_path(vpx_path)?;
+    let file_name = vpx_path
+        .file_name()
+        .ok_or_else(|| io::Error::other(format!("No file name: {}", vpx_path.display())))?;
+    let target = zip_path.parent().unwrap_or(Path::new("")).join(file_name);
+    if target.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("File already exists: {}", target.display()),
+        ));
+    }
+    Ok(target)
+}
+
+/// Extracts an archived vpx file next to its zip archive, returns the path of the extracted file.
+///
+/// Existing files are never overwritten.
+pub fn extract_archived_vpx(vpx_path: &Path) -> io::Result<PathBuf> {
+    let target = extracted_vpx_path(vpx_path)?;
+    let bytes = read_archived_vpx(vpx_path)?;
+    fs::write(&target, bytes)?;
+    Ok(target)
+}
+
+fn split_archived_path(vpx_path: &Path) -> io::Result<(&Path, String)> {
+    let zip_path = archive_path(vpx_path)
+        .ok_or_else(|| io::Error::other(format!("Not in a zip archive: {}", vpx_path.display())))?;
+    let entry_path = vpx_path.strip_prefix(zip_path).map_err(io::Error::other)?;
+    // zip entry names always use forward slashes
+    let entry_name = entry_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Ok((zip_path, entry_name))
+}
+
 /// Walks the directory and filters out .git and __MACOSX folders
 fn walk_dir_filtered(tables_path: &Path) -> FilterEntry<IntoIter, fn(&DirEntry) -> bool> {
     WalkDir::new(tables_path).into_iter().filter_entry(|entry| {
@@ -288,42 +1000,144 @@ fn walk_dir_filtered(tables_path: &Path) -> FilterEntry<IntoIter, fn(&DirEntry)
     })
 }
 
-pub trait Progress {
-    fn set_length(&self, len: u64);
-    fn set_position(&self, i: u64);
-    fn finish_and_clear(&self);
-}
-
-pub struct VoidProgress;
-impl Progress for VoidProgress {
-    fn set_length(&self, _len: u64) {}
-    fn set_position(&self, _i: u64) {}
-    fn finish_and_clear(&self) {}
+/// Reported to the progress callback after each vpx file has been parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct IndexProgress<'a> {
+    /// Number of files parsed so far, including this one
+    pub position: u64,
+    /// Total number of files that will be parsed
+    pub total: u64,
+    /// The file that was just parsed
+    pub path: &'a Path,
+    /// The parsed table, `None` if the file could not be parsed
+    pub table: Option<&'a IndexedTable>,
+    /// Why the file could not be parsed, the file is left out of the index
+    pub error: Option<&'a IndexError>,
 }
 
+#[derive(Debug, thiserror::Error)]
 pub enum IndexError {
+    #[error("Folder does not exist: {}", .0.display())]
     FolderDoesNotExist(PathBuf),
-    IoError(io::Error),
+    #[error("Not a vpx file: {}", .0.display())]
+    NotVpxFile(PathBuf),
+    /// The file is not an OLE compound document or misses the table streams
+    #[error("Not a valid vpx file {}: {source}", path.display())]
+    InvalidVpx { path: PathBuf, source: io::Error },
+    /// A sidecar file next to the table could not be parsed
+    #[error("Failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+    /// Reading a table or one of its sidecar files failed
+    #[error("Failed to read {}: {source}", path.display())]
+    File { path: PathBuf, source: io::Error },
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
 }
-impl Debug for IndexError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+
+impl IndexError {
+    /// The file the error is about, if any
+    pub fn path(&self) -> Option<&Path> {
         match self {
-            IndexError::FolderDoesNotExist(path) => {
-                write!(f, "Folder does not exist: {}", path.display())
-            }
-            IndexError::IoError(e) => write!(f, "IO error: {}", e),
+            IndexError::FolderDoesNotExist(path)
+            | IndexError::NotVpxFile(path)
+            | IndexError::InvalidVpx { path, .. }
+            | IndexError::Parse { path, .. }
+            | IndexError::File { path, .. } => Some(path),
+            IndexError::IoError(_) => None,
+        }
+    }
+
+    fn invalid_vpx(path: &Path) -> impl FnOnce(io::Error) -> IndexError {
+        move |source| IndexError::InvalidVpx {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    fn file(path: &Path) -> impl FnOnce(io::Error) -> IndexError {
+        move |source| IndexError::File {
+            path: path.to_path_buf(),
+            source,
         }
     }
 }
+
 impl From<IndexError> for io::Error {
     fn from(e: IndexError) -> io::Error {
-        io::Error::new(io::ErrorKind::Other, format!("{:?}", e))
+        match e {
+            IndexError::IoError(e) => e,
+            other => io::Error::other(other),
+        }
+    }
+}
+
+/// How many tables the indexer parses at the same time
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndexParallelism {
+    /// One table at a time on network shares, removable and spinning disks, all threads otherwise
+    #[default]
+    Auto,
+    /// One table at a time
+    Serial,
+    /// All threads, whatever the disk
+    Full,
+}
+
+impl IndexParallelism {
+    /// The number of threads for indexing the tables folder. `threads` is the number used for full
+    /// parallelism and where the disk kind can't be found out, all cores if not set.
+    pub fn threads(self, tables_folder: &Path, threads: Option<usize>) -> usize {
+        let full = threads.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+        match self {
+            IndexParallelism::Serial => 1,
+            IndexParallelism::Full => full.max(1),
+            IndexParallelism::Auto => match disk_kind(tables_folder) {
+                Some(kind) if kind.is_slow() => 1,
+                _ => full.max(1),
+            },
+        }
     }
 }
 
-impl From<io::Error> for IndexError {
-    fn from(e: io::Error) -> Self {
-        IndexError::IoError(e)
+/// Everything besides the tables folder that decides what ends up in the index. Commands sharing
+/// an index file should use the same options, see
+/// [ResolvedConfig::index_options](crate::config::ResolvedConfig::index_options).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexOptions {
+    pub recursive: bool,
+    /// Roms that are not next to the table are looked up here
+    pub global_roms_path: Option<PathBuf>,
+    /// File name patterns of files to skip, like backups
+    pub excluded_patterns: Vec<String>,
+    /// Index the vpx files inside zip archives as well
+    pub include_archives: bool,
+    /// Tables without a wheel image next to them get one from here if it has a match
+    pub wheel_folder: Option<PathBuf>,
+    /// Tables in here are indexed as well and flagged as staged
+    pub staging_folder: Option<PathBuf>,
+    /// NVRAM files are looked up here besides the table folders
+    pub nvram_folder: Option<PathBuf>,
+    /// Tables parsed at the same time
+    pub threads: usize,
+}
+
+impl Default for IndexOptions {
+    fn default() -> Self {
+        IndexOptions {
+            recursive: true,
+            global_roms_path: None,
+            excluded_patterns: Vec::new(),
+            include_archives: false,
+            wheel_folder: None,
+            staging_folder: None,
+            nvram_folder: None,
+            threads: 1,
+        }
     }
 }
 
@@ -331,24 +1145,37 @@ impl From<io::Error> for IndexError {
 /// Returns the index.
 /// If the index file already exists, it will be read and updated.
 /// If the index file does not exist, it will be created.
+/// Tables in `force_reindex` are parsed again even if they did not change.
 pub fn index_folder(
-    recursive: bool,
     tables_folder: &Path,
     tables_index_path: &Path,
-    global_roms_path: Option<&Path>,
-    progress: &impl Progress,
+    options: &IndexOptions,
+    progress: Option<&mut dyn FnMut(IndexProgress)>,
     force_reindex: Vec<PathBuf>,
 ) -> Result<TablesIndex, IndexError> {
-    let global_roms = global_roms_path
+    let recursive = options.recursive;
+    let include_archives = options.include_archives;
+    let excluded_patterns = options.excluded_patterns.as_slice();
+    let staging_folder = options.staging_folder.as_deref();
+    let nvram_folder = options.nvram_folder.as_deref();
+    let threads = options.threads.max(1);
+    let global_roms = options
+        .global_roms_path
+        .as_deref()
         .map(find_roms)
         .unwrap_or_else(|| Ok(HashMap::new()))?;
+    let wheels = options
+        .wheel_folder
+        .as_deref()
+        .map(find_wheels)
+        .unwrap_or_else(|| Ok(HashMap::new()))?;
     info!("Indexing {}", tables_folder.display());
 
     if !tables_folder.exists() {
         return Err(IndexError::FolderDoesNotExist(tables_folder.to_path_buf()));
     }
 
-    let existing_index = read_index_json(tables_index_path)?;
+    let existing_index = read_index(tables_index_path)?;
     if let Some(index) = &existing_index {
         info!(
             "  Found existing index with {} tables at {}",
@@ -356,9 +1183,32 @@ pub fn index_folder(
             tables_index_path.display()
         );
     }
-    let mut index = existing_index.unwrap_or(TablesIndex::empty());
+    let mut index = existing_index.clone().unwrap_or(TablesIndex::empty());
 
-    let vpx_files = find_vpx_files(recursive, tables_folder)?;
+    let mut vpx_files = find_vpx_files(recursive, tables_folder)?;
+    if include_archives {
+        vpx_files.extend(find_archived_vpx_files(recursive, tables_folder)?);
+    }
+    if let Some(staging_folder) = staging_folder {
+        if staging_folder.exists() {
+            vpx_files.extend(find_vpx_files(recursive, staging_folder)?);
+            if include_archives {
+                vpx_files.extend(find_archived_vpx_files(recursive, staging_folder)?);
+            }
+        } else {
+            info!(
+                "  Staging folder {} does not exist",
+                staging_folder.display()
+            );
+        }
+    }
+    // the staging folder can be inside the tables folder
+    let mut seen = HashSet::new();
+    vpx_files.retain(|vpx_file| seen.insert(vpx_file.path.clone()));
+    vpx_files.retain(|vpx_file| {
+        let file_name = vpx_file.path.file_name().unwrap_or_default();
+        !matches_any_pattern(&file_name.to_string_lossy(), excluded_patterns)
+    });
     info!("  Found {} tables", vpx_files.len());
     // remove files that are missing
     let removed_len = index.remove_missing(&vpx_files);
@@ -381,90 +1231,222 @@ pub fn index_folder(
 
     // find files that are missing or have been modified
     let mut vpx_files_to_index = Vec::new();
+    // tables that might only have been touched, with their indexed content hash
+    let mut known_hashes = HashMap::new();
     for vpx_file in vpx_files {
         if tables_with_missing_rom.contains(&vpx_file.path)
             || force_reindex.contains(&vpx_file.path)
-            || index.should_index(&vpx_file)
         {
             vpx_files_to_index.push(vpx_file);
+        } else if index.should_index(&vpx_file) {
+            if let Some(hash) = index.unchanged_hash(&vpx_file) {
+                known_hashes.insert(vpx_file.path.clone(), hash);
+            }
+            vpx_files_to_index.push(vpx_file);
         }
     }
 
-    info!("  {} tables need (re)indexing.", vpx_files_to_index.len());
-    let vpx_files_with_table_info = index_vpx_files(&vpx_files_to_index, &global_roms, progress);
+    info!(
+        "  {} tables need (re)indexing with {} threads.",
+        vpx_files_to_index.len(),
+        threads
+    );
+    let (vpx_files_with_table_info, unchanged) = index_changed_vpx_files(
+        &vpx_files_to_index,
+        &known_hashes,
+        &global_roms,
+        threads,
+        progress,
+    );
 
+    info!("  {} tables were only touched", unchanged.len());
+    for vpx_file in unchanged {
+        index.touch(vpx_file);
+    }
     // add new files to index
     index.merge(vpx_files_with_table_info);
+    // wheels are checked for all tables as they can be added without touching the table
+    let relinked_wheels = index.relink_wheels(&wheels);
+    info!("  {} wheel images have been updated", relinked_wheels);
+    // NVRAM files show up when a table is played
+    if let Some(nvram_folder) = nvram_folder {
+        let relinked_nvrams = index.relink_nvrams(&find_nvrams(nvram_folder)?);
+        info!("  {} NVRAM files have been updated", relinked_nvrams);
+    }
+    index.mark_staged(staging_folder);
 
-    // write the index to a file
-    write_index_json(&index, tables_index_path)?;
+    // an unchanged index is not written again, its modification time tells when tables changed
+    if existing_index.as_ref() != Some(&index) {
+        write_index(&index, tables_index_path)?;
+    }
 
     Ok(index)
 }
 
+/// Parses a single vpx file without reading or writing the index
+pub fn index_file(
+    vpx_path: &Path,
+    global_roms: &HashMap<String, PathBuf>,
+) -> Result<IndexedTable, IndexError> {
+    if !vpx_path.is_file() || !is_vpx_file(vpx_path) {
+        return Err(IndexError::NotVpxFile(vpx_path.to_path_buf()));
+    }
+    let vpx_file = PathWithMetadata {
+        path: vpx_path.to_path_buf(),
+        last_modified: last_modified(vpx_path).map_err(IndexError::file(vpx_path))?,
+        file_size: None,
+    };
+    let (_, table) = index_vpx_file(&vpx_file, global_roms)?;
+    Ok(table)
+}
+
 pub fn index_vpx_files(
     vpx_files: &[PathWithMetadata],
     global_roms: &HashMap<String, PathBuf>,
-    progress: &impl Progress,
+    progress: Option<&mut dyn FnMut(IndexProgress)>,
+) -> TablesIndex {
+    index_vpx_files_with_threads(vpx_files, global_roms, 1, progress)
+}
+
+/// Parses the files on up to `threads` threads, progress is reported in the order the files are
+/// done, on the calling thread.
+pub fn index_vpx_files_with_threads(
+    vpx_files: &[PathWithMetadata],
+    global_roms: &HashMap<String, PathBuf>,
+    threads: usize,
+    progress: Option<&mut dyn FnMut(IndexProgress)>,
 ) -> TablesIndex {
-    // TODO tried using rayon here but it's not faster and uses up all cpu
-    // use rayon::prelude::*;
-    // .par_iter() instead of .iter()
-    progress.set_length(vpx_files.len() as u64);
+    let (index, _) =
+        index_changed_vpx_files(vpx_files, &HashMap::new(), global_roms, threads, progress);
+    index
+}
 
-    let vpx_files_with_table_info: HashMap<PathBuf, IndexedTable> = vpx_files
-        .iter()
-        .enumerate()
-        .flat_map(|(i, vpx_file)| {
-            let optional = match index_vpx_file(vpx_file, global_roms) {
-                Ok(indexed_table) => Some(indexed_table),
-                Err(e) => {
-                    // TODO we want to return any failures instead of printing here
-                    let warning =
-                        format!("Not a valid vpx file {}: {}", vpx_file.path.display(), e);
-                    println!("{}", warning);
-                    None
+/// Like [index_vpx_files_with_threads], files with a known content hash are hashed first and
+/// only parsed when the hash differs. Returns the unchanged files next to the parsed tables.
+/// Progress is only reported for parsed files, the position counts the unchanged ones as well.
+fn index_changed_vpx_files<'a>(
+    vpx_files: &'a [PathWithMetadata],
+    known_hashes: &HashMap<PathBuf, String>,
+    global_roms: &HashMap<String, PathBuf>,
+    threads: usize,
+    mut progress: Option<&mut dyn FnMut(IndexProgress)>,
+) -> (TablesIndex, Vec<&'a PathWithMetadata>) {
+    let total = vpx_files.len() as u64;
+    let next_file = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut vpx_files_with_table_info: HashMap<PathBuf, IndexedTable> = HashMap::new();
+    let mut unchanged = Vec::new();
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, vpx_files.len().max(1)) {
+            let sender = sender.clone();
+            let next_file = &next_file;
+            scope.spawn(move || {
+                while let Some(vpx_file) = vpx_files.get(next_file.fetch_add(1, Ordering::Relaxed))
+                {
+                    // `None` when the file still has the content hash of the indexed table
+                    let outcome = match known_hashes.get(&vpx_file.path) {
+                        Some(known) => match content_hash(&vpx_file.path) {
+                            Ok(hash) if &hash == known => None,
+                            // the hash is reused, large tables are not read twice for it
+                            Ok(hash) => {
+                                Some(index_vpx_file_with_hash(vpx_file, global_roms, Some(hash)))
+                            }
+                            Err(_) => Some(index_vpx_file(vpx_file, global_roms)),
+                        },
+                        None => Some(index_vpx_file(vpx_file, global_roms)),
+                    };
+                    if sender.send((vpx_file, outcome)).is_err() {
+                        break;
+                    }
                 }
+            });
+        }
+        drop(sender);
+        for (i, (vpx_file, outcome)) in receiver.iter().enumerate() {
+            let Some(result) = outcome else {
+                unchanged.push(vpx_file);
+                continue;
             };
-            progress.set_position((i + 1) as u64);
-            optional
-        })
-        .collect();
+            match progress.as_mut() {
+                Some(progress) => progress(IndexProgress {
+                    position: (i + 1) as u64,
+                    total,
+                    path: &vpx_file.path,
+                    table: result.as_ref().ok().map(|(_, table)| table),
+                    error: result.as_ref().err(),
+                }),
+                // the progress callback reports the error otherwise
+                None => {
+                    if let Err(e) = &result {
+                        warn!("{}", e);
+                    }
+                }
+            }
+            vpx_files_with_table_info.extend(result.ok());
+        }
+    });
 
-    // sort by name
-    // vpx_files_with_table_info.sort_by(|a, b| table_name_compare(a, b));
-    TablesIndex {
+    let index = TablesIndex {
         tables: vpx_files_with_table_info,
-    }
+    };
+    (index, unchanged)
+}
+
+pub(crate) fn index_vpx_file(
+    vpx_file_path: &PathWithMetadata,
+    global_roms: &HashMap<String, PathBuf>,
+) -> Result<(PathBuf, IndexedTable), IndexError> {
+    index_vpx_file_with_hash(vpx_file_path, global_roms, None)
 }
 
-fn index_vpx_file(
+/// Parses the table, `content_hash` is the hash of the file if it was computed already
+fn index_vpx_file_with_hash(
     vpx_file_path: &PathWithMetadata,
     global_roms: &HashMap<String, PathBuf>,
-) -> io::Result<(PathBuf, IndexedTable)> {
+    content_hash: Option<String>,
+) -> Result<(PathBuf, IndexedTable), IndexError> {
     let path = &vpx_file_path.path;
-    let mut vpx_file = vpx::open(path)?;
+    if archive_path(path).is_some() {
+        return index_archived_vpx_file(vpx_file_path, global_roms);
+    }
+    let mut vpx_file = vpx::open(path).map_err(IndexError::invalid_vpx(path))?;
     // if there's an .info.json file, we should use that instead of the info in the vpx file
     let info_file_path = path.with_extension("info.json");
     let table_info = if info_file_path.exists() {
-        read_table_info_json(&info_file_path)
+        read_table_info_json(&info_file_path)?
     } else {
-        vpx_file.read_tableinfo()
-    }?;
-    let game_data = vpx_file.read_gamedata()?;
+        vpx_file
+            .read_tableinfo()
+            .map_err(IndexError::invalid_vpx(path))?
+    };
+    let game_data = vpx_file
+        .read_gamedata()
+        .map_err(IndexError::invalid_vpx(path))?;
+    let layout = TableLayout::from_game_data(&game_data);
+    let physics = TablePhysics::from_game_data(&game_data);
     let code = consider_sidecar_vbs(path, game_data)?;
     //  also this sidecar should be part of the cache key
     let game_name = extract_game_name(&code);
     let requires_pinmame = requires_pinmame(&code);
-    let rom_path = find_local_rom_path(vpx_file_path, &game_name).or_else(|| {
-        game_name
-            .as_ref()
-            .and_then(|game_name| global_roms.get(&game_name.to_lowercase()).cloned())
-    });
+    let dmd_kind = detect_dmd_kind(&code, requires_pinmame);
+    let referenced_files = extract_referenced_files(&code);
+    let required_components = extract_required_components(&code, requires_pinmame);
+    let rom_path = find_rom_path(path, &game_name, global_roms);
     let b2s_path = find_b2s_path(vpx_file_path);
     let wheel_path = find_wheel_path(vpx_file_path);
-    let last_modified = last_modified(path).unwrap();
+    let last_modified = last_modified(path).map_err(IndexError::file(path))?;
+    let created = path.metadata().and_then(|m| m.created()).ok();
+    let content_hash = match content_hash {
+        Some(content_hash) => content_hash,
+        None => self::content_hash(path).map_err(IndexError::file(path))?,
+    };
+    let file_size = path.metadata().map_err(IndexError::file(path))?.len();
     let indexed_table_info = IndexedTableInfo::from(table_info);
+    // before reading them, a sidecar written meanwhile is read again next time
+    let sidecars_modified = SidecarTimes::read(path);
+    // broken notes should not keep the table out of the index
+    let notes = read_notes(path).unwrap_or_default();
+    let ini_settings = read_table_ini(path).unwrap_or_default();
 
     let indexed = IndexedTable {
         path: path.clone(),
@@ -474,8 +1456,85 @@ fn index_vpx_file(
         rom_path,
         local_rom_path: None,
         wheel_path,
+        nvram_path: None,
         requires_pinmame,
+        dmd_kind,
+        layout,
+        physics: Some(physics),
         last_modified: IsoSystemTime(last_modified),
+        created: created.map(IsoSystemTime),
+        content_hash: Some(content_hash),
+        file_size: Some(file_size),
+        referenced_files,
+        required_components,
+        ini_settings,
+        archived: false,
+        staged: false,
+        suppressed_warnings: notes.suppressed_warnings,
+        tags: notes.tags,
+        rating: notes.rating,
+        play_time_secs: notes.play_time_secs,
+        sidecars_modified,
+    };
+    Ok((indexed.path.clone(), indexed))
+}
+
+/// Archived tables are parsed in memory, sidecar files are not considered as there are none yet.
+fn index_archived_vpx_file(
+    vpx_file_path: &PathWithMetadata,
+    global_roms: &HashMap<String, PathBuf>,
+) -> Result<(PathBuf, IndexedTable), IndexError> {
+    let path = &vpx_file_path.path;
+    let bytes = read_archived_vpx(path).map_err(IndexError::file(path))?;
+    let content_hash = format!("{:x}", Sha256::digest(&bytes));
+    let file_size = bytes.len() as u64;
+    let mut vpx_file =
+        vpx::VpxFile::open(io::Cursor::new(bytes)).map_err(IndexError::invalid_vpx(path))?;
+    let table_info = vpx_file
+        .read_tableinfo()
+        .map_err(IndexError::invalid_vpx(path))?;
+    let game_data = vpx_file
+        .read_gamedata()
+        .map_err(IndexError::invalid_vpx(path))?;
+    let layout = TableLayout::from_game_data(&game_data);
+    let physics = TablePhysics::from_game_data(&game_data);
+    let code = game_data.code.string;
+    let game_name = extract_game_name(&code);
+    let requires_pinmame = requires_pinmame(&code);
+    let dmd_kind = detect_dmd_kind(&code, requires_pinmame);
+    let referenced_files = extract_referenced_files(&code);
+    let required_components = extract_required_components(&code, requires_pinmame);
+    let rom_path = game_name
+        .as_ref()
+        .and_then(|game_name| global_roms.get(&game_name.to_lowercase()).cloned());
+
+    let indexed = IndexedTable {
+        path: path.clone(),
+        table_info: IndexedTableInfo::from(table_info),
+        game_name,
+        b2s_path: None,
+        rom_path,
+        local_rom_path: None,
+        wheel_path: None,
+        nvram_path: None,
+        requires_pinmame,
+        dmd_kind,
+        layout,
+        physics: Some(physics),
+        last_modified: IsoSystemTime(vpx_file_path.last_modified),
+        created: None,
+        content_hash: Some(content_hash),
+        file_size: Some(file_size),
+        referenced_files,
+        required_components,
+        ini_settings: None,
+        archived: true,
+        staged: false,
+        suppressed_warnings: Vec::new(),
+        tags: Vec::new(),
+        rating: None,
+        play_time_secs: None,
+        sidecars_modified: SidecarTimes::default(),
     };
     Ok((indexed.path.clone(), indexed))
 }
@@ -493,30 +1552,35 @@ pub fn get_romname_from_vpx(vpx_path: &Path) -> io::Result<Option<String>> {
     }
 }
 
-fn read_table_info_json(info_file_path: &Path) -> io::Result<TableInfo> {
-    let mut info_file = File::open(info_file_path)?;
-    let json = serde_json::from_reader(&mut info_file).map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::Other,
-            format!(
-                "Failed to parse/read json {}: {}",
-                info_file_path.display(),
-                e
-            ),
-        )
-    })?;
-    let (table_info, _custom_info_tags) = json_to_info(json, None)?;
+fn read_table_info_json(info_file_path: &Path) -> Result<TableInfo, IndexError> {
+    let parse_error = |message: String| IndexError::Parse {
+        path: info_file_path.to_path_buf(),
+        message,
+    };
+    let mut info_file = File::open(info_file_path).map_err(IndexError::file(info_file_path))?;
+    let json = serde_json::from_reader(&mut info_file).map_err(|e| parse_error(e.to_string()))?;
+    let (table_info, _custom_info_tags) =
+        json_to_info(json, None).map_err(|e| parse_error(e.to_string()))?;
     Ok(table_info)
 }
 
-fn find_local_rom_path(
-    vpx_file_path: &PathWithMetadata,
+/// The rom in the table folder takes precedence over the one in the global roms folder
+fn find_rom_path(
+    vpx_path: &Path,
     game_name: &Option<String>,
+    global_roms: &HashMap<String, PathBuf>,
 ) -> Option<PathBuf> {
+    find_local_rom_path(vpx_path, game_name).or_else(|| {
+        game_name
+            .as_ref()
+            .and_then(|game_name| global_roms.get(&game_name.to_lowercase()).cloned())
+    })
+}
+
+fn find_local_rom_path(vpx_path: &Path, game_name: &Option<String>) -> Option<PathBuf> {
     game_name.as_ref().and_then(|game_name| {
         let rom_file_name = format!("{}.zip", game_name.to_lowercase());
-        let rom_path = vpx_file_path
-            .path
+        let rom_path = vpx_path
             .parent()
             .unwrap()
             .join("pinmame")
@@ -563,16 +1627,162 @@ fn find_wheel_path(vpx_file_path: &PathWithMetadata) -> Option<PathBuf> {
     None
 }
 
+/// Extensions of files next to a vpx file that belong to that table
+pub const DEFAULT_SIDECAR_EXTENSIONS: [&str; 7] = [
+    "vbs",
+    "ini",
+    "directb2s",
+    "info.json",
+    "notes.json",
+    "wheel.png",
+    TABLE_JSON_EXTENSION,
+];
+
+/// Extension of the sidecar with the indexed table as json, written by `vpxtool export-sidecars`
+pub const TABLE_JSON_EXTENSION: &str = "vpxtool.json";
+
+/// File name patterns of backups and autosaves left behind by VPinball and table editors.
+/// `*` matches any number of characters, matching ignores case.
+pub const DEFAULT_BACKUP_PATTERNS: [&str; 4] = [
+    "*.vpx.bak",
+    "*-backup.vpx",
+    "*_backup.vpx",
+    "*-autosave*.vpx",
+];
+
+/// Checks the file name against `*` wildcard patterns, ignoring case
+pub fn matches_any_pattern<S: AsRef<str>>(file_name: &str, patterns: &[S]) -> bool {
+    let file_name = file_name.to_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| wildcard_match(&pattern.as_ref().to_lowercase(), &file_name))
+}
+
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len())
+                .filter(|i| text.is_char_boundary(*i))
+                .any(|i| wildcard_match(rest, &text[i..]))
+        }
+    }
+}
+
+/// Finds backups of the vpx file next to it, these start with the table file stem and match one
+/// of the patterns, eg `Table-backup.vpx` or `Table.vpx.bak` for `Table.vpx`.
+pub fn find_backup_files<S: AsRef<str>>(vpx_file_path: &Path, patterns: &[S]) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (vpx_file_path.parent(), vpx_file_path.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy().to_lowercase();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path != vpx_file_path && path.is_file())
+        .filter(|path| {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            file_name.to_lowercase().starts_with(&stem) && matches_any_pattern(&file_name, patterns)
+        })
+        .collect::<Vec<PathBuf>>();
+    backups.sort();
+    backups
+}
+
+/// The path relative to `base`, paths outside `base` and paths without a base stay as they are
+pub fn relative_path<'a>(path: &'a Path, base: Option<&Path>) -> &'a Path {
+    base.and_then(|base| path.strip_prefix(base).ok())
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .unwrap_or(path)
+}
+
+/// Finds files next to the vpx file that share its name, eg `Table.vbs` for `Table.vpx`.
+/// With `include_hidden` dotfiles like `.Table.ini` are also considered.
+pub fn find_sidecar_files<S: AsRef<str>>(
+    vpx_file_path: &Path,
+    extensions: &[S],
+    include_hidden: bool,
+) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (vpx_file_path.parent(), vpx_file_path.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy();
+    let mut sidecars = Vec::new();
+    for extension in extensions {
+        let extension = extension.as_ref().trim_start_matches('.');
+        let mut candidates = vec![dir.join(format!("{}.{}", stem, extension))];
+        if include_hidden {
+            candidates.push(dir.join(format!(".{}.{}", stem, extension)));
+        }
+        sidecars.extend(candidates.into_iter().filter(|path| path.is_file()));
+    }
+    sidecars
+}
+
+/// Files below `tables_path` that look like sidecar files but have no table next to them, the
+/// reverse of [find_sidecar_files]. Sorted by path.
+pub fn find_orphaned_sidecars<S: AsRef<str>>(
+    tables_path: &Path,
+    extensions: &[S],
+    include_hidden: bool,
+) -> io::Result<Vec<PathBuf>> {
+    let tables = find_vpx_files(true, tables_path)?
+        .into_iter()
+        .filter_map(|vpx_file| {
+            let stem = vpx_file.path.file_stem()?.to_string_lossy().to_string();
+            Some((vpx_file.path.parent()?.to_path_buf(), stem))
+        })
+        .collect::<HashSet<(PathBuf, String)>>();
+    let mut suffixes = extensions
+        .iter()
+        .map(|extension| format!(".{}", extension.as_ref().trim_start_matches('.')))
+        .collect::<Vec<String>>();
+    // longest first, `Table.info.json` is a sidecar of `Table`, not a `.json` of `Table.info`
+    suffixes.sort_by_key(|suffix| std::cmp::Reverse(suffix.len()));
+    let mut orphans = Vec::new();
+    for entry in walk_dir_filtered(tables_path) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy();
+        let Some(stem) = suffixes
+            .iter()
+            .find_map(|suffix| file_name.strip_suffix(suffix.as_str()))
+        else {
+            continue;
+        };
+        let stem = match stem.strip_prefix('.') {
+            Some(hidden) if include_hidden => hidden,
+            Some(_) => continue,
+            None => stem,
+        };
+        let dir = entry.path().parent().unwrap_or(tables_path).to_path_buf();
+        if !stem.is_empty() && !tables.contains(&(dir, stem.to_string())) {
+            orphans.push(entry.path().to_path_buf());
+        }
+    }
+    orphans.sort();
+    Ok(orphans)
+}
+
 /// If there is a file with the same name and extension .vbs we pick that code
 /// instead of the code in the vpx file.
 ///
 /// TODO if this file changes the index entry is currently not invalidated
-fn consider_sidecar_vbs(path: &Path, game_data: GameData) -> io::Result<String> {
+fn consider_sidecar_vbs(path: &Path, game_data: GameData) -> Result<String, IndexError> {
     let vbs_path = path.with_extension("vbs");
     let code = if vbs_path.exists() {
-        let mut vbs_file = File::open(vbs_path)?;
         let mut code = String::new();
-        vbs_file.read_to_string(&mut code)?;
+        File::open(&vbs_path)
+            .and_then(|mut vbs_file| vbs_file.read_to_string(&mut code))
+            .map_err(IndexError::file(&vbs_path))?;
         code
     } else {
         game_data.code.string
@@ -580,16 +1790,93 @@ fn consider_sidecar_vbs(path: &Path, game_data: GameData) -> io::Result<String>
     Ok(code)
 }
 
-fn last_modified(path: &Path) -> io::Result<SystemTime> {
-    let metadata: Metadata = path.metadata()?;
-    metadata.modified()
+/// SHA-256 of the raw file bytes as lowercase hex.
+///
+/// We hash the file as-is so the value matches what `sha256sum` reports on any platform.
+pub fn content_hash(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn last_modified(path: &Path) -> io::Result<SystemTime> {
+    let metadata: Metadata = path.metadata()?;
+    metadata.modified()
+}
+
+/// Index files with the `sqlite` extension are SQLite databases, see [crate::sqlite]
+pub fn is_sqlite_index(index_path: &Path) -> bool {
+    index_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("sqlite"))
+}
+
+/// Writes the index as json or to SQLite depending on the file extension
+pub fn write_index(indexed_tables: &TablesIndex, index_path: &Path) -> io::Result<()> {
+    if is_sqlite_index(index_path) {
+        #[cfg(feature = "sqlite")]
+        return crate::sqlite::write_index_sqlite(indexed_tables, index_path).map(|_| ());
+        #[cfg(not(feature = "sqlite"))]
+        return Err(sqlite_unsupported(index_path));
+    }
+    write_index_json(indexed_tables, index_path)
+}
+
+/// Reads the index as json or from SQLite depending on the file extension
+pub fn read_index(index_path: &Path) -> io::Result<Option<TablesIndex>> {
+    if is_sqlite_index(index_path) {
+        #[cfg(feature = "sqlite")]
+        return crate::sqlite::read_index_sqlite(index_path);
+        #[cfg(not(feature = "sqlite"))]
+        return Err(sqlite_unsupported(index_path));
+    }
+    read_index_json(index_path)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn sqlite_unsupported(index_path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{} is a SQLite index, vpxtool needs to be built with the sqlite feature to use it",
+            index_path.display()
+        ),
+    )
 }
 
+/// Writes the index to a temporary file next to it that then replaces the index, readers and
+/// other writers never see a half-written index. When two processes write at the same time the
+/// last one wins.
 pub fn write_index_json(indexed_tables: &TablesIndex, json_path: &Path) -> io::Result<()> {
-    let json_file = File::create(json_path)?;
     let indexed_tables_json: TablesIndexJson = indexed_tables.into();
-    serde_json::to_writer_pretty(json_file, &indexed_tables_json)
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    let temp_path = temp_path_for(json_path);
+    let written = File::create(&temp_path).and_then(|json_file| {
+        let mut writer = io::BufWriter::new(json_file);
+        serde_json::to_writer_pretty(&mut writer, &indexed_tables_json)
+            .map_err(io::Error::other)?;
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()
+    });
+    match written.and_then(|_| std::fs::rename(&temp_path, json_path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+/// A hidden file next to `path`, unique per write so concurrent writers don't share it
+fn temp_path_for(path: &Path) -> PathBuf {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        write
+    ))
 }
 
 pub fn read_index_json(json_path: &Path) -> io::Result<Option<TablesIndex>> {
@@ -603,7 +1890,7 @@ pub fn read_index_json(json_path: &Path) -> io::Result<Option<TablesIndex>> {
             Ok(Some(indexed_tables))
         }
         Err(e) => {
-            println!(
+            warn!(
                 "Failed to parse index file, ignoring existing index. ({})",
                 e
             );
@@ -651,6 +1938,100 @@ fn requires_pinmame<S: AsRef<str>>(code: S) -> bool {
         .any(|line| line.contains("loadvpm") && !re.is_match(line))
 }
 
+/// FlexDMD is checked first as scripts using it often also create an UltraDMD through it
+fn detect_dmd_kind<S: AsRef<str>>(code: S, requires_pinmame: bool) -> Option<DmdKind> {
+    const FLEX_RE: &str = r#"(?i)createobject\s*\(\s*"flexdmd\.flexdmd""#;
+    const ULTRA_RE: &str = r#"(?i)createobject\s*\(\s*"ultradmd\.dmdobject""#;
+    let unified = unify_line_endings(code.as_ref());
+    let creates = |re: &str| {
+        let re = regex::Regex::new(re).unwrap();
+        unified
+            .lines()
+            .filter(|line| !line.trim().starts_with('\''))
+            .any(|line| re.is_match(line))
+    };
+    if creates(FLEX_RE) {
+        Some(DmdKind::FlexDmd)
+    } else if creates(ULTRA_RE) {
+        Some(DmdKind::UltraDmd)
+    } else if requires_pinmame {
+        Some(DmdKind::PinMame)
+    } else {
+        None
+    }
+}
+
+/// Files loaded through calls like `PlayMusic "track.mp3"`
+fn extract_referenced_files<S: AsRef<str>>(code: S) -> Vec<String> {
+    const RE: &str = r#"(?i)\bplaymusic\s*\(?\s*"([^"]+)""#;
+    let re = regex::Regex::new(RE).unwrap();
+    let unified = unify_line_endings(code.as_ref());
+    let mut files: Vec<String> = unified
+        .lines()
+        .filter(|line| !line.trim().starts_with('\''))
+        .flat_map(|line| {
+            re.captures_iter(line)
+                .map(|caps| caps[1].to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Components behind the ActiveX objects the script creates, PinMAME tables also need VPinMAME
+/// which is created by the shared vbs scripts. Objects that come with Windows are left out.
+fn extract_required_components<S: AsRef<str>>(code: S, requires_pinmame: bool) -> Vec<String> {
+    const RE: &str = r#"(?i)\bcreateobject\s*\(\s*"([^"]+)""#;
+    const BUILT_IN_PREFIXES: [&str; 7] = [
+        "scripting.",
+        "wscript.",
+        "adodb.",
+        "msxml2.",
+        "shell.",
+        "sapi.",
+        "vbscript.",
+    ];
+    let re = regex::Regex::new(RE).unwrap();
+    let unified = unify_line_endings(code.as_ref());
+    let mut components: Vec<String> = unified
+        .lines()
+        .filter(|line| !line.trim().starts_with('\''))
+        .flat_map(|line| {
+            re.captures_iter(line)
+                .map(|caps| caps[1].trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|prog_id| {
+            let lower = prog_id.to_lowercase();
+            !BUILT_IN_PREFIXES
+                .iter()
+                .any(|prefix| lower.starts_with(prefix))
+        })
+        .map(|prog_id| component_name(&prog_id))
+        .collect();
+    if requires_pinmame {
+        components.push("VPinMAME".to_string());
+    }
+    components.sort_by_key(|component| component.to_lowercase());
+    components.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    components
+}
+
+/// The name the component is installed under for well known objects, the ProgID otherwise
+fn component_name(prog_id: &str) -> String {
+    match prog_id.to_lowercase().as_str() {
+        "vpinmame.controller" => "VPinMAME",
+        "b2s.server" => "B2SBackglassServer",
+        "flexdmd.flexdmd" => "FlexDMD",
+        "ultradmd.dmdobject" => "UltraDMD",
+        "pinupplayer.pindisplay" => "PinUpPlayer",
+        _ => prog_id,
+    }
+    .to_string()
+}
+
 /// Some scripts contain only CR as line separator. Eg "Monte Carlo (Premier 1987) (10.7) 1.6.vpx"
 /// Therefore we replace first all CRLF and then all leftover CR with LF
 fn unify_line_endings(code: &str) -> String {
@@ -660,9 +2041,11 @@ fn unify_line_endings(code: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::VpxFixture;
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::io::Write;
+    use std::time::Duration;
     use testdir::testdir;
     use vpin::vpx;
 
@@ -726,7 +2109,7 @@ mod tests {
         assert_eq!(vpx_files.len(), 3);
         let global_roms = find_roms(&global_rom_dir)?;
         assert_eq!(global_roms.len(), 1);
-        let indexed_tables = index_vpx_files(&vpx_files, &global_roms, &VoidProgress);
+        let indexed_tables = index_vpx_files(&vpx_files, &global_roms, None);
         assert_eq!(indexed_tables.tables.len(), 3);
         let table1 = indexed_tables
             .tables
@@ -749,6 +2132,213 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_index_vpx_file_errors() -> io::Result<()> {
+        let test_dir = testdir!();
+        let not_vpx = test_dir.join("not_vpx.vpx");
+        fs::write(&not_vpx, "not an ole file")?;
+        let result = index_vpx_file(
+            &PathWithMetadata {
+                path: not_vpx.clone(),
+                last_modified: SystemTime::now(),
+                file_size: None,
+            },
+            &HashMap::new(),
+        );
+        match result {
+            Err(e @ IndexError::InvalidVpx { .. }) => assert_eq!(e.path(), Some(not_vpx.as_path())),
+            other => panic!("expected InvalidVpx, got {:?}", other.map(|(path, _)| path)),
+        }
+
+        let vpx_path = VpxFixture::new()
+            .file_name("broken_info.vpx")
+            .build_in(&test_dir)?;
+        let info_path = vpx_path.with_extension("info.json");
+        fs::write(&info_path, "{")?;
+        let result = index_vpx_file(
+            &PathWithMetadata {
+                path: vpx_path,
+                last_modified: SystemTime::now(),
+                file_size: None,
+            },
+            &HashMap::new(),
+        );
+        match result {
+            Err(e @ IndexError::Parse { .. }) => assert_eq!(e.path(), Some(info_path.as_path())),
+            other => panic!("expected Parse, got {:?}", other.map(|(path, _)| path)),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_file() -> io::Result<()> {
+        let test_dir = testdir!();
+        let vpx_path = VpxFixture::new()
+            .file_name("single.vpx")
+            .table_name("Single Table")
+            .build_in(&test_dir)?;
+        let table = index_file(&vpx_path, &HashMap::new())?;
+        assert_eq!(table.path, vpx_path);
+        assert_eq!(
+            table.table_info.table_name,
+            Some("Single Table".to_string())
+        );
+        let not_vpx = test_dir.join("single.vbs");
+        fs::write(&not_vpx, "Option Explicit")?;
+        assert!(matches!(
+            index_file(&not_vpx, &HashMap::new()),
+            Err(IndexError::NotVpxFile(path)) if path == not_vpx
+        ));
+        assert!(matches!(
+            index_file(&test_dir, &HashMap::new()),
+            Err(IndexError::NotVpxFile(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_vpx_file_fixture() -> io::Result<()> {
+        let test_dir = testdir!();
+        let vpx_path = VpxFixture::new()
+            .file_name("fixture.vpx")
+            .table_name("Fixture Table")
+            .script("Const cGameName = \"fixturerom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .wall("Wall1")
+            .image("playfield")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let indexed_tables = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        let table = indexed_tables
+            .tables
+            .get(&vpx_path)
+            .expect("fixture not indexed");
+        assert_eq!(
+            table.table_info.table_name,
+            Some("Fixture Table".to_string())
+        );
+        assert_eq!(table.game_name, Some("fixturerom".to_string()));
+        assert!(table.requires_pinmame);
+        assert_eq!(table.rom_path(), None);
+        assert_eq!(table.content_hash, Some(content_hash(&vpx_path)?));
+        let layout = table.layout.expect("layout");
+        assert_eq!(layout.playfield_width, 952.0);
+        assert_eq!(layout.playfield_height, 2162.0);
+        let physics = table.physics.expect("physics");
+        assert_eq!(physics.physics_set, None);
+        assert_eq!(physics.description(), "table physics, difficulty 20%");
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout_orientation() {
+        let layout = |fullscreen_rotation| TableLayout {
+            playfield_width: 1000.0,
+            playfield_height: 2000.0,
+            fullscreen_rotation,
+        };
+        assert_eq!(layout(0.0).orientation(), Orientation::Portrait);
+        assert_eq!(layout(180.0).orientation(), Orientation::Portrait);
+        assert_eq!(layout(270.0).orientation(), Orientation::Landscape);
+        assert_eq!(layout(-90.0).orientation(), Orientation::Landscape);
+        assert_eq!(layout(88.5).orientation(), Orientation::Landscape);
+        assert_eq!(layout(0.0).aspect_ratio(), 2.0);
+    }
+
+    #[test]
+    fn test_index_vpx_files_progress() -> io::Result<()> {
+        let test_dir = testdir!();
+        VpxFixture::new().file_name("a.vpx").build_in(&test_dir)?;
+        VpxFixture::new().file_name("b.vpx").build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let mut reported = Vec::new();
+        let mut callback = |progress: IndexProgress| {
+            reported.push((progress.position, progress.total, progress.path.to_owned()));
+        };
+        index_vpx_files(&vpx_files, &HashMap::new(), Some(&mut callback));
+        let expected = vpx_files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| ((i + 1) as u64, 2, file.path.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(reported, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_vpx_files_with_threads() -> io::Result<()> {
+        let test_dir = testdir!();
+        for name in ["a", "b", "c", "d", "e"] {
+            VpxFixture::new()
+                .file_name(format!("{}.vpx", name))
+                .build_in(&test_dir)?;
+        }
+        std::fs::write(test_dir.join("broken.vpx"), "not a vpx file")?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let mut positions = Vec::new();
+        let mut failed = Vec::new();
+        let mut callback = |progress: IndexProgress| {
+            positions.push(progress.position);
+            if progress.error.is_some() {
+                assert!(progress.table.is_none());
+                failed.push(progress.path.to_owned());
+            }
+        };
+        let parallel =
+            index_vpx_files_with_threads(&vpx_files, &HashMap::new(), 3, Some(&mut callback));
+        assert_eq!(positions, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(failed, vec![test_dir.join("broken.vpx")]);
+        assert_eq!(parallel, index_vpx_files(&vpx_files, &HashMap::new(), None));
+        assert_eq!(parallel.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_changed_vpx_files() -> io::Result<()> {
+        let test_dir = testdir!();
+        let same = VpxFixture::new()
+            .file_name("same.vpx")
+            .build_in(&test_dir)?;
+        let changed = VpxFixture::new()
+            .file_name("changed.vpx")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let known_hashes = HashMap::from([
+            (same.clone(), content_hash(&same)?),
+            (changed.clone(), "outdated".to_string()),
+        ]);
+        let mut parsed = Vec::new();
+        let (index, unchanged) = index_changed_vpx_files(
+            &vpx_files,
+            &known_hashes,
+            &HashMap::new(),
+            2,
+            Some(&mut |progress: IndexProgress| parsed.push(progress.path.to_owned())),
+        );
+        assert_eq!(parsed, vec![changed.clone()]);
+        assert_eq!(
+            unchanged.iter().map(|file| &file.path).collect::<Vec<_>>(),
+            vec![&same]
+        );
+        assert_eq!(
+            index.tables.get(&changed).unwrap().content_hash,
+            Some(content_hash(&changed)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_parallelism_threads() {
+        let folder = Path::new("/tables");
+        assert_eq!(IndexParallelism::Serial.threads(folder, Some(8)), 1);
+        assert_eq!(IndexParallelism::Full.threads(folder, Some(8)), 8);
+        assert_eq!(IndexParallelism::Full.threads(folder, Some(0)), 1);
+        // the disk kind of a folder that doesn't exist is unknown
+        assert_eq!(
+            IndexParallelism::Auto.threads(Path::new("/does/not/exist"), Some(8)),
+            8
+        );
+    }
+
     fn test_script(temp_dir: &Path, game_name: &str) -> io::Result<PathBuf> {
         // write simple script in tempdir
         let script = format!(
@@ -804,6 +2394,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_index_concurrently() -> io::Result<()> {
+        let test_dir = testdir!();
+        let index_path = test_dir.join("test.json");
+        std::fs::write(&index_path, "not an index")?;
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        write_index_json(&TablesIndex::empty(), &index_path).unwrap();
+                    }
+                });
+            }
+            // readers never see a half-written index
+            for _ in 0..20 {
+                if let Ok(content) = std::fs::read_to_string(&index_path)
+                    && content != "not an index"
+                {
+                    assert!(serde_json::from_str::<TablesIndexJson>(&content).is_ok());
+                }
+            }
+        });
+        assert_eq!(read_index_json(&index_path)?, Some(TablesIndex::empty()));
+        // no temporary files are left behind
+        assert_eq!(std::fs::read_dir(&test_dir)?.count(), 1);
+        Ok(())
+    }
+
     #[test]
     fn test_write_read_single_item_index() -> io::Result<()> {
         let mut index = TablesIndex::empty();
@@ -828,8 +2446,38 @@ mod tests {
             rom_path: Some(PathBuf::from("testrom.zip")),
             local_rom_path: None,
             wheel_path: Some(PathBuf::from("test.png")),
+            nvram_path: Some(PathBuf::from("test.nv")),
             requires_pinmame: true,
+            dmd_kind: Some(DmdKind::PinMame),
+            layout: Some(TableLayout {
+                playfield_width: 952.0,
+                playfield_height: 2162.0,
+                fullscreen_rotation: 270.0,
+            }),
+            physics: Some(TablePhysics {
+                physics_set: Some(3),
+                difficulty: 0.5,
+            }),
             last_modified: IsoSystemTime(SystemTime::UNIX_EPOCH),
+            created: Some(IsoSystemTime(SystemTime::UNIX_EPOCH)),
+            content_hash: Some("abc123".to_string()),
+            file_size: Some(1024),
+            referenced_files: vec!["music.mp3".to_string()],
+            required_components: vec!["VPinMAME".to_string()],
+            ini_settings: Some(BTreeMap::from([(
+                "Player.PlayfieldWidth".to_string(),
+                "1920".to_string(),
+            )])),
+            archived: false,
+            staged: false,
+            suppressed_warnings: vec![],
+            tags: vec![],
+            rating: Some(3),
+            play_time_secs: Some(4200),
+            sidecars_modified: SidecarTimes {
+                notes: Some(IsoSystemTime(SystemTime::UNIX_EPOCH)),
+                ini: None,
+            },
         });
         let test_dir = testdir!();
         let index_path = test_dir.join("test.json");
@@ -839,6 +2487,158 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_detect_dmd_kind() {
+        let flex = r#"Set FlexDMD = CreateObject("FlexDMD.FlexDMD")
+Set UltraDMD = FlexDMD.NewUltraDMD()
+Set UltraDMD2 = CreateObject("UltraDMD.DMDObject")"#;
+        assert_eq!(detect_dmd_kind(flex, false), Some(DmdKind::FlexDmd));
+        let ultra = "Set UltraDMD = CreateObject ( \"ultradmd.dmdobject\" )";
+        assert_eq!(detect_dmd_kind(ultra, true), Some(DmdKind::UltraDmd));
+        let commented = "' Set FlexDMD = CreateObject(\"FlexDMD.FlexDMD\")";
+        assert_eq!(detect_dmd_kind(commented, true), Some(DmdKind::PinMame));
+        assert_eq!(detect_dmd_kind(commented, false), None);
+    }
+
+    #[test]
+    fn test_extract_referenced_files() {
+        let script = r#"
+' PlayMusic "commented.mp3"
+Sub Table1_Init
+    PlayMusic "theme.mp3"
+    PlayMusic("sub\intro.ogg") : PlayMusic "theme.mp3"
+End Sub
+"#;
+        assert_eq!(
+            extract_referenced_files(script),
+            vec!["sub\\intro.ogg".to_string(), "theme.mp3".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_extract_required_components() {
+        let script = r#"
+            Set Controller = CreateObject("B2S.Server")
+            Set FSO = CreateObject("Scripting.FileSystemObject")
+            ' Set DMD = CreateObject("UltraDMD.DMDObject")
+            Set FlexDMD = CreateObject ( "FlexDMD.FlexDMD" )
+            Set PuP = CreateObject("PinUpPlayer.PinDisplay")
+            Set Other = CreateObject("B2S.server")
+        "#;
+        assert_eq!(
+            extract_required_components(script, true),
+            vec!["B2SBackglassServer", "FlexDMD", "PinUpPlayer", "VPinMAME"]
+        );
+        assert!(extract_required_components("Option Explicit", false).is_empty());
+    }
+
+    #[test]
+    fn test_warnings_missing_referenced_files() -> io::Result<()> {
+        let test_dir = testdir!();
+        fs::create_dir(test_dir.join("music"))?;
+        File::create(test_dir.join("music").join("present.mp3"))?;
+        let vpx_path = VpxFixture::new()
+            .script("PlayMusic \"present.mp3\"\nPlayMusic \"missing.mp3\"")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let indexed_tables = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        let table = indexed_tables.tables.get(&vpx_path).unwrap();
+        assert_eq!(table.missing_referenced_files(), vec!["missing.mp3"]);
+        assert_eq!(
+            table.warnings(),
+            vec!["Referenced file not found: missing.mp3".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_sidecar_files() -> io::Result<()> {
+        let test_dir = testdir!();
+        let vpx_path = test_dir.join("Table.vpx");
+        File::create(&vpx_path)?;
+        File::create(test_dir.join("Table.vbs"))?;
+        File::create(test_dir.join("Table.info.json"))?;
+        File::create(test_dir.join(".Table.ini"))?;
+        File::create(test_dir.join("Table.cfg"))?;
+        File::create(test_dir.join("Other.vbs"))?;
+
+        assert_eq!(
+            find_sidecar_files(&vpx_path, &DEFAULT_SIDECAR_EXTENSIONS, false),
+            vec![test_dir.join("Table.vbs"), test_dir.join("Table.info.json")]
+        );
+        let mut extensions = DEFAULT_SIDECAR_EXTENSIONS.map(String::from).to_vec();
+        extensions.push(".cfg".to_string());
+        assert_eq!(
+            find_sidecar_files(&vpx_path, &extensions, true),
+            vec![
+                test_dir.join("Table.vbs"),
+                test_dir.join(".Table.ini"),
+                test_dir.join("Table.info.json"),
+                test_dir.join("Table.cfg")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let base = Path::new("/tables");
+        assert_eq!(
+            relative_path(Path::new("/tables/em/Table.vpx"), Some(base)),
+            Path::new("em/Table.vpx")
+        );
+        assert_eq!(
+            relative_path(Path::new("/roms/afm_113b.zip"), Some(base)),
+            Path::new("/roms/afm_113b.zip")
+        );
+        assert_eq!(
+            relative_path(Path::new("/tables/Table.vpx"), None),
+            Path::new("/tables/Table.vpx")
+        );
+    }
+
+    #[test]
+    fn test_find_orphaned_sidecars() -> io::Result<()> {
+        let test_dir = testdir!();
+        fs::create_dir_all(test_dir.join("em"))?;
+        File::create(test_dir.join("Table.vpx"))?;
+        File::create(test_dir.join("Table.info.json"))?;
+        File::create(test_dir.join(".Table.ini"))?;
+        File::create(test_dir.join("Gone.directb2s"))?;
+        File::create(test_dir.join(".Gone.ini"))?;
+        File::create(test_dir.join("Gone.png"))?;
+        File::create(test_dir.join("em").join("Table.vbs"))?;
+
+        assert_eq!(
+            find_orphaned_sidecars(&test_dir, &DEFAULT_SIDECAR_EXTENSIONS, false)?,
+            vec![
+                test_dir.join("Gone.directb2s"),
+                test_dir.join("em").join("Table.vbs")
+            ]
+        );
+        assert_eq!(
+            find_orphaned_sidecars(&test_dir, &DEFAULT_SIDECAR_EXTENSIONS, true)?,
+            vec![
+                test_dir.join(".Gone.ini"),
+                test_dir.join("Gone.directb2s"),
+                test_dir.join("em").join("Table.vbs")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_content_hash() -> io::Result<()> {
+        let test_dir = testdir!();
+        let path = test_dir.join("hello.txt");
+        fs::write(&path, "hello")?;
+        assert_eq!(
+            content_hash(&path)?,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_read_index_missing() -> io::Result<()> {
         let index_path = PathBuf::from("missing_index_file.json");
@@ -1024,4 +2824,509 @@ LoadVPM "01210000","sys80.vbs",3.10
             "LoadVPM \"01210000\", \"sys80.VBS\", 3.1\r\r'Sub LoadVPM(VPMver, VBSfile, VBSver)\r";
         assert!(requires_pinmame(code));
     }
+
+    fn write_test_zip(path: &Path) -> io::Result<()> {
+        let mut writer = zip::ZipWriter::new(File::create(path)?);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("rom.bin", options)?;
+        writer.write_all(b"some rom content")?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_rom_zip_ok() -> io::Result<()> {
+        let test_dir = testdir!();
+        let rom_path = test_dir.join("testrom.zip");
+        write_test_zip(&rom_path)?;
+        assert_eq!(verify_rom_zip(&rom_path)?, RomIntegrity::Ok { entries: 1 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_rom_zip_corrupt_content() -> io::Result<()> {
+        let test_dir = testdir!();
+        let rom_path = test_dir.join("testrom.zip");
+        write_test_zip(&rom_path)?;
+        let mut bytes = fs::read(&rom_path)?;
+        let content_offset = bytes
+            .windows(4)
+            .position(|w| w == b"some")
+            .expect("content not found");
+        bytes[content_offset] = b'S';
+        fs::write(&rom_path, bytes)?;
+        assert!(matches!(
+            verify_rom_zip(&rom_path)?,
+            RomIntegrity::Corrupt(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_rom_zip_truncated() -> io::Result<()> {
+        let test_dir = testdir!();
+        let rom_path = test_dir.join("testrom.zip");
+        write_test_zip(&rom_path)?;
+        let bytes = fs::read(&rom_path)?;
+        fs::write(&rom_path, &bytes[..bytes.len() / 2])?;
+        assert!(matches!(
+            verify_rom_zip(&rom_path)?,
+            RomIntegrity::Corrupt(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_any_pattern() {
+        let patterns = DEFAULT_BACKUP_PATTERNS;
+        assert!(matches_any_pattern("Table.vpx.bak", &patterns));
+        assert!(matches_any_pattern("Table-Backup.VPX", &patterns));
+        assert!(matches_any_pattern("Table-autosave3.vpx", &patterns));
+        assert!(!matches_any_pattern("Table.vpx", &patterns));
+        assert!(!matches_any_pattern("Backup Table.vpx", &patterns));
+    }
+
+    #[test]
+    fn test_find_backup_files() -> io::Result<()> {
+        let test_dir = testdir!();
+        let vpx_path = test_dir.join("Table.vpx");
+        File::create(&vpx_path)?;
+        File::create(test_dir.join("Table.vpx.bak"))?;
+        File::create(test_dir.join("Table-backup.vpx"))?;
+        File::create(test_dir.join("Other-backup.vpx"))?;
+        let backups = find_backup_files(&vpx_path, &DEFAULT_BACKUP_PATTERNS);
+        assert_eq!(
+            backups,
+            vec![
+                test_dir.join("Table-backup.vpx"),
+                test_dir.join("Table.vpx.bak"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_archived_vpx_file() -> io::Result<()> {
+        let test_dir = testdir!();
+        let source_dir = test_dir.join("source");
+        fs::create_dir(&source_dir)?;
+        let source_vpx = VpxFixture::new()
+            .file_name("Fixture.vpx")
+            .table_name("Archived Table")
+            .script("Const cGameName = \"fixturerom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .build_in(&source_dir)?;
+        let zip_path = test_dir.join("Bundle.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&zip_path)?);
+        writer.start_file(
+            "tables/Fixture.vpx",
+            zip::write::SimpleFileOptions::default(),
+        )?;
+        writer.write_all(&fs::read(&source_vpx)?)?;
+        writer.finish()?;
+
+        let archived = find_archived_vpx_files(false, &test_dir)?;
+        let vpx_path = zip_path.join("tables").join("Fixture.vpx");
+        assert_eq!(
+            archived.iter().map(|f| &f.path).collect::<Vec<_>>(),
+            vec![&vpx_path]
+        );
+        assert_eq!(archive_path(&vpx_path), Some(zip_path.as_path()));
+
+        let indexed_tables = index_vpx_files(&archived, &HashMap::new(), None);
+        let table = indexed_tables
+            .tables
+            .get(&vpx_path)
+            .expect("archived table not indexed");
+        assert!(table.archived);
+        assert_eq!(
+            table.table_info.table_name,
+            Some("Archived Table".to_string())
+        );
+        assert_eq!(table.game_name, Some("fixturerom".to_string()));
+        assert_eq!(table.content_hash, Some(content_hash(&source_vpx)?));
+
+        assert_eq!(extracted_vpx_path(&vpx_path)?, test_dir.join("Fixture.vpx"));
+        let extracted = extract_archived_vpx(&vpx_path)?;
+        assert_eq!(extracted, test_dir.join("Fixture.vpx"));
+        assert_eq!(fs::read(&extracted)?, fs::read(&source_vpx)?);
+        let again = extract_archived_vpx(&vpx_path);
+        assert_eq!(
+            again.map_err(|e| e.kind()),
+            Err(io::ErrorKind::AlreadyExists)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_path_plain_file() {
+        assert_eq!(archive_path(Path::new("/tables/Table.vpx")), None);
+    }
+
+    #[test]
+    fn test_relink_roms() -> io::Result<()> {
+        let test_dir = testdir!();
+        VpxFixture::new()
+            .file_name("fixture.vpx")
+            .script("Const cGameName = \"fixturerom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let mut index = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        assert_eq!(
+            index.tables()[0].warnings(),
+            vec!["Rom not found: fixturerom".to_string()]
+        );
+
+        let rom_path = PathBuf::from("/roms/fixturerom.zip");
+        let roms = HashMap::from([("fixturerom".to_string(), rom_path.clone())]);
+        assert_eq!(index.relink_roms(&roms), 1);
+        assert_eq!(index.tables()[0].rom_path(), Some(&rom_path));
+        assert!(index.tables()[0].warnings().is_empty());
+        assert_eq!(index.relink_roms(&roms), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_relink_wheels() -> io::Result<()> {
+        let test_dir = testdir!();
+        VpxFixture::new()
+            .file_name("fixture.vpx")
+            .script("Const cGameName = \"fixturerom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let mut index = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        assert_eq!(index.tables()[0].wheel_path, None);
+
+        let wheel_folder = test_dir.join("wheels-elsewhere");
+        fs::create_dir(&wheel_folder)?;
+        let wheel_path = wheel_folder.join("FixtureRom.PNG");
+        File::create(&wheel_path)?;
+        File::create(wheel_folder.join("other.png"))?;
+        let wheels = find_wheels(&wheel_folder)?;
+        assert_eq!(wheels.len(), 2);
+        assert_eq!(index.relink_wheels(&wheels), 1);
+        assert_eq!(index.tables()[0].wheel_path, Some(wheel_path.clone()));
+        assert_eq!(index.relink_wheels(&wheels), 0);
+
+        fs::remove_file(&wheel_path)?;
+        assert_eq!(index.relink_wheels(&find_wheels(&wheel_folder)?), 1);
+        assert_eq!(index.tables()[0].wheel_path, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_relink_nvram() -> io::Result<()> {
+        let test_dir = testdir!();
+        let nvram_folder = test_dir.join("nvram");
+        fs::create_dir(&nvram_folder)?;
+        VpxFixture::new()
+            .file_name("afm.vpx")
+            .script("Const cGameName = \"afm_113b\"\nLoadVPM \"01000200\", \"WPC.VBS\", 3.10")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let mut index = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        assert_eq!(index.relink_nvrams(&find_nvrams(&nvram_folder)?), 0);
+        assert_eq!(index.tables()[0].nvram_path, None);
+
+        let nvram_path = nvram_folder.join("AFM_113b.nv");
+        fs::write(&nvram_path, [0u8; 4])?;
+        assert_eq!(index.relink_nvrams(&find_nvrams(&nvram_folder)?), 1);
+        assert_eq!(index.tables()[0].nvram_path, Some(nvram_path));
+
+        // the one in the table folder takes precedence
+        let local_nvram_folder = test_dir.join("pinmame").join("nvram");
+        fs::create_dir_all(&local_nvram_folder)?;
+        let local_nvram_path = local_nvram_folder.join("afm_113b.nv");
+        fs::write(&local_nvram_path, [0u8; 4])?;
+        assert_eq!(index.relink_nvrams(&find_nvrams(&nvram_folder)?), 1);
+        assert_eq!(index.tables()[0].nvram_path, Some(local_nvram_path));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_folder_incremental() -> io::Result<()> {
+        let test_dir = testdir!();
+        let tables_folder = test_dir.join("tables");
+        fs::create_dir_all(&tables_folder)?;
+        let vpx_path = VpxFixture::new()
+            .file_name("table.vpx")
+            .table_name("Before")
+            .build_in(&tables_folder)?;
+        let index_path = test_dir.join("index.json");
+        let index = || {
+            index_folder(
+                &tables_folder,
+                &index_path,
+                &IndexOptions {
+                    recursive: false,
+                    ..IndexOptions::default()
+                },
+                None,
+                vec![],
+            )
+        };
+        index()?;
+        let written = last_modified(&index_path)?;
+
+        // nothing changed, the index is not written again
+        index()?;
+        assert_eq!(last_modified(&index_path)?, written);
+
+        // only touched, the content hash shows it is the same table
+        let touched = written + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&vpx_path)?
+            .set_modified(touched)?;
+        let mut parsed = 0;
+        let index_touched = index_folder(
+            &tables_folder,
+            &index_path,
+            &IndexOptions {
+                recursive: false,
+                ..IndexOptions::default()
+            },
+            Some(&mut |_| parsed += 1),
+            vec![],
+        )?;
+        assert_eq!(parsed, 0);
+        assert_eq!(
+            SystemTime::from(index_touched.tables.get(&vpx_path).unwrap().last_modified),
+            touched
+        );
+
+        // a new version with the old modification time is found by its size
+        let table_modified = last_modified(&vpx_path)?;
+        VpxFixture::new()
+            .file_name("table.vpx")
+            .table_name("After")
+            .script("' a longer script\n".repeat(2000))
+            .build_in(&tables_folder)?;
+        File::options()
+            .write(true)
+            .open(&vpx_path)?
+            .set_modified(table_modified)?;
+        let index = index()?;
+        assert_eq!(
+            index.tables.get(&vpx_path).unwrap().table_info.table_name,
+            Some("After".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_folder_sidecars_changed() -> io::Result<()> {
+        let test_dir = testdir!();
+        let tables_folder = test_dir.join("tables");
+        fs::create_dir_all(&tables_folder)?;
+        let vpx_path = VpxFixture::new()
+            .file_name("table.vpx")
+            .build_in(&tables_folder)?;
+        let index_path = test_dir.join("index.json");
+        let index = || {
+            index_folder(
+                &tables_folder,
+                &index_path,
+                &IndexOptions {
+                    recursive: false,
+                    ..IndexOptions::default()
+                },
+                None,
+                vec![],
+            )
+        };
+        index()?;
+
+        crate::notes::write_notes(
+            &vpx_path,
+            &crate::notes::TableNotes {
+                tags: vec!["kids".to_string()],
+                ..Default::default()
+            },
+        )?;
+        fs::write(ini_path_for(&vpx_path), "[Player]\nPlayfieldWidth = 1920\n")?;
+        let indexed = index()?;
+        let table = indexed.tables.get(&vpx_path).unwrap();
+        assert_eq!(table.tags, vec!["kids".to_string()]);
+        assert_eq!(
+            table
+                .ini_settings
+                .as_ref()
+                .and_then(|settings| settings.get("Player.PlayfieldWidth")),
+            Some(&"1920".to_string())
+        );
+
+        // any other modification time counts, like for an ini restored from a backup
+        fs::write(ini_path_for(&vpx_path), "[Player]\nPlayfieldWidth = 2560\n")?;
+        File::options()
+            .write(true)
+            .open(ini_path_for(&vpx_path))?
+            .set_modified(SystemTime::UNIX_EPOCH)?;
+        let indexed = index()?;
+        assert_eq!(
+            indexed
+                .tables
+                .get(&vpx_path)
+                .unwrap()
+                .ini_settings
+                .as_ref()
+                .and_then(|settings| settings.get("Player.PlayfieldWidth")),
+            Some(&"2560".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_folder_with_staging() -> io::Result<()> {
+        let test_dir = testdir!();
+        let tables_folder = test_dir.join("tables");
+        let staging_folder = test_dir.join("staging");
+        fs::create_dir_all(&tables_folder)?;
+        fs::create_dir_all(&staging_folder)?;
+        let library_path = VpxFixture::new()
+            .file_name("library.vpx")
+            .build_in(&tables_folder)?;
+        let staged_path = VpxFixture::new()
+            .file_name("staged.vpx")
+            .build_in(&staging_folder)?;
+        let index_path = test_dir.join("index.json");
+        let index_with = |staging: Option<&Path>| {
+            index_folder(
+                &tables_folder,
+                &index_path,
+                &IndexOptions {
+                    recursive: false,
+                    staging_folder: staging.map(Path::to_path_buf),
+                    ..IndexOptions::default()
+                },
+                None,
+                vec![],
+            )
+        };
+
+        let index = index_with(Some(&staging_folder))?;
+        assert_eq!(index.len(), 2);
+        assert!(!index.tables.get(&library_path).unwrap().staged);
+        assert!(index.tables.get(&staged_path).unwrap().staged);
+
+        // without staging folder the staged tables are dropped
+        let index = index_with(None)?;
+        assert_eq!(index.len(), 1);
+        assert!(index.tables.contains_key(&library_path));
+        Ok(())
+    }
+
+    #[test]
+    fn test_warnings_ignoring() -> io::Result<()> {
+        let test_dir = testdir!();
+        VpxFixture::new()
+            .file_name("fixture.vpx")
+            .script("Const cGameName = \"fixturerom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let index = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        let table = &index.tables()[0];
+        assert_eq!(
+            table.warnings(),
+            vec!["Rom not found: fixturerom".to_string()]
+        );
+        assert!(
+            table
+                .warnings_ignoring(&[WarningKind::MissingRom], &[], &ChdRequirements::default())
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_chd() -> io::Result<()> {
+        let test_dir = testdir!();
+        VpxFixture::new()
+            .file_name("fixture.vpx")
+            .script("Const cGameName = \"FixtureRom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .build_in(&test_dir)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let index = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        let table = &index.tables()[0];
+        let chd_folder = test_dir.join("roms");
+        let mut chds = ChdRequirements {
+            games: vec![],
+            folder: chd_folder.clone(),
+        };
+        // only configured roms need one
+        assert!(!table.missing_chd(&chds));
+        chds.games = vec!["fixturerom".to_string()];
+        assert!(table.missing_chd(&chds));
+        assert_eq!(
+            table.warnings_ignoring(&[WarningKind::MissingRom], &[], &chds),
+            vec!["CHD not found: FixtureRom".to_string()]
+        );
+        fs::create_dir_all(chd_folder.join("fixturerom"))?;
+        File::create(chd_folder.join("fixturerom").join("notes.txt"))?;
+        assert!(table.missing_chd(&chds));
+        File::create(chd_folder.join("fixturerom").join("disk.CHD"))?;
+        assert!(!table.missing_chd(&chds));
+
+        let local_roms = test_dir.join("pinmame").join("roms").join("fixturerom");
+        fs::create_dir_all(&local_roms)?;
+        File::create(local_roms.join("disk.chd"))?;
+        chds.folder = test_dir.join("elsewhere");
+        assert!(!table.missing_chd(&chds));
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_vpx_files_extension_case() -> io::Result<()> {
+        let test_dir = testdir!();
+        fs::create_dir(test_dir.join("subdir"))?;
+        File::create(test_dir.join("upper.VPX"))?;
+        File::create(test_dir.join("subdir").join("mixed.Vpx"))?;
+        File::create(test_dir.join("other.vpx.bak"))?;
+
+        let mut paths = find_vpx_files(true, &test_dir)?
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                test_dir.join("subdir").join("mixed.Vpx"),
+                test_dir.join("upper.VPX"),
+            ]
+        );
+        assert_eq!(find_vpx_files(false, &test_dir)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_suppressed_warnings() -> io::Result<()> {
+        let test_dir = testdir!();
+        let vpx_path = VpxFixture::new()
+            .file_name("fixture.vpx")
+            .script("Const cGameName = \"fixturerom\"\nLoadVPM \"01000200\", \"S11.VBS\", 3.10")
+            .build_in(&test_dir)?;
+        let notes = crate::notes::TableNotes {
+            suppressed_warnings: vec![WarningKind::MissingRom],
+            tags: vec!["kids".to_string()],
+            rating: Some(5),
+            play_time_secs: Some(600),
+        };
+        crate::notes::write_notes(&vpx_path, &notes)?;
+        let vpx_files = find_vpx_files(false, &test_dir)?;
+        let index = index_vpx_files(&vpx_files, &HashMap::new(), None);
+        let table = &index.tables()[0];
+        assert_eq!(table.tags, vec!["kids".to_string()]);
+        assert_eq!(table.rating, Some(5));
+        assert_eq!(table.play_time_secs, Some(600));
+        assert!(table.warnings().is_empty());
+        assert_eq!(
+            table.all_warnings(&[], &ChdRequirements::default()),
+            vec![(
+                WarningKind::MissingRom,
+                "Rom not found: fixturerom".to_string()
+            )]
+        );
+        Ok(())
+    }
 }
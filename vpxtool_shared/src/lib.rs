@@ -1,3 +1,24 @@
+pub mod changelog;
+pub mod completeness;
 pub mod config;
+pub mod disk;
+pub mod display;
+pub mod file_name_tags;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+pub mod inbox;
+pub mod index;
+pub mod index_diff;
 pub mod indexer;
+pub mod notes;
+pub mod reference;
+pub mod rom_audit;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod staging;
+pub mod stats;
+pub mod table_ini;
+pub mod tags_export;
 pub mod vpinball_config;
+pub mod vps;
+pub mod watch;
@@ -0,0 +1,127 @@
+//! Personal notes about a table, kept next to it in a `<table>.notes.json` sidecar file.
+use crate::indexer::WarningKind;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct TableNotes {
+    /// Warnings that are known to be false positives for this table
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed_warnings: Vec<WarningKind>,
+    /// Lowercase labels like `wip` or `kids`, see [parse_tags]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 1 to [MAX_RATING] stars, `None` for unrated tables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Total seconds the table was played from the frontend, `None` for tables never played
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub play_time_secs: Option<u64>,
+}
+
+impl TableNotes {
+    pub fn is_empty(&self) -> bool {
+        self.suppressed_warnings.is_empty()
+            && self.tags.is_empty()
+            && self.rating.is_none()
+            && self.play_time_secs.is_none()
+    }
+}
+
+pub const MAX_RATING: u8 = 5;
+
+/// Splits user input on whitespace and commas into unique lowercase tags
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(|c: char| c.is_whitespace() || c == ',') {
+        let tag = tag.trim_start_matches(['+', '#']).to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+pub fn notes_path_for(vpx_path: &Path) -> PathBuf {
+    vpx_path.with_extension("notes.json")
+}
+
+/// Reads the notes for the table, empty notes if there is no notes file
+pub fn read_notes(vpx_path: &Path) -> io::Result<TableNotes> {
+    let notes_path = notes_path_for(vpx_path);
+    if !notes_path.exists() {
+        return Ok(TableNotes::default());
+    }
+    let file = File::open(&notes_path)?;
+    serde_json::from_reader(file).map_err(|e| {
+        io::Error::other(format!(
+            "Failed to parse/read json {}: {}",
+            notes_path.display(),
+            e
+        ))
+    })
+}
+
+/// Writes the notes for the table, empty notes remove the notes file
+pub fn write_notes(vpx_path: &Path, notes: &TableNotes) -> io::Result<()> {
+    let notes_path = notes_path_for(vpx_path);
+    if notes.is_empty() {
+        if notes_path.exists() {
+            std::fs::remove_file(&notes_path)?;
+        }
+        return Ok(());
+    }
+    let file = File::create(&notes_path)?;
+    serde_json::to_writer_pretty(file, notes)?;
+    Ok(())
+}
+
+/// Adds a play session to the total play time of the table
+pub fn add_play_time(vpx_path: &Path, seconds: u64) -> io::Result<()> {
+    let mut notes = read_notes(vpx_path)?;
+    notes.play_time_secs = Some(notes.play_time_secs.unwrap_or(0) + seconds);
+    write_notes(vpx_path, &notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use testdir::testdir;
+
+    #[test]
+    fn test_write_read_notes() -> io::Result<()> {
+        let test_dir = testdir!();
+        let vpx_path = test_dir.join("Table.vpx");
+        assert_eq!(read_notes(&vpx_path)?, TableNotes::default());
+
+        let notes = TableNotes {
+            suppressed_warnings: vec![WarningKind::MissingRom],
+            tags: vec!["wip".to_string()],
+            rating: Some(4),
+            play_time_secs: None,
+        };
+        write_notes(&vpx_path, &notes)?;
+        assert!(test_dir.join("Table.notes.json").exists());
+        assert_eq!(read_notes(&vpx_path)?, notes);
+
+        add_play_time(&vpx_path, 90)?;
+        add_play_time(&vpx_path, 30)?;
+        assert_eq!(read_notes(&vpx_path)?.play_time_secs, Some(120));
+
+        write_notes(&vpx_path, &TableNotes::default())?;
+        assert!(!test_dir.join("Table.notes.json").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" Kids, wip +broken kids "),
+            vec!["kids", "wip", "broken"]
+        );
+        assert!(parse_tags(" , ").is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! Checks tables against a list of trusted content hashes.
+//!
+//! The list uses the `sha256sum` output format, one `<hash>  <file name>` per line. Lines starting
+//! with `#` are ignored. A file name can be listed more than once to trust several builds.
+use crate::indexer::IndexedTable;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum HashCheck {
+    /// The table hash is one of the trusted hashes for its file name
+    Matched,
+    /// The file name is not in the reference list
+    Unknown,
+    /// The file name is in the reference list with a different hash
+    Mismatch,
+}
+
+impl HashCheck {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HashCheck::Matched => "MATCHED",
+            HashCheck::Unknown => "UNKNOWN",
+            HashCheck::Mismatch => "MISMATCH",
+        }
+    }
+
+    /// Shown as table warning, `None` for matched tables
+    pub fn warning(&self) -> Option<&'static str> {
+        match self {
+            HashCheck::Matched => None,
+            HashCheck::Unknown => Some("Not in the reference hash list"),
+            HashCheck::Mismatch => Some("Content hash does not match the reference"),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct ReferenceHashes {
+    /// lowercase file name to lowercase hashes
+    hashes: HashMap<String, Vec<String>>,
+}
+
+impl ReferenceHashes {
+    pub fn parse(source: &str) -> Result<ReferenceHashes, String> {
+        let mut hashes: HashMap<String, Vec<String>> = HashMap::new();
+        for (nr, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((hash, file_name)) = line.split_once(char::is_whitespace) else {
+                return Err(format!("Line {}: expected <hash> <file name>", nr + 1));
+            };
+            // sha256sum marks binary mode with a leading '*'
+            let file_name = file_name.trim().trim_start_matches('*');
+            // the list might contain paths, only the file name is used
+            let file_name = Path::new(file_name)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .ok_or_else(|| format!("Line {}: missing file name", nr + 1))?;
+            hashes
+                .entry(file_name)
+                .or_default()
+                .push(hash.to_lowercase());
+        }
+        Ok(ReferenceHashes { hashes })
+    }
+
+    pub fn read(path: &Path) -> io::Result<ReferenceHashes> {
+        let source = std::fs::read_to_string(path)?;
+        ReferenceHashes::parse(&source).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid reference hash list {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn check(&self, table: &IndexedTable) -> HashCheck {
+        let file_name = table
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match self.hashes.get(&file_name) {
+            None => HashCheck::Unknown,
+            Some(hashes) => match &table.content_hash {
+                Some(hash) if hashes.contains(&hash.to_lowercase()) => HashCheck::Matched,
+                _ => HashCheck::Mismatch,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn table(path: &str, content_hash: &str) -> IndexedTable {
+        serde_json::from_value(json!({
+            "path": path,
+            "table_info": {"properties": {}},
+            "requires_pinmame": false,
+            "last_modified": "1970-01-01T00:00:00+00:00",
+            "content_hash": content_hash
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let reference = ReferenceHashes::parse(
+            "# trusted builds\naaa  Attack from Mars (Bally 1995).vpx\nBBB *./tables/Medieval Madness.vpx\nccc  Medieval Madness.vpx\n",
+        )
+        .unwrap();
+        let check = |path, hash| reference.check(&table(path, hash));
+        assert_eq!(
+            check("/t/Attack from Mars (Bally 1995).vpx", "aaa"),
+            HashCheck::Matched
+        );
+        assert_eq!(check("/t/medieval madness.vpx", "bbb"), HashCheck::Matched);
+        assert_eq!(check("/t/Medieval Madness.vpx", "ccc"), HashCheck::Matched);
+        assert_eq!(check("/t/Medieval Madness.vpx", "ddd"), HashCheck::Mismatch);
+        assert_eq!(check("/t/Other.vpx", "aaa"), HashCheck::Unknown);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            ReferenceHashes::parse("aaa"),
+            Err("Line 1: expected <hash> <file name>".to_string())
+        );
+    }
+}
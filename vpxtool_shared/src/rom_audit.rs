@@ -0,0 +1,188 @@
+//! Checks that rom zips contain all the files a game needs.
+//!
+//! A rom zip can be present but still miss files, the game then fails to start in PinMAME. The
+//! manifest lists the required files per rom, one `<rom name> <file name>...` per line. Lines
+//! starting with `#` are ignored and a rom can be listed on several lines. Files ending in `.chd`
+//! are not part of the zip, they are expected in a folder named after the rom next to the zip.
+use crate::indexer::IndexedTable;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum RomAudit {
+    /// All files listed for the rom are present
+    Complete,
+    /// The listed files that are missing
+    Incomplete(Vec<String>),
+    /// The rom is not in the manifest
+    Unlisted,
+    /// The rom zip can't be read
+    Unreadable(String),
+}
+
+impl RomAudit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RomAudit::Complete => "COMPLETE",
+            RomAudit::Incomplete(_) => "INCOMPLETE",
+            RomAudit::Unlisted => "UNLISTED",
+            RomAudit::Unreadable(_) => "UNREADABLE",
+        }
+    }
+
+    /// Shown as table warning, `None` for complete or unlisted roms
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            RomAudit::Complete | RomAudit::Unlisted => None,
+            RomAudit::Incomplete(missing) => Some(format!(
+                "Incomplete rom set, missing {}",
+                missing.join(", ")
+            )),
+            RomAudit::Unreadable(e) => Some(format!("Unable to read rom: {}", e)),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct RomManifest {
+    /// lowercase rom name to lowercase file names
+    files: HashMap<String, Vec<String>>,
+}
+
+impl RomManifest {
+    pub fn parse(source: &str) -> Result<RomManifest, String> {
+        let mut files: HashMap<String, Vec<String>> = HashMap::new();
+        for (nr, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let rom_name = parts.next().unwrap_or_default().to_lowercase();
+            let rom_files = files.entry(rom_name).or_default();
+            let before = rom_files.len();
+            rom_files.extend(parts.map(|file| file.to_lowercase()));
+            if rom_files.len() == before {
+                return Err(format!(
+                    "Line {}: expected <rom name> <file name>...",
+                    nr + 1
+                ));
+            }
+        }
+        Ok(RomManifest { files })
+    }
+
+    pub fn read(path: &Path) -> io::Result<RomManifest> {
+        let source = std::fs::read_to_string(path)?;
+        RomManifest::parse(&source).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid rom manifest {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Audits the rom of the table, `None` if the table has no rom to audit
+    pub fn audit(&self, table: &IndexedTable) -> Option<RomAudit> {
+        let rom_path = table.rom_path()?;
+        let rom_name = table.game_name.as_ref()?.to_lowercase();
+        Some(self.audit_rom(&rom_name, rom_path))
+    }
+
+    fn audit_rom(&self, rom_name: &str, rom_path: &Path) -> RomAudit {
+        let Some(required) = self.files.get(rom_name) else {
+            return RomAudit::Unlisted;
+        };
+        let entries = match zip_entry_names(rom_path) {
+            Ok(entries) => entries,
+            Err(e) => return RomAudit::Unreadable(e.to_string()),
+        };
+        let chd_folder = rom_path.with_file_name(rom_name);
+        let missing = required
+            .iter()
+            .filter(|file| {
+                if file.ends_with(".chd") {
+                    !chd_folder.join(file).exists()
+                } else {
+                    !entries.contains(*file)
+                }
+            })
+            .cloned()
+            .collect::<Vec<String>>();
+        if missing.is_empty() {
+            RomAudit::Complete
+        } else {
+            RomAudit::Incomplete(missing)
+        }
+    }
+}
+
+/// Lowercase file names in the zip, without their folder
+fn zip_entry_names(rom_path: &Path) -> io::Result<HashSet<String>> {
+    let archive = zip::ZipArchive::new(File::open(rom_path)?).map_err(io::Error::other)?;
+    Ok(archive
+        .file_names()
+        .filter_map(|name| name.ok())
+        .filter_map(|name| name.rsplit('/').next().map(str::to_lowercase))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use testdir::testdir;
+    use zip::write::SimpleFileOptions;
+
+    fn write_zip(path: &Path, names: &[&str]) -> io::Result<()> {
+        let mut zip = zip::ZipWriter::new(File::create(path)?);
+        for name in names {
+            zip.start_file(*name, SimpleFileOptions::default())
+                .map_err(io::Error::other)?;
+            zip.write_all(b"rom")?;
+        }
+        zip.finish().map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit_rom() -> io::Result<()> {
+        let dir = testdir!();
+        let rom_path = dir.join("afm_113b.zip");
+        write_zip(&rom_path, &["afm_113b.bin", "sound/U14.ROM"])?;
+        let manifest = RomManifest::parse(
+            "# attack from mars\nafm_113b afm_113b.bin u14.rom\nAFM_113B u15.rom\nmm_109c mm.bin\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.audit_rom("afm_113b", &rom_path),
+            RomAudit::Incomplete(vec!["u15.rom".to_string()])
+        );
+        assert_eq!(manifest.audit_rom("tz_92", &rom_path), RomAudit::Unlisted);
+
+        let manifest = RomManifest::parse("afm_113b afm_113b.bin u14.rom afm.chd").unwrap();
+        assert_eq!(
+            manifest.audit_rom("afm_113b", &rom_path),
+            RomAudit::Incomplete(vec!["afm.chd".to_string()])
+        );
+        std::fs::create_dir(dir.join("afm_113b"))?;
+        File::create(dir.join("afm_113b").join("afm.chd"))?;
+        assert_eq!(
+            manifest.audit_rom("afm_113b", &rom_path),
+            RomAudit::Complete
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            RomManifest::parse("afm_113b"),
+            Err("Line 1: expected <rom name> <file name>...".to_string())
+        );
+    }
+}
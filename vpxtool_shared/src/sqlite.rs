@@ -0,0 +1,167 @@
+//! SQLite storage for the tables index, an alternative to the json file for large collections.
+//!
+//! Every table is stored as a row keyed by its path. The complete table is kept as json in the
+//! `data` column, the most useful fields are also stored in their own columns for ad-hoc queries:
+//!
+//! ```sql
+//! SELECT path FROM tables WHERE requires_pinmame AND game_name IS NULL;
+//! ```
+use crate::indexer::{IndexedTable, TablesIndex};
+use log::warn;
+use rusqlite::{Connection, params};
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS tables (
+    path TEXT PRIMARY KEY NOT NULL,
+    last_modified TEXT NOT NULL,
+    content_hash TEXT,
+    game_name TEXT,
+    table_name TEXT,
+    requires_pinmame INTEGER NOT NULL,
+    data TEXT NOT NULL
+)";
+
+fn open(db_path: &Path) -> io::Result<Connection> {
+    let connection = Connection::open(db_path).map_err(io::Error::other)?;
+    connection.execute(SCHEMA, []).map_err(io::Error::other)?;
+    Ok(connection)
+}
+
+/// Writes the index, only rows of tables that changed are updated.
+///
+/// Returns the number of inserted, updated and removed rows.
+pub fn write_index_sqlite(index: &TablesIndex, db_path: &Path) -> io::Result<usize> {
+    let mut connection = open(db_path)?;
+    let transaction = connection.transaction().map_err(io::Error::other)?;
+    let mut changed = 0;
+    let mut paths = HashSet::new();
+    {
+        // The modification time alone is not enough, relinked roms and notes change the
+        // table without touching the vpx file.
+        let mut upsert = transaction
+            .prepare(
+                "INSERT INTO tables
+                    (path, last_modified, content_hash, game_name, table_name, requires_pinmame, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(path) DO UPDATE SET
+                    last_modified = excluded.last_modified,
+                    content_hash = excluded.content_hash,
+                    game_name = excluded.game_name,
+                    table_name = excluded.table_name,
+                    requires_pinmame = excluded.requires_pinmame,
+                    data = excluded.data
+                 WHERE tables.last_modified IS NOT excluded.last_modified
+                    OR tables.data IS NOT excluded.data",
+            )
+            .map_err(io::Error::other)?;
+        for table in index.tables() {
+            let path = table.path.to_string_lossy().to_string();
+            let data = serde_json::to_string(&table)?;
+            changed += upsert
+                .execute(params![
+                    path,
+                    last_modified(&table)?,
+                    table.content_hash,
+                    table.game_name,
+                    table.table_info.table_name,
+                    table.requires_pinmame,
+                    data,
+                ])
+                .map_err(io::Error::other)?;
+            paths.insert(path);
+        }
+        let stored = transaction
+            .prepare("SELECT path FROM tables")
+            .and_then(|mut select| {
+                select
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<String>, _>>()
+            })
+            .map_err(io::Error::other)?;
+        let mut delete = transaction
+            .prepare("DELETE FROM tables WHERE path = ?1")
+            .map_err(io::Error::other)?;
+        for path in stored.iter().filter(|path| !paths.contains(*path)) {
+            changed += delete.execute([path]).map_err(io::Error::other)?;
+        }
+    }
+    transaction.commit().map_err(io::Error::other)?;
+    Ok(changed)
+}
+
+/// Reads the index, `None` if the database does not exist or holds unreadable tables
+pub fn read_index_sqlite(db_path: &Path) -> io::Result<Option<TablesIndex>> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let connection = open(db_path)?;
+    let rows = connection
+        .prepare("SELECT data FROM tables")
+        .and_then(|mut select| {
+            select
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<String>, _>>()
+        })
+        .map_err(io::Error::other)?;
+    let mut index = TablesIndex::empty();
+    for data in rows {
+        match serde_json::from_str::<IndexedTable>(&data) {
+            Ok(table) => index.insert(table),
+            Err(e) => {
+                warn!(
+                    "Failed to parse index database, ignoring existing index. ({})",
+                    e
+                );
+                return Ok(None);
+            }
+        }
+    }
+    Ok(Some(index))
+}
+
+/// The same rfc3339 representation as used in the json index
+fn last_modified(table: &IndexedTable) -> io::Result<String> {
+    let value = serde_json::to_value(table.last_modified)?;
+    Ok(value.as_str().unwrap_or_default().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use testdir::testdir;
+
+    fn table(path: &str, last_modified: &str) -> IndexedTable {
+        serde_json::from_value(json!({
+            "path": path,
+            "table_info": {"table_name": "Test", "properties": {}},
+            "requires_pinmame": false,
+            "last_modified": last_modified,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_write_read_sqlite() -> io::Result<()> {
+        let db_path = testdir!().join("index.sqlite");
+        assert_eq!(read_index_sqlite(&db_path)?, None);
+
+        let mut index = TablesIndex::empty();
+        index.insert(table("/tables/a.vpx", "2024-01-01T00:00:00+00:00"));
+        index.insert(table("/tables/b.vpx", "2024-01-01T00:00:00+00:00"));
+        assert_eq!(write_index_sqlite(&index, &db_path)?, 2);
+        // nothing changed
+        assert_eq!(write_index_sqlite(&index, &db_path)?, 0);
+
+        let mut index = TablesIndex::empty();
+        index.insert(table("/tables/a.vpx", "2024-02-01T00:00:00+00:00"));
+        // a updated, b removed
+        assert_eq!(write_index_sqlite(&index, &db_path)?, 2);
+
+        assert_eq!(read_index_sqlite(&db_path)?, Some(index));
+        Ok(())
+    }
+}
@@ -0,0 +1,528 @@
+//! A staging folder holds new downloads before they are moved into the tables folder.
+//!
+//! Staged tables are indexed together with the tables folder and flagged as
+//! [IndexedTable::staged](crate::indexer::IndexedTable::staged). Promoting a staged table moves it
+//! and its sidecar files to the same relative location in the tables folder.
+//!
+//! The same moves are used to organize the tables folder, [move_table] moves a table with its
+//! sidecar files to any folder and [move_table_as] also renames them. [sidecar_renames] repairs
+//! sidecar files left behind with the old name when a table was renamed elsewhere.
+//!
+//! The last move or rename can be remembered with [record_last_move] and moved back with
+//! [undo_last_move], also by a later run of vpxtool.
+use crate::index::similar_names;
+use crate::indexer::{find_orphaned_sidecars, find_sidecar_files};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A file that was moved or renamed, see [record_last_move]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct RecordedMove {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+/// Where the table ends up in the tables folder when promoted
+pub fn promoted_path(
+    vpx_path: &Path,
+    staging_folder: &Path,
+    tables_folder: &Path,
+) -> io::Result<PathBuf> {
+    let relative = vpx_path.strip_prefix(staging_folder).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Not in the staging folder {}: {}",
+                staging_folder.display(),
+                vpx_path.display()
+            ),
+        )
+    })?;
+    Ok(tables_folder.join(relative))
+}
+
+/// Moves the table and its sidecar files into the tables folder.
+///
+/// Nothing is moved if any of the files already exists in the tables folder.
+/// Returns the new path of the table.
+pub fn promote<S: AsRef<str>>(
+    vpx_path: &Path,
+    staging_folder: &Path,
+    tables_folder: &Path,
+    sidecar_extensions: &[S],
+    include_hidden_sidecars: bool,
+) -> io::Result<PathBuf> {
+    let target = promoted_path(vpx_path, staging_folder, tables_folder)?;
+    let target_folder = target.parent().unwrap_or(tables_folder);
+    move_table(
+        vpx_path,
+        target_folder,
+        sidecar_extensions,
+        include_hidden_sidecars,
+    )
+}
+
+/// The files that move when moving the table to `target_folder`, the table first, then its
+/// sidecar files.
+///
+/// Fails if any of the files already exists in the target folder.
+pub fn table_moves<S: AsRef<str>>(
+    vpx_path: &Path,
+    target_folder: &Path,
+    sidecar_extensions: &[S],
+    include_hidden_sidecars: bool,
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let stem = vpx_path.file_stem().unwrap_or_default().to_string_lossy();
+    table_moves_as(
+        vpx_path,
+        target_folder,
+        &stem,
+        sidecar_extensions,
+        include_hidden_sidecars,
+    )
+}
+
+/// Like [table_moves] but the table and its sidecar files get `new_stem` as name, eg
+/// `Table.directb2s` becomes `New name.directb2s`.
+pub fn table_moves_as<S: AsRef<str>>(
+    vpx_path: &Path,
+    target_folder: &Path,
+    new_stem: &str,
+    sidecar_extensions: &[S],
+    include_hidden_sidecars: bool,
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let stem = vpx_path.file_stem().unwrap_or_default().to_string_lossy();
+    let target = |path: &Path| {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        // hidden sidecars start with a dot, the stem follows
+        target_folder.join(file_name.replacen(stem.as_ref(), new_stem, 1))
+    };
+    let mut moves = vec![(vpx_path.to_path_buf(), target(vpx_path))];
+    for sidecar in find_sidecar_files(vpx_path, sidecar_extensions, include_hidden_sidecars) {
+        let sidecar_target = target(&sidecar);
+        moves.push((sidecar, sidecar_target));
+    }
+    if let Some((_, existing)) = moves.iter().find(|(_, to)| to.exists()) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("File already exists: {}", existing.display()),
+        ));
+    }
+    Ok(moves)
+}
+
+/// Moves the table and its sidecar files to `target_folder`, which is created if needed.
+///
+/// Nothing is moved if any of the files already exists in the target folder.
+/// Returns the new path of the table.
+pub fn move_table<S: AsRef<str>>(
+    vpx_path: &Path,
+    target_folder: &Path,
+    sidecar_extensions: &[S],
+    include_hidden_sidecars: bool,
+) -> io::Result<PathBuf> {
+    let stem = vpx_path.file_stem().unwrap_or_default().to_string_lossy();
+    move_table_as(
+        vpx_path,
+        target_folder,
+        &stem,
+        sidecar_extensions,
+        include_hidden_sidecars,
+    )
+}
+
+/// Like [move_table] but renames the table and its sidecar files to `new_stem`, see
+/// [table_moves_as].
+pub fn move_table_as<S: AsRef<str>>(
+    vpx_path: &Path,
+    target_folder: &Path,
+    new_stem: &str,
+    sidecar_extensions: &[S],
+    include_hidden_sidecars: bool,
+) -> io::Result<PathBuf> {
+    let moves = table_moves_as(
+        vpx_path,
+        target_folder,
+        new_stem,
+        sidecar_extensions,
+        include_hidden_sidecars,
+    )?;
+    move_files(&moves)?;
+    Ok(moves[0].1.clone())
+}
+
+/// Moves the files planned by [table_moves] or [sidecar_renames], creating the folders they move
+/// to
+pub fn move_files(moves: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    for (from, to) in moves {
+        if let Some(folder) = to.parent() {
+            fs::create_dir_all(folder)?;
+        }
+        move_file(from, to)?;
+    }
+    Ok(())
+}
+
+/// Where the last move is remembered, next to the index file
+pub fn last_move_path(index_path: &Path) -> PathBuf {
+    index_path.with_file_name("vpxtool_last_move.json")
+}
+
+/// Remembers the moves as the last move in `record_path`, replacing the one before
+pub fn record_last_move(record_path: &Path, moves: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    let recorded = moves
+        .iter()
+        .map(|(from, to)| RecordedMove {
+            from: from.clone(),
+            to: to.clone(),
+        })
+        .collect::<Vec<RecordedMove>>();
+    serde_json::to_writer_pretty(File::create(record_path)?, &recorded)?;
+    Ok(())
+}
+
+/// The moves remembered in `record_path`, empty if nothing was recorded
+pub fn last_move(record_path: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    if !record_path.exists() {
+        return Ok(Vec::new());
+    }
+    let recorded: Vec<RecordedMove> =
+        serde_json::from_reader(File::open(record_path)?).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to read {}: {}", record_path.display(), e),
+            )
+        })?;
+    Ok(recorded
+        .into_iter()
+        .map(|recorded| (recorded.from, recorded.to))
+        .collect())
+}
+
+/// Moves the files of the last move back and forgets it, returns the moves that were undone.
+///
+/// Nothing is moved if a file is no longer where it was moved to or its old path is taken. With
+/// `dry_run` the moves are only checked.
+pub fn undo_last_move(record_path: &Path, dry_run: bool) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let moves = last_move(record_path)?;
+    if let Some((_, to)) = moves.iter().find(|(_, to)| !to.exists()) {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("File moved away since: {}", to.display()),
+        ));
+    }
+    if let Some((from, _)) = moves.iter().find(|(from, _)| from.exists()) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("File already exists: {}", from.display()),
+        ));
+    }
+    if !dry_run && !moves.is_empty() {
+        let back = moves
+            .iter()
+            .rev()
+            .map(|(from, to)| (to.clone(), from.clone()))
+            .collect::<Vec<(PathBuf, PathBuf)>>();
+        move_files(&back)?;
+        fs::remove_file(record_path)?;
+    }
+    Ok(moves)
+}
+
+/// Renames that give the orphaned sidecar files next to the table its name, for sidecars that
+/// kept the old name when the table was renamed. Only orphans with a name similar to the table
+/// are considered, see [similar_names]. Renames that would replace an existing file are left out.
+pub fn sidecar_renames<S: AsRef<str>>(
+    vpx_path: &Path,
+    sidecar_extensions: &[S],
+    include_hidden_sidecars: bool,
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let (Some(folder), Some(stem)) = (vpx_path.parent(), vpx_path.file_stem()) else {
+        return Ok(Vec::new());
+    };
+    let stem = stem.to_string_lossy();
+    let mut extensions = sidecar_extensions
+        .iter()
+        .map(|extension| extension.as_ref().trim_start_matches('.'))
+        .collect::<Vec<&str>>();
+    // longest first, like finding the orphans
+    extensions.sort_by_key(|extension| std::cmp::Reverse(extension.len()));
+    let mut renames: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for orphan in find_orphaned_sidecars(folder, sidecar_extensions, include_hidden_sidecars)? {
+        if orphan.parent() != Some(folder) {
+            continue;
+        }
+        let file_name = orphan.file_name().unwrap_or_default().to_string_lossy();
+        let (prefix, name) = match file_name.strip_prefix('.') {
+            Some(name) => (".", name),
+            None => ("", file_name.as_ref()),
+        };
+        let Some((old_stem, extension)) = extensions.iter().find_map(|extension| {
+            name.strip_suffix(&format!(".{}", extension))
+                .map(|old_stem| (old_stem, extension))
+        }) else {
+            continue;
+        };
+        if !similar_names(old_stem, &stem) {
+            continue;
+        }
+        let target = folder.join(format!("{}{}.{}", prefix, stem, extension));
+        if !target.exists() && !renames.iter().any(|(_, to)| *to == target) {
+            renames.push((orphan, target));
+        }
+    }
+    Ok(renames)
+}
+
+/// The folders below `root` relative to it, sorted and without hidden folders
+pub fn subfolders(root: &Path) -> Vec<PathBuf> {
+    let mut folders = WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_dir())
+        .filter_map(|entry| entry.path().strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect::<Vec<PathBuf>>();
+    folders.sort();
+    folders
+}
+
+/// A new folder below `root`, the name can contain nested folders but can't leave `root`
+pub fn folder_below(root: &Path, name: &str) -> io::Result<PathBuf> {
+    let relative = Path::new(name.trim());
+    let valid = relative.components().next().is_some()
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid folder name: {}", name),
+        ));
+    }
+    Ok(root.join(relative))
+}
+
+/// Renames the file, falling back to copy and remove when the folders are on different devices
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(from, to)?;
+            fs::remove_file(from)
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::DEFAULT_SIDECAR_EXTENSIONS;
+    use pretty_assertions::assert_eq;
+    use std::fs::File;
+    use testdir::testdir;
+
+    #[test]
+    fn test_promote() -> io::Result<()> {
+        let dir = testdir!();
+        let staging = dir.join("staging");
+        let tables = dir.join("tables");
+        fs::create_dir_all(staging.join("new"))?;
+        let vpx_path = staging.join("new").join("Table.vpx");
+        File::create(&vpx_path)?;
+        File::create(staging.join("new").join("Table.vbs"))?;
+        File::create(staging.join("new").join("Other.vbs"))?;
+
+        let promoted = promote(
+            &vpx_path,
+            &staging,
+            &tables,
+            &DEFAULT_SIDECAR_EXTENSIONS,
+            false,
+        )?;
+
+        assert_eq!(promoted, tables.join("new").join("Table.vpx"));
+        assert!(promoted.exists());
+        assert!(tables.join("new").join("Table.vbs").exists());
+        assert!(!vpx_path.exists());
+        assert!(staging.join("new").join("Other.vbs").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_promote_existing() -> io::Result<()> {
+        let dir = testdir!();
+        let staging = dir.join("staging");
+        let tables = dir.join("tables");
+        fs::create_dir_all(&staging)?;
+        fs::create_dir_all(&tables)?;
+        let vpx_path = staging.join("Table.vpx");
+        File::create(&vpx_path)?;
+        File::create(staging.join("Table.ini"))?;
+        File::create(tables.join("Table.ini"))?;
+
+        let result = promote(
+            &vpx_path,
+            &staging,
+            &tables,
+            &DEFAULT_SIDECAR_EXTENSIONS,
+            false,
+        );
+
+        assert_eq!(
+            result.map_err(|e| e.kind()),
+            Err(io::ErrorKind::AlreadyExists)
+        );
+        // nothing moved
+        assert!(vpx_path.exists());
+        assert!(!tables.join("Table.vpx").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_table() -> io::Result<()> {
+        let dir = testdir!();
+        let tables = dir.join("tables");
+        fs::create_dir_all(tables.join("em").join(".hidden"))?;
+        let vpx_path = tables.join("Table.vpx");
+        File::create(&vpx_path)?;
+        File::create(tables.join("Table.directb2s"))?;
+
+        assert_eq!(
+            subfolders(&tables),
+            vec![PathBuf::from("em")],
+            "hidden folders are skipped"
+        );
+        let target_folder = folder_below(&tables, "ss/williams")?;
+        let moves = table_moves(
+            &vpx_path,
+            &target_folder,
+            &DEFAULT_SIDECAR_EXTENSIONS,
+            false,
+        )?;
+        assert_eq!(moves.len(), 2);
+        let moved = move_table(
+            &vpx_path,
+            &target_folder,
+            &DEFAULT_SIDECAR_EXTENSIONS,
+            false,
+        )?;
+
+        assert_eq!(moved, tables.join("ss").join("williams").join("Table.vpx"));
+        assert!(moved.exists());
+        assert!(moved.with_extension("directb2s").exists());
+        assert!(!vpx_path.exists());
+        assert_eq!(
+            subfolders(&tables),
+            vec![
+                PathBuf::from("em"),
+                PathBuf::from("ss"),
+                PathBuf::from("ss").join("williams")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_last_move() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = dir.join("Table.vpx");
+        File::create(&vpx_path)?;
+        File::create(dir.join("Table.ini"))?;
+        let record_path = last_move_path(&dir.join("vpxtool_index.json"));
+        assert_eq!(undo_last_move(&record_path, false)?, vec![]);
+
+        let moves = table_moves(
+            &vpx_path,
+            &dir.join("em"),
+            &DEFAULT_SIDECAR_EXTENSIONS,
+            false,
+        )?;
+        move_files(&moves)?;
+        record_last_move(&record_path, &moves)?;
+        assert_eq!(last_move(&record_path)?, moves);
+        assert_eq!(undo_last_move(&record_path, true)?, moves);
+        assert!(!vpx_path.exists(), "nothing moved in a dry run");
+
+        File::create(&vpx_path)?;
+        let taken = undo_last_move(&record_path, false).map_err(|e| e.kind());
+        assert_eq!(taken, Err(io::ErrorKind::AlreadyExists));
+        fs::remove_file(&vpx_path)?;
+
+        assert_eq!(undo_last_move(&record_path, false)?, moves);
+        assert!(vpx_path.exists());
+        assert!(dir.join("Table.ini").exists());
+        assert!(!dir.join("em").join("Table.vpx").exists());
+        assert!(!record_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_table_as() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = dir.join("t2_v1.vpx");
+        File::create(&vpx_path)?;
+        File::create(dir.join(".t2_v1.ini"))?;
+        let tables = dir.join("tables");
+
+        let moved = move_table_as(
+            &vpx_path,
+            &tables,
+            "Terminator 2 (Williams 1991)",
+            &DEFAULT_SIDECAR_EXTENSIONS,
+            true,
+        )?;
+
+        assert_eq!(moved, tables.join("Terminator 2 (Williams 1991).vpx"));
+        assert!(moved.exists());
+        assert!(tables.join(".Terminator 2 (Williams 1991).ini").exists());
+        assert!(!vpx_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sidecar_renames() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = dir.join("Attack from Mars (Bally 1995).vpx");
+        File::create(&vpx_path)?;
+        File::create(dir.join("Attack from Mars (Bally 1995).ini"))?;
+        File::create(dir.join("Attack from Mars v1.1.directb2s"))?;
+        File::create(dir.join("Attack from Mars v1.1.ini"))?;
+        File::create(dir.join("Medieval Madness.vbs"))?;
+
+        let renames = sidecar_renames(&vpx_path, &DEFAULT_SIDECAR_EXTENSIONS, false)?;
+
+        assert_eq!(
+            renames,
+            vec![(
+                dir.join("Attack from Mars v1.1.directb2s"),
+                dir.join("Attack from Mars (Bally 1995).directb2s")
+            )],
+            "the ini exists already and the vbs is of another table"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_folder_below() {
+        let root = Path::new("/tables");
+        assert_eq!(
+            folder_below(root, " em ").unwrap(),
+            PathBuf::from("/tables/em")
+        );
+        for invalid in ["", "../other", "/other", "em/../.."] {
+            assert_eq!(
+                folder_below(root, invalid).map_err(|e| e.kind()),
+                Err(io::ErrorKind::InvalidInput),
+                "{invalid}"
+            );
+        }
+    }
+}
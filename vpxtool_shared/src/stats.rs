@@ -0,0 +1,134 @@
+//! Summary statistics for a collection of indexed tables.
+use crate::display::manufacturer_and_year;
+use crate::index::Index;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const UNKNOWN: &str = "Unknown";
+
+#[derive(Serialize, PartialEq, Debug, Default)]
+pub struct CollectionStats {
+    pub tables: usize,
+    /// Combined size of all vpx files in bytes
+    pub total_size: u64,
+    pub requires_rom: usize,
+    pub rom_found: usize,
+    pub rom_missing: usize,
+    pub with_b2s: usize,
+    pub without_b2s: usize,
+    pub with_warnings: usize,
+    /// Based on the `Title (Manufacturer Year)` file naming convention
+    pub per_manufacturer: BTreeMap<String, usize>,
+    /// Decades like `1990s`, based on the year in the file name
+    pub per_decade: BTreeMap<String, usize>,
+}
+
+pub fn collection_stats(index: &Index) -> CollectionStats {
+    let mut stats = CollectionStats {
+        tables: index.len(),
+        ..Default::default()
+    };
+    for table in index.iter() {
+        stats.total_size += table.path.metadata().map(|m| m.len()).unwrap_or(0);
+        if table.requires_pinmame {
+            stats.requires_rom += 1;
+            if table.rom_path().is_some() {
+                stats.rom_found += 1;
+            } else {
+                stats.rom_missing += 1;
+            }
+        }
+        if table.b2s_path.is_some() {
+            stats.with_b2s += 1;
+        } else {
+            stats.without_b2s += 1;
+        }
+        if !table.warnings().is_empty() {
+            stats.with_warnings += 1;
+        }
+        let stem = table
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (_, year) = manufacturer_and_year(&stem);
+        let decade = year
+            .and_then(|year| year.parse::<u32>().ok())
+            .map(|year| format!("{}s", year / 10 * 10));
+        *stats
+            .per_decade
+            .entry(decade.unwrap_or(UNKNOWN.to_string()))
+            .or_default() += 1;
+    }
+    for (manufacturer, tables) in index.by_manufacturer() {
+        *stats
+            .per_manufacturer
+            .entry(manufacturer.unwrap_or(UNKNOWN.to_string()))
+            .or_default() += tables.len();
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::IndexedTable;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::fs;
+    use testdir::testdir;
+
+    #[test]
+    fn test_collection_stats() {
+        let dir = testdir!();
+        let afm = dir.join("Attack from Mars (Bally 1995).vpx");
+        fs::write(&afm, [0u8; 10]).unwrap();
+        let tables: Vec<IndexedTable> = serde_json::from_value(json!([
+            {
+                "path": afm,
+                "table_info": {"properties": {}},
+                "game_name": "afm_113b",
+                "b2s_path": dir.join("Attack from Mars (Bally 1995).directb2s"),
+                "requires_pinmame": true,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            },
+            {
+                "path": dir.join("Medieval Madness (Williams 1997).vpx"),
+                "table_info": {"properties": {}},
+                "game_name": "mm_109c",
+                "rom_path": dir.join("mm_109c.zip"),
+                "requires_pinmame": true,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            },
+            {
+                "path": dir.join("original.vpx"),
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00"
+            }
+        ]))
+        .unwrap();
+
+        let stats = collection_stats(&Index::new(tables));
+
+        assert_eq!(
+            stats,
+            CollectionStats {
+                tables: 3,
+                total_size: 10,
+                requires_rom: 2,
+                rom_found: 1,
+                rom_missing: 1,
+                with_b2s: 1,
+                without_b2s: 2,
+                with_warnings: 1,
+                per_manufacturer: BTreeMap::from([
+                    ("Bally".to_string(), 1),
+                    ("Unknown".to_string(), 1),
+                    ("Williams".to_string(), 1),
+                ]),
+                per_decade: BTreeMap::from([("1990s".to_string(), 2), ("Unknown".to_string(), 1)]),
+            }
+        );
+    }
+}
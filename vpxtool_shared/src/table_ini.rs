@@ -0,0 +1,142 @@
+//! Per-table VPinball settings from the `.ini` file next to the table.
+//!
+//! VPinball writes these files itself, they can have a byte order mark, Latin-1 text, keys without
+//! a value and lines that are not settings at all. The parser skips whatever it doesn't understand
+//! instead of failing, so a single odd line doesn't hide the rest of the settings.
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Settings shown in the table info, label and `Section.Key`
+pub const SUMMARY_KEYS: [(&str, &str); 5] = [
+    ("Fullscreen", "Player.PlayfieldFullScreen"),
+    ("Sync", "Player.SyncMode"),
+    ("Nudge", "Player.NudgeStrength"),
+    ("Difficulty", "TableOverride.Difficulty"),
+    ("Camera", "TableOverride.ViewCabMode"),
+];
+
+/// The settings keyed by `Section.Key`, keys outside a section have no prefix.
+///
+/// Empty values are left out, VPinball writes those for settings that use the default. When a
+/// key is repeated the last value wins, like in VPinball.
+pub fn parse_table_ini(source: &str) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    let mut section = String::new();
+    for line in source.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key.is_empty() || value.is_empty() {
+            continue;
+        }
+        let key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", section, key)
+        };
+        settings.insert(key, value.to_string());
+    }
+    settings
+}
+
+pub fn ini_path_for(vpx_path: &Path) -> PathBuf {
+    vpx_path.with_extension("ini")
+}
+
+/// Reads the settings from the ini next to the table, `None` if there is no ini file
+pub fn read_table_ini(vpx_path: &Path) -> io::Result<Option<BTreeMap<String, String>>> {
+    let ini_path = ini_path_for(vpx_path);
+    if !ini_path.is_file() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(&ini_path)?;
+    let source = match String::from_utf8(bytes) {
+        Ok(source) => source,
+        // Latin-1 maps every byte to the code point with the same value
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    };
+    Ok(Some(parse_table_ini(&source)))
+}
+
+/// The [SUMMARY_KEYS] that are set, with the playfield resolution first
+pub fn summary(settings: &BTreeMap<String, String>) -> Vec<(&'static str, String)> {
+    let mut summary = Vec::new();
+    if let (Some(width), Some(height)) = (
+        settings.get("Player.PlayfieldWidth"),
+        settings.get("Player.PlayfieldHeight"),
+    ) {
+        summary.push(("Resolution", format!("{}x{}", width, height)));
+    }
+    summary.extend(
+        SUMMARY_KEYS
+            .iter()
+            .filter_map(|(label, key)| settings.get(*key).map(|value| (*label, value.clone()))),
+    );
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use testdir::testdir;
+
+    #[test]
+    fn test_parse_table_ini() {
+        let source = "\u{feff}; written by VPinball\r\n\
+            [Player]\r\n\
+            PlayfieldWidth = 1920\r\n\
+            PlayfieldHeight=1080\r\n\
+            PlayfieldFullScreen=\r\n\
+            not a setting\r\n\
+            =orphan\r\n\
+            SyncMode=1\r\n\
+            SyncMode=2\r\n\
+            [ TableOverride ]\r\n\
+            Difficulty=0.5\r\n";
+        let settings = parse_table_ini(source);
+        assert_eq!(
+            settings,
+            BTreeMap::from([
+                ("Player.PlayfieldHeight".to_string(), "1080".to_string()),
+                ("Player.PlayfieldWidth".to_string(), "1920".to_string()),
+                ("Player.SyncMode".to_string(), "2".to_string()),
+                ("TableOverride.Difficulty".to_string(), "0.5".to_string()),
+            ])
+        );
+        assert_eq!(
+            summary(&settings),
+            vec![
+                ("Resolution", "1920x1080".to_string()),
+                ("Sync", "2".to_string()),
+                ("Difficulty", "0.5".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_table_ini() -> io::Result<()> {
+        let dir = testdir!();
+        let vpx_path = dir.join("Table.vpx");
+        assert_eq!(read_table_ini(&vpx_path)?, None);
+        std::fs::write(dir.join("Table.ini"), b"[Player]\nSound=caf\xe9\n")?;
+        assert_eq!(
+            read_table_ini(&vpx_path)?,
+            Some(BTreeMap::from([(
+                "Player.Sound".to_string(),
+                "café".to_string()
+            )]))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+//! Favorites and tags of all tables in a file other frontends can read, and back.
+//!
+//! vpxtool has no separate favorites, a table is a favorite when it has the [FAVORITE_TAG]. Tables
+//! are keyed by their path relative to the tables folder with `/` separators.
+//!
+//! The `json` format looks like
+//! ```json
+//! {"tables": {"em/Fireball.vpx": {"favorite": true, "tags": ["kids"]}}}
+//! ```
+//! and the `ini` format, in the style of the VPinballX ini files, like
+//! ```ini
+//! [Favorites]
+//! em/Fireball.vpx=1
+//! [Tags]
+//! em/Fireball.vpx=kids
+//! ```
+use crate::index::Index;
+use crate::notes::{parse_tags, read_notes, write_notes};
+use ini::Ini;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Marks a table as favorite
+pub const FAVORITE_TAG: &str = "favorite";
+
+const FAVORITES_SECTION: &str = "Favorites";
+const TAGS_SECTION: &str = "Tags";
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TagsExportFormat {
+    #[default]
+    Json,
+    Ini,
+}
+
+impl TagsExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            TagsExportFormat::Json => "json",
+            TagsExportFormat::Ini => "ini",
+        }
+    }
+
+    /// The format matching the extension of the file, if any
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(TagsExportFormat::Json),
+            "ini" => Some(TagsExportFormat::Ini),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct TableTags {
+    #[serde(default)]
+    pub favorite: bool,
+    /// Without the [FAVORITE_TAG]
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct TagsExport {
+    pub tables: BTreeMap<String, TableTags>,
+}
+
+/// Favorites and tags of every table in the tables folder, tables in zip bundles have no notes
+/// and are left out
+pub fn tags_export(index: &Index, tables_folder: &Path) -> TagsExport {
+    let tables = index
+        .iter()
+        .filter(|table| !table.archived)
+        .filter_map(|table| {
+            let relative = table.path.strip_prefix(tables_folder).ok()?;
+            let key = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let tags = TableTags {
+                favorite: table.tags.iter().any(|tag| tag == FAVORITE_TAG),
+                tags: table
+                    .tags
+                    .iter()
+                    .filter(|tag| *tag != FAVORITE_TAG)
+                    .cloned()
+                    .collect(),
+            };
+            Some((key, tags))
+        })
+        .collect();
+    TagsExport { tables }
+}
+
+pub fn write_tags_export(
+    path: &Path,
+    format: TagsExportFormat,
+    export: &TagsExport,
+) -> io::Result<()> {
+    match format {
+        TagsExportFormat::Json => {
+            let file = File::create(path)?;
+            serde_json::to_writer_pretty(file, export)?;
+            Ok(())
+        }
+        TagsExportFormat::Ini => {
+            let mut ini = Ini::new();
+            for (table, tags) in &export.tables {
+                let favorite = if tags.favorite { "1" } else { "0" };
+                ini.with_section(Some(FAVORITES_SECTION))
+                    .set(table.as_str(), favorite);
+                ini.with_section(Some(TAGS_SECTION))
+                    .set(table.as_str(), tags.tags.join(","));
+            }
+            ini.write_to_file(path)
+        }
+    }
+}
+
+pub fn read_tags_export(path: &Path, format: TagsExportFormat) -> io::Result<TagsExport> {
+    match format {
+        TagsExportFormat::Json => {
+            let file = File::open(path)?;
+            serde_json::from_reader(file).map_err(|e| {
+                io::Error::other(format!(
+                    "Failed to parse/read json {}: {}",
+                    path.display(),
+                    e
+                ))
+            })
+        }
+        TagsExportFormat::Ini => {
+            let ini = Ini::load_from_file(path).map_err(|e| {
+                io::Error::other(format!(
+                    "Failed to parse/read ini {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let mut tables: BTreeMap<String, TableTags> = BTreeMap::new();
+            if let Some(favorites) = ini.section(Some(FAVORITES_SECTION)) {
+                for (table, value) in favorites.iter() {
+                    let favorite = matches!(value.trim(), "1" | "true");
+                    tables.entry(table.to_string()).or_default().favorite = favorite;
+                }
+            }
+            if let Some(tags) = ini.section(Some(TAGS_SECTION)) {
+                for (table, value) in tags.iter() {
+                    tables.entry(table.to_string()).or_default().tags = parse_tags(value);
+                }
+            }
+            Ok(TagsExport { tables })
+        }
+    }
+}
+
+/// Replaces the tags in the notes of the tables in the export, tables that are not in the export
+/// or not in the tables folder are left alone. Returns the paths of the tables whose tags changed.
+pub fn import_tags(
+    export: &TagsExport,
+    tables_folder: &Path,
+    dry_run: bool,
+) -> io::Result<Vec<PathBuf>> {
+    let mut changed = Vec::new();
+    for (table, tags) in &export.tables {
+        let vpx_path = tables_folder.join(table);
+        if !vpx_path.is_file() {
+            continue;
+        }
+        let mut notes = read_notes(&vpx_path)?;
+        let mut new_tags = tags
+            .tags
+            .iter()
+            .filter(|tag| *tag != FAVORITE_TAG)
+            .map(|tag| tag.to_lowercase())
+            .collect::<Vec<_>>();
+        if tags.favorite {
+            new_tags.insert(0, FAVORITE_TAG.to_string());
+        }
+        let mut old_tags = notes.tags.clone();
+        let mut sorted_new_tags = new_tags.clone();
+        old_tags.sort();
+        sorted_new_tags.sort();
+        if old_tags == sorted_new_tags {
+            continue;
+        }
+        notes.tags = new_tags;
+        if !dry_run {
+            write_notes(&vpx_path, &notes)?;
+        }
+        changed.push(vpx_path);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::IndexedTable;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use testdir::testdir;
+
+    fn export() -> TagsExport {
+        TagsExport {
+            tables: BTreeMap::from([
+                (
+                    "Attack from Mars.vpx".to_string(),
+                    TableTags {
+                        favorite: true,
+                        tags: vec![],
+                    },
+                ),
+                (
+                    "em/Fireball.vpx".to_string(),
+                    TableTags {
+                        favorite: false,
+                        tags: vec!["kids".to_string(), "wip".to_string()],
+                    },
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_tags_export() {
+        let tables: Vec<IndexedTable> = serde_json::from_value(json!([
+            {
+                "path": "/tables/Attack from Mars.vpx",
+                "table_info": {"properties": {}},
+                "requires_pinmame": true,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "tags": ["favorite"]
+            },
+            {
+                "path": "/tables/em/Fireball.vpx",
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "tags": ["kids", "wip"]
+            },
+            {
+                "path": "/tables/bundle.zip/Bundled.vpx",
+                "table_info": {"properties": {}},
+                "requires_pinmame": false,
+                "last_modified": "1970-01-01T00:00:00+00:00",
+                "archived": true,
+                "tags": ["kids"]
+            }
+        ]))
+        .unwrap();
+        assert_eq!(
+            tags_export(&Index::new(tables), Path::new("/tables")),
+            export()
+        );
+    }
+
+    #[test]
+    fn test_write_read_tags_export() -> io::Result<()> {
+        let dir = testdir!();
+        for format in [TagsExportFormat::Json, TagsExportFormat::Ini] {
+            let path = dir.join(format!("tags.{}", format.extension()));
+            assert_eq!(TagsExportFormat::from_path(&path), Some(format));
+            write_tags_export(&path, format, &export())?;
+            assert_eq!(read_tags_export(&path, format)?, export());
+        }
+        let ini = std::fs::read_to_string(dir.join("tags.ini"))?;
+        assert!(ini.contains("[Favorites]\nAttack from Mars.vpx=1\nem/Fireball.vpx=0\n"));
+        assert!(ini.contains("[Tags]\nAttack from Mars.vpx=\nem/Fireball.vpx=kids,wip\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_tags() -> io::Result<()> {
+        let dir = testdir!();
+        std::fs::create_dir_all(dir.join("em"))?;
+        let afm = dir.join("Attack from Mars.vpx");
+        let fireball = dir.join("em/Fireball.vpx");
+        std::fs::write(&afm, "")?;
+        std::fs::write(&fireball, "")?;
+        let mut notes = read_notes(&fireball)?;
+        notes.tags = vec!["wip".to_string(), "kids".to_string()];
+        notes.rating = Some(4);
+        write_notes(&fireball, &notes)?;
+
+        assert_eq!(import_tags(&export(), &dir, true)?, vec![afm.clone()]);
+        assert_eq!(read_notes(&afm)?.tags, Vec::<String>::new());
+
+        assert_eq!(import_tags(&export(), &dir, false)?, vec![afm.clone()]);
+        assert_eq!(read_notes(&afm)?.tags, vec!["favorite".to_string()]);
+        // same tags in another order are left alone
+        assert_eq!(read_notes(&fireball)?, notes);
+        Ok(())
+    }
+}
@@ -0,0 +1,362 @@
+//! Matches tables against the Virtual Pinball Spreadsheet database to find newer releases.
+//!
+//! The database is the `vpsdb.json` published at [VPS_DATABASE_URL]. vpxtool does not download
+//! it, a copy is kept somewhere on disk and refreshed with a browser or `curl` now and then.
+//! The matches are not stored in the index and are not part of the table warnings, the database
+//! changes independently of the tables so they are worked out when they are shown.
+//!
+//! Games are matched by name, the table name first and the file name second. When several games
+//! share the name, like remakes, the manufacturer and year from a `Title (Manufacturer Year)` file
+//! name pick one. The local version is the table version from the table info, or the version in
+//! the file name when that is empty.
+use crate::display::manufacturer_and_year;
+use crate::file_name_tags::FileNamePatterns;
+use crate::index::comparable_name;
+use crate::indexer::IndexedTable;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+pub const VPS_DATABASE_URL: &str =
+    "https://virtualpinballspreadsheet.github.io/vps-db/db/vpsdb.json";
+
+/// Only table files in this format are considered for the latest version
+const VPX_FORMAT: &str = "VPX";
+
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VpsGame {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub manufacturer: Option<String>,
+    #[serde(default)]
+    pub year: Option<u32>,
+    #[serde(default)]
+    pub ipdb_url: Option<String>,
+    #[serde(default)]
+    pub table_files: Vec<VpsTableFile>,
+}
+
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VpsTableFile {
+    pub id: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Milliseconds since the epoch
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+    #[serde(default)]
+    pub table_format: Option<String>,
+}
+
+impl VpsGame {
+    /// The IPDB machine number from the IPDB link, like `4032` for
+    /// `https://www.ipdb.org/machine.cgi?id=4032`
+    pub fn ipdb_number(&self) -> Option<u32> {
+        let url = self.ipdb_url.as_ref()?;
+        let (_, query) = url.split_once("id=")?;
+        query
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// The version of the most recently updated VPX table file
+    pub fn latest_version(&self) -> Option<&str> {
+        self.table_files
+            .iter()
+            .filter(|file| {
+                file.table_format
+                    .as_deref()
+                    .is_none_or(|format| format.eq_ignore_ascii_case(VPX_FORMAT))
+            })
+            .filter(|file| {
+                file.version
+                    .as_deref()
+                    .is_some_and(|v| !v.trim().is_empty())
+            })
+            .max_by_key(|file| file.updated_at.unwrap_or_default())
+            .and_then(|file| file.version.as_deref())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum VpsCheck {
+    /// The local version is the latest one
+    UpToDate,
+    /// There is a newer version
+    Outdated,
+    /// Matched, but there is no version to compare
+    Unknown,
+    /// No game with that name in the database
+    Unmatched,
+}
+
+impl VpsCheck {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VpsCheck::UpToDate => "UP-TO-DATE",
+            VpsCheck::Outdated => "OUTDATED",
+            VpsCheck::Unknown => "UNKNOWN",
+            VpsCheck::Unmatched => "UNMATCHED",
+        }
+    }
+
+    /// Shown as table warning, `None` when there is nothing to act on
+    pub fn warning(&self) -> Option<&'static str> {
+        match self {
+            VpsCheck::Outdated => Some("Newer version on VPS"),
+            VpsCheck::Unmatched => Some("Not found in the VPS database"),
+            VpsCheck::UpToDate | VpsCheck::Unknown => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct VpsStatus<'a> {
+    pub game: Option<&'a VpsGame>,
+    pub local_version: Option<String>,
+    pub check: VpsCheck,
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct VpsDatabase {
+    games: Vec<VpsGame>,
+    /// The games by [match_key] of their name, in database order
+    by_key: HashMap<String, Vec<usize>>,
+}
+
+impl VpsDatabase {
+    pub fn parse(source: &str) -> Result<VpsDatabase, String> {
+        let games: Vec<VpsGame> = serde_json::from_str(source).map_err(|e| e.to_string())?;
+        let mut by_key: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, game) in games.iter().enumerate() {
+            by_key.entry(match_key(&game.name)).or_default().push(i);
+        }
+        Ok(VpsDatabase { games, by_key })
+    }
+
+    pub fn read(path: &Path) -> io::Result<VpsDatabase> {
+        let source = std::fs::read_to_string(path)?;
+        VpsDatabase::parse(&source).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid VPS database {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// The game the table is a version of, `None` if there is no game with that name
+    pub fn find(&self, table: &IndexedTable) -> Option<&VpsGame> {
+        let stem = table
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (manufacturer, year) = manufacturer_and_year(&stem);
+        let names = [table.table_info.table_name.as_deref(), Some(stem.as_str())];
+        names
+            .into_iter()
+            .flatten()
+            .map(match_key)
+            .filter(|key| !key.is_empty())
+            .find_map(|key| {
+                let candidates = self.by_key.get(&key)?.iter().map(|&i| &self.games[i]);
+                // first best match, a matching manufacturer counts more than the year
+                candidates.rev().max_by_key(|game| {
+                    let same_manufacturer = manufacturer.as_ref().is_some_and(|m| {
+                        game.manufacturer
+                            .as_ref()
+                            .is_some_and(|gm| gm.eq_ignore_ascii_case(m))
+                    });
+                    let same_year = year
+                        .as_ref()
+                        .is_some_and(|y| game.year.map(|gy| gy.to_string()).as_ref() == Some(y));
+                    (same_manufacturer, same_year)
+                })
+            })
+    }
+
+    pub fn status(&self, table: &IndexedTable, patterns: &FileNamePatterns) -> VpsStatus<'_> {
+        let local_version = local_version(table, patterns);
+        let game = self.find(table);
+        let check = match game {
+            None => VpsCheck::Unmatched,
+            Some(game) => match (local_version.as_deref(), game.latest_version()) {
+                (Some(local), Some(latest)) => match is_older(local, latest) {
+                    Some(true) => VpsCheck::Outdated,
+                    Some(false) => VpsCheck::UpToDate,
+                    None => VpsCheck::Unknown,
+                },
+                _ => VpsCheck::Unknown,
+            },
+        };
+        VpsStatus {
+            game,
+            local_version,
+            check,
+        }
+    }
+}
+
+/// The table version from the table info, or from the file name when that is empty
+pub fn local_version(table: &IndexedTable, patterns: &FileNamePatterns) -> Option<String> {
+    table
+        .table_info
+        .table_version
+        .as_ref()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .or_else(|| patterns.version(&table.path))
+}
+
+/// Name without bracketed parts, versions, the word `the` and punctuation, so that
+/// `The Addams Family (Bally 1992) v2.1` and `Addams Family, The` compare equal
+fn match_key(name: &str) -> String {
+    comparable_name(name)
+        .split_whitespace()
+        .filter(|word| !is_version_word(word))
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty() && word != "the")
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `v1`, `v1.2` or `1.2.3`, but not a number like `1942`
+fn is_version_word(word: &str) -> bool {
+    let (prefixed, digits) = match word.strip_prefix('v') {
+        Some(digits) => (true, digits),
+        None => (false, word),
+    };
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && (prefixed || digits.contains('.'))
+}
+
+/// Compares the numbers in the versions, `None` if either has none. `v1.2` equals `1.2.0`.
+fn is_older(local: &str, latest: &str) -> Option<bool> {
+    let numbers = |version: &str| {
+        version
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<u64>().unwrap_or(u64::MAX))
+            .collect::<Vec<u64>>()
+    };
+    let mut local = numbers(local);
+    let mut latest = numbers(latest);
+    if local.is_empty() || latest.is_empty() {
+        return None;
+    }
+    let len = local.len().max(latest.len());
+    local.resize(len, 0);
+    latest.resize(len, 0);
+    Some(local < latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const DATABASE: &str = r#"[
+        {
+            "id": "afm",
+            "name": "Attack from Mars",
+            "manufacturer": "Bally",
+            "year": 1995,
+            "ipdbUrl": "https://www.ipdb.org/machine.cgi?id=3781",
+            "tableFiles": [
+                {"id": "afm-1", "version": "1.1", "updatedAt": 1000, "tableFormat": "VPX"},
+                {"id": "afm-2", "version": "2.0.1", "updatedAt": 3000, "tableFormat": "VPX"},
+                {"id": "afm-fp", "version": "9.0", "updatedAt": 4000, "tableFormat": "FP"}
+            ]
+        },
+        {"id": "taf-williams", "name": "The Addams Family", "manufacturer": "Williams", "year": 1992},
+        {"id": "taf-bally", "name": "The Addams Family", "manufacturer": "Bally", "year": 1992}
+    ]"#;
+
+    fn table(path: &str, name: Option<&str>, version: Option<&str>) -> IndexedTable {
+        serde_json::from_value(json!({
+            "path": path,
+            "table_info": {"table_name": name, "table_version": version, "properties": {}},
+            "requires_pinmame": false,
+            "last_modified": "1970-01-01T00:00:00+00:00"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find() {
+        let database = VpsDatabase::parse(DATABASE).unwrap();
+        let find = |path, name| {
+            database
+                .find(&table(path, name, None))
+                .map(|g| g.id.as_str())
+        };
+        assert_eq!(find("/t/afm.vpx", Some("Attack from Mars")), Some("afm"));
+        assert_eq!(
+            find("/t/Attack From Mars (Bally 1995) v2.0.vpx", Some(" ")),
+            Some("afm")
+        );
+        assert_eq!(
+            find("/t/Addams Family, The (Bally 1992).vpx", None),
+            Some("taf-bally")
+        );
+        assert_eq!(find("/t/The Addams Family.vpx", None), Some("taf-williams"));
+        assert_eq!(find("/t/Medieval Madness.vpx", None), None);
+    }
+
+    #[test]
+    fn test_status() {
+        let database = VpsDatabase::parse(DATABASE).unwrap();
+        let patterns = FileNamePatterns::default();
+        let check = |path, version| {
+            database
+                .status(&table(path, None, version), &patterns)
+                .check
+        };
+        assert_eq!(
+            check("/t/Attack from Mars v1.1.vpx", None),
+            VpsCheck::Outdated
+        );
+        assert_eq!(
+            check("/t/Attack from Mars.vpx", Some("v2.0.1")),
+            VpsCheck::UpToDate
+        );
+        assert_eq!(
+            check("/t/Attack from Mars.vpx", Some("final")),
+            VpsCheck::Unknown
+        );
+        assert_eq!(
+            check("/t/The Addams Family.vpx", Some("1.0")),
+            VpsCheck::Unknown
+        );
+        assert_eq!(check("/t/Medieval Madness.vpx", None), VpsCheck::Unmatched);
+
+        let afm = database.find(&table("/t/afm.vpx", Some("Attack from Mars"), None));
+        assert_eq!(afm.and_then(VpsGame::ipdb_number), Some(3781));
+        assert_eq!(afm.and_then(VpsGame::latest_version), Some("2.0.1"));
+    }
+
+    #[test]
+    fn test_is_older() {
+        assert_eq!(is_older("1.2", "1.10"), Some(true));
+        assert_eq!(is_older("v1.2", "1.2.0"), Some(false));
+        assert_eq!(is_older("2.0", "1.9.9"), Some(false));
+        assert_eq!(is_older("final", "1.0"), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(VpsDatabase::parse("{}").is_err());
+    }
+}
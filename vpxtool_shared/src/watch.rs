@@ -0,0 +1,226 @@
+//! Waits for files to change in the tables folders, so the index can be kept current.
+//!
+//! Only Linux gets notifications, through inotify. Elsewhere, and when the folders can't be
+//! watched, like on most network shares or when the inotify watch limit is reached, waiting
+//! falls back to a fixed interval and it is up to the caller to find out what changed. For the
+//! index that is cheap as only changed tables are parsed again.
+#[cfg(target_os = "linux")]
+use log::warn;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub struct FolderWatcher {
+    /// Returning too early would report tables that are still being copied
+    settle: Duration,
+    /// Changes to these files are not reported, like the index file in the tables folder
+    ignored: Vec<PathBuf>,
+    #[cfg(target_os = "linux")]
+    notifications: Option<linux::Notifications>,
+}
+
+impl FolderWatcher {
+    /// Watches the folders, and all their subfolders with `recursive`. Without notifications
+    /// [FolderWatcher::wait] returns every `settle` duration.
+    pub fn new(folders: &[&Path], recursive: bool, settle: Duration) -> FolderWatcher {
+        #[cfg(target_os = "linux")]
+        let notifications = match linux::Notifications::new(folders, recursive) {
+            Ok(notifications) => Some(notifications),
+            Err(e) => {
+                warn!("Unable to watch for changes, checking every {settle:?}: {e}");
+                None
+            }
+        };
+        #[cfg(not(target_os = "linux"))]
+        let _ = (folders, recursive);
+        FolderWatcher {
+            settle,
+            ignored: Vec::new(),
+            #[cfg(target_os = "linux")]
+            notifications,
+        }
+    }
+
+    /// Changes to this file do not end [FolderWatcher::wait]
+    pub fn ignore(mut self, path: &Path) -> FolderWatcher {
+        self.ignored.push(path.to_path_buf());
+        self
+    }
+
+    /// Whether changes are noticed as they happen, otherwise waiting is just sleeping
+    pub fn has_notifications(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.notifications.is_some();
+        #[cfg(not(target_os = "linux"))]
+        false
+    }
+
+    /// Blocks until a file in the watched folders changed and nothing changed for the settle
+    /// duration since. Without notifications this only waits for the settle duration.
+    pub fn wait(&mut self) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(notifications) = &mut self.notifications {
+            while !notifications.wait_for_change(&self.ignored)? {}
+            // a table being copied keeps changing, wait until it is done
+            loop {
+                std::thread::sleep(self.settle);
+                if !notifications.changed_since(&self.ignored)? {
+                    return Ok(());
+                }
+            }
+        }
+        std::thread::sleep(self.settle);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use inotify::{EventMask, Events, Inotify, WatchDescriptor, WatchMask};
+    use std::collections::HashMap;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use walkdir::WalkDir;
+
+    /// Room for many events with file names, the kernel limits names to 255 bytes
+    const BUFFER_SIZE: usize = 64 * 1024;
+
+    pub(super) struct Notifications {
+        inotify: Inotify,
+        recursive: bool,
+        /// inotify only reports the file name, this is the folder of each watch
+        folders: HashMap<WatchDescriptor, PathBuf>,
+        buffer: Vec<u8>,
+    }
+
+    impl Notifications {
+        pub(super) fn new(folders: &[&Path], recursive: bool) -> io::Result<Notifications> {
+            let mut notifications = Notifications {
+                inotify: Inotify::init()?,
+                recursive,
+                folders: HashMap::new(),
+                buffer: vec![0; BUFFER_SIZE],
+            };
+            for folder in folders {
+                notifications.watch(folder)?;
+            }
+            Ok(notifications)
+        }
+
+        /// Watches the folder and with `recursive` its subfolders
+        fn watch(&mut self, folder: &Path) -> io::Result<()> {
+            let max_depth = if self.recursive { usize::MAX } else { 0 };
+            for entry in WalkDir::new(folder).max_depth(max_depth) {
+                let entry = entry?;
+                if entry.file_type().is_dir() {
+                    let descriptor = self.inotify.watches().add(
+                        entry.path(),
+                        WatchMask::CREATE
+                            | WatchMask::CLOSE_WRITE
+                            | WatchMask::DELETE
+                            | WatchMask::MOVE
+                            | WatchMask::ATTRIB,
+                    )?;
+                    self.folders.insert(descriptor, entry.path().to_path_buf());
+                }
+            }
+            Ok(())
+        }
+
+        /// Blocks until there are events, returns whether any of them is about a file that is
+        /// not ignored
+        pub(super) fn wait_for_change(&mut self, ignored: &[PathBuf]) -> io::Result<bool> {
+            let mut buffer = std::mem::take(&mut self.buffer);
+            let result = self
+                .inotify
+                .read_events_blocking(&mut buffer)
+                .and_then(|events| self.handle(events, ignored));
+            self.buffer = buffer;
+            result
+        }
+
+        /// Reads the events that came in without blocking, returns whether any of them is about
+        /// a file that is not ignored
+        pub(super) fn changed_since(&mut self, ignored: &[PathBuf]) -> io::Result<bool> {
+            let mut buffer = std::mem::take(&mut self.buffer);
+            let mut changed = false;
+            let result = loop {
+                match self.inotify.read_events(&mut buffer) {
+                    Ok(events) => match self.handle(events, ignored) {
+                        Ok(events_changed) => changed |= events_changed,
+                        Err(e) => break Err(e),
+                    },
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break Ok(changed),
+                    Err(e) => break Err(e),
+                }
+            };
+            self.buffer = buffer;
+            result
+        }
+
+        fn handle(&mut self, events: Events, ignored: &[PathBuf]) -> io::Result<bool> {
+            let mut changed = false;
+            let mut new_folders = Vec::new();
+            for event in events {
+                let Some(folder) = self.folders.get(&event.wd) else {
+                    continue;
+                };
+                let path = match event.name {
+                    Some(name) => folder.join(name),
+                    None => folder.clone(),
+                };
+                if self.recursive
+                    && event.mask.contains(EventMask::ISDIR)
+                    && event
+                        .mask
+                        .intersects(EventMask::CREATE | EventMask::MOVED_TO)
+                {
+                    new_folders.push(path.clone());
+                }
+                if event.mask.contains(EventMask::IGNORED) {
+                    self.folders.remove(&event.wd);
+                }
+                changed |= !ignored.contains(&path);
+            }
+            for folder in new_folders {
+                // gone again before we got to it
+                if folder.is_dir() {
+                    self.watch(&folder)?;
+                }
+            }
+            Ok(changed)
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::mpsc;
+    use std::thread;
+    use testdir::testdir;
+
+    #[test]
+    fn test_wait_for_change() -> io::Result<()> {
+        let test_dir = testdir!();
+        fs::create_dir(test_dir.join("sub"))?;
+        let ignored = test_dir.join("index.json");
+        let mut watcher =
+            FolderWatcher::new(&[&test_dir], true, Duration::from_millis(50)).ignore(&ignored);
+        assert!(watcher.has_notifications());
+
+        let (sender, receiver) = mpsc::channel();
+        let waiting = thread::spawn(move || {
+            let result = watcher.wait();
+            sender.send(()).unwrap();
+            result
+        });
+        fs::write(&ignored, "{}")?;
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        fs::write(test_dir.join("sub").join("table.vpx"), "")?;
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+        waiting.join().unwrap()
+    }
+}